@@ -1,4 +1,8 @@
 fn main() {
+    // Das Programmsymbol gibt es nur für die Desktop-Version
+    if std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32") {
+        return;
+    }
     let mut res = winres::WindowsResource::new();
     res.set_icon("Zeichner.ico");
     res.compile().unwrap();
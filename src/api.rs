@@ -0,0 +1,195 @@
+// Lokale HTTP-Schnittstelle für andere Programme (z.B. Kalkulationssoftware), nur auf 127.0.0.1
+// Läuft als Task auf der tokio-Laufzeit der App
+// Beispiel: POST /calculate {"sides_mm": [4000, 3000, 4000, 3000], "angles": [90, null, null, null]}
+
+use crate::cli::quad_json;
+use crate::config::{Language, VertexLabels};
+use crate::diagnostics::{self, LogLevel};
+use crate::format::NumberFormat;
+use crate::geometry::{solve_with_tolerance, PlausibilityTolerance, QuadInput};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Größere Anfragen werden abgewiesen
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Warnbereich aus den Einstellungen, von der Oberfläche bei Änderungen aktualisiert
+pub type SharedTolerance = Arc<Mutex<PlausibilityTolerance>>;
+
+/// Wartezeit nach einem fehlgeschlagenen accept, damit der Fehler die CPU nicht auslastet
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Öffnet den Port und nimmt im Hintergrund Anfragen an; Abbruch über `JoinHandle::abort`
+/// Muss innerhalb der tokio-Laufzeit aufgerufen werden
+pub fn start(port: u16, tolerance: SharedTolerance) -> Result<JoinHandle<()>, String> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Port {} konnte nicht geöffnet werden: {}", port, e))?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let listener = TcpListener::from_std(listener).map_err(|e| e.to_string())?;
+
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve(stream, tolerance.clone()));
+                }
+                Err(e) => {
+                    diagnostics::log(LogLevel::Error, "HTTP-Schnittstelle", &format!("Verbindung nicht angenommen: {}", e));
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                }
+            }
+        }
+    }))
+}
+
+/// Beantwortet eine Anfrage und schließt die Verbindung
+async fn serve(mut stream: TcpStream, tolerance: SharedTolerance) {
+    let (status, body) = match read_request(&mut stream).await {
+        Ok((method, path, body)) => {
+            let tolerance = *tolerance.lock().unwrap();
+            handle(&method, &path, &body, &tolerance)
+        }
+        Err(e) => (400, json!({ "error": e })),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Liest Methode, Pfad und Inhalt (laut Content-Length)
+async fn read_request(stream: &mut TcpStream) -> Result<(String, String, String), String> {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        if let Some(head_end) = find_head_end(&data) {
+            let head = String::from_utf8_lossy(&data[..head_end]).to_string();
+            let (method, path, content_length) = parse_head(&head).ok_or("Ungültige Anfrage")?;
+            let body_start = head_end + 4;
+            if body_start + content_length > MAX_REQUEST_BYTES {
+                return Err("Anfrage zu groß".to_string());
+            }
+            if data.len() >= body_start + content_length {
+                let body = String::from_utf8_lossy(&data[body_start..body_start + content_length]).to_string();
+                return Ok((method, path, body));
+            }
+        } else if data.len() > MAX_REQUEST_BYTES {
+            return Err("Anfrage zu groß".to_string());
+        }
+
+        let read = stream.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("Verbindung vorzeitig geschlossen".to_string());
+        }
+        data.extend_from_slice(&buffer[..read]);
+    }
+}
+
+fn find_head_end(data: &[u8]) -> Option<usize> {
+    data.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+/// Anfragezeile und Content-Length aus dem Kopf
+fn parse_head(head: &str) -> Option<(String, String, usize)> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(Some(0))?;
+    Some((method, path, content_length))
+}
+
+/// Statuscode und JSON-Antwort zu einer Anfrage
+/// Widersprüche im Warnbereich stehen als Meldungen unter "warnings"
+fn handle(method: &str, path: &str, body: &str, tolerance: &PlausibilityTolerance) -> (u16, Value) {
+    match (method, path) {
+        ("POST", "/calculate") => match serde_json::from_str::<QuadInput>(body) {
+            Ok(input) => match solve_with_tolerance(&input, tolerance) {
+                Ok(solution) => {
+                    let mut json = quad_json(&solution.quad);
+                    let warnings: Vec<String> =
+                        solution.warnings.iter().map(|warning| warning.message(Language::German, &NumberFormat::default(), &VertexLabels::default())).collect();
+                    json["warnings"] = json!(warnings);
+                    (200, json)
+                }
+                Err(e) => (422, json!({ "kind": e.kind(), "message": e.to_string() })),
+            },
+            Err(e) => (400, json!({ "error": format!("Ungültiges JSON: {}", e) })),
+        },
+        ("GET", "/version") => (200, json!({ "version": env!("CARGO_PKG_VERSION") })),
+        (_, "/calculate" | "/version") => (405, json!({ "error": "Methode nicht erlaubt" })),
+        _ => (404, json!({ "error": "Unbekannter Pfad (POST /calculate, GET /version)" })),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let head = "POST /calculate HTTP/1.1\r\nHost: localhost\r\ncontent-length: 42";
+        assert_eq!(parse_head(head), Some(("POST".to_string(), "/calculate".to_string(), 42)));
+        assert_eq!(parse_head("GET /version HTTP/1.1"), Some(("GET".to_string(), "/version".to_string(), 0)));
+        assert_eq!(parse_head("POST /calculate HTTP/1.1\r\nContent-Length: x"), None);
+        assert_eq!(find_head_end(b"GET / HTTP/1.1\r\n\r\nbody"), Some(14));
+    }
+
+    #[test]
+    fn test_handle() {
+        let body = r#"{"sides_mm": [4000, 3000, 4000, 3000], "angles": [90, null, null, null]}"#;
+        let tolerance = PlausibilityTolerance::default();
+        let (status, json) = handle("POST", "/calculate", body, &tolerance);
+        assert_eq!(status, 200);
+        assert!((json["area_m2"].as_f64().unwrap() - 12.0).abs() < 1e-6);
+        assert_eq!(json["warnings"], json!([]));
+
+        let (status, json) = handle("POST", "/calculate", r#"{"sides_mm": [4000, null, null, null], "angles": [null, null, null, null]}"#, &tolerance);
+        assert_eq!(status, 422);
+        assert_eq!(json["kind"], "missing_input");
+        assert!(json["message"].as_str().unwrap().contains("Nicht genug Informationen"));
+        assert_eq!(handle("POST", "/calculate", "kein json", &tolerance).0, 400);
+        assert_eq!(handle("GET", "/calculate", "", &tolerance).0, 405);
+        assert_eq!(handle("GET", "/", "", &tolerance).0, 404);
+    }
+
+    #[test]
+    fn test_handle_uses_tolerance() {
+        // Überzählige Diagonale 2 mm zu lang: im Warnbereich eine Warnung, ohne Warnbereich ein Fehler
+        let body = r#"{"sides_mm": [4000, 3000, 4000, 3000], "angles": [90, null, null, null], "diagonals_mm": [5002, null]}"#;
+        let (status, json) = handle("POST", "/calculate", body, &PlausibilityTolerance::default());
+        assert_eq!(status, 200);
+        assert_eq!(json["warnings"].as_array().unwrap().len(), 1);
+
+        let (status, json) = handle("POST", "/calculate", body, &PlausibilityTolerance::STRICT);
+        assert_eq!(status, 422);
+        assert_eq!(json["kind"], "diagonal_mismatch");
+    }
+}
@@ -0,0 +1,53 @@
+// Dateiverknüpfung für Projektdateien (.cadz) unter Windows
+// Einträge landen unter HKEY_CURRENT_USER, daher ohne Administratorrechte
+
+use crate::project::PROJECT_EXTENSION;
+
+/// Programm-ID der Projektdateien in der Registry
+const PROG_ID: &str = "CAD-Zeichner.Projekt";
+
+/// Registry-Schlüssel und Standardwert für Endung, Symbol und Öffnen-Befehl
+#[cfg_attr(not(windows), allow(dead_code))]
+fn registry_entries(exe: &str) -> Vec<(String, String)> {
+    let classes = r"HKCU\Software\Classes";
+    vec![
+        (format!(r"{}\.{}", classes, PROJECT_EXTENSION), PROG_ID.to_string()),
+        (format!(r"{}\{}", classes, PROG_ID), "CAD-Zeichner Projekt".to_string()),
+        (format!(r"{}\{}\DefaultIcon", classes, PROG_ID), format!("\"{}\",0", exe)),
+        (format!(r"{}\{}\shell\open\command", classes, PROG_ID), format!("\"{}\" \"%1\"", exe)),
+    ]
+}
+
+/// Verknüpft .cadz mit der laufenden Programmdatei
+#[cfg(windows)]
+pub fn register() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Programmpfad nicht gefunden: {}", e))?;
+    for (key, value) in registry_entries(&exe.display().to_string()) {
+        let status = std::process::Command::new("reg")
+            .args(["add", &key, "/ve", "/d", &value, "/f"])
+            .output()
+            .map_err(|e| format!("Registry konnte nicht geändert werden: {}", e))?
+            .status;
+        if !status.success() {
+            return Err(format!("Registry-Eintrag {} konnte nicht gesetzt werden", key));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn register() -> Result<(), String> {
+    Err("Dateiverknüpfungen werden nur unter Windows eingerichtet".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_entries() {
+        let entries = registry_entries(r"C:\Programme\CAD\cad.exe");
+        assert_eq!(entries[0], (r"HKCU\Software\Classes\.cadz".to_string(), PROG_ID.to_string()));
+        assert_eq!(entries[3].1, r#""C:\Programme\CAD\cad.exe" "%1""#);
+    }
+}
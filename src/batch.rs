@@ -0,0 +1,199 @@
+// Stapelberechnung: eine CSV-Zeile je Viereck, Ergebnis wieder als CSV
+// Wird vom Kommandozeilen-Modus (--batch) und vom Import-Dialog der GUI verwendet
+
+use crate::export::{csv_delimiter, csv_field};
+use crate::format::NumberFormat;
+use crate::geometry::{parse_angle_input, parse_length_input, solve, QuadInput, DIAGONAL_NAMES, SIDE_NAMES, VERTEX_NAMES};
+
+/// Spalte der Eingabedatei
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    Name,
+    Side(usize),
+    Angle(usize),
+    Diagonal(usize),
+    Ignored,
+}
+
+/// Zusammenfassung eines Stapellaufs
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchResult {
+    pub csv: String,
+    pub solved: usize,
+    pub failed: usize,
+}
+
+/// Erkennt das Spaltentrennzeichen an der Kopfzeile (Semikolon hat Vorrang)
+fn detect_delimiter(header: &str) -> char {
+    if header.contains(';') {
+        ';'
+    } else {
+        ','
+    }
+}
+
+/// Zerlegt eine Zeile unter Beachtung von Anführungszeichen
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn parse_header(fields: &[String]) -> Vec<Column> {
+    fields
+        .iter()
+        .map(|f| {
+            let name = f.trim().to_uppercase();
+            if name == "NAME" || name == "BEZEICHNUNG" {
+                Column::Name
+            } else if let Some(i) = SIDE_NAMES.iter().position(|s| *s == name) {
+                Column::Side(i)
+            } else if let Some(i) = VERTEX_NAMES.iter().position(|v| *v == name) {
+                Column::Angle(i)
+            } else if let Some(i) = DIAGONAL_NAMES.iter().position(|d| *d == name) {
+                Column::Diagonal(i)
+            } else {
+                Column::Ignored
+            }
+        })
+        .collect()
+}
+
+/// Fehlermeldungen des Solvers einzeilig und ohne Symbol
+fn single_line(message: &str) -> String {
+    message
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_start_matches('❌')
+        .trim()
+        .to_string()
+}
+
+/// Berechnet alle Zeilen der Eingabe-CSV
+/// Kopfzeile mit den Spalten AB, BC, CD, DA, A, B, C, D (optional Name); leere Zellen = nicht gemessen
+/// Komma und Punkt werden als Dezimaltrennzeichen akzeptiert, die Ausgabe folgt `number`
+pub fn run_batch(input: &str, number: &NumberFormat) -> Result<BatchResult, String> {
+    let mut lines = input.lines().filter(|l| !l.trim().is_empty());
+    let header = lines.next().ok_or("Die Datei ist leer")?;
+    let in_delimiter = detect_delimiter(header);
+    let columns = parse_header(&split_row(header, in_delimiter));
+    if !columns.iter().any(|c| matches!(c, Column::Side(_))) {
+        return Err("Kopfzeile ohne Seitenspalten (AB, BC, CD, DA)".to_string());
+    }
+
+    let number = NumberFormat { grouping: false, ..*number };
+    let d = csv_delimiter(&number);
+    let mut csv = format!("Name{d}AB (mm){d}BC (mm){d}CD (mm){d}DA (mm){d}A (°){d}B (°){d}C (°){d}D (°){d}Fläche (m²){d}Fehler\n");
+    let (mut solved, mut failed) = (0, 0);
+
+    for (row, line) in lines.enumerate() {
+        let fields = split_row(line, in_delimiter);
+        let mut name = format!("Zeile {}", row + 1);
+        let mut input = QuadInput::default();
+        let mut errors = Vec::new();
+
+        for (column, field) in columns.iter().zip(fields.iter()) {
+            let parsed = match *column {
+                Column::Name => {
+                    if !field.trim().is_empty() {
+                        name = field.trim().to_string();
+                    }
+                    continue;
+                }
+                Column::Side(i) => parse_length_input(field, &NumberFormat::default())
+                    .map(|v| input.sides_mm[i] = v)
+                    .map_err(|e| format!("{}: {}", SIDE_NAMES[i], e)),
+                Column::Angle(i) => parse_angle_input(field, &NumberFormat::default())
+                    .map(|v| input.angles[i] = v)
+                    .map_err(|e| format!("{}: {}", VERTEX_NAMES[i], e)),
+                Column::Diagonal(i) => parse_length_input(field, &NumberFormat::default())
+                    .map(|v| input.diagonals_mm[i] = v)
+                    .map_err(|e| format!("{}: {}", DIAGONAL_NAMES[i], e)),
+                Column::Ignored => continue,
+            };
+            if let Err(e) = parsed {
+                errors.push(e);
+            }
+        }
+
+        let result = if errors.is_empty() {
+            solve(&input).map(|solution| solution.quad).map_err(|e| single_line(&e.to_string()))
+        } else {
+            Err(errors.join(", "))
+        };
+
+        csv.push_str(&csv_field(&name, d));
+        match result {
+            Ok(quad) => {
+                solved += 1;
+                let angles = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d];
+                for i in 0..4 {
+                    csv.push_str(&format!("{d}{}", number.number(quad.get_side_length_mm(i))));
+                }
+                for angle in angles {
+                    csv.push_str(&format!("{d}{}", angle.map(|a| number.number(a)).unwrap_or_default()));
+                }
+                csv.push_str(&format!("{d}{}{d}\n", number.number(quad.area_mm2() / 1_000_000.0)));
+            }
+            Err(e) => {
+                failed += 1;
+                csv.push_str(&format!("{d}{d}{d}{d}{d}{d}{d}{d}{d}{d}{}\n", csv_field(&e, d)));
+            }
+        }
+    }
+
+    Ok(BatchResult { csv, solved, failed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_batch() {
+        let input = "Name;AB;BC;CD;DA;A;B;C;D\n\
+                     Bad;4000;3000;4000;3000;90;;;\n\
+                     \"Flur; EG\";4000;;;;90;;;\n\
+                     Küche;abc;3000;4000;3000;90;;;\n";
+        let result = run_batch(input, &NumberFormat::new(1)).unwrap();
+        assert_eq!(result.solved, 1);
+        assert_eq!(result.failed, 2);
+
+        let rows: Vec<&str> = result.csv.lines().collect();
+        assert!(rows[1].starts_with("Bad;4000,0;3000,0;4000,0;3000,0;90,0;90,0;90,0;90,0;12,0;"));
+        assert!(rows[2].starts_with("\"Flur; EG\";;;;;;;;;;"));
+        assert!(rows[3].ends_with("AB: Keine gültige Zahl"));
+    }
+
+    #[test]
+    fn test_run_batch_diagonal() {
+        let input = "AB,BC,CD,DA,AC\n4000,3000,4000,3000,5000\n";
+        let result = run_batch(input, &NumberFormat::new(1)).unwrap();
+        assert_eq!(result.solved, 1);
+        assert!(result.csv.lines().nth(1).unwrap().starts_with("Zeile 1;4000,0;3000,0;4000,0;3000,0;90,0;"));
+    }
+
+    #[test]
+    fn test_run_batch_header() {
+        assert!(run_batch("", &NumberFormat::default()).is_err());
+        assert!(run_batch("x,y\n1,2\n", &NumberFormat::default()).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+// Kommandozeilen-Modus: Berechnung ohne GUI
+// Beispiel: cad-zeichner --ab 4000 --bc 3000 --cd 4100 --da 2900 --angle-a 92
+// Stapel:   cad-zeichner --batch aufmass.csv --output ergebnis.csv
+
+use crate::batch;
+use crate::config::{AppConfig, VertexLabels};
+use crate::format::NumberFormat;
+use crate::geometry::{
+    parse_angle_input, parse_length_input, solve, QuadInput, QuadSolution, Quadrilateral, SIDE_NAMES, VERTEX_NAMES,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug)]
+struct CliArgs {
+    input: QuadInput,
+    format: OutputFormat,
+}
+
+/// Prüft ob die App im Kommandozeilen-Modus gestartet wurde
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.iter().any(|a| a.starts_with("--") || a == "-h")
+}
+
+/// Führt den Kommandozeilen-Modus aus und gibt den Exit-Code zurück
+pub fn run(args: &[String]) -> i32 {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        print_usage();
+        return 0;
+    }
+
+    if let Some(pos) = args.iter().position(|a| a == "--batch") {
+        return run_batch(args, pos);
+    }
+
+    let cli = match parse_args(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Fehler: {}\n", e);
+            print_usage();
+            return 2;
+        }
+    };
+
+    match solve(&cli.input) {
+        Ok(solution) => {
+            match cli.format {
+                OutputFormat::Table => print_table(&solution, &AppConfig::load().labels),
+                OutputFormat::Json => print_json(&solution.quad),
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Stapelberechnung: Ergebnis in --output oder auf die Standardausgabe
+fn run_batch(args: &[String], pos: usize) -> i32 {
+    let Some(input_path) = args.get(pos + 1) else {
+        eprintln!("Fehler: Eingabedatei für --batch fehlt\n");
+        print_usage();
+        return 2;
+    };
+    let output_path = args
+        .iter()
+        .position(|a| a == "--output")
+        .and_then(|i| args.get(i + 1));
+
+    let content = match std::fs::read_to_string(input_path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Fehler: {} konnte nicht gelesen werden: {}", input_path, e);
+            return 1;
+        }
+    };
+
+    let result = match batch::run_batch(&content, &NumberFormat::from_config(&AppConfig::load())) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Fehler: {}", e);
+            return 1;
+        }
+    };
+
+    match output_path {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, &result.csv) {
+                eprintln!("Fehler: {} konnte nicht geschrieben werden: {}", path, e);
+                return 1;
+            }
+            eprintln!("{} berechnet, {} fehlerhaft → {}", result.solved, result.failed, path);
+        }
+        None => print!("{}", result.csv),
+    }
+
+    // Exit-Code 1, wenn mindestens eine Zeile nicht lösbar war
+    if result.failed > 0 { 1 } else { 0 }
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut input = QuadInput::default();
+    let mut format = OutputFormat::Table;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == "--json" {
+            format = OutputFormat::Json;
+            continue;
+        }
+
+        // Komma und Punkt werden beide als Dezimaltrennzeichen akzeptiert
+        let mut value = |parse: fn(&str, &NumberFormat) -> Result<Option<f64>, String>| -> Result<Option<f64>, String> {
+            let raw = iter
+                .next()
+                .ok_or_else(|| format!("Wert für {} fehlt", arg))?;
+            parse(raw, &NumberFormat::default()).map_err(|e| format!("{} '{}': {}", arg, raw, e))
+        };
+
+        match arg.as_str() {
+            "--ab" => input.sides_mm[0] = value(parse_length_input)?,
+            "--bc" => input.sides_mm[1] = value(parse_length_input)?,
+            "--cd" => input.sides_mm[2] = value(parse_length_input)?,
+            "--da" => input.sides_mm[3] = value(parse_length_input)?,
+            "--angle-a" => input.angles[0] = value(parse_angle_input)?,
+            "--angle-b" => input.angles[1] = value(parse_angle_input)?,
+            "--angle-c" => input.angles[2] = value(parse_angle_input)?,
+            "--angle-d" => input.angles[3] = value(parse_angle_input)?,
+            "--ac" => input.diagonals_mm[0] = value(parse_length_input)?,
+            "--bd" => input.diagonals_mm[1] = value(parse_length_input)?,
+            _ => return Err(format!("Unbekannte Option: {}", arg)),
+        }
+    }
+
+    Ok(CliArgs { input, format })
+}
+
+fn print_usage() {
+    println!(
+        "CAD-Zeichner {} - Kommandozeilen-Modus\n\n\
+        Verwendung:\n  \
+          cad-zeichner [--ab MM] [--bc MM] [--cd MM] [--da MM]\n               \
+          [--angle-a GRAD] [--angle-b GRAD] [--angle-c GRAD] [--angle-d GRAD]\n               \
+          [--ac MM] [--bd MM] [--json]\n  \
+          cad-zeichner --batch EINGABE.csv [--output AUSGABE.csv]\n\n\
+        Benötigt: 4 Seiten + mind. 1 Winkel, 3 Seiten + 2 benachbarte Winkel\n          \
+          oder 4 Seiten + 1 Diagonale (--ac/--bd)\n\
+        Stapel-CSV: Kopfzeile mit AB;BC;CD;DA;A;B;C;D (optional Name, AC, BD), eine Zeile je Viereck",
+        env!("CARGO_PKG_VERSION")
+    );
+}
+
+/// Tabelle mit den Bezeichnungen aus den Einstellungen; JSON behält feste Schlüssel
+fn print_table(solution: &QuadSolution, labels: &VertexLabels) {
+    println!("{:<8}{:>14}", "Seite", "Länge (mm)");
+    for (i, side) in solution.sides_mm.iter().enumerate() {
+        println!("{:<8}{:>14.3}", labels.side(i), side);
+    }
+
+    println!();
+    println!("{:<8}{:>14}", "Winkel", "Grad");
+    for (i, angle) in solution.angles_deg.iter().enumerate() {
+        println!("{:<8}{:>14.3}", labels.vertex(i), angle);
+    }
+
+    println!();
+    println!("Fläche: {:.6} m²", solution.quad.area_mm2() / 1_000_000.0);
+}
+
+fn print_json(quad: &Quadrilateral) {
+    println!("{}", serde_json::to_string_pretty(&quad_json(quad)).unwrap_or_default());
+}
+
+/// Berechnetes Viereck mit festen Schlüsseln, auch für die lokale HTTP-Schnittstelle
+pub fn quad_json(quad: &Quadrilateral) -> serde_json::Value {
+    let mut sides = serde_json::Map::new();
+    for (i, name) in SIDE_NAMES.iter().enumerate() {
+        sides.insert(name.to_string(), serde_json::json!(quad.get_side_length_mm(i)));
+    }
+
+    let mut angles = serde_json::Map::new();
+    let values = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d];
+    for (name, angle) in VERTEX_NAMES.iter().zip(values.iter()) {
+        angles.insert(name.to_string(), serde_json::json!(angle));
+    }
+
+    let vertices: Vec<[f64; 2]> = quad
+        .vertices
+        .iter()
+        .map(|v| [v.x / 1000.0, v.y / 1000.0])
+        .collect();
+
+    serde_json::json!({
+        "sides_mm": sides,
+        "angles_deg": angles,
+        "area_m2": quad.area_mm2() / 1_000_000.0,
+        "vertices_mm": vertices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cli = parse_args(&args(&["--ab", "4000", "--da", "2900,5", "--angle-a", "92", "--json"])).unwrap();
+        assert_eq!(cli.input.sides_mm[0], Some(4000.0));
+        assert_eq!(cli.input.sides_mm[3], Some(2900.5));
+        assert_eq!(cli.input.angles[0], Some(92.0));
+        assert_eq!(cli.format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&args(&["--ab"])).is_err());
+        assert!(parse_args(&args(&["--ab", "abc"])).is_err());
+        assert!(parse_args(&args(&["--xy", "1"])).is_err());
+        assert!(parse_args(&args(&["--angle-b", "400"])).is_err());
+    }
+}
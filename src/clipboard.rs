@@ -0,0 +1,19 @@
+// Bilder in die System-Zwischenablage legen (z.B. Zeichnung zum Einfügen in E-Mail oder Word)
+
+use std::borrow::Cow;
+
+/// Legt das Bild in die Zwischenablage
+pub fn copy_image(image: &egui::ColorImage) -> Result<(), String> {
+    let [width, height] = image.size;
+    let bytes: Vec<u8> = image.pixels.iter().flat_map(|c| c.to_srgba_unmultiplied()).collect();
+
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| {
+            clipboard.set_image(arboard::ImageData {
+                width,
+                height,
+                bytes: Cow::Owned(bytes),
+            })
+        })
+        .map_err(|e| format!("Zwischenablage nicht verfügbar: {}", e))
+}
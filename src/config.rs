@@ -0,0 +1,431 @@
+// Dauerhafte Einstellungen der App
+// Werden als JSON im Konfigurationsverzeichnis des Betriebssystems gespeichert (im Web im Browser-Speicher)
+
+use crate::costs::{CostRates, QuoteRules};
+use crate::export::GeoReference;
+use crate::raster::ImageExportSettings;
+use crate::report::CompanyBranding;
+use crate::geometry::{MeasurementUncertainty, PlausibilityTolerance, PointGridSpec, QuadInput, TileSpec};
+use crate::updater::UpdateChannel;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Einheit für angezeigte Längen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    Auto, // cm, ab 10 m Seitenlänge m
+    Millimeter,
+    Centimeter,
+    Meter,
+}
+
+impl LengthUnit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LengthUnit::Auto => "Automatisch (cm/m)",
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+        }
+    }
+
+    /// Millimeter je Einheit; "Automatisch" gilt bei Eingaben als mm
+    pub fn mm_per_unit(&self) -> f64 {
+        match self {
+            LengthUnit::Auto | LengthUnit::Millimeter => 1.0,
+            LengthUnit::Centimeter => 10.0,
+            LengthUnit::Meter => 1000.0,
+        }
+    }
+}
+
+/// Angezeigter Winkel an den Ecken; Außenwinkel = 360° − Innenwinkel, z.B. für Trockenbau und Bodenleger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AngleDisplay {
+    Interior,
+    Exterior,
+    Both,
+}
+
+impl AngleDisplay {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AngleDisplay::Interior => "Innenwinkel",
+            AngleDisplay::Exterior => "Außenwinkel",
+            AngleDisplay::Both => "Innen- und Außenwinkel",
+        }
+    }
+
+    /// Anzuzeigende Werte einer Ecke mit Zusatz zur Bezeichnung; Innenwinkel allein ohne Zusatz
+    pub fn corner_values(&self, interior_deg: f64) -> Vec<(&'static str, f64)> {
+        let exterior_deg = 360.0 - interior_deg;
+        match self {
+            AngleDisplay::Interior => vec![("", interior_deg)],
+            AngleDisplay::Exterior => vec![(" außen", exterior_deg)],
+            AngleDisplay::Both => vec![(" innen", interior_deg), (" außen", exterior_deg)],
+        }
+    }
+}
+
+/// Dezimaltrennzeichen für Anzeige und Eingabe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecimalSeparator {
+    Comma, // 1.234,5
+    Point, // 1,234.5
+}
+
+impl DecimalSeparator {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DecimalSeparator::Comma => "Komma (12,5)",
+            DecimalSeparator::Point => "Punkt (12.5)",
+        }
+    }
+}
+
+/// Sprache der Oberfläche
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    German,
+    English,
+}
+
+impl Language {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::German => "Deutsch",
+            Language::English => "English",
+        }
+    }
+}
+
+/// Farbschema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeMode {
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemeMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemeMode::System => "Wie System",
+            ThemeMode::Light => "Hell",
+            ThemeMode::Dark => "Dunkel",
+        }
+    }
+}
+
+/// Fenstermodus beim Start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowMode {
+    Fullscreen,
+    Maximized,
+    Windowed,
+}
+
+impl WindowMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowMode::Fullscreen => "Vollbild",
+            WindowMode::Maximized => "Maximiert",
+            WindowMode::Windowed => "Fenster",
+        }
+    }
+}
+
+/// Fangoptionen beim Zeichnen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapSettings {
+    pub vertices: bool,       // Linien rasten an Eckpunkten ein
+    pub circle_centers: bool, // Kreismittelpunkte rasten an Ecken, Seiten und Linien ein
+    pub radius_px: f32,       // Fangradius in Pixel
+    pub nudge_step_mm: f64,   // Schrittweite der Pfeiltasten, mit Shift das Zehnfache
+    pub magnifier: bool,      // Lupe beim Zeichnen und Verschieben von Linienenden
+    pub magnifier_zoom: f32,  // Vergrößerung der Lupe
+    pub centroid_midpoints: bool, // Linien rasten an Schwerpunkt und Seitenmitten ein (bei eingeblendeter Anzeige)
+    pub smart_guides: bool,   // Hilfslinien beim Ziehen von Linienenden (Flucht, gleicher Abstand, runde Länge)
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            vertices: true,
+            circle_centers: true,
+            radius_px: 14.0,
+            nudge_step_mm: 1.0,
+            magnifier: true,
+            magnifier_zoom: 4.0,
+            centroid_midpoints: true,
+            smart_guides: true,
+        }
+    }
+}
+
+/// Füllmuster für die Viereckfläche
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillPattern {
+    None,
+    Solid,
+    Hatch,
+    CrossHatch,
+}
+
+impl FillPattern {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FillPattern::None => "Keine",
+            FillPattern::Solid => "Flächig",
+            FillPattern::Hatch => "Schraffur",
+            FillPattern::CrossHatch => "Kreuzschraffur",
+        }
+    }
+}
+
+/// Darstellung der Viereckfläche (Bildschirm und Export)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FillSettings {
+    pub pattern: FillPattern,
+    pub color: [u8; 3],
+    pub opacity: u8, // 0 bis 255
+}
+
+impl Default for FillSettings {
+    fn default() -> Self {
+        Self {
+            pattern: FillPattern::None,
+            color: [50, 50, 200],
+            opacity: 60,
+        }
+    }
+}
+
+/// Bezeichnungen der Eckpunkte und Umlaufsinn der Beschriftung
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VertexLabels {
+    pub names: [String; 4], // Eckpunkte in Konstruktionsreihenfolge (intern A, B, C, D)
+    pub clockwise: bool,    // Beschriftung läuft auf dem Bildschirm im Uhrzeigersinn
+}
+
+impl Default for VertexLabels {
+    fn default() -> Self {
+        Self {
+            names: ["A", "B", "C", "D"].map(String::from),
+            clockwise: true,
+        }
+    }
+}
+
+impl VertexLabels {
+    /// Name des Eckpunkts; leere Eingaben fallen auf A–D zurück
+    pub fn vertex(&self, i: usize) -> &str {
+        let name = self.names[i].trim();
+        if name.is_empty() {
+            ["A", "B", "C", "D"][i]
+        } else {
+            name
+        }
+    }
+
+    /// Name der Seite vom Eckpunkt i zum nächsten ("AB" bzw. "P1–P2")
+    pub fn side(&self, i: usize) -> String {
+        self.segment(i, (i + 1) % 4)
+    }
+
+    /// Name der Diagonale (0 = AC, 1 = BD)
+    pub fn diagonal(&self, i: usize) -> String {
+        self.segment(i, i + 2)
+    }
+
+    /// Name der Strecke zwischen zwei Eckpunkten
+    pub fn segment(&self, from: usize, to: usize) -> String {
+        let from = self.vertex(from);
+        let to = self.vertex(to);
+        if from.chars().count() == 1 && to.chars().count() == 1 {
+            format!("{}{}", from, to)
+        } else {
+            format!("{}–{}", from, to)
+        }
+    }
+}
+
+/// Gespeicherter Satz Eingabewerte für wiederkehrende Maße, z.B. "Standard-Garage 6×3"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputTemplate {
+    pub name: String,
+    pub input: QuadInput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub update_channel: UpdateChannel,
+    pub check_updates_on_startup: bool,
+    pub skipped_versions: Vec<String>,
+    pub show_grid: bool,
+    pub grid_spacing_mm: f64, // Abstand der feinen Rasterlinien, jede 10. Linie ist kräftiger
+    pub fill: FillSettings,
+    pub labels: VertexLabels,
+    pub show_stations: bool, // Stationierung entlang des Umfangs ab A
+    pub show_centroid: bool, // Schwerpunkt und Seitenmitten mit Koordinaten
+    pub show_bimedians: bool, // Schwerlinien (Bimedianen) und Symmetrieachsen
+    pub show_point_grid: bool, // Punktraster im Viereck
+    pub extent_side: usize,    // Bezugsseite der Außenmaße (0=AB … 3=DA)
+    pub reference_side: Option<usize>, // Seite, die in Zeichnung und Protokoll waagerecht unten liegt (None = wie berechnet)
+    pub export_construction_lines: bool, // Hilfslinien auch im Aufmaß-Protokoll zeichnen
+    pub check_tolerance_mm: f64, // Zulässige Abweichung beim Kontrollmaß
+    pub plausibility: PlausibilityTolerance, // Warnbereich für widersprüchliche Maße
+    pub room_height_mm: f64,     // Extrusionshöhe der 3D-Vorschau
+    pub keep_lines_on_recalc: bool, // Linien beim Neuberechnen auf das neue Viereck übertragen
+    pub rectangle_mode: bool,       // Schnelleingabe nur Breite × Höhe
+    pub api_enabled: bool,          // Lokale HTTP-Schnittstelle (nur 127.0.0.1)
+    pub api_port: u16,
+    pub tutorial_seen: bool,        // Einführung abgeschlossen oder übersprungen
+    pub uncertainty: MeasurementUncertainty,
+    pub length_unit: LengthUnit,
+    pub angle_display: AngleDisplay, // Innen-, Außenwinkel oder beide in Zeichnung, Ergebnissen und Export
+    pub input_unit: LengthUnit, // Einheit für Maße ohne Einheitenangabe
+    pub language: Language,
+    pub theme: ThemeMode,
+    pub decimal_places: usize,
+    pub decimal_separator: DecimalSeparator,
+    pub thousands_grouping: bool,
+    pub snap: SnapSettings,
+    pub window_mode: WindowMode,
+    pub tiles: TileSpec,
+    pub point_grid: PointGridSpec,
+    pub costs: CostRates,
+    pub quote: QuoteRules, // Rundung der Mengen für Angebote
+    pub templates: Vec<InputTemplate>,
+    pub image_export: ImageExportSettings,
+    pub geo_reference: GeoReference, // Lageplan-Koordinaten für den GeoJSON-Export
+    pub branding: CompanyBranding,   // Logo und Anschrift im Kopf der Protokolle
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            update_channel: UpdateChannel::Stable,
+            check_updates_on_startup: false,
+            skipped_versions: Vec::new(),
+            show_grid: false,
+            grid_spacing_mm: 100.0,
+            fill: FillSettings::default(),
+            labels: VertexLabels::default(),
+            show_stations: false,
+            show_centroid: false,
+            show_bimedians: false,
+            show_point_grid: false,
+            extent_side: 0,
+            reference_side: None,
+            export_construction_lines: false,
+            check_tolerance_mm: 5.0,
+            plausibility: PlausibilityTolerance::default(),
+            room_height_mm: 2500.0,
+            keep_lines_on_recalc: false,
+            rectangle_mode: false,
+            api_enabled: false,
+            api_port: 8765,
+            tutorial_seen: false,
+            uncertainty: MeasurementUncertainty::default(),
+            length_unit: LengthUnit::Auto,
+            angle_display: AngleDisplay::Interior,
+            input_unit: LengthUnit::Millimeter,
+            language: Language::German,
+            theme: ThemeMode::System,
+            decimal_places: 3,
+            decimal_separator: DecimalSeparator::Comma,
+            thousands_grouping: false,
+            snap: SnapSettings::default(),
+            window_mode: WindowMode::Fullscreen,
+            tiles: TileSpec::default(),
+            point_grid: PointGridSpec::default(),
+            costs: CostRates::default(),
+            quote: QuoteRules::default(),
+            templates: Vec::new(),
+            image_export: ImageExportSettings::default(),
+            geo_reference: GeoReference::default(),
+            branding: CompanyBranding::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Lädt die Einstellungen, bei Fehlern werden Standardwerte verwendet
+    pub fn load() -> Self {
+        storage::load_setting(CONFIG_FILE_NAME)
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Speichert die Eingaben als Vorlage; eine gleichnamige Vorlage wird ersetzt
+    /// Gibt true zurück, wenn eine vorhandene Vorlage überschrieben wurde
+    pub fn store_template(&mut self, name: &str, input: QuadInput) -> bool {
+        let name = name.trim().to_string();
+        match self.templates.iter_mut().find(|t| t.name == name) {
+            Some(existing) => {
+                existing.input = input;
+                true
+            }
+            None => {
+                self.templates.push(InputTemplate { name, input });
+                false
+            }
+        }
+    }
+
+    /// Prüft ob der Benutzer diese Version übersprungen hat
+    pub fn is_version_skipped(&self, version: &str) -> bool {
+        self.skipped_versions.iter().any(|v| v == version)
+    }
+
+    /// Speichert die Einstellungen
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Einstellungen konnten nicht serialisiert werden: {}", e))?;
+
+        storage::save_setting(CONFIG_FILE_NAME, &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vertex_labels() {
+        let mut labels = VertexLabels::default();
+        assert_eq!(labels.side(3), "DA");
+        assert_eq!(labels.diagonal(1), "BD");
+
+        labels.names = ["P1", "P2", "P3", ""].map(String::from);
+        assert_eq!(labels.vertex(3), "D");
+        assert_eq!(labels.side(0), "P1–P2");
+        assert_eq!(labels.side(3), "D–P1");
+    }
+
+    #[test]
+    fn test_angle_display() {
+        assert_eq!(AngleDisplay::Interior.corner_values(90.0), vec![("", 90.0)]);
+        assert_eq!(AngleDisplay::Exterior.corner_values(135.0), vec![(" außen", 225.0)]);
+        assert_eq!(AngleDisplay::Both.corner_values(200.0), vec![(" innen", 200.0), (" außen", 160.0)]);
+    }
+
+    #[test]
+    fn test_store_template() {
+        let mut config = AppConfig::default();
+        assert!(!config.store_template(" Garage 6×3 ", QuadInput::rectangle(6000.0, 3000.0)));
+        assert!(!config.store_template("Bad", QuadInput::rectangle(2000.0, 1800.0)));
+        assert!(config.store_template("Garage 6×3", QuadInput::rectangle(6000.0, 3500.0)));
+
+        assert_eq!(config.templates.len(), 2);
+        assert_eq!(config.templates[0].name, "Garage 6×3");
+        assert_eq!(config.templates[0].input, QuadInput::rectangle(6000.0, 3500.0));
+    }
+}
@@ -0,0 +1,117 @@
+// Kostenschätzung aus Fläche, Umfang und eingezeichneten Linien
+
+use serde::{Deserialize, Serialize};
+
+/// Preise je Mengeneinheit (0 = nicht berücksichtigen)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostRates {
+    pub per_m2: f64,          // Fläche
+    pub per_m_perimeter: f64, // Umfang (z.B. Sockelleiste)
+    pub per_m_lines: f64,     // Eingezeichnete Linien (z.B. Trennwände, Fugen)
+}
+
+/// Kaufmännische Rundung der Mengen für Angebote
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuoteRules {
+    pub area_step_m2: f64,  // Fläche auf Vielfache davon aufrunden (0 = nicht runden)
+    pub length_step_m: f64, // Umfang und Linien ebenso
+    pub waste_percent: f64, // Verschnittzuschlag, wird vor dem Runden aufgeschlagen
+}
+
+impl Default for QuoteRules {
+    fn default() -> Self {
+        Self { area_step_m2: 0.05, length_step_m: 0.1, waste_percent: 0.0 }
+    }
+}
+
+impl QuoteRules {
+    /// Angebotsfläche aus der exakten Fläche
+    pub fn area_m2(&self, exact_m2: f64) -> f64 {
+        round_up(self.with_waste(exact_m2), self.area_step_m2)
+    }
+
+    /// Angebotslänge aus der exakten Länge
+    pub fn length_m(&self, exact_m: f64) -> f64 {
+        round_up(self.with_waste(exact_m), self.length_step_m)
+    }
+
+    fn with_waste(&self, value: f64) -> f64 {
+        value * (1.0 + self.waste_percent / 100.0)
+    }
+}
+
+/// Rundet auf das nächste Vielfache von `step` auf
+/// Glatte Werte bleiben trotz Rechenungenauigkeit stehen (12,00 wird nicht zu 12,05)
+pub fn round_up(value: f64, step: f64) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    let steps = value / step;
+    let whole = if (steps - steps.round()).abs() < 1e-9 { steps.round() } else { steps.ceil() };
+    whole * step
+}
+
+/// Eine Zeile der Kostentabelle
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostItem {
+    pub label: &'static str,
+    pub quantity: f64,
+    pub unit: &'static str,
+    pub rate: f64,
+}
+
+impl CostItem {
+    pub fn total(&self) -> f64 {
+        self.quantity * self.rate
+    }
+}
+
+/// Erstellt die Kostentabelle; Positionen ohne Preis werden weggelassen
+pub fn estimate_costs(rates: &CostRates, area_m2: f64, perimeter_m: f64, lines_m: f64) -> Vec<CostItem> {
+    [
+        CostItem { label: "Fläche", quantity: area_m2, unit: "m²", rate: rates.per_m2 },
+        CostItem { label: "Umfang", quantity: perimeter_m, unit: "m", rate: rates.per_m_perimeter },
+        CostItem { label: "Linien", quantity: lines_m, unit: "m", rate: rates.per_m_lines },
+    ]
+    .into_iter()
+    .filter(|item| item.rate > 0.0)
+    .collect()
+}
+
+/// Summe aller Positionen
+pub fn total_cost(items: &[CostItem]) -> f64 {
+    items.iter().map(CostItem::total).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_costs() {
+        let rates = CostRates { per_m2: 40.0, per_m_perimeter: 0.0, per_m_lines: 12.5 };
+        let items = estimate_costs(&rates, 12.0, 14.0, 4.0);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].total(), 480.0);
+        assert_eq!(items[1].label, "Linien");
+        assert_eq!(total_cost(&items), 530.0);
+    }
+
+    #[test]
+    fn test_quote_rules() {
+        let rules = QuoteRules::default();
+        assert!((rules.area_m2(12.3412) - 12.35).abs() < 1e-9);
+        assert!((rules.area_m2(12.0) - 12.0).abs() < 1e-9);
+        assert!((rules.length_m(14.01) - 14.1).abs() < 1e-9);
+
+        let with_waste = QuoteRules { waste_percent: 10.0, ..rules };
+        assert!((with_waste.area_m2(12.0) - 13.2).abs() < 1e-9);
+        assert!((with_waste.length_m(4.0) - 4.4).abs() < 1e-9);
+
+        let exact = QuoteRules { area_step_m2: 0.0, length_step_m: 0.0, waste_percent: 0.0 };
+        assert_eq!(exact.area_m2(12.3412), 12.3412);
+    }
+}
@@ -0,0 +1,125 @@
+// Fehlerprotokoll für die Fehlersuche: Rechenfehler, Abstürze und fehlgeschlagene Updates
+// Liegt neben den Einstellungen (im Web im Browser-Speicher); wird die Datei zu groß,
+// wandert sie in eine Vorgängerdatei und die älteste entfällt
+
+use crate::storage;
+
+const LOG_FILE_NAME: &str = "protokoll.log";
+/// Größe, ab der eine neue Protokolldatei begonnen wird
+const MAX_LOG_BYTES: usize = 256 * 1024;
+/// Anzahl aufbewahrter Vorgängerdateien (protokoll.1.log = die jüngste)
+const ROTATED_FILES: usize = 2;
+
+/// Schwere eines Eintrags
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Warning,
+    Error,
+    Panic,
+}
+
+impl LogLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Warning => "WARNUNG",
+            LogLevel::Error => "FEHLER",
+            LogLevel::Panic => "ABSTURZ",
+        }
+    }
+}
+
+/// Schreibt einen Eintrag mit Zeitstempel; scheitert das Schreiben, bleibt nur die Ausgabe auf stderr
+/// Die Zeile wird angehängt, nur beim Rotieren werden die Dateien umkopiert
+pub fn log(level: LogLevel, source: &str, message: &str) {
+    let line = entry(&chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(), level, source, message);
+    eprint!("{}", line);
+
+    if needs_rotation(storage::setting_len(LOG_FILE_NAME), line.len()) {
+        rotate();
+    }
+    if let Err(e) = storage::append_setting(LOG_FILE_NAME, &line) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Verschiebt die aktuelle Datei nach protokoll.1.log, die älteste entfällt
+fn rotate() {
+    for i in (1..ROTATED_FILES).rev() {
+        if let Some(older) = storage::load_setting(&rotated_name(i)) {
+            let _ = storage::save_setting(&rotated_name(i + 1), &older);
+        }
+    }
+    let current = storage::load_setting(LOG_FILE_NAME).unwrap_or_default();
+    let _ = storage::save_setting(&rotated_name(1), &current);
+    let _ = storage::save_setting(LOG_FILE_NAME, "");
+}
+
+/// Protokolliert Abstürze, bevor die Standardausgabe des Panics erfolgt
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unbekannte Ursache".to_string());
+        let location = info.location().map(|l| format!(" ({}:{})", l.file(), l.line())).unwrap_or_default();
+        log(LogLevel::Panic, &format!("Version {}", env!("CARGO_PKG_VERSION")), &format!("{}{}", payload, location));
+        default_hook(info);
+    }));
+}
+
+/// Gesamtes Protokoll zum Anzeigen und Versenden, ältere Einträge zuerst
+pub fn read_all() -> String {
+    let mut text = format!(
+        "CAD-Zeichner {} ({}, {})\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    for i in (1..=ROTATED_FILES).rev() {
+        text.push_str(&storage::load_setting(&rotated_name(i)).unwrap_or_default());
+    }
+    text.push_str(&storage::load_setting(LOG_FILE_NAME).unwrap_or_default());
+    text
+}
+
+/// Leert das Protokoll samt Vorgängerdateien
+pub fn clear() -> Result<(), String> {
+    for i in 1..=ROTATED_FILES {
+        storage::save_setting(&rotated_name(i), "")?;
+    }
+    storage::save_setting(LOG_FILE_NAME, "")
+}
+
+fn rotated_name(i: usize) -> String {
+    format!("protokoll.{}.log", i)
+}
+
+/// Eine Zeile pro Eintrag, Zeilenumbrüche der Meldung werden zusammengezogen
+fn entry(time: &str, level: LogLevel, source: &str, message: &str) -> String {
+    let message: Vec<&str> = message.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    format!("{} [{}] {}: {}\n", time, level.label(), source, message.join(" | "))
+}
+
+/// Passt die Zeile nicht mehr in die aktuelle Datei, beginnt eine neue
+fn needs_rotation(current_len: usize, line_len: usize) -> bool {
+    current_len > 0 && current_len + line_len > MAX_LOG_BYTES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_entry_and_rotation() {
+        let line = entry("2026-10-15 08:30:00", LogLevel::Error, "Berechnung", "Kreise schneiden sich nicht\n  Eingabe prüfen");
+        assert_eq!(line, "2026-10-15 08:30:00 [FEHLER] Berechnung: Kreise schneiden sich nicht | Eingabe prüfen\n");
+
+        assert!(!needs_rotation(0, line.len()));
+        assert!(!needs_rotation(0, MAX_LOG_BYTES + 1));
+        assert!(!needs_rotation(MAX_LOG_BYTES - line.len(), line.len()));
+        assert!(needs_rotation(MAX_LOG_BYTES - 10, line.len()));
+    }
+}
@@ -0,0 +1,234 @@
+// CAD-Austausch als DXF (Version R12) ohne zusätzliche Abhängigkeit
+// Koordinaten in mm mit y nach oben, Lage wie im Protokoll; Füllung als SOLID-Dreiecke und
+// Schraffur als einzelne Linien, damit auch ältere CAD-Programme sie ohne HATCH-Objekt lesen
+
+use crate::config::FillPattern;
+use crate::geometry::{construction_segment, Point};
+use crate::report::Report;
+use crate::view::{hatch_segments, ViewOrientation};
+use egui::Pos2;
+use std::fmt::Display;
+
+/// Linienabstand der Schraffur
+const HATCH_SPACING_MM: f32 = 100.0;
+/// Kreisbögen werden als Linienzug ausgegeben, da Spiegeln ihre Drehrichtung umkehrt
+const ARC_SEGMENTS: usize = 48;
+
+/// Schreibt Gruppencode und Wert paarweise
+struct DxfWriter {
+    out: String,
+    orientation: ViewOrientation,
+}
+
+impl DxfWriter {
+    fn pair(&mut self, code: u16, value: impl Display) {
+        self.out.push_str(&format!("{:>3}\n{}\n", code, value));
+    }
+
+    /// Weltkoordinaten (µm, y nach unten) → DXF (mm, y nach oben)
+    fn map(&self, p: &Point) -> [f64; 2] {
+        let p = self.orientation.apply(p);
+        [p.x / 1000.0, -p.y / 1000.0]
+    }
+
+    fn point(&mut self, code: u16, [x, y]: [f64; 2]) {
+        self.pair(code, format!("{:.3}", x));
+        self.pair(code + 10, format!("{:.3}", y));
+        self.pair(code + 20, "0.0");
+    }
+
+    fn entity(&mut self, kind: &str, layer: &str) {
+        self.pair(0, kind);
+        self.pair(8, layer);
+    }
+
+    fn line(&mut self, layer: &str, start: [f64; 2], end: [f64; 2]) {
+        self.entity("LINE", layer);
+        self.point(10, start);
+        self.point(11, end);
+    }
+
+    /// Dreieck als SOLID (vierter Punkt = dritter)
+    fn solid(&mut self, layer: &str, [a, b, c]: [[f64; 2]; 3]) {
+        self.entity("SOLID", layer);
+        self.point(10, a);
+        self.point(11, b);
+        self.point(12, c);
+        self.point(13, c);
+    }
+
+    fn circle(&mut self, layer: &str, center: [f64; 2], radius_mm: f64) {
+        self.entity("CIRCLE", layer);
+        self.point(10, center);
+        self.pair(40, format!("{:.3}", radius_mm));
+    }
+
+    /// Text mittig um den Punkt
+    fn text(&mut self, layer: &str, at: [f64; 2], height_mm: f64, text: &str) {
+        self.entity("TEXT", layer);
+        self.point(10, at);
+        self.pair(40, format!("{:.3}", height_mm));
+        self.pair(1, escape(text));
+        self.pair(72, 1);
+        self.point(11, at);
+        self.pair(73, 2);
+    }
+}
+
+/// Zeilenumbrüche entfernen, Umlaute usw. als \U+XXXX (R12 kennt kein UTF-8)
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\n' | '\r' => " ".to_string(),
+            c if c.is_ascii() => c.to_string(),
+            c => format!("\\U+{:04X}", c as u32),
+        })
+        .collect()
+}
+
+/// Zeichnung aus dem Protokoll: Viereck, Eckpunkte, sichtbare Linien, Kreise, Hilfslinien und Füllung
+pub fn report_drawing(report: &Report) -> String {
+    let quad = report.quad;
+    let mut dxf = DxfWriter { out: String::new(), orientation: report.orientation() };
+    let vertices = quad.vertices.clone().map(|v| dxf.map(&v));
+    let longest_mm = (0..4).map(|i| quad.get_side_length_mm(i)).fold(0.0, f64::max);
+    let text_height = (longest_mm / 40.0).max(10.0);
+
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "HEADER");
+    dxf.pair(9, "$ACADVER");
+    dxf.pair(1, "AC1009");
+    dxf.pair(0, "ENDSEC");
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "ENTITIES");
+
+    // Füllung zuerst, damit die Umrisse darüber liegen
+    match report.fill.pattern {
+        FillPattern::None => {}
+        FillPattern::Solid => {
+            // Wie in der Zeichenfläche an einer einspringenden Ecke teilen
+            let split = (0..4).find(|&i| quad.is_reflex_vertex(i)).unwrap_or(0);
+            let opposite = (split + 2) % 4;
+            for middle in [(split + 1) % 4, (split + 3) % 4] {
+                dxf.solid("FUELLUNG", [vertices[split], vertices[middle], vertices[opposite]]);
+            }
+        }
+        FillPattern::Hatch | FillPattern::CrossHatch => {
+            let polygon: Vec<Pos2> = vertices.iter().map(|[x, y]| Pos2::new(*x as f32, *y as f32)).collect();
+            let mut angles = vec![45.0];
+            if report.fill.pattern == FillPattern::CrossHatch {
+                angles.push(-45.0);
+            }
+            for angle in angles {
+                for [start, end] in hatch_segments(&polygon, angle, HATCH_SPACING_MM) {
+                    dxf.line("SCHRAFFUR", [start.x as f64, start.y as f64], [end.x as f64, end.y as f64]);
+                }
+            }
+        }
+    }
+
+    for line in report.construction_lines {
+        if let Some((start, end)) = construction_segment(quad, line, longest_mm * 2.0) {
+            let (start, end) = (dxf.map(&start), dxf.map(&end));
+            dxf.line("HILFSLINIEN", start, end);
+        }
+    }
+
+    for i in 0..4 {
+        dxf.line("VIERECK", vertices[i], vertices[(i + 1) % 4]);
+    }
+
+    for line in report.visible_lines() {
+        let (start, end) = (dxf.map(&line.start), dxf.map(&line.end));
+        dxf.line("LINIEN", start, end);
+        let middle = [(start[0] + end[0]) / 2.0, (start[1] + end[1]) / 2.0 + text_height * 0.6];
+        dxf.text("BESCHRIFTUNG", middle, text_height * 0.6, &line.name);
+    }
+
+    for circle in report.circles {
+        match circle.arc {
+            None => {
+                let center = dxf.map(&circle.center);
+                dxf.circle("KREISE", center, circle.radius_um as f64 / 1000.0);
+            }
+            Some(_) => {
+                let outline: Vec<[f64; 2]> = circle.outline(ARC_SEGMENTS).iter().map(|p| dxf.map(p)).collect();
+                for pair in outline.windows(2) {
+                    dxf.line("KREISE", pair[0], pair[1]);
+                }
+            }
+        }
+    }
+
+    // Eckpunkte mit Abstand nach außen beschriften
+    let center = vertices.iter().fold([0.0, 0.0], |acc, [x, y]| [acc[0] + x / 4.0, acc[1] + y / 4.0]);
+    for (i, [x, y]) in vertices.iter().enumerate() {
+        let (dx, dy) = (x - center[0], y - center[1]);
+        let len = (dx * dx + dy * dy).sqrt().max(1.0);
+        let at = [x + dx / len * text_height * 1.5, y + dy / len * text_height * 1.5];
+        dxf.text("BESCHRIFTUNG", at, text_height, report.labels.vertex(i));
+    }
+
+    dxf.pair(0, "ENDSEC");
+    dxf.pair(0, "EOF");
+    dxf.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AngleDisplay, FillSettings, LengthUnit, VertexLabels};
+    use crate::format::NumberFormat;
+    use crate::geometry::{solve, CustomLine, LineAnchor, QuadInput};
+    use crate::project::ProjectMetadata;
+    use crate::report::CompanyBranding;
+
+    #[test]
+    fn test_report_drawing() {
+        let quad = solve(&QuadInput::rectangle(4000.0, 3000.0)).unwrap().quad;
+        let mut labels = VertexLabels::default();
+        labels.names[0] = "Tür".to_string();
+        let metadata = ProjectMetadata::default();
+        let mut hidden = CustomLine::new("Versteckt".to_string(), LineAnchor::Vertex(1), LineAnchor::Vertex(3), &quad, &[]);
+        hidden.hidden = true;
+        let lines = [CustomLine::new("Achse".to_string(), LineAnchor::Vertex(0), LineAnchor::Vertex(2), &quad, &[]), hidden];
+        let report = Report {
+            metadata: &metadata,
+            date: String::new(),
+            quad: &quad,
+            input: None,
+            lines: &lines,
+            circles: &[],
+            construction_lines: &[],
+            labels: &labels,
+            reference_side: None,
+            number: NumberFormat::default(),
+            unit: LengthUnit::Meter,
+            angle_display: AngleDisplay::Interior,
+            fill: &FillSettings::default(),
+            triangle_diagonal: 0,
+            costs: &[],
+            branding: &CompanyBranding::default(),
+        };
+
+        let dxf = report_drawing(&report);
+        assert!(dxf.starts_with("  0\nSECTION\n"));
+        assert!(dxf.ends_with("  0\nEOF\n"));
+        assert_eq!(dxf.matches("\nVIERECK\n").count(), 4);
+        assert_eq!(dxf.matches("\nLINIEN\n").count(), 1);
+        assert!(dxf.contains("Achse"));
+        assert!(!dxf.contains("Versteckt"));
+        assert!(dxf.contains("T\\U+00FCr"));
+        assert!(!dxf.contains("SOLID"));
+
+        let solid = FillSettings { pattern: FillPattern::Solid, ..Default::default() };
+        let dxf = report_drawing(&Report { fill: &solid, date: String::new(), ..report });
+        assert_eq!(dxf.matches("SOLID").count(), 2);
+
+        // Unter 45° ist das Rechteck (4 m + 3 m) / √2 breit: bei 100 mm Abstand rund 49 Linien je Richtung
+        let hatch = FillSettings { pattern: FillPattern::CrossHatch, ..Default::default() };
+        let dxf = report_drawing(&Report { fill: &hatch, date: String::new(), ..report });
+        let count = dxf.matches("\nSCHRAFFUR\n").count();
+        assert!((95..=101).contains(&count), "{}", count);
+    }
+}
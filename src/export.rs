@@ -0,0 +1,190 @@
+// Export von Messdaten in Dateien (CSV, GeoJSON usw.)
+// Dateien landen wie Screenshots auf dem Desktop (im Web als Download), mit Zeitstempel im Namen
+
+use crate::config::{DecimalSeparator, VertexLabels};
+use crate::format::NumberFormat;
+use crate::geometry::{CustomLine, Point, Quadrilateral};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+
+/// Punkt mit lokalen Koordinaten in mm
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointRecord {
+    pub name: String,
+    pub x_mm: f64,
+    pub y_mm: f64,
+}
+
+/// Spaltentrenner passend zum Dezimaltrennzeichen (Semikolon bei Komma, sonst Komma)
+pub(crate) fn csv_delimiter(number: &NumberFormat) -> char {
+    match number.decimal_separator {
+        DecimalSeparator::Comma => ';',
+        DecimalSeparator::Point => ',',
+    }
+}
+
+/// Maskiert ein Textfeld, falls es Trennzeichen, Anführungszeichen oder Umbrüche enthält
+pub(crate) fn csv_field(text: &str, delimiter: char) -> String {
+    if text.contains(delimiter) || text.contains('"') || text.contains('\n') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Punktliste als CSV (ohne Tausendertrennung, damit Tabellenprogramme die Werte lesen)
+pub fn points_csv(points: &[PointRecord], number: &NumberFormat) -> String {
+    let number = NumberFormat { grouping: false, ..*number };
+    let d = csv_delimiter(&number);
+
+    let mut csv = format!("Punkt{d}X (mm){d}Y (mm)\n");
+    for point in points {
+        csv.push_str(&format!(
+            "{}{d}{}{d}{}\n",
+            csv_field(&point.name, d),
+            number.number(point.x_mm),
+            number.number(point.y_mm),
+        ));
+    }
+    csv
+}
+
+/// Georeferenzierung für den GeoJSON-Export: ein Eckpunkt mit bekannten Lageplan-Koordinaten
+/// und die Richtung der von ihm abgehenden Seite
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeoReference {
+    pub origin_vertex: usize, // Eckpunkt mit bekannten Koordinaten (0 = A), Richtung zur nächsten Ecke
+    pub easting_m: f64,       // Rechtswert (Ost)
+    pub northing_m: f64,      // Hochwert (Nord)
+    pub azimuth_deg: f64,     // Richtungswinkel der Seite, von Nord im Uhrzeigersinn
+    pub epsg: Option<u32>,    // Koordinatensystem, z.B. 25832 für ETRS89 / UTM 32N
+}
+
+impl Default for GeoReference {
+    fn default() -> Self {
+        // Seite AB nach Osten: Koordinaten in m wie in der Zeichnung
+        Self { origin_vertex: 0, easting_m: 0.0, northing_m: 0.0, azimuth_deg: 90.0, epsg: None }
+    }
+}
+
+impl GeoReference {
+    /// Lageplan-Koordinaten (Ost, Nord) in m, auf mm gerundet
+    /// Die Form liegt wie in der Zeichnung, ohne Spiegelung der Ansicht
+    pub fn site_coordinates(&self, quad: &Quadrilateral, p: &Point) -> [f64; 2] {
+        // In der Zeichnung zeigt Y nach unten, Nord zeigt nach oben
+        let north_up = |p: &Point| (p.x / 1000.0, -p.y / 1000.0);
+        let origin = north_up(&quad.vertices[self.origin_vertex % 4]);
+        let target = north_up(&quad.vertices[(self.origin_vertex + 1) % 4]);
+        let point = north_up(p);
+
+        // Richtungswinkel von Nord im Uhrzeigersinn = mathematischer Winkel 90° − Azimut
+        let rotation = (90.0 - self.azimuth_deg).to_radians() - (target.1 - origin.1).atan2(target.0 - origin.0);
+        let (sin, cos) = rotation.sin_cos();
+        let (dx, dy) = (point.0 - origin.0, point.1 - origin.1);
+        let round_mm = |m: f64| (m * 1000.0).round() / 1000.0;
+        [
+            round_mm(self.easting_m + (dx * cos - dy * sin) / 1000.0),
+            round_mm(self.northing_m + (dx * sin + dy * cos) / 1000.0),
+        ]
+    }
+}
+
+/// Eckpunkte, Viereck und eingeblendete Linien als GeoJSON-FeatureCollection in Lageplan-Koordinaten (m)
+pub fn geojson(quad: &Quadrilateral, lines: &[CustomLine], labels: &VertexLabels, reference: &GeoReference) -> String {
+    let site = |p: &Point| reference.site_coordinates(quad, p);
+
+    // Außenring geschlossen und gegen den Uhrzeigersinn (RFC 7946)
+    let mut ring: Vec<[f64; 2]> = quad.vertices.iter().map(site).collect();
+    let twice_area: f64 = (0..4).map(|i| ring[i][0] * ring[(i + 1) % 4][1] - ring[(i + 1) % 4][0] * ring[i][1]).sum();
+    if twice_area < 0.0 {
+        ring.reverse();
+    }
+    ring.push(ring[0]);
+
+    let perimeter_mm: f64 = (0..4).map(|side| quad.get_side_length_mm(side)).sum();
+    let mut features = vec![json!({
+        "type": "Feature",
+        "geometry": { "type": "Polygon", "coordinates": [ring] },
+        "properties": {
+            "name": "Viereck",
+            "area_m2": quad.area_mm2() / 1_000_000.0,
+            "perimeter_m": perimeter_mm / 1000.0,
+        },
+    })];
+    for (i, vertex) in quad.vertices.iter().enumerate() {
+        features.push(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": site(vertex) },
+            "properties": { "name": labels.vertex(i) },
+        }));
+    }
+    for line in lines.iter().filter(|line| !line.hidden) {
+        features.push(json!({
+            "type": "Feature",
+            "geometry": { "type": "LineString", "coordinates": [site(&line.start), site(&line.end)] },
+            "properties": { "name": line.name, "length_m": Quadrilateral::um_to_mm(line.length_um) / 1000.0 },
+        }));
+    }
+
+    let mut collection = json!({ "type": "FeatureCollection", "features": features });
+    if let Some(epsg) = reference.epsg {
+        // Veraltetes, aber von GIS-Programmen gelesenes crs-Element für projizierte Koordinaten
+        collection["crs"] = json!({ "type": "name", "properties": { "name": format!("urn:ogc:def:crs:EPSG::{}", epsg) } });
+    }
+    serde_json::to_string_pretty(&collection).unwrap_or_default()
+}
+
+/// Speichert den Inhalt auf dem Desktop als "<prefix>_<Zeitstempel>.<extension>"
+pub fn save_to_desktop(prefix: &str, extension: &str, content: &[u8]) -> Result<PathBuf, String> {
+    let path = storage::desktop_dir().join(format!(
+        "{}_{}.{}",
+        prefix,
+        chrono::Local::now().format("%Y%m%d_%H%M%S"),
+        extension
+    ));
+
+    storage::write(&path, content)
+        .map_err(|e| format!("Datei konnte nicht gespeichert werden: {}", e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points_csv() {
+        let points = vec![
+            PointRecord { name: "A".to_string(), x_mm: 0.0, y_mm: 0.0 },
+            PointRecord { name: "Linie 1; Ende".to_string(), x_mm: 1234.5, y_mm: -20.25 },
+        ];
+
+        let german = NumberFormat { grouping: true, ..NumberFormat::new(2) };
+        assert_eq!(
+            points_csv(&points, &german),
+            "Punkt;X (mm);Y (mm)\nA;0,00;0,00\n\"Linie 1; Ende\";1234,50;-20,25\n"
+        );
+
+        let english = NumberFormat { decimal_separator: DecimalSeparator::Point, ..german };
+        assert!(points_csv(&points, &english).starts_with("Punkt,X (mm),Y (mm)\nA,0.00,0.00\n"));
+    }
+
+    #[test]
+    fn test_geojson() {
+        let quad = crate::geometry::solve(&crate::geometry::QuadInput::rectangle(4000.0, 3000.0)).unwrap().quad;
+        // A bekannt, Seite AB zeigt nach Norden; D liegt in der Zeichnung unter A, also östlich
+        let reference = GeoReference { easting_m: 1000.0, northing_m: 2000.0, azimuth_deg: 0.0, epsg: Some(25832), ..GeoReference::default() };
+        let close = |a: [f64; 2], b: [f64; 2]| (a[0] - b[0]).abs() < 1e-6 && (a[1] - b[1]).abs() < 1e-6;
+        assert!(close(reference.site_coordinates(&quad, &quad.vertices[1]), [1000.0, 2004.0]));
+        assert!(close(reference.site_coordinates(&quad, &quad.vertices[3]), [1003.0, 2000.0]));
+
+        let json: serde_json::Value = serde_json::from_str(&geojson(&quad, &[], &VertexLabels::default(), &reference)).unwrap();
+        assert_eq!(json["crs"]["properties"]["name"], "urn:ogc:def:crs:EPSG::25832");
+        assert_eq!(json["features"].as_array().unwrap().len(), 5);
+        assert_eq!(json["features"][0]["properties"]["area_m2"], 12.0);
+        assert_eq!(json["features"][0]["geometry"]["coordinates"][0].as_array().unwrap().len(), 5);
+    }
+}
@@ -0,0 +1,217 @@
+// Zentrale Zahlenformatierung für alle Anzeigen und Eingaben
+// Nachkommastellen, Dezimaltrennzeichen und Tausendergruppierung kommen aus den Einstellungen
+
+use crate::config::{AngleDisplay, AppConfig, DecimalSeparator, LengthUnit};
+
+/// Höchste einstellbare Anzahl Nachkommastellen
+pub const MAX_DECIMAL_PLACES: usize = 4;
+
+/// Formatiert und liest Zahlen einheitlich
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumberFormat {
+    pub decimals: usize,
+    pub decimal_separator: DecimalSeparator,
+    pub grouping: bool, // Tausendertrennzeichen in der Anzeige
+}
+
+impl NumberFormat {
+    pub fn new(decimals: usize) -> Self {
+        Self {
+            decimals: decimals.min(MAX_DECIMAL_PLACES),
+            decimal_separator: DecimalSeparator::Comma,
+            grouping: false,
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            decimal_separator: config.decimal_separator,
+            grouping: config.thousands_grouping,
+            ..Self::new(config.decimal_places)
+        }
+    }
+
+    pub fn decimal_char(&self) -> char {
+        match self.decimal_separator {
+            DecimalSeparator::Comma => ',',
+            DecimalSeparator::Point => '.',
+        }
+    }
+
+    /// Tausendertrennzeichen ist jeweils das andere Zeichen
+    pub fn group_char(&self) -> char {
+        match self.decimal_separator {
+            DecimalSeparator::Comma => '.',
+            DecimalSeparator::Point => ',',
+        }
+    }
+
+    /// Zahl im eingestellten Format, z.B. "1.234,568"
+    pub fn number(&self, value: f64) -> String {
+        let text = format!("{:.*}", self.decimals, value);
+        let (sign, digits) = match text.strip_prefix('-') {
+            // "-0,000" entsteht durch Rundung kleiner negativer Werte
+            Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => ("", rest),
+            Some(rest) => ("-", rest),
+            None => ("", text.as_str()),
+        };
+        let (int_part, frac_part) = match digits.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+            None => (digits, None),
+        };
+
+        let mut result = String::from(sign);
+        for (i, c) in int_part.chars().enumerate() {
+            if self.grouping && i > 0 && (int_part.len() - i) % 3 == 0 {
+                result.push(self.group_char());
+            }
+            result.push(c);
+        }
+        if let Some(frac_part) = frac_part {
+            result.push(self.decimal_char());
+            result.push_str(frac_part);
+        }
+        result
+    }
+
+    /// Zahl mit Vorzeichen, z.B. "+3,200"
+    pub fn signed(&self, value: f64) -> String {
+        let text = self.number(value);
+        if text.starts_with('-') {
+            text
+        } else {
+            format!("+{}", text)
+        }
+    }
+
+    /// Längeneingabe mit optionaler Einheit ("1,25 m", "125 cm", "1250"), Ergebnis in mm
+    pub fn parse_length_mm(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let lower = text.to_lowercase();
+        let (number, factor) = if let Some(rest) = lower.strip_suffix("mm") {
+            (rest, 1.0)
+        } else if let Some(rest) = lower.strip_suffix("cm") {
+            (rest, 10.0)
+        } else if let Some(rest) = lower.strip_suffix('m') {
+            (rest, 1000.0)
+        } else {
+            (lower.as_str(), 1.0)
+        };
+        self.parse(number.trim()).map(|value| value * factor)
+    }
+
+    /// Winkel in Grad, z.B. "90,000°"
+    pub fn angle(&self, degrees: f64) -> String {
+        format!("{}°", self.number(degrees))
+    }
+
+    /// Winkel einer Ecke je nach Anzeige, bei beiden Werten "90° / 270°"
+    pub fn corner_angle(&self, interior_deg: f64, display: AngleDisplay) -> String {
+        display
+            .corner_values(interior_deg)
+            .into_iter()
+            .map(|(_, degrees)| self.angle(degrees))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    /// Länge in mm in der gewählten Einheit (Auto muss vorher aufgelöst sein)
+    pub fn length(&self, mm: f64, unit: LengthUnit) -> String {
+        match unit {
+            LengthUnit::Millimeter => format!("{} mm", self.number(mm)),
+            LengthUnit::Auto | LengthUnit::Centimeter => format!("{} cm", self.number(mm / 10.0)),
+            LengthUnit::Meter => format!("{} m", self.number(mm / 1000.0)),
+        }
+    }
+
+    /// Liest eine Zahl im eingestellten Format
+    /// Bei aktiver Gruppierung werden gültige Tausendergruppen ("1.234,5") werden entfernt, ein einzelnes anderes
+    /// Trennzeichen gilt als Dezimaltrennzeichen ("12.5" bei Komma-Einstellung)
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let mut text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let (decimal, group) = (self.decimal_char(), self.group_char());
+
+        if self.grouping && self.has_valid_grouping(&text) {
+            text = text.replace(group, "");
+        }
+        let text = text.replace([decimal, group], ".");
+
+        text.parse::<f64>().ok().filter(|v| v.is_finite())
+    }
+
+    /// Prüft ob das Tausendertrennzeichen nur an gültigen Stellen steht (Dreiergruppen)
+    fn has_valid_grouping(&self, text: &str) -> bool {
+        let group = self.group_char();
+        let int_part = text.split(self.decimal_char()).next().unwrap_or("");
+        let frac_part = &text[int_part.len()..];
+        if !int_part.contains(group) || frac_part.contains(group) {
+            return false;
+        }
+
+        let int_part = int_part.strip_prefix('-').unwrap_or(int_part);
+        let mut groups = int_part.split(group);
+        let first = groups.next().unwrap_or("");
+        let all_digits = |g: &str| g.chars().all(|c| c.is_ascii_digit());
+        (1..=3).contains(&first.len())
+            && all_digits(first)
+            && groups.all(|g| g.len() == 3 && all_digits(g))
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_format() {
+        assert_eq!(NumberFormat::new(3).number(12.3456), "12,346");
+        assert_eq!(NumberFormat::new(0).number(12.5001), "13");
+        assert_eq!(NumberFormat::new(2).number(-0.001), "0,00");
+        assert_eq!(NumberFormat::new(9).decimals, MAX_DECIMAL_PLACES);
+        assert_eq!(NumberFormat::new(1).signed(3.21), "+3,2");
+        assert_eq!(NumberFormat::new(1).length(4512.0, LengthUnit::Meter), "4,5 m");
+        assert_eq!(NumberFormat::new(1).corner_angle(90.0, AngleDisplay::Both), "90,0° / 270,0°");
+    }
+
+    #[test]
+    fn test_grouping_and_separator() {
+        let german = NumberFormat { grouping: true, ..NumberFormat::new(1) };
+        assert_eq!(german.number(1234567.26), "1.234.567,3");
+        assert_eq!(german.number(-1234.0), "-1.234,0");
+        assert_eq!(german.number(123.0), "123,0");
+
+        let english = NumberFormat { decimal_separator: DecimalSeparator::Point, ..german };
+        assert_eq!(english.number(1234.5), "1,234.5");
+    }
+
+    #[test]
+    fn test_parse_length_mm() {
+        let german = NumberFormat::default();
+        assert_eq!(german.parse_length_mm("1,25 m"), Some(1250.0));
+        assert_eq!(german.parse_length_mm("12,5cm"), Some(125.0));
+        assert_eq!(german.parse_length_mm(" 800 MM "), Some(800.0));
+        assert_eq!(german.parse_length_mm("800"), Some(800.0));
+        assert_eq!(german.parse_length_mm("m"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let german = NumberFormat { grouping: true, ..NumberFormat::default() };
+        assert_eq!(german.parse("1.234,5"), Some(1234.5));
+        assert_eq!(german.parse(" 12,5 "), Some(12.5));
+        assert_eq!(german.parse("12.5"), Some(12.5)); // keine gültige Gruppe → Dezimalpunkt
+        assert_eq!(german.parse("1 234"), Some(1234.0));
+        assert_eq!(german.parse("1.2.3"), None);
+
+        let english = NumberFormat { decimal_separator: DecimalSeparator::Point, ..german };
+        assert_eq!(english.parse("1,234.5"), Some(1234.5));
+        assert_eq!(english.parse("12,5"), Some(12.5));
+        assert_eq!(english.parse("abc"), None);
+    }
+}
@@ -0,0 +1,94 @@
+// Kreise und Kreisbögen: Punkte auf dem Umfang und Trefferprüfung
+
+use super::types::{CustomArc, CustomCircle, Point};
+use super::utils::distance_f64;
+
+impl CustomArc {
+    /// Prüft ob ein Winkel (in Grad) im Bogen liegt
+    pub fn contains(&self, angle_deg: f64) -> bool {
+        let offset = (angle_deg - self.start_deg).rem_euclid(360.0);
+        offset <= self.sweep_deg
+    }
+}
+
+impl CustomCircle {
+    pub const DEFAULT_WIDTH: f32 = 2.5;
+    pub const DEFAULT_COLOR: [u8; 3] = [120, 60, 160];
+
+    pub fn new(name: String, center: Point, radius_um: i64, arc: Option<CustomArc>) -> Self {
+        Self {
+            name,
+            center,
+            radius_um,
+            arc,
+            width: Self::DEFAULT_WIDTH,
+            color: Self::DEFAULT_COLOR,
+        }
+    }
+
+    /// Punkt auf dem Umfang beim Winkel (in Grad)
+    pub fn point_at(&self, angle_deg: f64) -> Point {
+        let rad = angle_deg.to_radians();
+        let r = self.radius_um as f64;
+        Point::new(self.center.x + r * rad.cos(), self.center.y + r * rad.sin())
+    }
+
+    /// Umriss als Punktfolge (für Darstellung und Export)
+    pub fn outline(&self, segments: usize) -> Vec<Point> {
+        let (start, sweep) = match self.arc {
+            Some(arc) => (arc.start_deg, arc.sweep_deg),
+            None => (0.0, 360.0),
+        };
+        let segments = segments.max(2);
+
+        (0..=segments)
+            .map(|i| self.point_at(start + sweep * i as f64 / segments as f64))
+            .collect()
+    }
+
+    /// Kürzester Abstand eines Punktes zum Kreis bzw. Bogen (in µm)
+    pub fn distance_to(&self, p: &Point) -> f64 {
+        let r = self.radius_um as f64;
+        let to_center = distance_f64(&self.center, p);
+
+        match self.arc {
+            None => (to_center - r).abs(),
+            Some(arc) => {
+                let angle = (p.y - self.center.y).atan2(p.x - self.center.x).to_degrees();
+                if arc.contains(angle) {
+                    (to_center - r).abs()
+                } else {
+                    let start = self.point_at(arc.start_deg);
+                    let end = self.point_at(arc.start_deg + arc.sweep_deg);
+                    distance_f64(p, &start).min(distance_f64(p, &end))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circle_distance() {
+        let circle = CustomCircle::new(String::new(), Point::new(0.0, 0.0), 1000, None);
+        assert!((circle.distance_to(&Point::new(1500.0, 0.0)) - 500.0).abs() < 1e-9);
+        assert!((circle.distance_to(&Point::new(0.0, 0.0)) - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_arc_distance() {
+        let arc = CustomArc { start_deg: 0.0, sweep_deg: 90.0 };
+        let circle = CustomCircle::new(String::new(), Point::new(0.0, 0.0), 1000, Some(arc));
+
+        assert!(arc.contains(45.0));
+        assert!(!arc.contains(180.0));
+        assert!(arc.contains(-315.0));
+
+        // Gegenüberliegende Seite: Abstand zum nächsten Bogenende
+        let d = circle.distance_to(&Point::new(-1000.0, 0.0));
+        assert!((d - 2f64.sqrt() * 1000.0).abs() < 1e-6);
+    }
+}
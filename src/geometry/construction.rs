@@ -1,238 +1,99 @@
 // Konstruktionsmethoden für Vierecke
 // Verwendet Mikrometer (µm) für maximale Präzision
 
+use super::error::GeometryError;
+use super::trace::ConstructionMethod;
 use super::types::{Point, Quadrilateral};
-use super::utils::{distance_um, find_circle_intersection};
+use super::utils::{distance_um, find_circle_intersection, ray_circle_intersections};
 use std::f64::consts::PI;
 
 impl Quadrilateral {
     /// Wählt die passende Konstruktionsmethode basierend auf gegebenen Werten
-    pub(crate) fn construct_quadrilateral(&mut self) -> Result<(), String> {
-        let has_ab = self.side_ab_um.is_some();
-        let has_bc = self.side_bc_um.is_some();
-        let has_cd = self.side_cd_um.is_some();
-        let has_da = self.side_da_um.is_some();
-
-        let has_angle_a = self.angle_a.is_some();
-        let has_angle_b = self.angle_b.is_some();
-        let has_angle_c = self.angle_c.is_some();
-        let has_angle_d = self.angle_d.is_some();
+    pub(crate) fn select_construction(&self) -> Result<ConstructionMethod, GeometryError> {
+        let sides = [self.side_ab_um, self.side_bc_um, self.side_cd_um, self.side_da_um].map(|s| s.is_some());
+        let angles = [self.angle_a, self.angle_b, self.angle_c, self.angle_d].map(|a| a.is_some());
+        let adjacent = |vertex: usize| angles[vertex] && angles[(vertex + 1) % 4];
 
         // === Alle 4 Seiten + Winkel ===
-        if has_ab && has_bc && has_cd && has_da {
-            if has_angle_a && has_angle_b {
-                return self.construct_from_all_sides_angles_a_b();
-            }
-            if has_angle_b && has_angle_c {
-                return self.construct_from_all_sides_angles_b_c();
-            }
-            if has_angle_c && has_angle_d {
-                return self.construct_from_all_sides_angles_c_d();
-            }
-            if has_angle_d && has_angle_a {
-                return self.construct_from_all_sides_angles_d_a();
-            }
-            if has_angle_a {
-                return self.construct_from_all_sides_angle_a();
+        if sides.iter().all(|&given| given) {
+            // Zwei benachbarte Winkel vor einem einzelnen, jeweils in der Reihenfolge A, B, C, D
+            if let Some(vertex) = (0..4).find(|&vertex| adjacent(vertex)) {
+                return Ok(ConstructionMethod::AdjacentAngles { vertex });
             }
-            if has_angle_b {
-                return self.construct_from_all_sides_angle_b();
+            if let Some(vertex) = angles.iter().position(|&given| given) {
+                return Ok(ConstructionMethod::SingleAngle { vertex });
             }
-            if has_angle_c {
-                return self.construct_from_all_sides_angle_c();
-            }
-            if has_angle_d {
-                return self.construct_from_all_sides_angle_d();
+            if let Some(diagonal) = [self.diagonal_ac_um, self.diagonal_bd_um].iter().position(Option::is_some) {
+                return Ok(ConstructionMethod::Diagonal { diagonal });
             }
         }
 
         // === 3 Seiten + 2 benachbarte Winkel ===
-        if has_ab && has_bc && has_da && !has_cd && has_angle_a && has_angle_b {
-            return self.construct_from_ab_bc_da_angles_a_b();
-        }
-        if has_bc && has_cd && has_ab && !has_da && has_angle_b && has_angle_c {
-            return self.construct_from_bc_cd_ab_angles_b_c();
-        }
-        if has_cd && has_da && has_bc && !has_ab && has_angle_c && has_angle_d {
-            return self.construct_from_cd_da_bc_angles_c_d();
-        }
-        if has_da && has_ab && has_cd && !has_bc && has_angle_d && has_angle_a {
-            return self.construct_from_da_ab_cd_angles_d_a();
-        }
-        if has_bc && has_cd && has_da && !has_ab && has_angle_b && has_angle_c {
-            return self.construct_from_bc_cd_da_angles_b_c();
-        }
-
-        Err(
-            "❌ Diese Kombination kann noch nicht berechnet werden.\n\n\
-            Bitte stellen Sie sicher, dass:\n\
-            • Alle 4 Seiten + mind. 1 Winkel ODER\n\
-            • 3 Seiten + 2 benachbarte Winkel\n\
-            gegeben sind.".to_string()
-        )
-    }
-
-    // === Konstruktionsmethoden: 3 Seiten + 2 Winkel ===
-
-    pub(crate) fn construct_from_ab_bc_da_angles_a_b(&mut self) -> Result<(), String> {
-        let ab = self.side_ab_um.unwrap() as f64;
-        let bc = self.side_bc_um.unwrap() as f64;
-        let da = self.side_da_um.unwrap() as f64;
-        let angle_a = self.angle_a.unwrap();
-        let angle_b = self.angle_b.unwrap();
-
-        self.vertices[0] = Point::new(0.0, 0.0);
-        self.vertices[1] = Point::new(ab, 0.0);
-
-        let angle_a_rad = angle_a * PI / 180.0;
-        self.vertices[3] = Point::new(
-            da * angle_a_rad.cos(),
-            da * angle_a_rad.sin(),
-        );
-
-        let angle_b_rad = (180.0 - angle_b) * PI / 180.0;
-        self.vertices[2] = Point::new(
-            ab + bc * angle_b_rad.cos(),
-            bc * angle_b_rad.sin(),
-        );
-
-        let calculated_cd_um = distance_um(&self.vertices[2], &self.vertices[3]);
-        if let Some(input_cd_um) = self.side_cd_um {
-            self.validate_length_um("CD", calculated_cd_um, input_cd_um)?;
-        } else {
-            self.side_cd_um = Some(calculated_cd_um);
-        }
-
-        self.calculate_angles_from_vertices();
-        Ok(())
-    }
-
-    pub(crate) fn construct_from_bc_cd_ab_angles_b_c(&mut self) -> Result<(), String> {
-        let bc = self.side_bc_um.unwrap() as f64;
-        let cd = self.side_cd_um.unwrap() as f64;
-        let ab = self.side_ab_um.unwrap() as f64;
-        let angle_b = self.angle_b.unwrap();
-        let angle_c = self.angle_c.unwrap();
-
-        self.vertices[1] = Point::new(0.0, 0.0);
-        self.vertices[2] = Point::new(bc, 0.0);
-
-        let angle_b_rad = angle_b * PI / 180.0;
-        self.vertices[0] = Point::new(
-            -ab * angle_b_rad.cos(),
-            ab * angle_b_rad.sin(),
-        );
-
-        let angle_c_rad = (180.0 - angle_c) * PI / 180.0;
-        self.vertices[3] = Point::new(
-            bc + cd * angle_c_rad.cos(),
-            cd * angle_c_rad.sin(),
-        );
-
-        let calculated_da_um = distance_um(&self.vertices[3], &self.vertices[0]);
-        if let Some(input_da_um) = self.side_da_um {
-            self.validate_length_um("DA", calculated_da_um, input_da_um)?;
-        } else {
-            self.side_da_um = Some(calculated_da_um);
+        // In der gedrehten Beschriftung (fehlende Seite = AB) zuerst C+D, dann B+C, D+A, A+B
+        if sides.iter().filter(|&&given| given).count() == 3 {
+            let missing_side = sides.iter().position(|&given| !given).unwrap_or(0);
+            if let Some(vertex) = [2, 1, 3, 0].map(|i| (i + missing_side) % 4).into_iter().find(|&vertex| adjacent(vertex)) {
+                return Ok(ConstructionMethod::ThreeSides { missing_side, vertex });
+            }
         }
 
-        self.calculate_angles_from_vertices();
-        Ok(())
+        Err(GeometryError::UnsupportedCombination)
     }
 
-    pub(crate) fn construct_from_cd_da_bc_angles_c_d(&mut self) -> Result<(), String> {
-        let cd = self.side_cd_um.unwrap() as f64;
-        let da = self.side_da_um.unwrap() as f64;
-        let bc = self.side_bc_um.unwrap() as f64;
-        let angle_c = self.angle_c.unwrap();
-        let angle_d = self.angle_d.unwrap();
-
-        self.vertices[2] = Point::new(0.0, 0.0);
-        self.vertices[3] = Point::new(cd, 0.0);
-
-        let angle_c_rad = angle_c * PI / 180.0;
-        self.vertices[1] = Point::new(
-            -bc * angle_c_rad.cos(),
-            bc * angle_c_rad.sin(),
-        );
-
-        let angle_d_rad = (180.0 - angle_d) * PI / 180.0;
-        self.vertices[0] = Point::new(
-            cd + da * angle_d_rad.cos(),
-            da * angle_d_rad.sin(),
-        );
-
-        let calculated_ab_um = distance_um(&self.vertices[0], &self.vertices[1]);
-        if let Some(input_ab_um) = self.side_ab_um {
-            self.validate_length_um("AB", calculated_ab_um, input_ab_um)?;
-        } else {
-            self.side_ab_um = Some(calculated_ab_um);
-        }
-
-        self.calculate_angles_from_vertices();
-        Ok(())
+    /// Konstruiert das Viereck auf dem gewählten Weg
+    pub(crate) fn construct_quadrilateral(&mut self) -> Result<ConstructionMethod, GeometryError> {
+        let method = self.select_construction()?;
+        match method {
+            ConstructionMethod::AdjacentAngles { vertex: 0 } => self.construct_from_all_sides_angles_a_b(),
+            ConstructionMethod::AdjacentAngles { vertex: 1 } => self.construct_from_all_sides_angles_b_c(),
+            ConstructionMethod::AdjacentAngles { vertex: 2 } => self.construct_from_all_sides_angles_c_d(),
+            ConstructionMethod::AdjacentAngles { .. } => self.construct_from_all_sides_angles_d_a(),
+            ConstructionMethod::SingleAngle { vertex: 0 } => self.construct_from_all_sides_angle_a(),
+            ConstructionMethod::SingleAngle { vertex: 1 } => self.construct_from_all_sides_angle_b(),
+            ConstructionMethod::SingleAngle { vertex: 2 } => self.construct_from_all_sides_angle_c(),
+            ConstructionMethod::SingleAngle { .. } => self.construct_from_all_sides_angle_d(),
+            ConstructionMethod::Diagonal { diagonal } => self.construct_from_all_sides_diagonal(diagonal),
+            ConstructionMethod::ThreeSides { missing_side, vertex } => self.construct_from_three_sides(missing_side, vertex),
+        }?;
+        Ok(method)
     }
 
-    pub(crate) fn construct_from_da_ab_cd_angles_d_a(&mut self) -> Result<(), String> {
-        let da = self.side_da_um.unwrap() as f64;
-        let ab = self.side_ab_um.unwrap() as f64;
-        let cd = self.side_cd_um.unwrap() as f64;
-        let angle_d = self.angle_d.unwrap();
-        let angle_a = self.angle_a.unwrap();
-
-        self.vertices[0] = Point::new(0.0, 0.0);
-        self.vertices[1] = Point::new(ab, 0.0);
-
-        let angle_a_rad = angle_a * PI / 180.0;
-        self.vertices[3] = Point::new(
-            da * angle_a_rad.cos(),
-            da * angle_a_rad.sin(),
-        );
-
-        let target_angle_d_rad = (180.0 - angle_d) * PI / 180.0;
-        self.vertices[2] = Point::new(
-            self.vertices[3].x - cd * target_angle_d_rad.cos(),
-            self.vertices[3].y - cd * target_angle_d_rad.sin(),
-        );
-
-        let calculated_bc_um = distance_um(&self.vertices[1], &self.vertices[2]);
-        if let Some(input_bc_um) = self.side_bc_um {
-            self.validate_length_um("BC", calculated_bc_um, input_bc_um)?;
-        } else {
-            self.side_bc_um = Some(calculated_bc_um);
+    // === Konstruktionsmethoden: 3 Seiten + 2 Winkel ===
+    // Die Beschriftung wird gedreht, bis die fehlende Seite AB ist. Dann bleiben vier Lagen
+    // für das Winkelpaar: C+D (direkt), B+C, D+A und A+B (Strahl schneidet Kreis)
+
+    /// `missing` = fehlende Seite, `vertex` = erste Ecke des Winkelpaars (Indizes im Viereck)
+    pub(crate) fn construct_from_three_sides(&mut self, missing: usize, vertex: usize) -> Result<(), GeometryError> {
+        let sides = [self.side_ab_um, self.side_bc_um, self.side_cd_um, self.side_da_um];
+        let angles = [self.angle_a, self.angle_b, self.angle_c, self.angle_d];
+
+        // Index in der gedrehten Beschriftung → Index im Viereck
+        let rotated = |i: usize| (i + missing) % 4;
+        let side = |i: usize| sides[rotated(i)].map_or(0.0, |um| um as f64);
+        let angle = |i: usize| angles[rotated(i)].ok_or(GeometryError::UnsupportedCombination);
+        let (bc, cd, da) = (side(1), side(2), side(3));
+
+        let candidates = match (vertex + 4 - missing) % 4 {
+            2 => vec![three_sides_angles_c_d(bc, cd, da, angle(2)?, angle(3)?)],
+            1 => three_sides_angles_b_c(bc, cd, da, angle(1)?, angle(2)?),
+            3 => three_sides_angles_d_a(bc, cd, da, angle(3)?, angle(0)?),
+            _ => three_sides_angles_a_b(bc, cd, da, angle(0)?, angle(1)?),
+        };
+        let vertices = candidates
+            .iter()
+            .find(|v| is_convex_ccw(v))
+            .or(candidates.first())
+            .ok_or(GeometryError::CirclesDontIntersect)?;
+
+        for (i, vertex) in vertices.iter().enumerate() {
+            self.vertices[rotated(i)] = vertex.clone();
         }
-
-        self.calculate_angles_from_vertices();
-        Ok(())
-    }
-
-    pub(crate) fn construct_from_bc_cd_da_angles_b_c(&mut self) -> Result<(), String> {
-        let bc = self.side_bc_um.unwrap() as f64;
-        let cd = self.side_cd_um.unwrap() as f64;
-        let da = self.side_da_um.unwrap() as f64;
-        let angle_b = self.angle_b.unwrap();
-        let angle_c = self.angle_c.unwrap();
-
-        self.vertices[1] = Point::new(0.0, 0.0);
-        self.vertices[2] = Point::new(bc, 0.0);
-
-        let angle_c_rad = (180.0 - angle_c) * PI / 180.0;
-        self.vertices[3] = Point::new(
-            bc + cd * angle_c_rad.cos(),
-            cd * angle_c_rad.sin(),
-        );
-
-        let angle_b_rad = angle_b * PI / 180.0;
-        self.vertices[0] = Point::new(
-            -da * (180.0_f64.to_radians() - angle_b_rad).cos(),
-            -da * (180.0_f64.to_radians() - angle_b_rad).sin(),
-        );
-
-        let calculated_ab_um = distance_um(&self.vertices[0], &self.vertices[1]);
-        if let Some(input_ab_um) = self.side_ab_um {
-            self.validate_length_um("AB", calculated_ab_um, input_ab_um)?;
-        } else {
-            self.side_ab_um = Some(calculated_ab_um);
+        let calculated_um = distance_um(&self.vertices[missing], &self.vertices[(missing + 1) % 4]);
+        match missing {
+            0 => self.side_ab_um = Some(calculated_um),
+            1 => self.side_bc_um = Some(calculated_um),
+            2 => self.side_cd_um = Some(calculated_um),
+            _ => self.side_da_um = Some(calculated_um),
         }
 
         self.calculate_angles_from_vertices();
@@ -240,11 +101,11 @@ impl Quadrilateral {
     }
 
     // === Alle 4 Seiten + 2 Winkel ===
+    // Die gegenüberliegende Seite ist überzählig und wird in `calculate` gegen die Messung geprüft
 
-    pub(crate) fn construct_from_all_sides_angles_a_b(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angles_a_b(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
         let bc = self.side_bc_um.unwrap() as f64;
-        let cd = self.side_cd_um.unwrap() as f64;
         let da = self.side_da_um.unwrap() as f64;
         let angle_a = self.angle_a.unwrap();
         let angle_b = self.angle_b.unwrap();
@@ -264,18 +125,14 @@ impl Quadrilateral {
             bc * angle_b_rad.sin(),
         );
 
-        let calculated_cd_um = distance_um(&self.vertices[2], &self.vertices[3]);
-        self.validate_length_um("CD", calculated_cd_um, self.side_cd_um.unwrap())?;
-
         self.calculate_angles_from_vertices();
         Ok(())
     }
 
-    pub(crate) fn construct_from_all_sides_angles_b_c(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angles_b_c(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
         let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
-        let da = self.side_da_um.unwrap() as f64;
         let angle_b = self.angle_b.unwrap();
         let angle_c = self.angle_c.unwrap();
 
@@ -284,7 +141,7 @@ impl Quadrilateral {
 
         let angle_b_rad = angle_b * PI / 180.0;
         self.vertices[0] = Point::new(
-            -ab * angle_b_rad.cos(),
+            ab * angle_b_rad.cos(),
             ab * angle_b_rad.sin(),
         );
 
@@ -294,15 +151,11 @@ impl Quadrilateral {
             cd * angle_c_rad.sin(),
         );
 
-        let calculated_da_um = distance_um(&self.vertices[3], &self.vertices[0]);
-        self.validate_length_um("DA", calculated_da_um, da as i64)?;
-
         self.calculate_angles_from_vertices();
         Ok(())
     }
 
-    pub(crate) fn construct_from_all_sides_angles_c_d(&mut self) -> Result<(), String> {
-        let ab = self.side_ab_um.unwrap() as f64;
+    pub(crate) fn construct_from_all_sides_angles_c_d(&mut self) -> Result<(), GeometryError> {
         let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
         let da = self.side_da_um.unwrap() as f64;
@@ -314,7 +167,7 @@ impl Quadrilateral {
 
         let angle_c_rad = angle_c * PI / 180.0;
         self.vertices[1] = Point::new(
-            -bc * angle_c_rad.cos(),
+            bc * angle_c_rad.cos(),
             bc * angle_c_rad.sin(),
         );
 
@@ -324,16 +177,12 @@ impl Quadrilateral {
             da * angle_d_rad.sin(),
         );
 
-        let calculated_ab_um = distance_um(&self.vertices[0], &self.vertices[1]);
-        self.validate_length_um("AB", calculated_ab_um, ab as i64)?;
-
         self.calculate_angles_from_vertices();
         Ok(())
     }
 
-    pub(crate) fn construct_from_all_sides_angles_d_a(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angles_d_a(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
-        let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
         let da = self.side_da_um.unwrap() as f64;
         let angle_d = self.angle_d.unwrap();
@@ -344,7 +193,7 @@ impl Quadrilateral {
 
         let angle_d_rad = angle_d * PI / 180.0;
         self.vertices[2] = Point::new(
-            -cd * angle_d_rad.cos(),
+            cd * angle_d_rad.cos(),
             cd * angle_d_rad.sin(),
         );
 
@@ -354,8 +203,26 @@ impl Quadrilateral {
             ab * angle_a_rad.sin(),
         );
 
-        let calculated_bc_um = distance_um(&self.vertices[1], &self.vertices[2]);
-        self.validate_length_um("BC", calculated_bc_um, bc as i64)?;
+        self.calculate_angles_from_vertices();
+        Ok(())
+    }
+
+    // === Alle 4 Seiten + 1 Diagonale (5-Maße-Methode, zwei Dreiecke SSS) ===
+
+    /// Diagonale 0 = AC, 1 = BD; für BD wird die Beschriftung um eine Ecke gedreht
+    pub(crate) fn construct_from_all_sides_diagonal(&mut self, diagonal: usize) -> Result<(), GeometryError> {
+        let sides = [self.side_ab_um, self.side_bc_um, self.side_cd_um, self.side_da_um];
+        let length_um = [self.diagonal_ac_um, self.diagonal_bd_um][diagonal].map_or(0.0, |um| um as f64);
+        let rotated = |i: usize| (i + diagonal) % 4;
+        let side = |i: usize| sides[rotated(i)].map_or(0.0, |um| um as f64);
+
+        // Diagonale auf der x-Achse, B rechts davon (unten), D links davon (oben)
+        let b = triangle_apex(length_um, side(0), side(1), false)?;
+        let d = triangle_apex(length_um, side(3), side(2), true)?;
+        let vertices = [Point::new(0.0, 0.0), b, Point::new(length_um, 0.0), d];
+        for (i, vertex) in vertices.into_iter().enumerate() {
+            self.vertices[rotated(i)] = vertex;
+        }
 
         self.calculate_angles_from_vertices();
         Ok(())
@@ -363,7 +230,7 @@ impl Quadrilateral {
 
     // === Alle 4 Seiten + 1 Winkel (Kreis-Schnitt-Methode) ===
 
-    pub(crate) fn construct_from_all_sides_angle_a(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angle_a(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
         let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
@@ -379,14 +246,14 @@ impl Quadrilateral {
             da * angle_a_rad.sin(),
         );
 
-        let c_point = find_circle_intersection(&self.vertices[1], bc, &self.vertices[3], cd)?;
+        let c_point = find_circle_intersection(&self.vertices[1], bc, &self.vertices[3], cd, &self.vertices[0])?;
         self.vertices[2] = c_point;
 
         self.calculate_angles_from_vertices();
         Ok(())
     }
 
-    pub(crate) fn construct_from_all_sides_angle_b(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angle_b(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
         let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
@@ -402,14 +269,14 @@ impl Quadrilateral {
             bc * angle_b_rad.sin(),
         );
 
-        let d_point = find_circle_intersection(&self.vertices[0], da, &self.vertices[2], cd)?;
+        let d_point = find_circle_intersection(&self.vertices[0], da, &self.vertices[2], cd, &self.vertices[1])?;
         self.vertices[3] = d_point;
 
         self.calculate_angles_from_vertices();
         Ok(())
     }
 
-    pub(crate) fn construct_from_all_sides_angle_c(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angle_c(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
         let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
@@ -425,14 +292,14 @@ impl Quadrilateral {
             cd * angle_c_rad.sin(),
         );
 
-        let a_point = find_circle_intersection(&self.vertices[1], ab, &self.vertices[3], da)?;
+        let a_point = find_circle_intersection(&self.vertices[1], ab, &self.vertices[3], da, &self.vertices[2])?;
         self.vertices[0] = a_point;
 
         self.calculate_angles_from_vertices();
         Ok(())
     }
 
-    pub(crate) fn construct_from_all_sides_angle_d(&mut self) -> Result<(), String> {
+    pub(crate) fn construct_from_all_sides_angle_d(&mut self) -> Result<(), GeometryError> {
         let ab = self.side_ab_um.unwrap() as f64;
         let bc = self.side_bc_um.unwrap() as f64;
         let cd = self.side_cd_um.unwrap() as f64;
@@ -448,10 +315,82 @@ impl Quadrilateral {
             da * angle_d_rad.sin(),
         );
 
-        let b_point = find_circle_intersection(&self.vertices[0], ab, &self.vertices[2], bc)?;
+        let b_point = find_circle_intersection(&self.vertices[0], ab, &self.vertices[2], bc, &self.vertices[3])?;
         self.vertices[1] = b_point;
 
         self.calculate_angles_from_vertices();
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+// === Normierte Fälle für 3 Seiten + 2 Winkel (AB fehlt, Eckpunkte A, B, C, D) ===
+// Vorgänger einer Ecke liegt gegen den Uhrzeigersinn um den Innenwinkel gedreht
+
+fn three_sides_angles_c_d(bc: f64, cd: f64, da: f64, angle_c: f64, angle_d: f64) -> [Point; 4] {
+    let c = Point::new(0.0, 0.0);
+    let d = Point::new(cd, 0.0);
+    let b = Point::new(bc * angle_c.to_radians().cos(), bc * angle_c.to_radians().sin());
+    let direction_da = (180.0 - angle_d).to_radians();
+    let a = Point::new(cd + da * direction_da.cos(), da * direction_da.sin());
+    [a, b, c, d]
+}
+
+/// A liegt auf dem Strahl von B im Winkel B, im Abstand DA von D
+fn three_sides_angles_b_c(bc: f64, cd: f64, da: f64, angle_b: f64, angle_c: f64) -> Vec<[Point; 4]> {
+    let b = Point::new(0.0, 0.0);
+    let c = Point::new(bc, 0.0);
+    let direction_cd = (180.0 - angle_c).to_radians();
+    let d = Point::new(bc + cd * direction_cd.cos(), cd * direction_cd.sin());
+    ray_circle_intersections(&b, angle_b.to_radians(), &d, da)
+        .into_iter()
+        .map(|a| [a, b.clone(), c.clone(), d.clone()])
+        .collect()
+}
+
+/// B liegt auf dem Strahl von A (Richtung A→D um den Winkel A zurückgedreht), im Abstand BC von C
+fn three_sides_angles_d_a(bc: f64, cd: f64, da: f64, angle_d: f64, angle_a: f64) -> Vec<[Point; 4]> {
+    let c = Point::new(0.0, 0.0);
+    let d = Point::new(cd, 0.0);
+    let direction_da = (180.0 - angle_d).to_radians();
+    let a = Point::new(cd + da * direction_da.cos(), da * direction_da.sin());
+    let direction_ab = -(angle_d + angle_a).to_radians();
+    ray_circle_intersections(&a, direction_ab, &c, bc)
+        .into_iter()
+        .map(|b| [a.clone(), b, c.clone(), d.clone()])
+        .collect()
+}
+
+/// AB liegt auf der x-Achse mit unbekannter Länge; C = B + v hat den Abstand CD von D,
+/// also liegt B im Abstand CD von D − v
+fn three_sides_angles_a_b(bc: f64, cd: f64, da: f64, angle_a: f64, angle_b: f64) -> Vec<[Point; 4]> {
+    let a = Point::new(0.0, 0.0);
+    let d = Point::new(da * angle_a.to_radians().cos(), da * angle_a.to_radians().sin());
+    let direction_bc = (180.0 - angle_b).to_radians();
+    let (vx, vy) = (bc * direction_bc.cos(), bc * direction_bc.sin());
+    ray_circle_intersections(&a, 0.0, &Point::new(d.x - vx, d.y - vy), cd)
+        .into_iter()
+        .map(|b| {
+            let c = Point::new(b.x + vx, b.y + vy);
+            [a.clone(), b, c, d.clone()]
+        })
+        .collect()
+}
+
+/// Spitze eines Dreiecks über der Grundseite von (0, 0) nach (base, 0) aus den Abständen zu
+/// beiden Enden; `above` wählt die Seite oberhalb der Grundseite
+fn triangle_apex(base: f64, to_start: f64, to_end: f64, above: bool) -> Result<Point, GeometryError> {
+    if base <= 0.0 || to_start + to_end < base || (to_start - to_end).abs() > base {
+        return Err(GeometryError::CirclesDontIntersect);
+    }
+    let x = (to_start * to_start - to_end * to_end + base * base) / (2.0 * base);
+    let y = (to_start * to_start - x * x).max(0.0).sqrt();
+    Ok(Point::new(x, if above { y } else { -y }))
+}
+
+/// Alle Ecken links abbiegend (gegen den Uhrzeigersinn konvex)
+fn is_convex_ccw(vertices: &[Point; 4]) -> bool {
+    (0..4).all(|i| {
+        let (a, b, c) = (&vertices[i], &vertices[(i + 1) % 4], &vertices[(i + 2) % 4]);
+        (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x) > 0.0
+    })
+}
@@ -0,0 +1,336 @@
+// Fehler bei der Berechnung des Vierecks
+// Strukturiert, damit Oberfläche und Bibliotheksnutzer den Fall unterscheiden können; der Text
+// entsteht erst bei der Anzeige in der eingestellten Sprache
+
+use crate::config::{Language, VertexLabels};
+use crate::format::NumberFormat;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeometryError {
+    /// Weder 4 Seiten + 1 Winkel noch 3 Seiten + 2 benachbarte Winkel noch 4 Seiten + 1 Diagonale
+    MissingInput { sides: usize, angles: usize },
+    /// Zulässige Anzahl, aber keine passende Konstruktion (z.B. Winkel nicht an der fehlenden Seite)
+    UnsupportedCombination,
+    /// Alle 4 Winkel gegeben, Summe weicht von 360° ab
+    AngleSumInvalid { sum_deg: f64 },
+    /// 3 Winkel gegeben, der vierte läge nicht zwischen 0° und 360°
+    MissingAngleOutOfRange { sum_deg: f64, missing_deg: f64 },
+    /// Berechnete Seite (0=AB … 3=DA) passt nicht zur gemessenen (Längen in µm)
+    SideMismatch { side: usize, expected: i64, actual: i64 },
+    /// Gemessene Diagonale (0=AC, 1=BD) passt nicht zur Konstruktion (Längen in µm)
+    DiagonalMismatch { diagonal: usize, expected: i64, actual: i64 },
+    /// Die Kreise um zwei Eckpunkte schneiden sich nicht
+    CirclesDontIntersect,
+    /// Zwei Eckpunkte fallen zusammen (0=AB … 3=DA)
+    ZeroLengthSide { side: usize, length_um: i64 },
+    /// Drei aufeinanderfolgende Eckpunkte liegen nahezu auf einer Geraden
+    CollinearVertices { vertex: usize, angle_deg: f64 },
+    /// Fläche verschwindend klein im Verhältnis zur längsten Seite
+    ZeroArea { area_um2: f64 },
+}
+
+impl GeometryError {
+    /// Meldung mit Hinweis zur Abhilfe für den Fehlerdialog, Zahlen und Namen wie in der Anzeige
+    pub fn message(&self, language: Language, number: &NumberFormat, labels: &VertexLabels) -> String {
+        match language {
+            Language::German => self.message_de(number, labels),
+            Language::English => self.message_en(number, labels),
+        }
+    }
+
+    /// Kurzer, sprachunabhängiger Name des Fehlerfalls (z.B. für die HTTP-Schnittstelle)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GeometryError::MissingInput { .. } => "missing_input",
+            GeometryError::UnsupportedCombination => "unsupported_combination",
+            GeometryError::AngleSumInvalid { .. } => "angle_sum_invalid",
+            GeometryError::MissingAngleOutOfRange { .. } => "missing_angle_out_of_range",
+            GeometryError::SideMismatch { .. } => "side_mismatch",
+            GeometryError::DiagonalMismatch { .. } => "diagonal_mismatch",
+            GeometryError::CirclesDontIntersect => "circles_dont_intersect",
+            GeometryError::ZeroLengthSide { .. } => "zero_length_side",
+            GeometryError::CollinearVertices { .. } => "collinear_vertices",
+            GeometryError::ZeroArea { .. } => "zero_area",
+        }
+    }
+
+    fn message_de(&self, number: &NumberFormat, labels: &VertexLabels) -> String {
+        let mm = |um: i64| format!("{} mm", number.number(um as f64 / 1000.0));
+        match *self {
+            GeometryError::MissingInput { sides, angles } => format!(
+                "❌ Nicht genug Informationen für eindeutige Lösung!\n\n\
+                Gegeben: {} Seiten, {} Winkel\n\n\
+                Benötigt wird EINE der folgenden Kombinationen:\n\
+                • 4 Seiten + mindestens 1 Winkel\n\
+                • 3 Seiten + 2 benachbarte Winkel (z.B. {}+{} oder {}+{})\n\
+                • 4 Seiten + 1 Diagonale (ohne Winkel)\n\n\
+                Tipp: Messen Sie einen weiteren Wert!",
+                sides,
+                angles,
+                labels.vertex(0),
+                labels.vertex(1),
+                labels.vertex(1),
+                labels.vertex(2)
+            ),
+            GeometryError::UnsupportedCombination => "❌ Diese Kombination kann noch nicht berechnet werden.\n\n\
+                Bitte stellen Sie sicher, dass:\n\
+                • Alle 4 Seiten + mind. 1 Winkel ODER\n\
+                • 3 Seiten + 2 benachbarte Winkel\n\
+                gegeben sind."
+                .to_string(),
+            GeometryError::AngleSumInvalid { sum_deg } => format!(
+                "❌ Fehler: Winkelsumme muss 360° sein!\n\
+                Ihre Summe: {} (Differenz: {})",
+                number.angle(sum_deg),
+                number.angle(sum_deg - 360.0)
+            ),
+            GeometryError::MissingAngleOutOfRange { sum_deg, missing_deg } => format!(
+                "❌ Fehler: Die 3 Winkel summieren sich auf {}!\n\
+                Der 4. Winkel müsste {} sein (ungültig).",
+                number.angle(sum_deg),
+                number.angle(missing_deg)
+            ),
+            GeometryError::SideMismatch { side, expected, actual } => {
+                let (name, diff_um) = (labels.side(side), (actual - expected).abs());
+                format!(
+                    "⚠️ WARNUNG: Seite {} passt nicht!\n\n\
+                    • Seite {} (berechnet): {}\n\
+                    • Seite {} (vorgegeben): {}\n\
+                    • Abweichung: {} ({}%)\n\n\
+                    Das Viereck kann so nicht gebaut werden!\n\
+                    Bitte überprüfen Sie die Messungen.",
+                    name,
+                    name,
+                    mm(actual),
+                    name,
+                    mm(expected),
+                    mm(diff_um),
+                    number.number(diff_um as f64 / expected as f64 * 100.0)
+                )
+            }
+            GeometryError::DiagonalMismatch { diagonal, expected, actual } => {
+                let name = labels.diagonal(diagonal);
+                format!(
+                    "⚠️ WARNUNG: Diagonale {} passt nicht!\n\n\
+                    • Diagonale {} (berechnet): {}\n\
+                    • Diagonale {} (gemessen): {}\n\n\
+                    Die Maße widersprechen sich – bitte Seiten und Diagonalen nachmessen.",
+                    name,
+                    name,
+                    mm(actual),
+                    name,
+                    mm(expected)
+                )
+            }
+            GeometryError::CirclesDontIntersect => "❌ Geometrischer Konflikt: Die Kreise schneiden sich nicht!\n\
+                Die angegebenen Seitenlängen passen nicht zusammen."
+                .to_string(),
+            GeometryError::ZeroLengthSide { side, length_um } => format!(
+                "❌ Entartetes Viereck: Seite {} ist praktisch null lang ({}).\n\n\
+                Die Eckpunkte {} und {} fallen zusammen.\n\
+                Bitte prüfen Sie die Winkel an diesen Ecken.",
+                labels.side(side),
+                mm(length_um),
+                labels.vertex(side),
+                labels.vertex((side + 1) % 4)
+            ),
+            GeometryError::CollinearVertices { vertex, angle_deg } => format!(
+                "❌ Entartetes Viereck: Der Winkel bei {} beträgt {}.\n\n\
+                Die Eckpunkte {}, {} und {} liegen nahezu auf einer Geraden,\n\
+                die Figur ist damit ein Dreieck oder eine Linie.\n\
+                Bitte prüfen Sie die Winkelmessungen (keiner nahe 0° oder 180°).",
+                labels.vertex(vertex),
+                number.angle(angle_deg),
+                labels.vertex((vertex + 3) % 4),
+                labels.vertex(vertex),
+                labels.vertex((vertex + 1) % 4)
+            ),
+            GeometryError::ZeroArea { area_um2 } => format!(
+                "❌ Entartetes Viereck: Die Fläche ist nahezu null ({} m²).\n\n\
+                Die Seiten falten sich aufeinander, es entsteht nur ein schmaler Streifen.\n\
+                Bitte prüfen Sie Winkel und Seitenlängen.",
+                number.number(area_um2 / 1e12)
+            ),
+        }
+    }
+
+    fn message_en(&self, number: &NumberFormat, labels: &VertexLabels) -> String {
+        let mm = |um: i64| format!("{} mm", number.number(um as f64 / 1000.0));
+        match *self {
+            GeometryError::MissingInput { sides, angles } => format!(
+                "❌ Not enough information for a unique solution!\n\n\
+                Given: {} sides, {} angles\n\n\
+                ONE of the following combinations is required:\n\
+                • 4 sides + at least 1 angle\n\
+                • 3 sides + 2 adjacent angles (e.g. {}+{} or {}+{})\n\
+                • 4 sides + 1 diagonal (no angles)\n\n\
+                Tip: measure one more value!",
+                sides,
+                angles,
+                labels.vertex(0),
+                labels.vertex(1),
+                labels.vertex(1),
+                labels.vertex(2)
+            ),
+            GeometryError::UnsupportedCombination => "❌ This combination cannot be calculated yet.\n\n\
+                Please make sure that either:\n\
+                • all 4 sides + at least 1 angle OR\n\
+                • 3 sides + 2 adjacent angles\n\
+                are given."
+                .to_string(),
+            GeometryError::AngleSumInvalid { sum_deg } => format!(
+                "❌ Error: the angles must add up to 360°!\n\
+                Your sum: {} (difference: {})",
+                number.angle(sum_deg),
+                number.angle(sum_deg - 360.0)
+            ),
+            GeometryError::MissingAngleOutOfRange { sum_deg, missing_deg } => format!(
+                "❌ Error: the 3 angles add up to {}!\n\
+                The 4th angle would have to be {} (invalid).",
+                number.angle(sum_deg),
+                number.angle(missing_deg)
+            ),
+            GeometryError::SideMismatch { side, expected, actual } => {
+                let (name, diff_um) = (labels.side(side), (actual - expected).abs());
+                format!(
+                    "⚠️ WARNING: side {} does not fit!\n\n\
+                    • Side {} (calculated): {}\n\
+                    • Side {} (given): {}\n\
+                    • Deviation: {} ({}%)\n\n\
+                    The quadrilateral cannot be built like this!\n\
+                    Please check the measurements.",
+                    name,
+                    name,
+                    mm(actual),
+                    name,
+                    mm(expected),
+                    mm(diff_um),
+                    number.number(diff_um as f64 / expected as f64 * 100.0)
+                )
+            }
+            GeometryError::DiagonalMismatch { diagonal, expected, actual } => {
+                let name = labels.diagonal(diagonal);
+                format!(
+                    "⚠️ WARNING: diagonal {} does not fit!\n\n\
+                    • Diagonal {} (calculated): {}\n\
+                    • Diagonal {} (measured): {}\n\n\
+                    The measurements contradict each other – please re-measure sides and diagonals.",
+                    name,
+                    name,
+                    mm(actual),
+                    name,
+                    mm(expected)
+                )
+            }
+            GeometryError::CirclesDontIntersect => "❌ Geometric conflict: the circles do not intersect!\n\
+                The given side lengths do not fit together."
+                .to_string(),
+            GeometryError::ZeroLengthSide { side, length_um } => format!(
+                "❌ Degenerate quadrilateral: side {} is practically zero ({}).\n\n\
+                Vertices {} and {} coincide.\n\
+                Please check the angles at these corners.",
+                labels.side(side),
+                mm(length_um),
+                labels.vertex(side),
+                labels.vertex((side + 1) % 4)
+            ),
+            GeometryError::CollinearVertices { vertex, angle_deg } => format!(
+                "❌ Degenerate quadrilateral: the angle at {} is {}.\n\n\
+                Vertices {}, {} and {} lie almost on a straight line,\n\
+                so the shape is a triangle or a line.\n\
+                Please check the angle measurements (none close to 0° or 180°).",
+                labels.vertex(vertex),
+                number.angle(angle_deg),
+                labels.vertex((vertex + 3) % 4),
+                labels.vertex(vertex),
+                labels.vertex((vertex + 1) % 4)
+            ),
+            GeometryError::ZeroArea { area_um2 } => format!(
+                "❌ Degenerate quadrilateral: the area is almost zero ({} m²).\n\n\
+                The sides fold onto each other, leaving only a thin strip.\n\
+                Please check angles and side lengths.",
+                number.number(area_um2 / 1e12)
+            ),
+        }
+    }
+}
+
+/// Kleiner Widerspruch im Warnbereich: das Viereck wird trotzdem gezeichnet (Längen in µm)
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlausibilityWarning {
+    /// Überzählige Seite (0=AB … 3=DA) weicht von der Konstruktion ab
+    SideDeviation { side: usize, expected_um: i64, actual_um: i64 },
+    /// Gemessene Diagonale (0=AC, 1=BD) weicht von der Konstruktion ab
+    DiagonalDeviation { diagonal: usize, expected_um: i64, actual_um: i64 },
+    /// Winkelsumme weicht von 360° ab, der Fehlbetrag wurde auf alle Winkel verteilt
+    AngleSum { sum_deg: f64 },
+}
+
+impl PlausibilityWarning {
+    /// Einzeilige Meldung für das Warnband
+    pub fn message(&self, language: Language, number: &NumberFormat, labels: &VertexLabels) -> String {
+        let german = language == Language::German;
+        let mm = |um: i64| format!("{} mm", number.number(um as f64 / 1000.0));
+        match *self {
+            PlausibilityWarning::SideDeviation { side, expected_um, actual_um } => {
+                let (name, diff) = (labels.side(side), number.signed((actual_um - expected_um) as f64 / 1000.0));
+                if german {
+                    format!("Seite {} weicht um {} mm vom Maß ab (gemessen {}, konstruiert {})", name, diff, mm(expected_um), mm(actual_um))
+                } else {
+                    format!("Side {} deviates by {} mm (measured {}, constructed {})", name, diff, mm(expected_um), mm(actual_um))
+                }
+            }
+            PlausibilityWarning::DiagonalDeviation { diagonal, expected_um, actual_um } => {
+                let (name, diff) = (labels.diagonal(diagonal), number.signed((actual_um - expected_um) as f64 / 1000.0));
+                if german {
+                    format!("Diagonale {} weicht um {} mm vom Maß ab (gemessen {}, konstruiert {})", name, diff, mm(expected_um), mm(actual_um))
+                } else {
+                    format!("Diagonal {} deviates by {} mm (measured {}, constructed {})", name, diff, mm(expected_um), mm(actual_um))
+                }
+            }
+            PlausibilityWarning::AngleSum { sum_deg } => {
+                let (sum, share) = (number.angle(sum_deg), number.signed((360.0 - sum_deg) / 4.0));
+                if german {
+                    format!("Winkelsumme {} statt 360°, je {}° auf alle Winkel verteilt", sum, share)
+                } else {
+                    format!("Angle sum {} instead of 360°, {}° applied to each angle", sum, share)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for GeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Language::German, &NumberFormat::default(), &VertexLabels::default()))
+    }
+}
+
+impl std::error::Error for GeometryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DecimalSeparator;
+
+    #[test]
+    fn test_messages() {
+        let error = GeometryError::SideMismatch { side: 2, expected: 4_000_000, actual: 4_100_000 };
+        assert!(error.to_string().contains("Seite CD passt nicht"));
+        assert!(error.to_string().contains("Abweichung: 100,000 mm (2,500%)"));
+
+        let english = NumberFormat { decimal_separator: DecimalSeparator::Point, ..NumberFormat::new(2) };
+        let mut labels = VertexLabels::default();
+        labels.names[2] = "Tür".to_string();
+        let message = error.message(Language::English, &english, &labels);
+        assert!(message.contains("side Tür–D does not fit"));
+        assert!(message.contains("(2.50%)"));
+
+        let warning = PlausibilityWarning::DiagonalDeviation { diagonal: 0, expected_um: 5_000_000, actual_um: 5_002_500 };
+        assert_eq!(
+            warning.message(Language::German, &NumberFormat::new(1), &labels),
+            "Diagonale A–Tür weicht um +2,5 mm vom Maß ab (gemessen 5000,0 mm, konstruiert 5002,5 mm)"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// Konstruktionsverlauf: Grundviereck und eingezeichnete Linien als Baum ihrer Abhängigkeiten
+// Linien speichern nur ihre Anker (Seite oder Linie + Verhältnis, Eckpunkt), daher lässt sich
+// der Verlauf mit geänderten Maßen erneut abspielen (`update_all_lines`)
+
+use super::types::{CustomLine, LineAnchor};
+
+/// Eintrag im Verlaufsbaum
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HistoryNode {
+    pub line: usize,
+    pub depth: usize, // 1 = hängt nur am Grundviereck, sonst eine Ebene unter der übergeordneten Linie
+}
+
+/// Übergeordnete Linie: die zuletzt eingezeichnete Linie, an der ein Ende hängt
+pub fn parent_line(line: &CustomLine) -> Option<usize> {
+    [line.start_anchor, line.end_anchor]
+        .iter()
+        .filter_map(|anchor| match anchor {
+            LineAnchor::Line { line, .. } => Some(*line),
+            _ => None,
+        })
+        .max()
+}
+
+/// Linien in Baumreihenfolge (Tiefensuche, Geschwister in Konstruktionsreihenfolge)
+pub fn construction_tree(lines: &[CustomLine]) -> Vec<HistoryNode> {
+    let mut children = vec![Vec::new(); lines.len()];
+    let mut roots = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        match parent_line(line).filter(|&parent| parent < idx) {
+            Some(parent) => children[parent].push(idx),
+            None => roots.push(idx),
+        }
+    }
+
+    let mut nodes = Vec::with_capacity(lines.len());
+    let mut stack: Vec<HistoryNode> = roots.iter().rev().map(|&line| HistoryNode { line, depth: 1 }).collect();
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        for &child in children[node.line].iter().rev() {
+            stack.push(HistoryNode { line: child, depth: node.depth + 1 });
+        }
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{solve, QuadInput};
+
+    #[test]
+    fn test_construction_tree() {
+        let quad = solve(&QuadInput::rectangle(1000.0, 1000.0)).unwrap().quad;
+        let mut lines = Vec::new();
+        let anchors = [
+            (LineAnchor::Side { side: 0, ratio: 0.5 }, LineAnchor::Side { side: 2, ratio: 0.5 }),
+            (LineAnchor::Vertex(0), LineAnchor::Vertex(2)),
+            (LineAnchor::Line { line: 0, ratio: 0.3 }, LineAnchor::Side { side: 1, ratio: 0.5 }),
+            (LineAnchor::Line { line: 1, ratio: 0.5 }, LineAnchor::Line { line: 2, ratio: 0.5 }),
+        ];
+        for (idx, (start, end)) in anchors.into_iter().enumerate() {
+            let line = CustomLine::new(format!("Linie {}", idx + 1), start, end, &quad, &lines);
+            lines.push(line);
+        }
+
+        let tree: Vec<(usize, usize)> = construction_tree(&lines).iter().map(|n| (n.line, n.depth)).collect();
+        assert_eq!(tree, vec![(0, 1), (2, 2), (3, 3), (1, 1)]);
+    }
+}
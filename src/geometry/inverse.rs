@@ -0,0 +1,161 @@
+// Rückwärtsrechnung: bei festen Seiten den Winkel bei A suchen, der ein gewünschtes Maß ergibt
+// Gerechnet wird mit derselben Konstruktion wie bei der Eingabe "4 Seiten + Winkel A",
+// damit der gefundene Winkel beim Übernehmen genau dieses Viereck liefert
+
+use super::solver::{solve, QuadInput, DIAGONAL_NAMES};
+
+/// Stützstellen über den möglichen Winkelbereich, zwischen denen nach Vorzeichenwechseln gesucht wird
+const SAMPLES: usize = 720;
+/// Halbierungsschritte je Lösung (Winkelgenauigkeit weit unter 1e-9°)
+const BISECTION_STEPS: usize = 60;
+
+/// Gewünschtes Maß bei festen Seiten
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InverseTarget {
+    /// Diagonale 0 = AC, 1 = BD in mm
+    Diagonal { diagonal: usize, length_mm: f64 },
+    /// Fläche in m²
+    Area { area_m2: f64 },
+}
+
+impl InverseTarget {
+    /// Wert dieses Maßes am konstruierten Viereck (mm bzw. m²)
+    fn measure(&self, input: &QuadInput) -> Option<f64> {
+        let quad = solve(input).ok()?.quad;
+        Some(match *self {
+            InverseTarget::Diagonal { diagonal, .. } => quad.diagonal_um(diagonal) as f64 / 1000.0,
+            InverseTarget::Area { .. } => quad.area_mm2() / 1e6,
+        })
+    }
+
+    fn value(&self) -> f64 {
+        match *self {
+            InverseTarget::Diagonal { length_mm, .. } => length_mm,
+            InverseTarget::Area { area_m2 } => area_m2,
+        }
+    }
+}
+
+/// Winkel bei A, der das Zielmaß ergibt, mit den übrigen Maßen des Vierecks
+#[derive(Clone, Debug, PartialEq)]
+pub struct InverseSolution {
+    pub angle_a_deg: f64,
+    pub diagonals_mm: [f64; 2], // AC, BD
+    pub area_m2: f64,
+}
+
+/// Sucht alle Winkel bei A (aufsteigend), bei denen das Viereck mit den Seiten `sides_mm`
+/// (AB, BC, CD, DA) das Zielmaß erreicht; bei der Fläche gibt es meist zwei Lösungen
+pub fn angle_for_target(sides_mm: [f64; 4], target: &InverseTarget) -> Result<Vec<InverseSolution>, String> {
+    if sides_mm.iter().any(|&side| side <= 0.0) {
+        return Err("❌ Alle vier Seiten müssen bekannt sein.".to_string());
+    }
+    if let InverseTarget::Diagonal { diagonal, .. } = target {
+        if *diagonal > 1 {
+            return Err("❌ Unbekannte Diagonale.".to_string());
+        }
+    }
+    let input_at = |angle: f64| QuadInput {
+        sides_mm: sides_mm.map(Some),
+        angles: [Some(angle), None, None, None],
+        ..QuadInput::default()
+    };
+    let deviation_at = |angle: f64| target.measure(&input_at(angle)).map(|value| value - target.value());
+
+    let samples: Vec<(f64, Option<f64>)> = (1..SAMPLES)
+        .map(|i| {
+            let angle = 180.0 * i as f64 / SAMPLES as f64;
+            (angle, deviation_at(angle))
+        })
+        .collect();
+    let reachable: Vec<f64> = samples.iter().filter_map(|&(_, d)| d.map(|d| d + target.value())).collect();
+    if reachable.is_empty() {
+        return Err("❌ Mit diesen Seiten lässt sich kein Viereck bilden.".to_string());
+    }
+
+    let mut solutions = Vec::new();
+    for pair in samples.windows(2) {
+        let ((mut low, Some(d_low)), (mut high, Some(d_high))) = (pair[0], pair[1]) else {
+            continue;
+        };
+        if d_low == 0.0 {
+            solutions.push(low);
+            continue;
+        }
+        // Treffer genau auf der Stützstelle zählt erst im nächsten Abschnitt
+        if d_high == 0.0 || d_low.signum() == d_high.signum() {
+            continue;
+        }
+        for _ in 0..BISECTION_STEPS {
+            let mid = (low + high) / 2.0;
+            match deviation_at(mid) {
+                Some(d) if d.signum() == d_low.signum() => low = mid,
+                Some(_) => high = mid,
+                None => break,
+            }
+        }
+        solutions.push((low + high) / 2.0);
+    }
+
+    if solutions.is_empty() {
+        let min = reachable.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = reachable.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        return Err(match target {
+            InverseTarget::Diagonal { diagonal, .. } => format!(
+                "❌ Diagonale {} ist mit diesen Seiten nicht erreichbar.\nMöglich: {:.1} mm bis {:.1} mm",
+                DIAGONAL_NAMES[*diagonal], min, max
+            ),
+            InverseTarget::Area { .. } => format!(
+                "❌ Diese Fläche ist mit diesen Seiten nicht erreichbar.\nMöglich: {:.3} m² bis {:.3} m²",
+                min, max
+            ),
+        });
+    }
+
+    Ok(solutions
+        .into_iter()
+        .filter_map(|angle| {
+            let quad = solve(&input_at(angle)).ok()?.quad;
+            Some(InverseSolution {
+                angle_a_deg: angle,
+                diagonals_mm: [0, 1].map(|d| quad.diagonal_um(d) as f64 / 1000.0),
+                area_m2: quad.area_mm2() / 1e6,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angle_for_diagonal() {
+        // 3-4-5-Rechteck: Diagonale 5000 mm gibt es nur bei 90°
+        let sides = [4000.0, 3000.0, 4000.0, 3000.0];
+        for diagonal in 0..2 {
+            let solutions = angle_for_target(sides, &InverseTarget::Diagonal { diagonal, length_mm: 5000.0 }).unwrap();
+            assert_eq!(solutions.len(), 1);
+            assert!((solutions[0].angle_a_deg - 90.0).abs() < 1e-3, "{:?}", solutions);
+        }
+
+        // Diagonale BD aus dem Kosinussatz: 4000² + 3000² − 2·4000·3000·cos 60°
+        let expected = (4000.0_f64.powi(2) + 3000.0_f64.powi(2) - 12_000_000.0).sqrt();
+        let solutions = angle_for_target(sides, &InverseTarget::Diagonal { diagonal: 1, length_mm: expected }).unwrap();
+        assert!((solutions[0].angle_a_deg - 60.0).abs() < 1e-3);
+
+        assert!(angle_for_target(sides, &InverseTarget::Diagonal { diagonal: 0, length_mm: 9000.0 })
+            .unwrap_err()
+            .contains("nicht erreichbar"));
+    }
+
+    #[test]
+    fn test_angle_for_area() {
+        // Parallelogramm 4 × 3 m: Fläche 6 m² bei 30° und 150°
+        let solutions = angle_for_target([4000.0, 3000.0, 4000.0, 3000.0], &InverseTarget::Area { area_m2: 6.0 }).unwrap();
+        let angles: Vec<f64> = solutions.iter().map(|s| s.angle_a_deg).collect();
+        assert_eq!(angles.len(), 2, "{:?}", angles);
+        assert!((angles[0] - 30.0).abs() < 1e-3 && (angles[1] - 150.0).abs() < 1e-3, "{:?}", angles);
+        assert!(angle_for_target([4000.0, 3000.0, 4000.0, 3000.0], &InverseTarget::Area { area_m2: 13.0 }).is_err());
+    }
+}
@@ -0,0 +1,825 @@
+// Verankerung und Neuberechnung eingezeichneter Linien
+// Linienenden hängen an Seiten des Vierecks oder an anderen Linien
+
+use super::types::{ConstructionLine, CustomLine, LineAnchor, LineStyle, Point, Quadrilateral};
+use serde::{Deserialize, Serialize};
+use super::utils::{
+    angle_between_vectors, calculate_intersection_angle, distance_f64, distance_point_to_segment, distance_um, line_segment_intersection,
+    segment_intersection,
+};
+
+/// Kreuzungspunkt zweier eingezeichneter Linien
+#[derive(Clone, Debug)]
+pub struct LineCrossing {
+    pub lines: (usize, usize),
+    pub ratios: (f64, f64), // Lage auf der jeweiligen Linie (0 = Anfang, 1 = Ende)
+    pub point: Point,
+    pub angle_deg: f64,     // Spitzer Schnittwinkel (0° bis 90°)
+}
+
+/// Kante, bis zu der eine Linie getrimmt oder verlängert wird (bzw. Schenkel eines Winkelmaßes)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CutEdge {
+    Side(usize),
+    Line(usize), // Nur Linien mit kleinerem Index (Verankerung zeigt immer nach vorne)
+}
+
+/// Angeheftetes Winkelmaß zwischen zwei Kanten
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AngleDimension {
+    pub first: CutEdge,
+    pub second: CutEdge,
+}
+
+impl AngleDimension {
+    /// Passt Linienindizes nach `remove_line_cascade` an, None = eine Kante wurde entfernt
+    pub fn remap(&self, removed: &[usize]) -> Option<Self> {
+        let remap = |edge: CutEdge| match edge {
+            CutEdge::Side(side) => Some(CutEdge::Side(side)),
+            CutEdge::Line(line) => remap_index(Some(line), removed).map(CutEdge::Line),
+        };
+        Some(Self { first: remap(self.first)?, second: remap(self.second)? })
+    }
+}
+
+/// Scheitel und Schenkel eines Winkels zwischen zwei Kanten
+#[derive(Clone, Debug)]
+pub struct EdgeAngle {
+    pub vertex: Point,
+    pub directions: [(f64, f64); 2], // Einheitsvektoren vom Scheitel zur jeweiligen Kante
+    pub angle_deg: f64,              // 0° bis 180°
+}
+
+/// Ergebnis von `trim_extend_line`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineEdit {
+    Trimmed,
+    Extended,
+}
+
+/// Bezugsstrecke eines Ankers (Seite des Vierecks oder andere Linie)
+/// Für den Schwerpunkt eine Strecke der Länge 0, für freie Punkte die Seite, auf die sie sich beziehen
+pub fn anchor_segment(quad: &Quadrilateral, lines: &[CustomLine], anchor: &LineAnchor) -> (Point, Point) {
+    match *anchor {
+        LineAnchor::Centroid => {
+            let centroid = quad.centroid();
+            (centroid.clone(), centroid)
+        }
+        LineAnchor::Side { side, .. } | LineAnchor::Vertex(side) | LineAnchor::Free { side, .. } => (
+            quad.vertices[side % 4].clone(),
+            quad.vertices[(side + 1) % 4].clone(),
+        ),
+        LineAnchor::Line { line, .. } => match lines.get(line) {
+            Some(parent) => (parent.start.clone(), parent.end.clone()),
+            None => (quad.vertices[0].clone(), quad.vertices[1].clone()),
+        },
+    }
+}
+
+/// Punkt, an dem der Anker liegt
+pub fn anchor_point(quad: &Quadrilateral, lines: &[CustomLine], anchor: &LineAnchor) -> Point {
+    let (a, b) = anchor_segment(quad, lines, anchor);
+    let ratio = anchor.ratio();
+    let on_segment = Point::new(a.x + (b.x - a.x) * ratio, a.y + (b.y - a.y) * ratio);
+    match *anchor {
+        LineAnchor::Free { offset_mm, .. } => {
+            let len = distance_f64(&a, &b);
+            if len == 0.0 {
+                return on_segment;
+            }
+            let scale = offset_mm * 1000.0 / len;
+            Point::new(on_segment.x - (b.y - a.y) * scale, on_segment.y + (b.x - a.x) * scale)
+        }
+        _ => on_segment,
+    }
+}
+
+/// Freier Anker für einen beliebigen Punkt, bezogen auf die nächstgelegene Seite
+pub fn free_anchor(quad: &Quadrilateral, point: &Point) -> LineAnchor {
+    let side = (0..4)
+        .min_by(|&i, &j| {
+            let distance = |side: usize| distance_point_to_segment(point, &quad.vertices[side], &quad.vertices[(side + 1) % 4]);
+            distance(i).total_cmp(&distance(j))
+        })
+        .unwrap_or(0);
+    let (a, b) = (&quad.vertices[side], &quad.vertices[(side + 1) % 4]);
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len_sq = dx * dx + dy * dy;
+    if len_sq == 0.0 {
+        return LineAnchor::Free { side, ratio: 0.0, offset_mm: 0.0 };
+    }
+
+    let (px, py) = (point.x - a.x, point.y - a.y);
+    LineAnchor::Free {
+        side,
+        ratio: (px * dx + py * dy) / len_sq,
+        offset_mm: (dx * py - dy * px) / len_sq.sqrt() / 1000.0,
+    }
+}
+
+/// Verschiebt einen Anker um `delta_mm` entlang seiner Bezugsstrecke (begrenzt auf deren Enden)
+/// Eckpunkt-Anker werden dabei zu Anker auf der abgehenden Seite, freie Punkte sind nicht begrenzt
+pub fn nudge_anchor(quad: &Quadrilateral, lines: &[CustomLine], anchor: &LineAnchor, delta_mm: f64) -> LineAnchor {
+    let (a, b) = anchor_segment(quad, lines, anchor);
+    let length_um = distance_f64(&a, &b);
+    if length_um == 0.0 {
+        return *anchor;
+    }
+
+    let ratio = anchor.ratio() + delta_mm * 1000.0 / length_um;
+    match anchor {
+        LineAnchor::Free { .. } => anchor.with_ratio(ratio),
+        _ => anchor.with_ratio(ratio.clamp(0.0, 1.0)),
+    }
+}
+
+/// Anker im Abstand `distance_mm` vom Anfang seiner Bezugsstrecke (Seite ab ihrem ersten Eckpunkt, Linie ab ihrem Start)
+pub fn anchor_at_distance(
+    quad: &Quadrilateral,
+    lines: &[CustomLine],
+    anchor: &LineAnchor,
+    distance_mm: f64,
+) -> Result<LineAnchor, String> {
+    let (a, b) = anchor_segment(quad, lines, anchor);
+    let length_mm = distance_f64(&a, &b) / 1000.0;
+    if length_mm == 0.0 {
+        return Err("Bezugsstrecke hat keine Länge".to_string());
+    }
+    if !(0.0..=length_mm).contains(&distance_mm) {
+        return Err(format!("Abstand muss zwischen 0 und {:.3} mm liegen", length_mm));
+    }
+
+    Ok(anchor.with_ratio(distance_mm / length_mm))
+}
+
+/// Anker für eine parallel um `offset_mm` versetzte Kopie der Linie `idx`
+/// Positiver Versatz liegt in Laufrichtung Start→Ende gesehen rechts (Weltkoordinaten, y nach unten)
+/// Die Kopie wird hinten angehängt und darf daher an allen vorhandenen Linien hängen
+pub fn offset_anchors(
+    quad: &Quadrilateral,
+    lines: &[CustomLine],
+    idx: usize,
+    offset_mm: f64,
+) -> Result<(LineAnchor, LineAnchor), String> {
+    let line = lines.get(idx).ok_or("Linie nicht gefunden")?;
+    parallel_anchors(quad, lines, line, offset_mm)
+}
+
+/// Neue Anker, um die Linie `idx` selbst parallel um `offset_mm` zu verschieben
+/// Richtung bleibt erhalten, die Enden gleiten auf ihren Bezugsstrecken; als Bezug
+/// kommen nur Linien mit kleinerem Index in Frage
+pub fn translate_anchors(
+    quad: &Quadrilateral,
+    lines: &[CustomLine],
+    idx: usize,
+    offset_mm: f64,
+) -> Result<(LineAnchor, LineAnchor), String> {
+    let line = lines.get(idx).ok_or("Linie nicht gefunden")?;
+    parallel_anchors(quad, &lines[..idx], line, offset_mm)
+}
+
+/// Anker der um `offset_mm` versetzten Parallele zu `line`, verankert an `parents`
+fn parallel_anchors(
+    quad: &Quadrilateral,
+    parents: &[CustomLine],
+    line: &CustomLine,
+    offset_mm: f64,
+) -> Result<(LineAnchor, LineAnchor), String> {
+    let (dx, dy) = (line.end.x - line.start.x, line.end.y - line.start.y);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len == 0.0 {
+        return Err("Linie hat keine Länge".to_string());
+    }
+
+    let (nx, ny) = (-dy / len * offset_mm * 1000.0, dx / len * offset_mm * 1000.0);
+    let p1 = Point::new(line.start.x + nx, line.start.y + ny);
+    let p2 = Point::new(line.end.x + nx, line.end.y + ny);
+
+    let start = offset_end_anchor(quad, parents, &line.start_anchor, &p1, &p2, 0.0)?;
+    let end = offset_end_anchor(quad, parents, &line.end_anchor, &p1, &p2, 1.0)?;
+    if distance_um(&anchor_point(quad, parents, &start), &anchor_point(quad, parents, &end)) == 0 {
+        return Err("Versatz zu groß: die Linie hätte keine Länge".to_string());
+    }
+    Ok((start, end))
+}
+
+/// Anker eines Endes der versetzten Linie p1→p2
+/// Bevorzugt dieselbe Bezugsstrecke wie das Original, sonst die Seite oder Linie,
+/// deren Schnittpunkt am nächsten beim versetzten Ende (Parameter `t_target`) liegt
+fn offset_end_anchor(
+    quad: &Quadrilateral,
+    lines: &[CustomLine],
+    original: &LineAnchor,
+    p1: &Point,
+    p2: &Point,
+    t_target: f64,
+) -> Result<LineAnchor, String> {
+    let (a, b) = anchor_segment(quad, lines, original);
+    if let Some((_, u)) = line_segment_intersection(p1, p2, &a, &b) {
+        return Ok(original.with_ratio(u));
+    }
+
+    let candidates = (0..4)
+        .map(|side| LineAnchor::Side { side, ratio: 0.0 })
+        .chain((0..lines.len()).map(|line| LineAnchor::Line { line, ratio: 0.0 }));
+
+    let mut best: Option<(LineAnchor, f64)> = None;
+    for candidate in candidates {
+        let (a, b) = anchor_segment(quad, lines, &candidate);
+        if let Some((t, u)) = line_segment_intersection(p1, p2, &a, &b) {
+            let dist = (t - t_target).abs();
+            if best.is_none_or(|(_, best_dist)| dist < best_dist) {
+                best = Some((candidate.with_ratio(u), dist));
+            }
+        }
+    }
+
+    best.map(|(anchor, _)| anchor)
+        .ok_or_else(|| "Die versetzte Linie trifft keine Seite oder Linie".to_string())
+}
+
+impl CustomLine {
+    /// Erstellt eine neue Linie zwischen zwei Ankern
+    /// `lines` sind die bereits vorhandenen Linien (mögliche Eltern)
+    pub fn new(
+        name: String,
+        start_anchor: LineAnchor,
+        end_anchor: LineAnchor,
+        quad: &Quadrilateral,
+        lines: &[CustomLine],
+    ) -> Self {
+        let mut line = Self {
+            name,
+            start: Point::new(0.0, 0.0),
+            end: Point::new(0.0, 0.0),
+            length_um: 0,
+            start_anchor,
+            end_anchor,
+            start_angle: 0.0,
+            end_angle: 0.0,
+            style: LineStyle::Solid,
+            width: Self::DEFAULT_WIDTH,
+            color: Self::DEFAULT_COLOR,
+            locked: false,
+            group: None,
+            hidden: false,
+        };
+        line.update_geometry(quad, lines);
+        line
+    }
+
+    /// Berechnet Endpunkte, Länge und Schnittwinkel aus den Ankern neu
+    /// `parents` muss alle Linien enthalten, auf die die Anker verweisen
+    pub fn update_geometry(&mut self, quad: &Quadrilateral, parents: &[CustomLine]) {
+        self.start = anchor_point(quad, parents, &self.start_anchor);
+        self.end = anchor_point(quad, parents, &self.end_anchor);
+        self.length_um = distance_um(&self.start, &self.end);
+
+        let (s1, s2) = anchor_segment(quad, parents, &self.start_anchor);
+        self.start_angle = calculate_intersection_angle(&s1, &s2, &self.start, &self.end);
+
+        let (e1, e2) = anchor_segment(quad, parents, &self.end_anchor);
+        self.end_angle = calculate_intersection_angle(&e1, &e2, &self.end, &self.start);
+    }
+
+    /// Prüft ob die Linie an der Linie mit Index `idx` hängt
+    pub fn depends_on(&self, idx: usize) -> bool {
+        [self.start_anchor, self.end_anchor]
+            .iter()
+            .any(|a| matches!(a, LineAnchor::Line { line, .. } if *line == idx))
+    }
+}
+
+/// Endpunkte einer Hilfslinie; unbegrenzte Linien reichen `unlimited_mm` über die Eckpunkte hinaus
+/// None, wenn die Seite keine Länge hat
+pub fn construction_segment(quad: &Quadrilateral, line: &ConstructionLine, unlimited_mm: f64) -> Option<(Point, Point)> {
+    let a = &quad.vertices[line.side % 4];
+    let b = &quad.vertices[(line.side + 1) % 4];
+    let len = distance_f64(a, b);
+    if len == 0.0 {
+        return None;
+    }
+
+    let reach = line.extension_mm.unwrap_or(unlimited_mm) * 1000.0 / len;
+    let (dx, dy) = ((b.x - a.x) * reach, (b.y - a.y) * reach);
+    Some((Point::new(a.x - dx, a.y - dy), Point::new(b.x + dx, b.y + dy)))
+}
+
+/// Aktualisiert alle Linien in Index-Reihenfolge
+/// Linien hängen nur an Linien mit kleinerem Index, daher genügt ein Durchlauf
+pub fn update_all_lines(quad: &Quadrilateral, lines: &mut [CustomLine]) {
+    for i in 0..lines.len() {
+        let (parents, rest) = lines.split_at_mut(i);
+        rest[0].update_geometry(quad, parents);
+    }
+}
+
+/// Namen aller Gruppen in der Reihenfolge ihres ersten Auftretens
+pub fn group_names(lines: &[CustomLine]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for group in lines.iter().filter_map(|line| line.group.as_ref()) {
+        if !names.contains(group) {
+            names.push(group.clone());
+        }
+    }
+    names
+}
+
+/// Indizes der Linien einer Gruppe
+pub fn group_members(lines: &[CustomLine], group: &str) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.group.as_deref() == Some(group))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Erster freier Gruppenname "Gruppe n"
+pub fn next_group_name(lines: &[CustomLine]) -> String {
+    let names = group_names(lines);
+    (1..)
+        .map(|n| format!("Gruppe {}", n))
+        .find(|name| !names.contains(name))
+        .unwrap_or_default()
+}
+
+/// Verschiebt beide Enden mehrerer Linien um `delta_mm` entlang ihrer Bezugsstrecken
+/// Gesperrte Linien bleiben stehen, abhängige Linien wandern mit
+pub fn move_lines(quad: &Quadrilateral, lines: &mut [CustomLine], indices: &[usize], delta_mm: f64) {
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    let count = lines.len();
+    for idx in indices.into_iter().filter(|&idx| idx < count) {
+        let (parents, rest) = lines.split_at_mut(idx);
+        let line = &mut rest[0];
+        if line.locked {
+            continue;
+        }
+        line.start_anchor = nudge_anchor(quad, parents, &line.start_anchor, delta_mm);
+        line.end_anchor = nudge_anchor(quad, parents, &line.end_anchor, delta_mm);
+        // Nachfolgende Linien der Auswahl beziehen sich auf die neue Lage
+        update_all_lines(quad, lines);
+    }
+}
+
+/// Entfernt eine Linie samt aller davon abhängigen Linien
+/// Gibt die entfernten Indizes (aufsteigend) zurück
+pub fn remove_line_cascade(lines: &mut Vec<CustomLine>, idx: usize) -> Vec<usize> {
+    let mut removed = vec![idx];
+    for (i, line) in lines.iter().enumerate().skip(idx + 1) {
+        if removed.iter().any(|&r| line.depends_on(r)) {
+            removed.push(i);
+        }
+    }
+
+    for &r in removed.iter().rev() {
+        lines.remove(r);
+    }
+
+    // Verweise auf verbliebene Linien neu nummerieren
+    for line in lines.iter_mut() {
+        for anchor in [&mut line.start_anchor, &mut line.end_anchor] {
+            if let LineAnchor::Line { line: parent, .. } = anchor {
+                let shift = removed.iter().filter(|&&r| r < *parent).count();
+                *parent -= shift;
+            }
+        }
+    }
+
+    removed
+}
+
+/// Sucht alle Kreuzungen zwischen eingezeichneten Linien
+/// Linienenden, die an einer anderen Linie hängen, gelten nicht als Kreuzung
+pub fn line_crossings(lines: &[CustomLine]) -> Vec<LineCrossing> {
+    let mut crossings = Vec::new();
+    for i in 0..lines.len() {
+        for j in (i + 1)..lines.len() {
+            let (a, b) = (&lines[i], &lines[j]);
+            let Some((t, u)) = segment_intersection(&a.start, &a.end, &b.start, &b.end) else {
+                continue;
+            };
+            let angle = angle_between_vectors(
+                a.end.x - a.start.x,
+                a.end.y - a.start.y,
+                b.end.x - b.start.x,
+                b.end.y - b.start.y,
+            );
+            crossings.push(LineCrossing {
+                lines: (i, j),
+                ratios: (t, u),
+                point: Point::new(a.start.x + (a.end.x - a.start.x) * t, a.start.y + (a.end.y - a.start.y) * t),
+                angle_deg: angle.min(180.0 - angle),
+            });
+        }
+    }
+    crossings
+}
+
+/// Endpunkte einer Kante, None bei ungültigem Index
+pub fn edge_segment(quad: &Quadrilateral, lines: &[CustomLine], edge: CutEdge) -> Option<(Point, Point)> {
+    match edge {
+        CutEdge::Side(side) if side < 4 => Some((quad.vertices[side].clone(), quad.vertices[(side + 1) % 4].clone())),
+        CutEdge::Side(_) => None,
+        CutEdge::Line(line) => lines.get(line).map(|l| (l.start.clone(), l.end.clone())),
+    }
+}
+
+/// Winkel zwischen zwei Kanten im Schnittpunkt ihrer Verlängerungen
+/// Die Schenkel zeigen jeweils zum weiter entfernten Ende der Kante; None bei parallelen Kanten
+pub fn edge_angle(quad: &Quadrilateral, lines: &[CustomLine], first: CutEdge, second: CutEdge) -> Option<EdgeAngle> {
+    let (a1, a2) = edge_segment(quad, lines, first)?;
+    let (b1, b2) = edge_segment(quad, lines, second)?;
+    let d1 = (a2.x - a1.x, a2.y - a1.y);
+    let d2 = (b2.x - b1.x, b2.y - b1.y);
+    let len1 = (d1.0 * d1.0 + d1.1 * d1.1).sqrt();
+    let len2 = (d2.0 * d2.0 + d2.1 * d2.1).sqrt();
+    let denom = d1.0 * d2.1 - d1.1 * d2.0;
+    if len1 == 0.0 || len2 == 0.0 || denom.abs() < 1e-9 * len1 * len2 {
+        return None;
+    }
+
+    let t = ((b1.x - a1.x) * d2.1 - (b1.y - a1.y) * d2.0) / denom;
+    let vertex = Point::new(a1.x + d1.0 * t, a1.y + d1.1 * t);
+    let ray = |p: &Point, q: &Point| {
+        let far = if distance_f64(&vertex, p) > distance_f64(&vertex, q) { p } else { q };
+        let (dx, dy) = (far.x - vertex.x, far.y - vertex.y);
+        let len = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+        (dx / len, dy / len)
+    };
+    let directions = [ray(&a1, &a2), ray(&b1, &b2)];
+    let angle_deg = angle_between_vectors(directions[0].0, directions[0].1, directions[1].0, directions[1].1);
+    Some(EdgeAngle { vertex, directions, angle_deg })
+}
+
+/// Trimmt oder verlängert Linie `idx` genau bis zur Schnittkante
+/// Das Ende, das näher am Schnittpunkt liegt, wird neu auf der Kante verankert
+/// Daran hängende Linien behalten ihre Lage, solange sie auf der neuen Linie liegen
+pub fn trim_extend_line(
+    quad: &Quadrilateral,
+    lines: &mut [CustomLine],
+    idx: usize,
+    edge: CutEdge,
+) -> Result<LineEdit, String> {
+    let line = lines.get(idx).ok_or("Linie nicht gefunden")?;
+    let (a, b, mut new_anchor) = match edge {
+        CutEdge::Side(side) if side < 4 => (
+            quad.vertices[side].clone(),
+            quad.vertices[(side + 1) % 4].clone(),
+            LineAnchor::Side { side, ratio: 0.0 },
+        ),
+        CutEdge::Line(other) if other < idx => (
+            lines[other].start.clone(),
+            lines[other].end.clone(),
+            LineAnchor::Line { line: other, ratio: 0.0 },
+        ),
+        CutEdge::Line(_) => return Err("Schnittkante muss eine früher gezeichnete Linie sein".to_string()),
+        CutEdge::Side(_) => return Err("Ungültige Seite".to_string()),
+    };
+
+    let (t, u) = line_segment_intersection(&line.start, &line.end, &a, &b)
+        .ok_or("Linie und Schnittkante schneiden sich nicht")?;
+    if t.abs() < 1e-6 || (t - 1.0).abs() < 1e-6 {
+        return Err("Linie endet bereits an der Schnittkante".to_string());
+    }
+
+    // Abschnitt der alten Linie (in alten Parametern), der erhalten bleibt
+    let move_start = t < 0.5;
+    let (t0, t1) = if move_start { (t, 1.0) } else { (0.0, t) };
+    if ((t1 - t0) * line.length_um as f64).abs() < 1.0 {
+        return Err("Linie würde zu kurz".to_string());
+    }
+
+    if let LineAnchor::Side { ratio, .. } | LineAnchor::Line { ratio, .. } = &mut new_anchor {
+        *ratio = u;
+    }
+    let line = &mut lines[idx];
+    if move_start {
+        line.start_anchor = new_anchor;
+    } else {
+        line.end_anchor = new_anchor;
+    }
+
+    for child in lines.iter_mut().skip(idx + 1) {
+        for anchor in [&mut child.start_anchor, &mut child.end_anchor] {
+            if let LineAnchor::Line { line: parent, ratio } = anchor {
+                if *parent == idx {
+                    *ratio = ((*ratio - t0) / (t1 - t0)).clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+
+    update_all_lines(quad, lines);
+    Ok(if (0.0..=1.0).contains(&t) { LineEdit::Trimmed } else { LineEdit::Extended })
+}
+
+/// Neuer Index einer Linie nach `remove_line_cascade` (None = entfernt)
+pub fn remap_index(old: Option<usize>, removed: &[usize]) -> Option<usize> {
+    let old = old?;
+    if removed.contains(&old) {
+        None
+    } else {
+        Some(old - removed.iter().filter(|&&r| r < old).count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Quadrilateral {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(1_000_000.0, 0.0),
+            Point::new(1_000_000.0, 1_000_000.0),
+            Point::new(0.0, 1_000_000.0),
+        ];
+        quad
+    }
+
+    #[test]
+    fn test_dependent_line_follows_parent() {
+        let quad = square();
+        let mut lines = Vec::new();
+        lines.push(CustomLine::new(
+            "Linie 1".to_string(),
+            LineAnchor::Side { side: 0, ratio: 0.5 },
+            LineAnchor::Side { side: 2, ratio: 0.5 },
+            &quad,
+            &lines,
+        ));
+        let child = CustomLine::new(
+            "Linie 2".to_string(),
+            LineAnchor::Line { line: 0, ratio: 0.5 },
+            LineAnchor::Side { side: 1, ratio: 0.5 },
+            &quad,
+            &lines,
+        );
+        lines.push(child);
+        assert_eq!(lines[1].length_um, 500_000);
+        assert!((lines[1].start_angle - 90.0).abs() < 1e-6);
+
+        // Elternlinie verschieben: Kind folgt
+        lines[0].start_anchor = LineAnchor::Side { side: 0, ratio: 0.25 };
+        lines[0].end_anchor = LineAnchor::Side { side: 2, ratio: 0.75 };
+        update_all_lines(&quad, &mut lines);
+        assert_eq!(lines[1].length_um, 750_000);
+    }
+
+    #[test]
+    fn test_vertex_anchor() {
+        let quad = square();
+        let diagonal = CustomLine::new(
+            "Diagonale AC".to_string(),
+            LineAnchor::Vertex(0),
+            LineAnchor::Vertex(2),
+            &quad,
+            &[],
+        );
+        assert_eq!(diagonal.length_um, 1_414_214);
+        assert!((diagonal.start_angle - 45.0).abs() < 1e-6);
+        assert!((diagonal.end_angle - 45.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_edge_angle() {
+        let quad = square();
+        let lines = vec![CustomLine::new("Diagonale AC".to_string(), LineAnchor::Vertex(0), LineAnchor::Vertex(2), &quad, &[])];
+
+        let corner = edge_angle(&quad, &lines, CutEdge::Side(0), CutEdge::Side(1)).unwrap();
+        assert!((corner.vertex.x - 1_000_000.0).abs() < 1e-6 && corner.vertex.y.abs() < 1e-6);
+        assert!((corner.angle_deg - 90.0).abs() < 1e-9);
+
+        let diagonal = edge_angle(&quad, &lines, CutEdge::Line(0), CutEdge::Side(0)).unwrap();
+        assert!((diagonal.angle_deg - 45.0).abs() < 1e-9);
+
+        assert!(edge_angle(&quad, &lines, CutEdge::Side(0), CutEdge::Side(2)).is_none());
+        assert!(edge_angle(&quad, &lines, CutEdge::Line(1), CutEdge::Side(0)).is_none());
+
+        let dimension = AngleDimension { first: CutEdge::Line(2), second: CutEdge::Side(1) };
+        assert_eq!(dimension.remap(&[0]), Some(AngleDimension { first: CutEdge::Line(1), second: CutEdge::Side(1) }));
+        assert_eq!(dimension.remap(&[2]), None);
+    }
+
+    #[test]
+    fn test_remove_line_cascade() {
+        let quad = square();
+        let mut lines = Vec::new();
+        for anchor in [
+            LineAnchor::Side { side: 3, ratio: 0.5 },
+            LineAnchor::Side { side: 3, ratio: 0.2 },
+        ] {
+            let line = CustomLine::new(String::new(), anchor, LineAnchor::Side { side: 1, ratio: 0.5 }, &quad, &lines);
+            lines.push(line);
+        }
+        let dependent = CustomLine::new(
+            String::new(),
+            LineAnchor::Line { line: 1, ratio: 0.5 },
+            LineAnchor::Side { side: 0, ratio: 0.5 },
+            &quad,
+            &lines,
+        );
+        lines.push(dependent);
+
+        let removed = remove_line_cascade(&mut lines, 1);
+        assert_eq!(removed, vec![1, 2]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(remap_index(Some(0), &removed), Some(0));
+        assert_eq!(remap_index(Some(2), &removed), None);
+    }
+
+    #[test]
+    fn test_line_crossings() {
+        let quad = square();
+        let mut lines = Vec::new();
+        for (name, start, end) in [
+            ("Diagonale", LineAnchor::Vertex(0), LineAnchor::Vertex(2)),
+            ("Mitte", LineAnchor::Side { side: 0, ratio: 0.5 }, LineAnchor::Side { side: 2, ratio: 0.5 }),
+        ] {
+            let line = CustomLine::new(name.to_string(), start, end, &quad, &lines);
+            lines.push(line);
+        }
+        // Hängt an der Diagonale: keine Kreuzung
+        let child = CustomLine::new(
+            "Ast".to_string(),
+            LineAnchor::Line { line: 0, ratio: 0.25 },
+            LineAnchor::Side { side: 3, ratio: 0.25 },
+            &quad,
+            &lines,
+        );
+        lines.push(child);
+
+        let crossings = line_crossings(&lines);
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].lines, (0, 1));
+        assert!((crossings[0].ratios.0 - 0.5).abs() < 1e-9);
+        assert!((crossings[0].angle_deg - 45.0).abs() < 1e-9);
+        assert!((crossings[0].point.x - 500_000.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_trim_extend_line() {
+        let quad = square();
+        let mut lines = Vec::new();
+        for (start, end) in [
+            (LineAnchor::Side { side: 0, ratio: 0.4 }, LineAnchor::Side { side: 2, ratio: 0.6 }),
+            (LineAnchor::Side { side: 1, ratio: 0.5 }, LineAnchor::Side { side: 3, ratio: 0.5 }),
+        ] {
+            let line = CustomLine::new("Linie".to_string(), start, end, &quad, &lines);
+            lines.push(line);
+        }
+        assert_eq!(lines[1].length_um, 1_000_000);
+
+        // Trimmen: das nähere Ende (an DA) rückt auf die senkrechte Linie
+        assert_eq!(trim_extend_line(&quad, &mut lines, 1, CutEdge::Line(0)), Ok(LineEdit::Trimmed));
+        assert_eq!(lines[1].length_um, 600_000);
+        assert!(matches!(lines[1].end_anchor, LineAnchor::Line { line: 0, ratio } if (ratio - 0.5).abs() < 1e-9));
+
+        // Verlängern: zurück bis zur Seite DA
+        assert_eq!(trim_extend_line(&quad, &mut lines, 1, CutEdge::Side(3)), Ok(LineEdit::Extended));
+        assert_eq!(lines[1].length_um, 1_000_000);
+
+        // Spätere Linie als Schnittkante ist nicht erlaubt
+        assert!(trim_extend_line(&quad, &mut lines, 0, CutEdge::Line(1)).is_err());
+    }
+
+    #[test]
+    fn test_nudge_anchor() {
+        let quad = square();
+        let lines = Vec::new();
+
+        let nudged = nudge_anchor(&quad, &lines, &LineAnchor::Side { side: 0, ratio: 0.5 }, 10.0);
+        assert!(matches!(nudged, LineAnchor::Side { side: 0, ratio } if (ratio - 0.51).abs() < 1e-9));
+
+        // Eckpunkt wird zum Seitenanker, Ende der Seite begrenzt
+        let nudged = nudge_anchor(&quad, &lines, &LineAnchor::Vertex(1), 1.0);
+        assert!(matches!(nudged, LineAnchor::Side { side: 1, ratio } if (ratio - 0.001).abs() < 1e-9));
+        let nudged = nudge_anchor(&quad, &lines, &LineAnchor::Side { side: 2, ratio: 0.999 }, 5.0);
+        assert_eq!(nudged, LineAnchor::Side { side: 2, ratio: 1.0 });
+    }
+
+    #[test]
+    fn test_anchor_at_distance() {
+        let quad = square();
+        let lines = Vec::new();
+
+        let anchor = anchor_at_distance(&quad, &lines, &LineAnchor::Side { side: 1, ratio: 0.7 }, 250.0).unwrap();
+        assert!(matches!(anchor, LineAnchor::Side { side: 1, ratio } if (ratio - 0.25).abs() < 1e-9));
+        assert!(anchor_at_distance(&quad, &lines, &LineAnchor::Side { side: 1, ratio: 0.7 }, 1000.5).is_err());
+        assert!(anchor_at_distance(&quad, &lines, &LineAnchor::Side { side: 1, ratio: 0.7 }, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_offset_anchors() {
+        let quad = square();
+        let lines = vec![CustomLine::new(
+            "Trennwand".to_string(),
+            LineAnchor::Side { side: 0, ratio: 0.5 },
+            LineAnchor::Side { side: 2, ratio: 0.5 },
+            &quad,
+            &[],
+        )];
+
+        // Linie läuft in +y, rechts davon liegt -x
+        let (start, end) = offset_anchors(&quad, &lines, 0, 200.0).unwrap();
+        assert!(matches!(start, LineAnchor::Side { side: 0, ratio } if (ratio - 0.3).abs() < 1e-9));
+        assert!(matches!(end, LineAnchor::Side { side: 2, ratio } if (ratio - 0.7).abs() < 1e-9));
+
+        assert!(offset_anchors(&quad, &lines, 0, 800.0).is_err());
+    }
+
+    #[test]
+    fn test_translate_anchors() {
+        let quad = square();
+        let mut lines = vec![CustomLine::new(
+            "Regal".to_string(),
+            LineAnchor::Side { side: 3, ratio: 0.5 },
+            LineAnchor::Side { side: 1, ratio: 0.5 },
+            &quad,
+            &[],
+        )];
+        let child = CustomLine::new(
+            "Brett".to_string(),
+            LineAnchor::Side { side: 0, ratio: 0.5 },
+            LineAnchor::Line { line: 0, ratio: 0.5 },
+            &quad,
+            &lines,
+        );
+        lines.push(child);
+
+        // Linie läuft in +x, rechts davon liegt +y (Richtung CD)
+        let (start, end) = translate_anchors(&quad, &lines, 0, 100.0).unwrap();
+        assert!(matches!(start, LineAnchor::Side { side: 3, ratio } if (ratio - 0.4).abs() < 1e-9));
+        assert!(matches!(end, LineAnchor::Side { side: 1, ratio } if (ratio - 0.6).abs() < 1e-9));
+
+        // Kindlinie hängt an Linie 0 und darf sich nicht an sich selbst verankern
+        let (start, end) = translate_anchors(&quad, &lines, 1, 250.0).unwrap();
+        assert!(matches!(start, LineAnchor::Side { side: 0, ratio } if (ratio - 0.25).abs() < 1e-9));
+        assert!(matches!(end, LineAnchor::Line { line: 0, ratio } if (ratio - 0.25).abs() < 1e-9));
+        assert!(translate_anchors(&quad, &lines, 1, 600.0).is_err());
+    }
+
+    #[test]
+    fn test_free_anchor() {
+        let quad = square();
+        // 300 mm vor der Seite BC, auf Höhe der Verlängerung über C hinaus
+        let outside = Point::new(1_300_000.0, 1_200_000.0);
+        let anchor = free_anchor(&quad, &outside);
+        assert!(matches!(anchor, LineAnchor::Free { side: 1, ratio, offset_mm }
+            if (ratio - 1.2).abs() < 1e-9 && (offset_mm + 300.0).abs() < 1e-9));
+        let point = anchor_point(&quad, &[], &anchor);
+        assert!(distance_f64(&point, &outside) < 1e-6);
+
+        // Linie nach außen: Länge und Verschieben ohne Begrenzung auf die Seite
+        let line = CustomLine::new("Öffnung".to_string(), LineAnchor::Vertex(2), anchor, &quad, &[]);
+        assert_eq!(line.length_um, 360_555);
+        let nudged = nudge_anchor(&quad, &[], &anchor, 500.0);
+        assert!(matches!(nudged, LineAnchor::Free { ratio, .. } if (ratio - 1.7).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_construction_segment() {
+        let quad = square();
+        let limited = ConstructionLine { side: 1, extension_mm: Some(250.0) };
+        let (start, end) = construction_segment(&quad, &limited, 10_000.0).unwrap();
+        assert!((start.x - 1_000_000.0).abs() < 1e-6 && (start.y + 250_000.0).abs() < 1e-6);
+        assert!((end.y - 1_250_000.0).abs() < 1e-6);
+
+        let unlimited = ConstructionLine { side: 1, extension_mm: None };
+        let (start, _) = construction_segment(&quad, &unlimited, 10_000.0).unwrap();
+        assert!((start.y + 10_000_000.0).abs() < 1e-6);
+        assert!(construction_segment(&Quadrilateral::new(), &unlimited, 10_000.0).is_none());
+    }
+
+    #[test]
+    fn test_groups_and_move_lines() {
+        let quad = square();
+        let mut lines = Vec::new();
+        for (start, end) in [
+            (LineAnchor::Side { side: 0, ratio: 0.5 }, LineAnchor::Side { side: 2, ratio: 0.5 }),
+            (LineAnchor::Vertex(0), LineAnchor::Vertex(2)),
+            (LineAnchor::Side { side: 1, ratio: 0.5 }, LineAnchor::Side { side: 3, ratio: 0.5 }),
+        ] {
+            let line = CustomLine::new("Linie".to_string(), start, end, &quad, &lines);
+            lines.push(line);
+        }
+        lines[0].group = Some("Gruppe 1".to_string());
+        lines[2].group = Some("Gruppe 1".to_string());
+        lines[2].locked = true;
+
+        assert_eq!(group_names(&lines), vec!["Gruppe 1".to_string()]);
+        assert_eq!(group_members(&lines, "Gruppe 1"), vec![0, 2]);
+        assert_eq!(next_group_name(&lines), "Gruppe 2");
+
+        move_lines(&quad, &mut lines, &[0, 2], 100.0);
+        assert!(matches!(lines[0].start_anchor, LineAnchor::Side { side: 0, ratio } if (ratio - 0.6).abs() < 1e-9));
+        assert!(matches!(lines[0].end_anchor, LineAnchor::Side { side: 2, ratio } if (ratio - 0.6).abs() < 1e-9));
+        assert!((lines[0].start.x - 600_000.0).abs() < 1e-6);
+        // Gesperrte Linie bleibt stehen
+        assert_eq!(lines[2].start_anchor, LineAnchor::Side { side: 1, ratio: 0.5 });
+    }
+}
@@ -2,17 +2,41 @@
 // Exportiert alle öffentlichen Typen und Funktionen
 
 pub mod types;
+pub mod error;
 pub mod validation;
 pub mod construction;
 pub mod utils;
+pub mod solver;
+pub mod lines;
+pub mod tools;
+pub mod circles;
+pub mod uncertainty;
+pub mod stakeout;
+pub mod tiling;
+pub mod prism;
+pub mod history;
+pub mod point_grid;
+pub mod partition;
+pub mod trace;
+pub mod inverse;
 
 // Re-exports für einfachen Zugriff
-pub use types::{Point, Quadrilateral, CustomLine};
-pub use utils::{
-    distance_um, 
-    distance_f64,
-    calculate_interior_angle, 
-    calculate_intersection_angle,
-    format_length_um,
-    angle_between_vectors,
-};
\ No newline at end of file
+pub use types::{Point, Quadrilateral, CustomLine, LineAnchor, LineStyle, CustomCircle, CustomArc, ConstructionLine};
+pub use lines::{
+    anchor_at_distance, anchor_point, anchor_segment, construction_segment, edge_angle, edge_segment, free_anchor, group_members, group_names, line_crossings, move_lines, next_group_name,
+    nudge_anchor, offset_anchors, remap_index, remove_line_cascade,
+    translate_anchors, trim_extend_line, update_all_lines, AngleDimension, CutEdge, LineEdit,
+};
+pub use tools::AxisThrough;
+pub use stakeout::{perimeter_stations, Baseline, LocalFrame, StationSource};
+pub use tiling::{TileEstimate, TileSpec};
+pub use trace::{ConstructionMethod, DerivationStep};
+pub use inverse::{angle_for_target, InverseSolution, InverseTarget};
+pub use prism::PrismView;
+pub use history::construction_tree;
+pub use point_grid::PointGridSpec;
+pub use uncertainty::{propagate, MeasurementUncertainty, UncertaintyResult};
+pub use error::PlausibilityWarning;
+pub use validation::{missing_input, InputValue, MissingInput, PlausibilityTolerance};
+pub use solver::{solve, solve_with_tolerance, QuadInput, QuadSolution, SIDE_NAMES, VERTEX_NAMES, DIAGONAL_NAMES, parse_length_input, parse_length_with_unit, parse_angle_input};
+pub use utils::{distance_f64, distance_point_to_segment};
\ No newline at end of file
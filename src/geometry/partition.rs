@@ -0,0 +1,112 @@
+// Aufteilung des Vierecks durch eine Trennlinie (z.B. Raum in Zonen)
+// Die Linie muss an beiden Enden auf dem Rand liegen (Seite oder Eckpunkt)
+
+use super::types::{CustomLine, LineAnchor, Point, Quadrilateral};
+use super::utils::distance_f64;
+
+/// Kleinste Teilfläche, die noch als Zone gilt (1 cm²)
+const MIN_PART_AREA_MM2: f64 = 100.0;
+
+/// Teilfläche auf einer Seite der Trennlinie
+#[derive(Clone, Debug)]
+pub struct PartialArea {
+    pub outline: Vec<Point>, // Beginnt mit den Enden der Trennlinie
+    pub area_mm2: f64,
+    pub perimeter_mm: f64, // Einschließlich der Trennlinie
+}
+
+impl PartialArea {
+    fn new(outline: Vec<Point>) -> Self {
+        let n = outline.len();
+        let mut twice_area = 0.0;
+        let mut perimeter = 0.0;
+        for i in 0..n {
+            let (p, q) = (&outline[i], &outline[(i + 1) % n]);
+            twice_area += p.x * q.y - q.x * p.y;
+            perimeter += distance_f64(p, q);
+        }
+        // Punkte in µm
+        Self { outline, area_mm2: (twice_area / 2.0).abs() / 1e6, perimeter_mm: perimeter / 1000.0 }
+    }
+
+    /// Schwerpunkt der Eckpunkte, genügt als Lage für die Beschriftung
+    pub fn label_point(&self) -> Point {
+        let n = self.outline.len() as f64;
+        let (x, y) = self.outline.iter().fold((0.0, 0.0), |(x, y), p| (x + p.x, y + p.y));
+        Point::new(x / n, y / n)
+    }
+}
+
+/// Lage eines Ankers auf dem Umfang: Seite + Verhältnis (0.0 bis 4.0 ab A), None abseits des Rands
+fn perimeter_position(anchor: &LineAnchor) -> Option<f64> {
+    match *anchor {
+        LineAnchor::Side { side, ratio } => Some(side as f64 + ratio),
+        LineAnchor::Vertex(vertex) => Some(vertex as f64),
+        LineAnchor::Line { .. } | LineAnchor::Centroid | LineAnchor::Free { .. } => None,
+    }
+}
+
+impl Quadrilateral {
+    /// Eckpunkte, die auf dem Umfang strikt zwischen `from` und `to` liegen (in Laufrichtung)
+    fn vertices_between(&self, from: f64, to: f64) -> Vec<Point> {
+        const EPS: f64 = 1e-9;
+        let to = if to <= from { to + 4.0 } else { to };
+        (1..=4)
+            .map(|k| from.floor() + k as f64)
+            .filter(|&k| k > from + EPS && k < to - EPS)
+            .map(|k| self.vertices[(k as usize) % 4].clone())
+            .collect()
+    }
+
+    /// Beide Teilflächen, in die die Linie das Viereck teilt
+    /// None, wenn ein Ende nicht auf dem Rand liegt oder die Linie am Rand entlang läuft
+    pub fn split_by_line(&self, line: &CustomLine) -> Option<[PartialArea; 2]> {
+        let from = perimeter_position(&line.start_anchor)? % 4.0;
+        let to = perimeter_position(&line.end_anchor)? % 4.0;
+
+        let mut first = vec![line.start.clone(), line.end.clone()];
+        first.extend(self.vertices_between(to, from));
+        let mut second = vec![line.end.clone(), line.start.clone()];
+        second.extend(self.vertices_between(from, to));
+
+        let parts = [PartialArea::new(first), PartialArea::new(second)];
+        parts.iter().all(|part| part.area_mm2 >= MIN_PART_AREA_MM2).then_some(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{solve, CustomLine, LineAnchor, QuadInput};
+
+    #[test]
+    fn test_split_by_line() {
+        let quad = solve(&QuadInput::rectangle(4000.0, 3000.0)).unwrap().quad;
+
+        // Quer durch den Raum: 1 m ab A auf AB bis 1 m ab D auf CD (CD läuft von C nach D)
+        let line = CustomLine::new(
+            "Zone".to_string(),
+            LineAnchor::Side { side: 0, ratio: 0.25 },
+            LineAnchor::Side { side: 2, ratio: 0.75 },
+            &quad,
+            &[],
+        );
+        let [first, second] = quad.split_by_line(&line).unwrap();
+        let mut areas = [first.area_mm2, second.area_mm2];
+        areas.sort_by(f64::total_cmp);
+        assert!((areas[0] - 3_000_000.0).abs() < 1.0);
+        assert!((areas[1] - 9_000_000.0).abs() < 1.0);
+        assert!((first.perimeter_mm + second.perimeter_mm - 14_000.0 - 6_000.0).abs() < 1e-6);
+
+        // Diagonale: zwei gleich große Dreiecke
+        let diagonal = CustomLine::new("AC".to_string(), LineAnchor::Vertex(0), LineAnchor::Vertex(2), &quad, &[]);
+        let [first, second] = quad.split_by_line(&diagonal).unwrap();
+        assert_eq!((first.outline.len(), second.outline.len()), (3, 3));
+        assert!((first.area_mm2 - second.area_mm2).abs() < 1.0);
+
+        // Entlang einer Seite: keine Teilung
+        let along = CustomLine::new("AB".to_string(), LineAnchor::Vertex(0), LineAnchor::Side { side: 0, ratio: 0.5 }, &quad, &[]);
+        assert!(quad.split_by_line(&along).is_none());
+        let centroid = CustomLine::new("S".to_string(), LineAnchor::Centroid, LineAnchor::Vertex(1), &quad, &[]);
+        assert!(quad.split_by_line(&centroid).is_none());
+    }
+}
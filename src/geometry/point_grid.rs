@@ -0,0 +1,132 @@
+// Regelmäßiges Punktraster innerhalb des Vierecks (z.B. Ankerbolzen, Pflanzpläne)
+// Zeilen laufen parallel zur gewählten Seite, das Raster beginnt am Anfangspunkt dieser Seite
+
+use super::stakeout::{Baseline, LocalFrame};
+use super::types::{Point, Quadrilateral};
+use serde::{Deserialize, Serialize};
+
+/// Obergrenze, damit ein zu kleiner Abstand die Oberfläche nicht blockiert
+const MAX_POINTS: usize = 10_000;
+
+/// Abstände in mm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PointGridSpec {
+    pub side: usize,        // Ausrichtung: Zeilen parallel zu dieser Seite (0=AB … 3=DA)
+    pub spacing_along_mm: f64,
+    pub spacing_across_mm: f64,
+    pub margin_mm: f64,     // Mindestabstand zu allen Seiten
+}
+
+impl Default for PointGridSpec {
+    fn default() -> Self {
+        Self {
+            side: 0,
+            spacing_along_mm: 1000.0,
+            spacing_across_mm: 1000.0,
+            margin_mm: 500.0,
+        }
+    }
+}
+
+/// Rasterpunkt; Zeile 1 liegt der Bezugsseite am nächsten, Spalte 1 ihrem Anfangspunkt
+#[derive(Debug, Clone)]
+pub struct GridPoint {
+    pub row: usize,
+    pub column: usize,
+    pub point: Point,
+}
+
+/// Liegt der Punkt im Polygon und mindestens `margin` von allen Kanten entfernt?
+fn inside_with_margin(p: (f64, f64), polygon: &[(f64, f64)], margin: f64) -> bool {
+    let mut inside = false;
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+            inside = !inside;
+        }
+
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len_sq = dx * dx + dy * dy;
+        let t = if len_sq > 0.0 { (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+        let (cx, cy) = (a.0 + t * dx - p.0, a.1 + t * dy - p.1);
+        if (cx * cx + cy * cy).sqrt() < margin - 1e-6 {
+            return false;
+        }
+    }
+    inside
+}
+
+impl Quadrilateral {
+    /// Rasterpunkte im Viereck, zeilenweise von der Bezugsseite aus
+    pub fn point_grid(&self, spec: &PointGridSpec) -> Result<Vec<GridPoint>, String> {
+        if spec.spacing_along_mm <= 0.0 || spec.spacing_across_mm <= 0.0 || spec.margin_mm < 0.0 {
+            return Err("Rasterabstände müssen größer als 0 sein".to_string());
+        }
+
+        let baseline = Baseline { origin: spec.side % 4, axis_to: (spec.side + 1) % 4 };
+        let frame = LocalFrame::new(self, baseline).ok_or_else(|| "Bezugsseite hat die Länge 0".to_string())?;
+        let polygon: Vec<(f64, f64)> = self.vertices.iter().map(|v| frame.to_local_mm(v)).collect();
+
+        let (min_x, max_x) = polygon.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+        let (min_y, max_y) = polygon.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+        let steps = |min: f64, max: f64, spacing: f64| {
+            (((min - spec.margin_mm) / spacing).ceil() as i64)..=(((max - spec.margin_mm) / spacing).floor() as i64)
+        };
+        let (columns, rows) = (steps(min_x, max_x, spec.spacing_along_mm), steps(min_y, max_y, spec.spacing_across_mm));
+        let count = (columns.end() - columns.start() + 1).max(0) * (rows.end() - rows.start() + 1).max(0);
+        if count as usize > MAX_POINTS {
+            return Err(format!("Mehr als {} Rasterpunkte – bitte größeren Abstand wählen", MAX_POINTS));
+        }
+
+        let mut cells = Vec::new();
+        for ky in rows {
+            for kx in columns.clone() {
+                let local = (
+                    spec.margin_mm + kx as f64 * spec.spacing_along_mm,
+                    spec.margin_mm + ky as f64 * spec.spacing_across_mm,
+                );
+                if inside_with_margin(local, &polygon, spec.margin_mm) {
+                    cells.push((kx, ky, local));
+                }
+            }
+        }
+
+        // Nummerierung ab dem ersten belegten Feld
+        let first_column = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let first_row = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        Ok(cells
+            .into_iter()
+            .map(|(kx, ky, (x, y))| GridPoint {
+                row: (ky - first_row) as usize + 1,
+                column: (kx - first_column) as usize + 1,
+                point: frame.to_world(x, y),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{solve, PointGridSpec, QuadInput};
+
+    #[test]
+    fn test_point_grid_rectangle() {
+        let quad = solve(&QuadInput::rectangle(4000.0, 3000.0)).unwrap().quad;
+        let points = quad.point_grid(&PointGridSpec::default()).unwrap();
+        // 4 Spalten (500, 1500, 2500, 3500) × 3 Zeilen (500, 1500, 2500)
+        assert_eq!(points.len(), 12);
+        assert_eq!((points[0].row, points[0].column), (1, 1));
+        assert_eq!((points[11].row, points[11].column), (3, 4));
+
+        let a = &quad.vertices[0];
+        let distance = ((points[0].point.x - a.x).powi(2) + (points[0].point.y - a.y).powi(2)).sqrt();
+        assert!((distance - 500_000.0 * 2f64.sqrt()).abs() < 1.0);
+
+        let spec = PointGridSpec { spacing_along_mm: 0.0, ..PointGridSpec::default() };
+        assert!(quad.point_grid(&spec).is_err());
+        let spec = PointGridSpec { spacing_along_mm: 1.0, spacing_across_mm: 1.0, ..PointGridSpec::default() };
+        assert!(quad.point_grid(&spec).is_err());
+    }
+}
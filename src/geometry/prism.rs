@@ -0,0 +1,115 @@
+// Raum als Prisma: Grundriss um die Raumhöhe extrudiert
+// Volumen, Wandflächen und eine einfache Parallelprojektion für die 3D-Vorschau
+
+use super::types::Quadrilateral;
+
+/// Seitenflächen und Kennwerte des extrudierten Vierecks
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrismMeasures {
+    pub floor_m2: f64,
+    pub wall_areas_m2: [f64; 4], // Wand über AB, BC, CD, DA
+    pub volume_m3: f64,
+}
+
+impl PrismMeasures {
+    pub fn total_wall_area_m2(&self) -> f64 {
+        self.wall_areas_m2.iter().sum()
+    }
+}
+
+/// Blickrichtung der 3D-Vorschau
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrismView {
+    pub yaw_deg: f64,   // Drehung um die senkrechte Achse
+    pub pitch_deg: f64, // Blickhöhe: 0° = Ansicht von vorn, 90° = Draufsicht
+}
+
+impl Default for PrismView {
+    fn default() -> Self {
+        Self { yaw_deg: 30.0, pitch_deg: 30.0 }
+    }
+}
+
+/// Projizierter Eckpunkt: Bildkoordinaten in mm (y nach oben) und Tiefe (größer = näher am Betrachter)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectedPoint {
+    pub x: f64,
+    pub y: f64,
+    pub depth: f64,
+}
+
+impl Quadrilateral {
+    /// Fläche, Wandflächen und Volumen bei gegebener Raumhöhe
+    pub fn prism_measures(&self, height_mm: f64) -> PrismMeasures {
+        let floor_m2 = self.area_mm2() / 1_000_000.0;
+        let height_m = height_mm / 1000.0;
+        let wall_areas_m2 = std::array::from_fn(|i| self.get_side_length_mm(i) / 1000.0 * height_m);
+        PrismMeasures { floor_m2, wall_areas_m2, volume_m3: floor_m2 * height_m }
+    }
+
+    /// Projiziert die 8 Ecken des Prismas (0–3 Boden A–D, 4–7 Decke A–D)
+    /// Drehpunkt ist der Schwerpunkt der Eckpunkte auf halber Höhe
+    pub fn project_prism(&self, height_mm: f64, view: &PrismView) -> [ProjectedPoint; 8] {
+        let cx = self.vertices.iter().map(|v| v.x).sum::<f64>() / 4000.0;
+        let cy = self.vertices.iter().map(|v| v.y).sum::<f64>() / 4000.0;
+        let (sin_yaw, cos_yaw) = view.yaw_deg.to_radians().sin_cos();
+        let (sin_pitch, cos_pitch) = view.pitch_deg.to_radians().sin_cos();
+
+        std::array::from_fn(|i| {
+            let vertex = &self.vertices[i % 4];
+            let x = vertex.x / 1000.0 - cx;
+            let y = vertex.y / 1000.0 - cy;
+            let z = if i < 4 { -height_mm / 2.0 } else { height_mm / 2.0 };
+
+            // Drehung um die Hochachse, dann Neigung zum Betrachter
+            let rx = x * cos_yaw - y * sin_yaw;
+            let ry = x * sin_yaw + y * cos_yaw;
+            ProjectedPoint {
+                x: rx,
+                y: ry * sin_pitch + z * cos_pitch,
+                depth: -ry * cos_pitch + z * sin_pitch,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    fn room() -> Quadrilateral {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(4_000_000.0, 3_000_000.0),
+            Point::new(0.0, 3_000_000.0),
+        ];
+        quad
+    }
+
+    #[test]
+    fn test_prism_measures() {
+        let measures = room().prism_measures(2500.0);
+        assert!((measures.floor_m2 - 12.0).abs() < 1e-9);
+        assert!((measures.volume_m3 - 30.0).abs() < 1e-9);
+        assert!((measures.wall_areas_m2[0] - 10.0).abs() < 1e-9);
+        assert!((measures.wall_areas_m2[1] - 7.5).abs() < 1e-9);
+        assert!((measures.total_wall_area_m2() - 35.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_project_prism() {
+        // Draufsicht: Boden und Decke fallen zusammen, Decke liegt vorn
+        let top = room().project_prism(2500.0, &PrismView { yaw_deg: 0.0, pitch_deg: 90.0 });
+        assert!((top[0].x + 2000.0).abs() < 1e-9);
+        assert!((top[0].y + 1500.0).abs() < 1e-9);
+        assert!((top[4].x - top[0].x).abs() < 1e-9 && (top[4].y - top[0].y).abs() < 1e-9);
+        assert!(top[4].depth > top[0].depth);
+
+        // Vorderansicht: Höhe erscheint unverzerrt
+        let front = room().project_prism(2500.0, &PrismView { yaw_deg: 0.0, pitch_deg: 0.0 });
+        assert!((front[4].y - front[0].y - 2500.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,430 @@
+// Einstiegspunkt für die Berechnung aus Benutzereingaben
+// Wird von der UI und vom Kommandozeilen-Modus gemeinsam verwendet
+
+use super::error::{GeometryError, PlausibilityWarning};
+use super::trace::ConstructionTrace;
+use super::types::Quadrilateral;
+use super::validation::PlausibilityTolerance;
+use crate::config::LengthUnit;
+use crate::format::NumberFormat;
+use serde::{Deserialize, Serialize};
+
+pub const SIDE_NAMES: [&str; 4] = ["AB", "BC", "CD", "DA"];
+pub const VERTEX_NAMES: [&str; 4] = ["A", "B", "C", "D"];
+pub const DIAGONAL_NAMES: [&str; 2] = ["AC", "BD"];
+
+/// Eingabewerte wie vom Benutzer angegeben
+/// Seiten in Millimetern, Winkel in Grad; None = nicht gemessen
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuadInput {
+    pub sides_mm: [Option<f64>; 4], // AB, BC, CD, DA
+    pub angles: [Option<f64>; 4],   // A, B, C, D
+    #[serde(default)]
+    pub diagonals_mm: [Option<f64>; 2], // AC, BD
+}
+
+impl QuadInput {
+    /// Rechteck aus Breite (AB, CD) und Höhe (BC, DA) mit rechtem Winkel bei A
+    pub fn rectangle(width_mm: f64, height_mm: f64) -> Self {
+        Self {
+            sides_mm: [Some(width_mm), Some(height_mm), Some(width_mm), Some(height_mm)],
+            angles: [Some(90.0), None, None, None],
+            diagonals_mm: [None; 2],
+        }
+    }
+}
+
+/// Ab dieser Abweichung gilt ein angezeigter Wert nicht mehr als der gemessene (Rundung der µm-Koordinaten)
+const SAME_LENGTH_MM: f64 = 0.002;
+const SAME_ANGLE_DEG: f64 = 0.001;
+
+/// Herkunft eines angezeigten Werts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueSource {
+    Measured,      // Eingabe, von der Konstruktion unverändert
+    Adjusted(f64), // Eingegeben, die Konstruktion ergibt aber einen anderen Wert (enthält die Eingabe)
+    Calculated,
+}
+
+impl ValueSource {
+    fn of(measured: Option<f64>, shown: f64, same: f64) -> Self {
+        match measured {
+            Some(value) if (value - shown).abs() <= same => Self::Measured,
+            Some(value) => Self::Adjusted(value),
+            None => Self::Calculated,
+        }
+    }
+
+    /// Bezeichnung, bei abweichender Konstruktion mit dem Messwert ("angepasst, gemessen 150,3 cm")
+    pub fn describe(self, format: impl Fn(f64) -> String) -> String {
+        match self {
+            Self::Measured => "gemessen".to_string(),
+            Self::Adjusted(measured) => format!("angepasst, gemessen {}", format(measured)),
+            Self::Calculated => "berechnet".to_string(),
+        }
+    }
+}
+
+impl QuadInput {
+    /// Herkunft der angezeigten Seitenlänge (mm)
+    pub fn side_source(&self, side: usize, shown_mm: f64) -> ValueSource {
+        ValueSource::of(self.sides_mm[side], shown_mm, SAME_LENGTH_MM)
+    }
+
+    /// Herkunft der angezeigten Diagonale (mm)
+    pub fn diagonal_source(&self, diagonal: usize, shown_mm: f64) -> ValueSource {
+        ValueSource::of(self.diagonals_mm[diagonal], shown_mm, SAME_LENGTH_MM)
+    }
+
+    /// Herkunft des angezeigten Innenwinkels (Grad)
+    pub fn angle_source(&self, vertex: usize, shown_deg: f64) -> ValueSource {
+        ValueSource::of(self.angles[vertex], shown_deg, SAME_ANGLE_DEG)
+    }
+}
+
+/// Ergebnis der Berechnung: Eckpunkte sowie alle Seiten und Winkel (gemessen oder berechnet)
+#[derive(Clone, Debug)]
+pub struct QuadSolution {
+    pub quad: Quadrilateral,
+    pub sides_mm: [f64; 4],   // AB, BC, CD, DA aus den Eckpunkten
+    pub angles_deg: [f64; 4], // A, B, C, D
+    pub trace: ConstructionTrace, // Konstruktionsweg mit Zwischenwerten
+    pub warnings: Vec<PlausibilityWarning>, // Widersprüche im Warnbereich
+}
+
+/// Berechnet das Viereck, ohne die Eingabe zu verändern
+pub fn solve(input: &QuadInput) -> Result<QuadSolution, GeometryError> {
+    solve_with_tolerance(input, &PlausibilityTolerance::STRICT)
+}
+
+/// Wie `solve`, kleine Widersprüche im Warnbereich ergeben aber Warnungen statt eines Fehlers
+pub fn solve_with_tolerance(input: &QuadInput, tolerance: &PlausibilityTolerance) -> Result<QuadSolution, GeometryError> {
+    let mut quad = Quadrilateral::new();
+
+    for (name, side) in SIDE_NAMES.iter().zip(input.sides_mm.iter()) {
+        if let Some(mm) = side {
+            quad.set_side_mm(name, *mm);
+        }
+    }
+
+    quad.angle_a = input.angles[0];
+    quad.angle_b = input.angles[1];
+    quad.angle_c = input.angles[2];
+    quad.angle_d = input.angles[3];
+    quad.diagonal_ac_um = input.diagonals_mm[0].map(Quadrilateral::mm_to_um);
+    quad.diagonal_bd_um = input.diagonals_mm[1].map(Quadrilateral::mm_to_um);
+
+    let (method, warnings) = quad.calculate(tolerance)?;
+    // Bei drei gemessenen Winkeln ergänzt die Berechnung den vierten aus der Winkelsumme
+    let missing_angle = match input.angles.iter().flatten().count() {
+        3 => input.angles.iter().position(Option::is_none),
+        _ => None,
+    };
+    let trace = ConstructionTrace::new(method, &quad, missing_angle);
+    // Der Konstruktionsweg braucht noch die gemessenen Werte, das Ergebnis nur noch die gezeichneten
+    quad.store_sides_from_vertices();
+    quad.store_angles_from_vertices();
+
+    let sides_mm = [0, 1, 2, 3].map(|side| quad.get_side_length_mm(side));
+    let angles_deg = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d].map(|angle| angle.unwrap_or_default());
+    Ok(QuadSolution { quad, sides_mm, angles_deg, trace, warnings })
+}
+
+/// Einheiten, die hinter einer Länge stehen dürfen; "mm" vor "m" prüfen
+const LENGTH_SUFFIXES: [(&str, f64); 4] = [("mm", 1.0), ("cm", 10.0), ("dm", 100.0), ("m", 1000.0)];
+
+/// Liest eine Längeneingabe in mm im eingestellten Zahlenformat, Zahlen ohne Einheit in mm
+/// Leere Eingabe = Ok(None), ungültige oder nicht positive Werte = Err mit Hinweis
+pub fn parse_length_input(text: &str, number: &NumberFormat) -> Result<Option<f64>, String> {
+    parse_length_with_unit(text, number, LengthUnit::Millimeter)
+}
+
+/// Wie `parse_length_input`, eine Einheit hinter der Zahl ("2,5 m", "250cm") hat Vorrang,
+/// Zahlen ohne Einheit gelten in `default_unit`; Ergebnis in mm
+pub fn parse_length_with_unit(text: &str, number: &NumberFormat, default_unit: LengthUnit) -> Result<Option<f64>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let lower = text.to_lowercase();
+    let (digits, mm_per_unit) = LENGTH_SUFFIXES
+        .iter()
+        .find_map(|(suffix, factor)| lower.strip_suffix(suffix).map(|rest| (rest.trim_end(), *factor)))
+        .unwrap_or((lower.as_str(), default_unit.mm_per_unit()));
+    let value = number.parse(digits).ok_or_else(|| "Keine gültige Zahl".to_string())?;
+
+    if !value.is_finite() || value <= 0.0 {
+        return Err("Länge muss größer als 0 sein".to_string());
+    }
+
+    Ok(Some(value * mm_per_unit))
+}
+
+/// Liest eine Winkeleingabe in Grad (0° < Winkel < 360°)
+pub fn parse_angle_input(text: &str, number: &NumberFormat) -> Result<Option<f64>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let value = number.parse(text).ok_or_else(|| "Keine gültige Zahl".to_string())?;
+
+    if !value.is_finite() || value <= 0.0 || value >= 360.0 {
+        return Err("Winkel muss zwischen 0° und 360° liegen".to_string());
+    }
+
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    #[test]
+    fn test_solve_rectangle() {
+        let input = QuadInput {
+            sides_mm: [Some(4000.0), Some(3000.0), Some(4000.0), Some(3000.0)],
+            angles: [Some(90.0), None, None, None],
+            ..QuadInput::default()
+        };
+
+        let quad = solve(&input).unwrap().quad;
+        assert!((quad.angle_c.unwrap() - 90.0).abs() < 1e-6);
+        assert!((quad.area_mm2() - 12_000_000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_rectangle_input() {
+        let quad = solve(&QuadInput::rectangle(5000.0, 2000.0)).unwrap().quad;
+        assert!((quad.get_side_length_mm(2) - 5000.0).abs() < 1e-3);
+        assert!((quad.get_side_length_mm(3) - 2000.0).abs() < 1e-3);
+        assert_eq!(quad.diagonal_um(0), quad.diagonal_um(1));
+    }
+
+    #[test]
+    fn test_solve_not_enough_values() {
+        let input = QuadInput {
+            sides_mm: [Some(4000.0), Some(3000.0), None, None],
+            angles: [Some(90.0), None, None, None],
+            ..QuadInput::default()
+        };
+
+        assert!(solve(&input).is_err());
+    }
+
+    /// Alle unterstützten Kombinationen für ein Viereck mit bekannten Seiten und Winkeln:
+    /// 4 Seiten + 1 oder 2 Diagonalen, 4 Seiten + 1 oder 2 benachbarte Winkel,
+    /// 3 Seiten + die beiden Winkel gegenüber der fehlenden Seite
+    fn input_combinations(sides: [f64; 4], angles: [f64; 4], diagonals: [f64; 2]) -> Vec<QuadInput> {
+        let mut inputs = Vec::new();
+        for given in [[true, false], [false, true], [true, true]] {
+            let diagonals_mm = [0, 1].map(|i| Some(diagonals[i]).filter(|_| given[i]));
+            inputs.push(QuadInput { sides_mm: sides.map(Some), angles: [None; 4], diagonals_mm });
+        }
+        for i in 0..4 {
+            let mut input = QuadInput { sides_mm: sides.map(Some), ..QuadInput::default() };
+            input.angles[i] = Some(angles[i]);
+            inputs.push(input.clone());
+            input.angles[(i + 1) % 4] = Some(angles[(i + 1) % 4]);
+            inputs.push(input);
+
+            let mut input = QuadInput { sides_mm: sides.map(Some), ..QuadInput::default() };
+            input.sides_mm[i] = None;
+            input.angles[(i + 2) % 4] = Some(angles[(i + 2) % 4]);
+            input.angles[(i + 3) % 4] = Some(angles[(i + 3) % 4]);
+            inputs.push(input);
+        }
+        inputs
+    }
+
+    /// 3 Seiten + ein anderes benachbartes Winkelpaar; Strahl und Kreis können sich zweimal
+    /// schneiden, eindeutig sind nur die Vorgaben
+    fn ambiguous_combinations(sides: [f64; 4], angles: [f64; 4]) -> Vec<QuadInput> {
+        let mut inputs = Vec::new();
+        for missing in 0..4 {
+            for first in [missing, missing + 1, missing + 3] {
+                let mut input = QuadInput { sides_mm: sides.map(Some), ..QuadInput::default() };
+                input.sides_mm[missing] = None;
+                input.angles[first % 4] = Some(angles[first % 4]);
+                input.angles[(first + 1) % 4] = Some(angles[(first + 1) % 4]);
+                inputs.push(input);
+            }
+        }
+        inputs
+    }
+
+    #[test]
+    fn test_solve_reconstructs_inputs() {
+        // Sehnenvierecke mit zufälligen Eckpunkten auf einem Kreis (fester Startwert)
+        let mut seed: u64 = 0x5eed;
+        let mut random = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let close = |a: &[f64], b: &[f64], tolerance: f64| a.iter().zip(b).all(|(x, y)| (x - y).abs() < tolerance);
+
+        for _ in 0..200 {
+            let radius_mm = 1000.0 + 5000.0 * random();
+            let mut directions = [0.0; 4];
+            let mut direction = 0.0;
+            for d in directions.iter_mut() {
+                *d = direction;
+                direction += 50.0 + 40.0 * random(); // Abstand 50° bis 90°, Rest ≥ 90°
+            }
+            let points = directions.map(|deg: f64| {
+                let rad = deg.to_radians();
+                Point::new(radius_mm * 1000.0 * rad.cos(), radius_mm * 1000.0 * rad.sin())
+            });
+            let mut truth = Quadrilateral::new();
+            truth.vertices = points;
+            truth.calculate_angles_from_vertices();
+            let sides = [0, 1, 2, 3].map(|side| truth.get_side_length_mm(side));
+            let angles = [truth.angle_a, truth.angle_b, truth.angle_c, truth.angle_d].map(Option::unwrap);
+            let diagonals = [0, 1].map(|i| truth.diagonal_um(i) as f64 / 1000.0);
+
+            for input in input_combinations(sides, angles, diagonals) {
+                let solution = solve(&input).unwrap_or_else(|e| panic!("{:?}: {}", input, e));
+                assert!(close(&solution.sides_mm, &sides, 0.01), "{:?}", input);
+                assert!(close(&solution.angles_deg, &angles, 1e-3), "{:?}", input);
+            }
+
+            for input in ambiguous_combinations(sides, angles) {
+                let solution = solve(&input).unwrap_or_else(|e| panic!("{:?}: {}", input, e));
+                for (given, (calculated, truth)) in input.sides_mm.iter().zip(solution.sides_mm.iter().zip(sides)) {
+                    assert!(given.is_none() || (calculated - truth).abs() < 0.01, "{:?}", input);
+                }
+                for (given, (calculated, truth)) in input.angles.iter().zip(solution.angles_deg.iter().zip(angles)) {
+                    assert!(given.is_none() || (calculated - truth).abs() < 1e-3, "{:?}", input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let input = QuadInput { sides_mm: [Some(4000.0), Some(3000.0), None, None], angles: [Some(90.0), None, None, None], ..QuadInput::default() };
+        assert_eq!(solve(&input).unwrap_err(), GeometryError::MissingInput { sides: 2, angles: 1 });
+
+        let mut input = QuadInput::rectangle(4000.0, 3000.0);
+        input.angles = [Some(90.0), Some(90.0), Some(90.0), Some(100.0)];
+        assert_eq!(solve(&input).unwrap_err(), GeometryError::AngleSumInvalid { sum_deg: 370.0 });
+
+        // Rechte Winkel bei A und B, gegenüberliegende Seiten passen aber nicht zusammen
+        let input = QuadInput {
+            sides_mm: [Some(4000.0), Some(3000.0), Some(4000.0), Some(2000.0)],
+            angles: [Some(90.0), Some(90.0), None, None],
+            ..QuadInput::default()
+        };
+        assert!(matches!(solve(&input).unwrap_err(), GeometryError::SideMismatch { side: 2, .. }));
+
+        let input = QuadInput {
+            sides_mm: [Some(1000.0), Some(1000.0), Some(1000.0), Some(5000.0)],
+            angles: [Some(90.0), None, None, None],
+            ..QuadInput::default()
+        };
+        assert_eq!(solve(&input).unwrap_err(), GeometryError::CirclesDontIntersect);
+        assert_eq!(solve(&input).unwrap_err(), GeometryError::CirclesDontIntersect);
+
+        // 3-4-5: Diagonale AC passt, BD ist zu lang gemessen
+        let mut input = QuadInput { sides_mm: [Some(4000.0), Some(3000.0), Some(4000.0), Some(3000.0)], ..QuadInput::default() };
+        input.diagonals_mm = [Some(5000.0), Some(5100.0)];
+        assert!(matches!(solve(&input).unwrap_err(), GeometryError::DiagonalMismatch { diagonal: 1, .. }));
+        input.diagonals_mm[1] = None;
+        assert!((solve(&input).unwrap().angles_deg[1] - 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_plausibility_warnings() {
+        let tolerance = PlausibilityTolerance::default();
+
+        // CD 3 mm zu lang gemessen: Warnung, Viereck wird trotzdem konstruiert
+        let input = QuadInput {
+            sides_mm: [Some(1500.0), Some(1000.0), Some(1503.0), Some(1000.0)],
+            angles: [Some(90.0), Some(90.0), None, None],
+            ..QuadInput::default()
+        };
+        assert!(matches!(solve(&input).unwrap_err(), GeometryError::SideMismatch { side: 2, .. }));
+        let solution = solve_with_tolerance(&input, &tolerance).unwrap();
+        assert_eq!(
+            solution.warnings,
+            vec![PlausibilityWarning::SideDeviation { side: 2, expected_um: 1_503_000, actual_um: 1_500_000 }]
+        );
+        // Als Ergebnis steht die gezeichnete Seite, nicht der Messwert
+        assert_eq!(solution.quad.side_cd_um, Some(1_500_000));
+
+        // Diagonale 4 mm daneben: Warnung, 100 mm daneben: Fehler
+        let mut input = QuadInput { sides_mm: [Some(1500.0), Some(2000.0), Some(1500.0), Some(2000.0)], ..QuadInput::default() };
+        input.diagonals_mm = [Some(2500.0), Some(2504.0)];
+        let warnings = solve_with_tolerance(&input, &tolerance).unwrap().warnings;
+        assert!(matches!(warnings[..], [PlausibilityWarning::DiagonalDeviation { diagonal: 1, .. }]));
+        input.diagonals_mm[1] = Some(2600.0);
+        assert!(matches!(solve_with_tolerance(&input, &tolerance).unwrap_err(), GeometryError::DiagonalMismatch { .. }));
+
+        // Raumgroße Seiten: 3 mm auf 10 m liegen über der Rechentoleranz und ergeben eine Warnung
+        let input = QuadInput {
+            sides_mm: [Some(10_000.0), Some(4000.0), Some(10_003.0), Some(4000.0)],
+            angles: [Some(90.0), Some(90.0), None, None],
+            ..QuadInput::default()
+        };
+        assert!(matches!(solve(&input).unwrap_err(), GeometryError::SideMismatch { side: 2, .. }));
+        let warnings = solve_with_tolerance(&input, &tolerance).unwrap().warnings;
+        assert!(matches!(warnings[..], [PlausibilityWarning::SideDeviation { side: 2, .. }]));
+
+        // Winkelsumme 360,8°: Fehlbetrag wird verteilt, 370° bleibt ein Fehler
+        let mut input = QuadInput::rectangle(4000.0, 3000.0);
+        input.angles = [Some(90.2), Some(90.2), Some(90.2), Some(90.2)];
+        let solution = solve_with_tolerance(&input, &tolerance).unwrap();
+        assert!(matches!(solution.warnings[..], [PlausibilityWarning::AngleSum { .. }]));
+        assert!((solution.angles_deg[0] - 90.0).abs() < 1e-6);
+        input.angles = [Some(90.0), Some(90.0), Some(90.0), Some(100.0)];
+        assert!(matches!(solve_with_tolerance(&input, &tolerance).unwrap_err(), GeometryError::AngleSumInvalid { .. }));
+    }
+
+    #[test]
+    fn test_value_sources() {
+        let tolerance = PlausibilityTolerance::default();
+        let input = QuadInput {
+            sides_mm: [Some(1500.0), Some(1000.0), Some(1503.0), Some(1000.0)],
+            angles: [Some(90.0), Some(90.0), None, None],
+            ..QuadInput::default()
+        };
+        let solution = solve_with_tolerance(&input, &tolerance).unwrap();
+        assert_eq!(input.side_source(0, solution.sides_mm[0]), ValueSource::Measured);
+        assert_eq!(input.side_source(2, solution.sides_mm[2]), ValueSource::Adjusted(1503.0));
+        assert_eq!(input.angle_source(0, solution.angles_deg[0]), ValueSource::Measured);
+        assert_eq!(input.angle_source(2, solution.angles_deg[2]), ValueSource::Calculated);
+        assert_eq!(input.side_source(2, solution.sides_mm[2]).describe(|mm| format!("{} mm", mm)), "angepasst, gemessen 1503 mm");
+
+        // Alle Winkel stammen aus den Eckpunkten, auch die eingegebenen
+        let mut input = QuadInput::rectangle(4000.0, 3000.0);
+        input.angles = [Some(90.2), Some(90.2), Some(90.2), Some(90.2)];
+        let solution = solve_with_tolerance(&input, &tolerance).unwrap();
+        for vertex in 0..4 {
+            assert!((solution.angles_deg[vertex] - 90.0).abs() < 1e-6);
+            assert_eq!(input.angle_source(vertex, solution.angles_deg[vertex]), ValueSource::Adjusted(90.2));
+        }
+    }
+
+    #[test]
+    fn test_input_validation() {
+        let number = NumberFormat::default();
+        assert_eq!(parse_length_input("", &number), Ok(None));
+        assert_eq!(parse_length_input(" 12,5 ", &number), Ok(Some(12.5)));
+        assert!(parse_length_input("-3", &number).is_err());
+        assert!(parse_length_input("abc", &number).is_err());
+
+        assert_eq!(parse_length_input("2,5 m", &number), Ok(Some(2500.0)));
+        assert_eq!(parse_length_input("250cm", &number), Ok(Some(2500.0)));
+        assert_eq!(parse_length_input("2500 MM", &number), Ok(Some(2500.0)));
+        assert_eq!(parse_length_with_unit("2,5", &number, LengthUnit::Meter), Ok(Some(2500.0)));
+        assert_eq!(parse_length_with_unit("12 mm", &number, LengthUnit::Meter), Ok(Some(12.0)));
+        assert!(parse_length_input("m", &number).is_err());
+        assert!(parse_length_input("3 km", &number).is_err());
+
+        assert_eq!(parse_angle_input("90", &number), Ok(Some(90.0)));
+        assert!(parse_angle_input("360", &number).is_err());
+        assert!(parse_angle_input("0", &number).is_err());
+    }
+}
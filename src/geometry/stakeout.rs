@@ -0,0 +1,162 @@
+// Lokales Koordinatensystem für die Absteckung
+// Ursprung in einem Eckpunkt, X-Achse entlang einer angrenzenden Seite
+// Dazu die Stationierung entlang des Umfangs für das Übertragen mit einem Maßband
+
+use super::types::{CustomLine, LineAnchor, Point, Quadrilateral};
+
+/// Bezugssystem: Ursprung und Richtungspunkt der X-Achse (jeweils Eckpunkt-Index)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Baseline {
+    pub origin: usize,
+    pub axis_to: usize, // Nachbar-Eckpunkt, z.B. B für die Achse AB
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self { origin: 0, axis_to: 1 }
+    }
+}
+
+/// Rechnet Weltkoordinaten in das lokale System der Basislinie um (mm)
+/// Die Y-Achse zeigt ins Viereck hinein, damit die Fläche bei positiven Y-Werten liegt
+#[derive(Clone, Debug)]
+pub struct LocalFrame {
+    origin: Point,
+    axis: (f64, f64),   // Einheitsvektor der X-Achse
+    normal: (f64, f64), // Einheitsvektor der Y-Achse
+}
+
+impl LocalFrame {
+    pub fn new(quad: &Quadrilateral, baseline: Baseline) -> Option<Self> {
+        let origin = quad.vertices[baseline.origin].clone();
+        let target = &quad.vertices[baseline.axis_to];
+        let (dx, dy) = (target.x - origin.x, target.y - origin.y);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            return None;
+        }
+        let axis = (dx / len, dy / len);
+
+        // Normale zur Seite des Schwerpunkts der Eckpunkte drehen
+        let cx = quad.vertices.iter().map(|v| v.x).sum::<f64>() / 4.0 - origin.x;
+        let cy = quad.vertices.iter().map(|v| v.y).sum::<f64>() / 4.0 - origin.y;
+        let mut normal = (-axis.1, axis.0);
+        if cx * normal.0 + cy * normal.1 < 0.0 {
+            normal = (-normal.0, -normal.1);
+        }
+
+        Some(Self { origin, axis, normal })
+    }
+
+    /// Lokale Koordinaten (X, Y) in mm
+    pub fn to_local_mm(&self, p: &Point) -> (f64, f64) {
+        let (dx, dy) = (p.x - self.origin.x, p.y - self.origin.y);
+        (
+            (dx * self.axis.0 + dy * self.axis.1) / 1000.0,
+            (dx * self.normal.0 + dy * self.normal.1) / 1000.0,
+        )
+    }
+
+    /// Umkehrung von `to_local_mm`: Punkt in µm aus lokalen Koordinaten in mm
+    pub fn to_world(&self, x_mm: f64, y_mm: f64) -> Point {
+        Point::new(
+            self.origin.x + (x_mm * self.axis.0 + y_mm * self.normal.0) * 1000.0,
+            self.origin.y + (x_mm * self.axis.1 + y_mm * self.normal.1) * 1000.0,
+        )
+    }
+}
+
+/// Woher eine Station auf dem Umfang stammt
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StationSource {
+    Vertex(usize),
+    LineEnd { line: usize, at_start: bool },
+}
+
+/// Punkt auf dem Umfang mit seinem Abstand ab A
+#[derive(Clone, Debug)]
+pub struct Station {
+    pub station_mm: f64, // Entlang des Umfangs in Richtung A→B→C→D
+    pub point: Point,
+    pub source: StationSource,
+}
+
+/// Stationierung aller Eckpunkte und aller Linienenden, die auf einer Seite liegen
+/// Aufsteigend sortiert, A hat Station 0
+pub fn perimeter_stations(quad: &Quadrilateral, lines: &[CustomLine]) -> Vec<Station> {
+    let mut side_start_mm = [0.0; 4];
+    for side in 1..4 {
+        side_start_mm[side] = side_start_mm[side - 1] + quad.get_side_length_mm(side - 1);
+    }
+
+    let mut stations: Vec<Station> = (0..4)
+        .map(|i| Station {
+            station_mm: side_start_mm[i],
+            point: quad.vertices[i].clone(),
+            source: StationSource::Vertex(i),
+        })
+        .collect();
+
+    for (idx, line) in lines.iter().enumerate() {
+        for (anchor, point, at_start) in [(&line.start_anchor, &line.start, true), (&line.end_anchor, &line.end, false)] {
+            // Eckpunkt-Anker fallen mit der Station des Eckpunkts zusammen
+            if let LineAnchor::Side { side, ratio } = *anchor {
+                stations.push(Station {
+                    station_mm: side_start_mm[side] + ratio * quad.get_side_length_mm(side),
+                    point: point.clone(),
+                    source: StationSource::LineEnd { line: idx, at_start },
+                });
+            }
+        }
+    }
+
+    stations.sort_by(|a, b| a.station_mm.total_cmp(&b.station_mm));
+    stations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_frame() {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(1_000_000.0, 1_000_000.0),
+            Point::new(1_000_000.0, 4_000_000.0),
+            Point::new(5_000_000.0, 4_000_000.0),
+            Point::new(5_000_000.0, 1_000_000.0),
+        ];
+
+        let frame = LocalFrame::new(&quad, Baseline::default()).unwrap();
+        let (x, y) = frame.to_local_mm(&quad.vertices[1]);
+        assert!((x - 3_000.0).abs() < 1e-6 && y.abs() < 1e-6);
+        let (x, y) = frame.to_local_mm(&quad.vertices[2]);
+        assert!((x - 3_000.0).abs() < 1e-6 && (y - 4_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_perimeter_stations() {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(1_000_000.0, 0.0),
+            Point::new(1_000_000.0, 1_000_000.0),
+            Point::new(0.0, 1_000_000.0),
+        ];
+        let lines = vec![CustomLine::new(
+            "Linie 1".to_string(),
+            LineAnchor::Side { side: 0, ratio: 0.5 },
+            LineAnchor::Side { side: 2, ratio: 0.25 },
+            &quad,
+            &[],
+        )];
+
+        let stations: Vec<f64> = perimeter_stations(&quad, &lines).iter().map(|s| s.station_mm).collect();
+        let expected = [0.0, 500.0, 1_000.0, 2_000.0, 2_250.0, 3_000.0];
+        assert_eq!(stations.len(), expected.len());
+        for (station, expected) in stations.iter().zip(expected) {
+            assert!((station - expected).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Platten- und Fliesenbedarf
+// Einfache Verlegeheuristik: Raster entlang Seite AB, beginnend in Eckpunkt A.
+// Jede Platte, die die Fläche berührt, wird gezählt; Reststücke werden nicht wiederverwendet.
+
+use super::stakeout::{Baseline, LocalFrame};
+use super::types::Quadrilateral;
+use serde::{Deserialize, Serialize};
+
+/// Obergrenze, damit sehr kleine Platten die Oberfläche nicht blockieren
+const MAX_CELLS: i64 = 200_000;
+
+/// Plattenformat in mm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TileSpec {
+    pub width_mm: f64,
+    pub height_mm: f64,
+    pub joint_mm: f64, // Fugenbreite
+}
+
+impl Default for TileSpec {
+    fn default() -> Self {
+        Self {
+            width_mm: 600.0,
+            height_mm: 300.0,
+            joint_mm: 3.0,
+        }
+    }
+}
+
+/// Ergebnis der Bedarfsschätzung
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileEstimate {
+    pub full_tiles: usize,
+    pub cut_tiles: usize,
+    pub waste_percent: f64, // Anteil der gekauften Plattenfläche, der als Verschnitt abfällt
+}
+
+impl TileEstimate {
+    pub fn total_tiles(&self) -> usize {
+        self.full_tiles + self.cut_tiles
+    }
+}
+
+/// Fläche eines Polygons (mm²)
+fn polygon_area(points: &[(f64, f64)]) -> f64 {
+    let mut sum = 0.0;
+    for i in 0..points.len() {
+        let (x1, y1) = points[i];
+        let (x2, y2) = points[(i + 1) % points.len()];
+        sum += x1 * y2 - x2 * y1;
+    }
+    (sum / 2.0).abs()
+}
+
+/// Schneidet ein Polygon mit einem achsparallelen Rechteck (Sutherland-Hodgman)
+fn clip_to_rect(polygon: &[(f64, f64)], min: (f64, f64), max: (f64, f64)) -> Vec<(f64, f64)> {
+    // Jede Kante: Innen-Test und Schnittpunkt mit der Begrenzung
+    let edges: [(usize, f64, bool); 4] = [
+        (0, min.0, true),  // x >= min.x
+        (0, max.0, false), // x <= max.x
+        (1, min.1, true),  // y >= min.y
+        (1, max.1, false), // y <= max.y
+    ];
+
+    let mut output = polygon.to_vec();
+    for (axis, limit, keep_greater) in edges {
+        let input = std::mem::take(&mut output);
+        if input.is_empty() {
+            break;
+        }
+        let coord = |p: (f64, f64)| if axis == 0 { p.0 } else { p.1 };
+        let inside = |p: (f64, f64)| if keep_greater { coord(p) >= limit } else { coord(p) <= limit };
+        let intersect = |a: (f64, f64), b: (f64, f64)| {
+            let t = (limit - coord(a)) / (coord(b) - coord(a));
+            (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+        };
+
+        for i in 0..input.len() {
+            let current = input[i];
+            let previous = input[(i + input.len() - 1) % input.len()];
+            match (inside(previous), inside(current)) {
+                (true, true) => output.push(current),
+                (true, false) => output.push(intersect(previous, current)),
+                (false, true) => {
+                    output.push(intersect(previous, current));
+                    output.push(current);
+                }
+                (false, false) => {}
+            }
+        }
+    }
+    output
+}
+
+impl Quadrilateral {
+    /// Schätzt den Platten- bzw. Fliesenbedarf für die Viereckfläche
+    pub fn estimate_tiles(&self, spec: &TileSpec) -> Result<TileEstimate, String> {
+        if spec.width_mm <= 0.0 || spec.height_mm <= 0.0 || spec.joint_mm < 0.0 {
+            return Err("Plattenmaße müssen größer als 0 sein".to_string());
+        }
+
+        let frame = LocalFrame::new(self, Baseline::default())
+            .ok_or_else(|| "Seite AB hat die Länge 0".to_string())?;
+        let polygon: Vec<(f64, f64)> = self.vertices.iter().map(|v| frame.to_local_mm(v)).collect();
+
+        let pitch_x = spec.width_mm + spec.joint_mm;
+        let pitch_y = spec.height_mm + spec.joint_mm;
+        let (min_x, max_x) = polygon.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+        let (min_y, max_y) = polygon.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+
+        let (first_x, last_x) = ((min_x / pitch_x).floor() as i64, (max_x / pitch_x).ceil() as i64);
+        let (first_y, last_y) = ((min_y / pitch_y).floor() as i64, (max_y / pitch_y).ceil() as i64);
+        if (last_x - first_x) * (last_y - first_y) > MAX_CELLS {
+            return Err("Zu viele Platten für die Berechnung – bitte größeres Format wählen".to_string());
+        }
+
+        let tile_area = spec.width_mm * spec.height_mm;
+        let mut full_tiles = 0;
+        let mut cut_tiles = 0;
+        let mut used_area = 0.0;
+
+        for kx in first_x..last_x {
+            for ky in first_y..last_y {
+                let min = (kx as f64 * pitch_x, ky as f64 * pitch_y);
+                let max = (min.0 + spec.width_mm, min.1 + spec.height_mm);
+                let overlap = polygon_area(&clip_to_rect(&polygon, min, max));
+
+                if overlap >= tile_area * 0.999 {
+                    full_tiles += 1;
+                } else if overlap > tile_area * 1e-6 {
+                    cut_tiles += 1;
+                } else {
+                    continue;
+                }
+                used_area += overlap;
+            }
+        }
+
+        let bought_area = (full_tiles + cut_tiles) as f64 * tile_area;
+        let waste_percent = if bought_area > 0.0 {
+            (bought_area - used_area) / bought_area * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(TileEstimate { full_tiles, cut_tiles, waste_percent })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    fn rectangle(width_mm: f64, height_mm: f64) -> Quadrilateral {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(width_mm * 1000.0, 0.0),
+            Point::new(width_mm * 1000.0, height_mm * 1000.0),
+            Point::new(0.0, height_mm * 1000.0),
+        ];
+        quad
+    }
+
+    #[test]
+    fn test_exact_fit_without_waste() {
+        let spec = TileSpec { width_mm: 500.0, height_mm: 250.0, joint_mm: 0.0 };
+        let estimate = rectangle(2000.0, 1000.0).estimate_tiles(&spec).unwrap();
+        assert_eq!(estimate.full_tiles, 16);
+        assert_eq!(estimate.cut_tiles, 0);
+        assert!(estimate.waste_percent.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cut_tiles_and_waste() {
+        let spec = TileSpec { width_mm: 600.0, height_mm: 600.0, joint_mm: 0.0 };
+        let estimate = rectangle(1500.0, 600.0).estimate_tiles(&spec).unwrap();
+        assert_eq!(estimate.full_tiles, 2);
+        assert_eq!(estimate.cut_tiles, 1);
+        assert!((estimate.waste_percent - 100.0 * 0.5 / 3.0).abs() < 1e-6);
+    }
+}
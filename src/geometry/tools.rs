@@ -0,0 +1,562 @@
+// Konstruktionswerkzeuge auf Basis des berechneten Vierecks
+
+use super::stakeout::{Baseline, LocalFrame};
+use super::types::{LineAnchor, Point, Quadrilateral};
+use super::utils::{calculate_interior_angle, distance_f64, distance_um, ray_segment_intersection};
+
+/// Teildreieck für die Absteckung mit dem Maßband
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triangle {
+    pub vertices: [usize; 3],  // Eckpunkt-Indizes des Vierecks
+    pub sides_mm: [f64; 3],    // Seite i verbindet vertices[i] und vertices[(i + 1) % 3]
+    pub angles_deg: [f64; 3],  // Winkel an vertices[i]
+}
+
+/// Ergebnis des Kontrollmaßes: gemessene gegen berechnete Diagonale
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagonalCheck {
+    pub computed_mm: f64,
+    pub measured_mm: f64,
+    pub deviation_mm: f64,      // gemessen - berechnet
+    pub deviation_percent: f64, // bezogen auf die berechnete Diagonale
+    pub within_tolerance: bool,
+}
+
+/// Abweichungen eines Vierecks gegenüber einem Vergleichsviereck (dieses − Vergleich)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub sides_mm: [f64; 4],
+    pub angles_deg: [f64; 4],
+    pub area_m2: f64,
+}
+
+/// Zulässige Abweichung der Gegenwinkelsumme von 180° für ein Sehnenviereck
+pub const CYCLIC_TOLERANCE_DEG: f64 = 0.5;
+/// Zulässige Abweichung AB + CD gegen BC + DA (in % des Umfangs) für ein Tangentenviereck
+pub const TANGENTIAL_TOLERANCE_PERCENT: f64 = 0.5;
+
+/// Zulässiger Abstand gespiegelter Eckpunkte von ihrem Gegenüber (in % des Umfangs) für eine Symmetrieachse
+pub const SYMMETRY_TOLERANCE_PERCENT: f64 = 0.1;
+
+/// Bimediane (Schwerlinie): Verbindung der Mitten zweier gegenüberliegender Seiten
+/// 0 = Mitte AB – Mitte CD, 1 = Mitte BC – Mitte DA; beide schneiden sich im Eckenschwerpunkt
+#[derive(Clone, Debug)]
+pub struct Bimedian {
+    pub start: Point,
+    pub end: Point,
+    pub length_mm: f64,
+}
+
+/// Lage einer Symmetrieachse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AxisThrough {
+    /// Drachenviereck: Achse entlang der Diagonale (0 = AC, 1 = BD)
+    Diagonal(usize),
+    /// Gleichschenkliges Trapez: Achse entlang der Bimediane (0 = AB–CD, 1 = BC–DA)
+    Bimedian(usize),
+}
+
+/// Symmetrieachse mit ihrem Abschnitt im Viereck
+#[derive(Clone, Debug)]
+pub struct SymmetryAxis {
+    pub through: AxisThrough,
+    pub start: Point,
+    pub end: Point,
+    pub length_mm: f64,
+    pub deviation_mm: f64, // größter Abstand eines gespiegelten Eckpunkts von seinem Gegenüber
+}
+
+/// Umkreis eines Sehnenvierecks bzw. Inkreis eines Tangentenvierecks
+#[derive(Clone, Debug)]
+pub struct SpecialCircle {
+    pub center: Point,
+    pub radius_mm: f64,
+    pub deviation: f64, // Sehnenviereck: |A + C − 180°| in Grad, Tangentenviereck: |AB + CD − BC − DA| in mm
+}
+
+/// Einheitsvektor von `from` nach `to`
+fn unit(from: &Point, to: &Point) -> (f64, f64) {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let len = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+    (dx / len, dy / len)
+}
+
+/// Spiegelt `p` an der Geraden durch `a` und `b`
+fn reflect(p: &Point, a: &Point, b: &Point) -> Point {
+    let (ux, uy) = unit(a, b);
+    let (dx, dy) = (p.x - a.x, p.y - a.y);
+    let t = dx * ux + dy * uy;
+    Point::new(2.0 * (a.x + ux * t) - p.x, 2.0 * (a.y + uy * t) - p.y)
+}
+
+/// Mittelpunkt des Kreises durch drei Punkte
+fn circumcenter(a: &Point, b: &Point, c: &Point) -> Option<Point> {
+    // Relativ zu a rechnen, damit die Quadrate klein bleiben
+    let (bx, by) = (b.x - a.x, b.y - a.y);
+    let (cx, cy) = (c.x - a.x, c.y - a.y);
+    let d = 2.0 * (bx * cy - by * cx);
+    if d.abs() < f64::EPSILON {
+        return None;
+    }
+    let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+    Some(Point::new(a.x + (cy * b2 - by * c2) / d, a.y + (bx * c2 - cx * b2) / d))
+}
+
+impl Quadrilateral {
+    fn is_convex(&self) -> bool {
+        (0..4).all(|v| !self.is_reflex_vertex(v))
+    }
+
+    /// Umkreis, falls das Viereck (nahezu) ein Sehnenviereck ist (A + C ≈ 180°)
+    /// Bei kleinen Abweichungen wird der Mittelpunkt aus den Dreiecken ABC und ACD gemittelt
+    pub fn circumcircle(&self) -> Option<SpecialCircle> {
+        if !self.is_convex() {
+            return None;
+        }
+        let v = &self.vertices;
+        let angle_a = calculate_interior_angle(&v[3], &v[0], &v[1]);
+        let angle_c = calculate_interior_angle(&v[1], &v[2], &v[3]);
+        let deviation = (angle_a + angle_c - 180.0).abs();
+        if deviation > CYCLIC_TOLERANCE_DEG {
+            return None;
+        }
+
+        let first = circumcenter(&v[0], &v[1], &v[2])?;
+        let second = circumcenter(&v[0], &v[2], &v[3])?;
+        let center = Point::new((first.x + second.x) / 2.0, (first.y + second.y) / 2.0);
+        let radius_um = v.iter().map(|p| distance_f64(&center, p)).sum::<f64>() / 4.0;
+        Some(SpecialCircle { center, radius_mm: radius_um / 1000.0, deviation })
+    }
+
+    /// Inkreis, falls das Viereck (nahezu) ein Tangentenviereck ist (AB + CD ≈ BC + DA)
+    /// Mittelpunkt im Schnitt der Winkelhalbierenden bei A und B, Radius = Fläche / halber Umfang
+    pub fn incircle(&self) -> Option<SpecialCircle> {
+        if !self.is_convex() {
+            return None;
+        }
+        let sides: [f64; 4] = std::array::from_fn(|i| self.get_side_length_mm(i));
+        let perimeter: f64 = sides.iter().sum();
+        let deviation = (sides[0] + sides[2] - sides[1] - sides[3]).abs();
+        if perimeter <= 0.0 || deviation / perimeter * 100.0 > TANGENTIAL_TOLERANCE_PERCENT {
+            return None;
+        }
+
+        let v = &self.vertices;
+        let bisector = |i: usize| {
+            let (p, n) = (unit(&v[i], &v[(i + 3) % 4]), unit(&v[i], &v[(i + 1) % 4]));
+            (p.0 + n.0, p.1 + n.1)
+        };
+        let (da, db) = (bisector(0), bisector(1));
+        let det = da.0 * (-db.1) - da.1 * (-db.0);
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        // v[0] + t·da = v[1] + s·db
+        let (rx, ry) = (v[1].x - v[0].x, v[1].y - v[0].y);
+        let t = (rx * (-db.1) - ry * (-db.0)) / det;
+        let center = Point::new(v[0].x + da.0 * t, v[0].y + da.1 * t);
+        let radius_mm = self.area_mm2() / (perimeter / 2.0);
+        Some(SpecialCircle { center, radius_mm, deviation })
+    }
+
+    /// Flächenschwerpunkt; bei entartetem Viereck der Mittelwert der Eckpunkte
+    pub fn centroid(&self) -> Point {
+        let area = self.signed_area_um2();
+        if area.abs() < f64::EPSILON {
+            let x = self.vertices.iter().map(|v| v.x).sum::<f64>() / 4.0;
+            let y = self.vertices.iter().map(|v| v.y).sum::<f64>() / 4.0;
+            return Point::new(x, y);
+        }
+
+        // Relativ zu A, damit die Produkte klein bleiben
+        let origin = &self.vertices[0];
+        let (mut cx, mut cy) = (0.0, 0.0);
+        for i in 0..4 {
+            let (x1, y1) = (self.vertices[i].x - origin.x, self.vertices[i].y - origin.y);
+            let next = &self.vertices[(i + 1) % 4];
+            let (x2, y2) = (next.x - origin.x, next.y - origin.y);
+            let cross = x1 * y2 - x2 * y1;
+            cx += (x1 + x2) * cross;
+            cy += (y1 + y2) * cross;
+        }
+        Point::new(origin.x + cx / (6.0 * area), origin.y + cy / (6.0 * area))
+    }
+
+    /// Vorzeichenbehaftete Fläche in µm² (positiv = Eckpunkte gegen den Uhrzeigersinn
+    /// im mathematischen Koordinatensystem)
+    pub fn signed_area_um2(&self) -> f64 {
+        let mut sum = 0.0;
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            sum += self.vertices[i].x * self.vertices[next].y
+                - self.vertices[next].x * self.vertices[i].y;
+        }
+        sum / 2.0
+    }
+
+    /// Läuft A → B → C → D auf dem Bildschirm (y nach unten) im Uhrzeigersinn?
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area_um2() > 0.0
+    }
+
+    /// Prüft ob der Innenwinkel am Eckpunkt größer als 180° ist
+    pub fn is_reflex_vertex(&self, vertex: usize) -> bool {
+        let prev = &self.vertices[(vertex + 3) % 4];
+        let v = &self.vertices[vertex];
+        let next = &self.vertices[(vertex + 1) % 4];
+
+        let cross = (v.x - prev.x) * (next.y - v.y) - (v.y - prev.y) * (next.x - v.x);
+        cross * self.signed_area_um2() < 0.0
+    }
+
+    /// Außenmaße in mm: Breite entlang der Bezugsseite (0=AB … 3=DA) und Höhe quer dazu,
+    /// z.B. um zu prüfen, ob ein Werkstück durch eine Tür oder auf ein Fahrzeug passt
+    pub fn extent_mm(&self, side: usize) -> Option<(f64, f64)> {
+        let frame = LocalFrame::new(self, Baseline { origin: side % 4, axis_to: (side + 1) % 4 })?;
+        let local: Vec<(f64, f64)> = self.vertices.iter().map(|v| frame.to_local_mm(v)).collect();
+        let span = |values: Vec<f64>| {
+            values.iter().cloned().fold(f64::MIN, f64::max) - values.iter().cloned().fold(f64::MAX, f64::min)
+        };
+        Some((span(local.iter().map(|p| p.0).collect()), span(local.iter().map(|p| p.1).collect())))
+    }
+
+    /// Länge der Diagonale in µm (0 = AC, 1 = BD)
+    pub fn diagonal_um(&self, diagonal: usize) -> i64 {
+        distance_um(&self.vertices[diagonal], &self.vertices[diagonal + 2])
+    }
+
+    /// Vergleicht eine vor Ort gemessene Diagonale mit der berechneten
+    pub fn check_diagonal(&self, diagonal: usize, measured_mm: f64, tolerance_mm: f64) -> DiagonalCheck {
+        let computed_mm = self.diagonal_um(diagonal) as f64 / 1000.0;
+        let deviation_mm = measured_mm - computed_mm;
+        DiagonalCheck {
+            computed_mm,
+            measured_mm,
+            deviation_mm,
+            deviation_percent: if computed_mm > 0.0 { deviation_mm / computed_mm * 100.0 } else { 0.0 },
+            within_tolerance: deviation_mm.abs() <= tolerance_mm,
+        }
+    }
+
+    /// Liegt die Diagonale (0 = AC, 1 = BD) im Inneren des Vierecks?
+    /// Bei konkaven Vierecken nur die Diagonale durch den überstumpfen Winkel
+    pub fn diagonal_inside(&self, diagonal: usize) -> bool {
+        !self.is_reflex_vertex((diagonal + 1) % 4) && !self.is_reflex_vertex((diagonal + 3) % 4)
+    }
+
+    /// Zerlegt das Viereck entlang der Diagonale in zwei Dreiecke
+    pub fn split_triangles(&self, diagonal: usize) -> [Triangle; 2] {
+        let triangle = |vertices: [usize; 3]| {
+            let mut sides_mm = [0.0; 3];
+            let mut angles_deg = [0.0; 3];
+            for i in 0..3 {
+                let prev = &self.vertices[vertices[(i + 2) % 3]];
+                let v = &self.vertices[vertices[i]];
+                let next = &self.vertices[vertices[(i + 1) % 3]];
+                sides_mm[i] = distance_um(v, next) as f64 / 1000.0;
+                angles_deg[i] = calculate_interior_angle(prev, v, next);
+            }
+            Triangle { vertices, sides_mm, angles_deg }
+        };
+
+        let d = diagonal;
+        [
+            triangle([d, (d + 1) % 4, (d + 2) % 4]),
+            triangle([(d + 2) % 4, (d + 3) % 4, d]),
+        ]
+    }
+
+    /// Konstruiert die Winkelhalbierende am Eckpunkt bis zur gegenüberliegenden Begrenzung
+    /// Gibt den Endanker (Seite + Position) und den Schnittpunkt zurück
+    pub fn angle_bisector(&self, vertex: usize) -> Option<(LineAnchor, Point)> {
+        let prev = &self.vertices[(vertex + 3) % 4];
+        let v = &self.vertices[vertex];
+        let next = &self.vertices[(vertex + 1) % 4];
+
+        let unit = |p: &Point| -> Option<(f64, f64)> {
+            let dx = p.x - v.x;
+            let dy = p.y - v.y;
+            let len = (dx * dx + dy * dy).sqrt();
+            if len == 0.0 { None } else { Some((dx / len, dy / len)) }
+        };
+        let (u1x, u1y) = unit(prev)?;
+        let (u2x, u2y) = unit(next)?;
+
+        let mut dir = (u1x + u2x, u1y + u2y);
+        if (dir.0 * dir.0 + dir.1 * dir.1).sqrt() < 1e-9 {
+            // 180°: Halbierende steht senkrecht auf der Seite, Normale ins Innere wählen
+            dir = if self.signed_area_um2() > 0.0 { (-u2y, u2x) } else { (u2y, -u2x) };
+        } else if self.is_reflex_vertex(vertex) {
+            // Bei überstumpfen Winkeln zeigt die Summe nach außen
+            dir = (-dir.0, -dir.1);
+        }
+
+        // Nur die nicht angrenzenden Seiten kommen in Frage
+        let mut best: Option<(f64, usize, f64)> = None;
+        for side in [(vertex + 1) % 4, (vertex + 2) % 4] {
+            let a = &self.vertices[side];
+            let b = &self.vertices[(side + 1) % 4];
+            if let Some((t, ratio)) = ray_segment_intersection(v, dir, a, b) {
+                if t > 1e-6 && best.is_none_or(|(best_t, _, _)| t < best_t) {
+                    best = Some((t, side, ratio));
+                }
+            }
+        }
+
+        let (t, side, ratio) = best?;
+        let hit = Point::new(v.x + dir.0 * t, v.y + dir.1 * t);
+        Some((LineAnchor::Side { side, ratio }, hit))
+    }
+
+    /// Eckpunkte so verschoben und gedreht, dass A und die Richtung AB mit dem
+    /// Bezugsviereck übereinstimmen (zum Überlagern zweier Aufmaße)
+    pub fn aligned_to(&self, reference: &Quadrilateral) -> [Point; 4] {
+        let own = self.vertices[1].y - self.vertices[0].y;
+        let own = own.atan2(self.vertices[1].x - self.vertices[0].x);
+        let target = reference.vertices[1].y - reference.vertices[0].y;
+        let target = target.atan2(reference.vertices[1].x - reference.vertices[0].x);
+        let (sin, cos) = (target - own).sin_cos();
+
+        let origin = &self.vertices[0];
+        let anchor = &reference.vertices[0];
+        self.vertices.clone().map(|p| {
+            let (dx, dy) = (p.x - origin.x, p.y - origin.y);
+            Point::new(anchor.x + dx * cos - dy * sin, anchor.y + dx * sin + dy * cos)
+        })
+    }
+
+    /// Bimediane 0 (Mitte AB – Mitte CD) bzw. 1 (Mitte BC – Mitte DA)
+    pub fn bimedian(&self, index: usize) -> Bimedian {
+        let start = self.get_point_on_side(index, 0.5);
+        let end = self.get_point_on_side(index + 2, 0.5);
+        Bimedian { length_mm: distance_f64(&start, &end) / 1000.0, start, end }
+    }
+
+    /// Symmetrieachsen: Diagonalen (Drachen, Raute) und Bimedianen (gleichschenkliges Trapez, Rechteck);
+    /// ein Quadrat hat vier, ein allgemeines Viereck keine
+    pub fn symmetry_axes(&self) -> Vec<SymmetryAxis> {
+        let v = &self.vertices;
+        let perimeter_um: f64 = (0..4).map(|i| distance_f64(&v[i], &v[(i + 1) % 4])).sum();
+        let limit_um = perimeter_um * SYMMETRY_TOLERANCE_PERCENT / 100.0;
+        let mut axes = Vec::new();
+        let mut push = |through: AxisThrough, start: Point, end: Point, deviation_um: f64| {
+            let length_um = distance_f64(&start, &end);
+            if length_um > f64::EPSILON && deviation_um <= limit_um {
+                axes.push(SymmetryAxis { through, start, end, length_mm: length_um / 1000.0, deviation_mm: deviation_um / 1000.0 });
+            }
+        };
+
+        // Die beiden Ecken neben der Diagonale spiegeln sich aufeinander
+        for d in 0..2 {
+            let (a, b) = (&v[d], &v[d + 2]);
+            if distance_f64(a, b) > f64::EPSILON {
+                push(AxisThrough::Diagonal(d), a.clone(), b.clone(), distance_f64(&reflect(&v[d + 1], a, b), &v[(d + 3) % 4]));
+            }
+        }
+        // Die Enden beider geschnittener Seiten tauschen die Plätze
+        for i in 0..2 {
+            let bimedian = self.bimedian(i);
+            let (a, b) = (&bimedian.start, &bimedian.end);
+            if distance_f64(a, b) > f64::EPSILON {
+                let deviation = distance_f64(&reflect(&v[i], a, b), &v[i + 1]).max(distance_f64(&reflect(&v[(i + 3) % 4], a, b), &v[i + 2]));
+                push(AxisThrough::Bimedian(i), a.clone(), b.clone(), deviation);
+            }
+        }
+        axes
+    }
+
+    /// Abweichungen je Seite und Winkel gegenüber `reference`
+    pub fn compare(&self, reference: &Quadrilateral) -> Comparison {
+        let angles = |q: &Quadrilateral| [q.angle_a, q.angle_b, q.angle_c, q.angle_d].map(|a| a.unwrap_or(0.0));
+        let (own, other) = (angles(self), angles(reference));
+        Comparison {
+            sides_mm: [0, 1, 2, 3].map(|i| self.get_side_length_mm(i) - reference.get_side_length_mm(i)),
+            angles_deg: [0, 1, 2, 3].map(|i| own[i] - other[i]),
+            area_m2: (self.area_mm2() - reference.area_mm2()) / 1_000_000.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_centroid() {
+        // Gleichschenkliges Trapez, unten 4 m, oben 2 m, Höhe 3 m
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(3_000_000.0, 3_000_000.0),
+            Point::new(1_000_000.0, 3_000_000.0),
+        ];
+        // Symmetrisch: x in der Mitte, y = h/3 · (a + 2b) / (a + b) = 1 · 8 / 6 m
+        let centroid = quad.centroid();
+        assert!((centroid.x - 2_000_000.0).abs() < 1e-3);
+        assert!((centroid.y - 4_000_000.0 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_extent() {
+        // Trapez: unten 4 m, oben 2 m, Höhe 3 m
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(3_000_000.0, 3_000_000.0),
+            Point::new(1_000_000.0, 3_000_000.0),
+        ];
+        let (width, height) = quad.extent_mm(0).unwrap();
+        assert!((width - 4000.0).abs() < 1e-6 && (height - 3000.0).abs() < 1e-6);
+        let (width, height) = quad.extent_mm(2).unwrap();
+        assert!((width - 4000.0).abs() < 1e-6 && (height - 3000.0).abs() < 1e-6);
+
+        // Schräge Seite BC als Bezug: Höhe = Abstand von A zur Geraden BC
+        let (_, height) = quad.extent_mm(1).unwrap();
+        assert!((height - 4000.0 * 3.0 / 10f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_circumcircle_and_incircle() {
+        let mut square = Quadrilateral::new();
+        square.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(2_000_000.0, 0.0),
+            Point::new(2_000_000.0, 2_000_000.0),
+            Point::new(0.0, 2_000_000.0),
+        ];
+        let outer = square.circumcircle().unwrap();
+        assert!((outer.center.x - 1_000_000.0).abs() < 1e-3 && (outer.center.y - 1_000_000.0).abs() < 1e-3);
+        assert!((outer.radius_mm - 2.0_f64.sqrt() * 1000.0).abs() < 1e-6);
+        let inner = square.incircle().unwrap();
+        assert!((inner.center.x - 1_000_000.0).abs() < 1e-3 && (inner.center.y - 1_000_000.0).abs() < 1e-3);
+        assert!((inner.radius_mm - 1000.0).abs() < 1e-6);
+
+        // Rechteck 4 × 2 m: Sehnen-, aber kein Tangentenviereck
+        square.vertices[1].x = 4_000_000.0;
+        square.vertices[2].x = 4_000_000.0;
+        assert!(square.circumcircle().is_some());
+        assert!(square.incircle().is_none());
+    }
+
+    #[test]
+    fn test_bimedians_and_symmetry_axes() {
+        // Gleichschenkliges Trapez: unten 4 m, oben 2 m, Höhe 3 m → eine Achse durch Mitte AB und CD
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(3_000_000.0, 3_000_000.0),
+            Point::new(1_000_000.0, 3_000_000.0),
+        ];
+        assert!((quad.bimedian(0).length_mm - 3000.0).abs() < 1e-6);
+        assert!((quad.bimedian(1).length_mm - 3000.0).abs() < 1e-6);
+        let axes = quad.symmetry_axes();
+        assert_eq!(axes.len(), 1);
+        assert_eq!(axes[0].through, AxisThrough::Bimedian(0));
+
+        // Drachen: Achse entlang AC
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(1_000_000.0, -1_000_000.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(1_000_000.0, 1_000_000.0),
+        ];
+        let axes: Vec<AxisThrough> = quad.symmetry_axes().iter().map(|a| a.through).collect();
+        assert_eq!(axes, vec![AxisThrough::Diagonal(0)]);
+
+        // Quadrat: vier Achsen
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(2_000_000.0, 0.0),
+            Point::new(2_000_000.0, 2_000_000.0),
+            Point::new(0.0, 2_000_000.0),
+        ];
+        assert_eq!(quad.symmetry_axes().len(), 4);
+
+        // Allgemeines Viereck: keine
+        quad.vertices[2] = Point::new(2_500_000.0, 1_800_000.0);
+        assert!(quad.symmetry_axes().is_empty());
+    }
+
+    #[test]
+    fn test_angle_bisector_square() {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(1_000_000.0, 0.0),
+            Point::new(1_000_000.0, 1_000_000.0),
+            Point::new(0.0, 1_000_000.0),
+        ];
+
+        // Halbierende bei A trifft genau die Ecke C (Ende von BC)
+        let (anchor, hit) = quad.angle_bisector(0).unwrap();
+        assert!((hit.x - 1_000_000.0).abs() < 1e-3);
+        assert!((hit.y - 1_000_000.0).abs() < 1e-3);
+        assert!(matches!(anchor, LineAnchor::Side { side: 1 | 2, .. }));
+    }
+
+    #[test]
+    fn test_check_diagonal() {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(3_000_000.0, 0.0),
+            Point::new(3_000_000.0, 4_000_000.0),
+            Point::new(0.0, 4_000_000.0),
+        ];
+
+        let check = quad.check_diagonal(0, 5_003.0, 5.0);
+        assert!((check.computed_mm - 5_000.0).abs() < 1e-6);
+        assert!((check.deviation_mm - 3.0).abs() < 1e-6);
+        assert!((check.deviation_percent - 0.06).abs() < 1e-9);
+        assert!(check.within_tolerance);
+
+        assert!(!quad.check_diagonal(1, 4_990.0, 5.0).within_tolerance);
+    }
+
+    #[test]
+    fn test_split_triangles() {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(3_000_000.0, 0.0),
+            Point::new(3_000_000.0, 4_000_000.0),
+            Point::new(0.0, 4_000_000.0),
+        ];
+
+        let [first, second] = quad.split_triangles(0);
+        assert_eq!(first.vertices, [0, 1, 2]);
+        assert_eq!(second.vertices, [2, 3, 0]);
+        assert!((first.sides_mm[2] - 5_000.0).abs() < 1e-6);
+        assert!((first.angles_deg[1] - 90.0).abs() < 1e-9);
+        assert!((first.angles_deg.iter().sum::<f64>() - 180.0).abs() < 1e-9);
+        assert!(quad.diagonal_inside(0) && quad.diagonal_inside(1));
+    }
+
+    #[test]
+    fn test_aligned_to() {
+        let mut reference = Quadrilateral::new();
+        reference.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(4_000_000.0, 3_000_000.0),
+            Point::new(0.0, 3_000_000.0),
+        ];
+
+        // Gleiches Rechteck, verschoben und um 90° gedreht
+        let mut other = Quadrilateral::new();
+        other.vertices = [
+            Point::new(1_000_000.0, 1_000_000.0),
+            Point::new(1_000_000.0, 5_000_000.0),
+            Point::new(-2_000_000.0, 5_000_000.0),
+            Point::new(-2_000_000.0, 1_000_000.0),
+        ];
+
+        let aligned = other.aligned_to(&reference);
+        for (a, b) in aligned.iter().zip(reference.vertices.iter()) {
+            assert!((a.x - b.x).abs() < 1e-3 && (a.y - b.y).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// Herleitung der Berechnung: gewählter Konstruktionsweg und Zwischenwerte
+// Schritte enthalten nur Indizes und Zahlen, die Beschriftung übernimmt die Oberfläche
+
+use super::types::{Point, Quadrilateral};
+use super::utils::distance_um;
+
+/// Gewählter Konstruktionsweg; Ecke 0 = A, Seite 0 = AB
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConstructionMethod {
+    /// Alle Seiten + Winkel an `vertex` und der folgenden Ecke, gegenüberliegende Seite als Kontrolle
+    AdjacentAngles { vertex: usize },
+    /// Alle Seiten + ein Winkel, die gegenüberliegende Ecke als Kreisschnitt
+    SingleAngle { vertex: usize },
+    /// Alle Seiten + Diagonale (0 = AC, 1 = BD), zwei Dreiecke aus drei Seiten
+    Diagonal { diagonal: usize },
+    /// Drei Seiten + Winkel an `vertex` und der folgenden Ecke, die fehlende Seite wird berechnet
+    ThreeSides { missing_side: usize, vertex: usize },
+}
+
+/// Ein Schritt der Herleitung; Längen in mm, Winkel in Grad
+#[derive(Clone, Debug, PartialEq)]
+pub enum DerivationStep {
+    /// Fehlender Winkel aus der Winkelsumme 360°
+    AngleSum { vertex: usize, angle_deg: f64 },
+    /// Grundlinie: `from` im Ursprung, `vertex` im Abstand `length_mm` auf der x-Achse
+    Base { from: usize, vertex: usize, length_mm: f64 },
+    /// Ecke im Abstand `length_mm` von `from`, abgetragen unter dem Innenwinkel bei `from`
+    Polar { vertex: usize, from: usize, length_mm: f64, angle_deg: f64 },
+    /// Ecke als Schnitt zweier Kreise; der Mittelpunktabstand entscheidet, ob sie sich schneiden
+    CircleIntersection { vertex: usize, centers: [usize; 2], radii_mm: [f64; 2], center_distance_mm: f64 },
+    /// Ecke auf dem Strahl unter dem Winkel bei `from`, im Abstand `radius_mm` von `center`
+    RayCircle { vertex: usize, from: usize, angle_deg: f64, center: usize, radius_mm: f64 },
+    /// Ecke auf der Geraden durch `from` mit unbekanntem Abstand; die daran hängende Seite `side`
+    /// wird parallel mitgeführt, bis ihr Ende im Abstand `radius_mm` von `center` liegt
+    SlidingSide { vertex: usize, from: usize, side: usize, center: usize, radius_mm: f64 },
+    /// Nicht gemessene Seite aus den Eckpunkten
+    Side { side: usize, length_mm: f64 },
+    /// Überzähliges Maß: gemessen gegen konstruiert
+    Check { side: usize, measured_mm: f64, actual_mm: f64 },
+}
+
+/// Konstruktionsweg mit den einzelnen Schritten
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstructionTrace {
+    pub method: ConstructionMethod,
+    pub steps: Vec<DerivationStep>,
+}
+
+impl ConstructionTrace {
+    /// Schritte aus dem fertig konstruierten Viereck; `missing_angle` = aus der Winkelsumme ergänzt
+    pub(crate) fn new(method: ConstructionMethod, quad: &Quadrilateral, missing_angle: Option<usize>) -> Self {
+        let side = |i: usize| quad.get_side_length_mm(i % 4);
+        let angles = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d];
+        let angle = |i: usize| angles[i % 4].unwrap_or_default();
+        let distance = |a: usize, b: usize| distance_mm(&quad.vertices[a % 4], &quad.vertices[b % 4]);
+
+        let mut steps: Vec<DerivationStep> = missing_angle
+            .map(|vertex| DerivationStep::AngleSum { vertex, angle_deg: angle(vertex) })
+            .into_iter()
+            .collect();
+        match method {
+            ConstructionMethod::AdjacentAngles { vertex: v } => {
+                let measured = [quad.side_ab_um, quad.side_bc_um, quad.side_cd_um, quad.side_da_um][(v + 2) % 4];
+                steps.extend([
+                    DerivationStep::Base { from: v, vertex: (v + 1) % 4, length_mm: side(v) },
+                    DerivationStep::Polar { vertex: (v + 3) % 4, from: v, length_mm: side(v + 3), angle_deg: angle(v) },
+                    DerivationStep::Polar { vertex: (v + 2) % 4, from: (v + 1) % 4, length_mm: side(v + 1), angle_deg: angle(v + 1) },
+                    DerivationStep::Check {
+                        side: (v + 2) % 4,
+                        measured_mm: measured.map_or(0.0, Quadrilateral::um_to_mm),
+                        actual_mm: side(v + 2),
+                    },
+                ]);
+            }
+            ConstructionMethod::SingleAngle { vertex: v } => {
+                steps.extend([
+                    DerivationStep::Base { from: v, vertex: (v + 1) % 4, length_mm: side(v) },
+                    DerivationStep::Polar { vertex: (v + 3) % 4, from: v, length_mm: side(v + 3), angle_deg: angle(v) },
+                    DerivationStep::CircleIntersection {
+                        vertex: (v + 2) % 4,
+                        centers: [(v + 1) % 4, (v + 3) % 4],
+                        radii_mm: [side(v + 1), side(v + 2)],
+                        center_distance_mm: distance(v + 1, v + 3),
+                    },
+                ]);
+            }
+            ConstructionMethod::Diagonal { diagonal: d } => {
+                let base_mm = distance(d, d + 2);
+                steps.extend([
+                    DerivationStep::Base { from: d, vertex: d + 2, length_mm: base_mm },
+                    DerivationStep::CircleIntersection {
+                        vertex: d + 1,
+                        centers: [d, d + 2],
+                        radii_mm: [side(d), side(d + 1)],
+                        center_distance_mm: base_mm,
+                    },
+                    DerivationStep::CircleIntersection {
+                        vertex: (d + 3) % 4,
+                        centers: [d, d + 2],
+                        radii_mm: [side(d + 3), side(d + 2)],
+                        center_distance_mm: base_mm,
+                    },
+                ]);
+            }
+            ConstructionMethod::ThreeSides { missing_side: m, vertex } => {
+                // Gedrehte Beschriftung wie bei der Konstruktion: die fehlende Seite ist AB
+                let [a, b, c, d] = [0, 1, 2, 3].map(|i| (i + m) % 4);
+                match (vertex + 4 - m) % 4 {
+                    2 => steps.extend([
+                        DerivationStep::Base { from: c, vertex: d, length_mm: side(c) },
+                        DerivationStep::Polar { vertex: b, from: c, length_mm: side(b), angle_deg: angle(c) },
+                        DerivationStep::Polar { vertex: a, from: d, length_mm: side(d), angle_deg: angle(d) },
+                    ]),
+                    1 => steps.extend([
+                        DerivationStep::Base { from: b, vertex: c, length_mm: side(b) },
+                        DerivationStep::Polar { vertex: d, from: c, length_mm: side(c), angle_deg: angle(c) },
+                        DerivationStep::RayCircle { vertex: a, from: b, angle_deg: angle(b), center: d, radius_mm: side(d) },
+                    ]),
+                    3 => steps.extend([
+                        DerivationStep::Base { from: c, vertex: d, length_mm: side(c) },
+                        DerivationStep::Polar { vertex: a, from: d, length_mm: side(d), angle_deg: angle(d) },
+                        DerivationStep::RayCircle { vertex: b, from: a, angle_deg: angle(a), center: c, radius_mm: side(b) },
+                    ]),
+                    _ => steps.extend([
+                        DerivationStep::Polar { vertex: d, from: a, length_mm: side(d), angle_deg: angle(a) },
+                        DerivationStep::SlidingSide { vertex: b, from: a, side: b, center: d, radius_mm: side(c) },
+                        DerivationStep::Polar { vertex: c, from: b, length_mm: side(b), angle_deg: angle(b) },
+                    ]),
+                }
+                steps.push(DerivationStep::Side { side: m, length_mm: side(m) });
+            }
+        }
+        Self { method, steps }
+    }
+}
+
+fn distance_mm(a: &Point, b: &Point) -> f64 {
+    Quadrilateral::um_to_mm(distance_um(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{solve, QuadInput};
+
+    #[test]
+    fn test_construction_trace() {
+        let trace = solve(&QuadInput::rectangle(4000.0, 3000.0)).unwrap().trace;
+        assert_eq!(trace.method, ConstructionMethod::SingleAngle { vertex: 0 });
+        let Some(DerivationStep::CircleIntersection { vertex, center_distance_mm, .. }) = trace.steps.last() else {
+            panic!("{:?}", trace.steps);
+        };
+        assert_eq!(*vertex, 2);
+        assert!((center_distance_mm - 5000.0).abs() < 0.01);
+
+        // Drei Winkel: der vierte kommt aus der Winkelsumme, AB fehlt und wird berechnet
+        let input = QuadInput {
+            sides_mm: [None, Some(3000.0), Some(4000.0), Some(3000.0)],
+            angles: [Some(90.0), Some(90.0), Some(90.0), None],
+            ..QuadInput::default()
+        };
+        let trace = solve(&input).unwrap().trace;
+        assert_eq!(trace.method, ConstructionMethod::ThreeSides { missing_side: 0, vertex: 2 });
+        assert!(matches!(trace.steps[0], DerivationStep::AngleSum { vertex: 3, .. }));
+        let Some(DerivationStep::Side { side: 0, length_mm }) = trace.steps.last() else {
+            panic!("{:?}", trace.steps);
+        };
+        assert!((length_mm - 4000.0).abs() < 0.01);
+    }
+}
@@ -1,9 +1,11 @@
 // Grundlegende Datenstrukturen für die Geometrie
 // Verwendet Mikrometer (µm) als i64 für maximale Präzision
 
+use serde::{Deserialize, Serialize};
+
 /// Punkt in 2D-Raum
 /// Koordinaten werden als f64 gespeichert (für trigonometrische Berechnungen nötig)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Point {
     pub x: f64, // in µm (als Float für Trigonometrie)
     pub y: f64, // in µm (als Float für Trigonometrie)
@@ -21,7 +23,7 @@ impl Point {
 pub struct Quadrilateral {
     pub vertices: [Point; 4], // A, B, C, D im Uhrzeigersinn (in µm)
     
-    // Eingabewerte in µm (unveränderlich)
+    // Seitenlängen in µm: vor der Berechnung die Eingabe, danach aus den Eckpunkten
     pub side_ab_um: Option<i64>, // Mikrometer
     pub side_bc_um: Option<i64>,
     pub side_cd_um: Option<i64>,
@@ -32,19 +34,119 @@ pub struct Quadrilateral {
     pub angle_b: Option<f64>,
     pub angle_c: Option<f64>,
     pub angle_d: Option<f64>,
+
+    // Gemessene Diagonalen in µm (statt Winkeln oder zur Kontrolle)
+    pub diagonal_ac_um: Option<i64>,
+    pub diagonal_bd_um: Option<i64>,
 }
 
-#[derive(Clone, Debug)]
+/// Strichart einer eingezeichneten Linie
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LineStyle {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl LineStyle {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineStyle::Solid => "Durchgezogen",
+            LineStyle::Dashed => "Gestrichelt",
+            LineStyle::Dotted => "Gepunktet",
+        }
+    }
+}
+
+/// Woran ein Linienende befestigt ist
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LineAnchor {
+    /// Seite des Vierecks (0=AB, 1=BC, 2=CD, 3=DA), ratio 0.0 bis 1.0
+    Side { side: usize, ratio: f64 },
+    /// Andere eingezeichnete Linie (Index, immer kleiner als der eigene), ratio 0.0 bis 1.0
+    Line { line: usize, ratio: f64 },
+    /// Eckpunkt (0=A, 1=B, 2=C, 3=D); Winkel wird zur abgehenden Seite gemessen (z.B. AB bei A)
+    Vertex(usize),
+    /// Flächenschwerpunkt des Vierecks (ohne Bezugsstrecke, daher ohne Winkel)
+    Centroid,
+    /// Freier Punkt, auch außerhalb des Vierecks (z.B. Öffnung in der Nachbarwand)
+    /// Lage entlang der verlängerten Seite (ratio beliebig) und senkrechter Abstand in mm,
+    /// positiv rechts in Laufrichtung der Seite (Weltkoordinaten); folgt dem Viereck beim Neuberechnen
+    Free { side: usize, ratio: f64, offset_mm: f64 },
+}
+
+impl LineAnchor {
+    pub fn ratio(&self) -> f64 {
+        match *self {
+            LineAnchor::Side { ratio, .. } | LineAnchor::Line { ratio, .. } | LineAnchor::Free { ratio, .. } => ratio,
+            LineAnchor::Vertex(_) | LineAnchor::Centroid => 0.0,
+        }
+    }
+
+    /// Gleiche Bezugsstrecke mit neuer Position; Eckpunkt-Anker werden zu Anker auf der abgehenden Seite
+    /// Der Schwerpunkt hat keine Bezugsstrecke und bleibt unverändert, freie Punkte behalten ihren Abstand
+    pub fn with_ratio(&self, ratio: f64) -> LineAnchor {
+        match *self {
+            LineAnchor::Free { side, offset_mm, .. } => LineAnchor::Free { side, ratio, offset_mm },
+            LineAnchor::Side { side, .. } | LineAnchor::Vertex(side) => LineAnchor::Side { side, ratio },
+            LineAnchor::Line { line, .. } => LineAnchor::Line { line, ratio },
+            LineAnchor::Centroid => LineAnchor::Centroid,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CustomLine {
+    pub name: String, // z.B. "Linie 1" oder vom Benutzer vergeben ("Trennwand 1")
     pub start: Point,
     pub end: Point,
     pub length_um: i64, // in Mikrometer
-    pub start_side: usize, // Welche Seite (0=AB, 1=BC, 2=CD, 3=DA)
-    pub end_side: usize,
-    pub start_ratio: f64, // Position auf der Seite (0.0 bis 1.0)
-    pub end_ratio: f64,
+    pub start_anchor: LineAnchor,
+    pub end_anchor: LineAnchor,
     pub start_angle: f64, // Schnittwinkel am Start (in Grad)
     pub end_angle: f64,   // Schnittwinkel am Ende (in Grad)
+    
+    // Darstellung
+    pub style: LineStyle,
+    pub width: f32,      // Strichstärke in Pixel
+    pub color: [u8; 3],  // RGB
+    #[serde(default)]
+    pub locked: bool,    // Gesperrt: nicht verschieben, trimmen oder löschen
+    #[serde(default)]
+    pub group: Option<String>, // Gruppe: gemeinsam verschieben, ausblenden, gestalten und löschen
+    #[serde(default)]
+    pub hidden: bool,    // Ausgeblendet, bleibt aber Teil der Konstruktion
+}
+
+impl CustomLine {
+    pub const DEFAULT_WIDTH: f32 = 3.0;
+    pub const DEFAULT_COLOR: [u8; 3] = [200, 100, 0];
+}
+
+/// Kreisbogen-Bereich (Winkel in Grad, gemessen von der x-Achse in Weltkoordinaten)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CustomArc {
+    pub start_deg: f64,
+    pub sweep_deg: f64, // Überstrichener Winkel (0 bis 360)
+}
+
+/// Eingezeichneter Kreis oder Kreisbogen (z.B. Stütze, Rohrdurchführung)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomCircle {
+    pub name: String,
+    pub center: Point,
+    pub radius_um: i64,
+    pub arc: Option<CustomArc>, // None = Vollkreis
+    pub width: f32,
+    pub color: [u8; 3],
+}
+
+/// Hilfslinie: Verlängerung einer Seite über beide Eckpunkte hinaus (z.B. Flucht einer Wand)
+/// Dient nur der Konstruktion und wird dünn gestrichelt gezeichnet
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConstructionLine {
+    pub side: usize,               // 0=AB, 1=BC, 2=CD, 3=DA
+    pub extension_mm: Option<f64>, // Überstand je Eckpunkt, None = unbegrenzt
 }
 
 impl Quadrilateral {
@@ -64,6 +166,8 @@ impl Quadrilateral {
             angle_b: None,
             angle_c: None,
             angle_d: None,
+            diagonal_ac_um: None,
+            diagonal_bd_um: None,
         }
     }
 
@@ -118,6 +222,21 @@ impl Quadrilateral {
         Self::um_to_mm(self.get_side_length_um(side))
     }
 
+    /// Übernimmt alle vier Seitenlängen aus den fertigen Vertices, damit nach der Konstruktion
+    /// keine gerundeten oder abweichenden Eingabewerte als Ergebnis stehen bleiben
+    pub fn store_sides_from_vertices(&mut self) {
+        let [ab, bc, cd, da] = [0, 1, 2, 3].map(|side| Some(self.get_side_length_um(side)));
+        self.side_ab_um = ab;
+        self.side_bc_um = bc;
+        self.side_cd_um = cd;
+        self.side_da_um = da;
+    }
+
+    /// Berechnet die Fläche aus den Vertices (Gaußsche Trapezformel) in mm²
+    pub fn area_mm2(&self) -> f64 {
+        self.signed_area_um2().abs() / 1_000_000.0
+    }
+
     pub fn get_point_on_side(&self, side: usize, ratio: f64) -> Point {
         let (v1, v2) = match side {
             0 => (&self.vertices[0], &self.vertices[1]),
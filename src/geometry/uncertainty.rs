@@ -0,0 +1,126 @@
+// Fehlerfortpflanzung: Wie stark wirken sich Messunsicherheiten auf die Ergebnisse aus?
+// Jede gemessene Größe wird einzeln um ± ihre Unsicherheit verschoben und das Viereck
+// neu berechnet. Die Einzelbeiträge werden quadratisch addiert (unabhängige Fehler).
+
+use super::solver::{solve, QuadInput};
+use super::types::Quadrilateral;
+use serde::{Deserialize, Serialize};
+
+/// Messunsicherheit je Eingabewert
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeasurementUncertainty {
+    pub side_mm: f64,   // ± je Seite bzw. Diagonale
+    pub angle_deg: f64, // ± je Winkel
+}
+
+impl MeasurementUncertainty {
+    pub fn is_zero(&self) -> bool {
+        self.side_mm <= 0.0 && self.angle_deg <= 0.0
+    }
+}
+
+/// Unsicherheit der berechneten Werte (jeweils ±)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UncertaintyResult {
+    pub sides_mm: [f64; 4],     // AB, BC, CD, DA
+    pub angles_deg: [f64; 4],   // A, B, C, D
+    pub diagonals_mm: [f64; 2], // AC, BD
+}
+
+/// Alle Ergebniswerte eines berechneten Vierecks in fester Reihenfolge
+fn outputs(quad: &Quadrilateral) -> [f64; 10] {
+    let angles = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d];
+    let mut values = [0.0; 10];
+    for i in 0..4 {
+        values[i] = quad.get_side_length_mm(i);
+        values[4 + i] = angles[i].unwrap_or(0.0);
+    }
+    values[8] = quad.diagonal_um(0) as f64 / 1000.0;
+    values[9] = quad.diagonal_um(1) as f64 / 1000.0;
+    values
+}
+
+/// Pflanzt die Messunsicherheiten durch die Konstruktion fort
+pub fn propagate(input: &QuadInput, uncertainty: &MeasurementUncertainty) -> Result<UncertaintyResult, String> {
+    let base = outputs(&solve(input).map_err(|e| e.to_string())?.quad);
+    let mut sum_sq = [0.0; 10];
+
+    // (Index: 0–3 Seiten, 4–7 Winkel, 8–9 Diagonalen; Schrittweite)
+    let mut perturbations = Vec::new();
+    for i in 0..4 {
+        if input.sides_mm[i].is_some() && uncertainty.side_mm > 0.0 {
+            perturbations.push((i, uncertainty.side_mm));
+        }
+        if input.angles[i].is_some() && uncertainty.angle_deg > 0.0 {
+            perturbations.push((4 + i, uncertainty.angle_deg));
+        }
+    }
+    for i in 0..2 {
+        if input.diagonals_mm[i].is_some() && uncertainty.side_mm > 0.0 {
+            perturbations.push((8 + i, uncertainty.side_mm));
+        }
+    }
+
+    for (index, step) in perturbations {
+        let shifted = |delta: f64| -> Option<[f64; 10]> {
+            let mut changed = input.clone();
+            let value = match index {
+                0..=3 => &mut changed.sides_mm[index],
+                4..=7 => &mut changed.angles[index - 4],
+                _ => &mut changed.diagonals_mm[index - 8],
+            };
+            *value = value.map(|v| v + delta);
+            solve(&changed).ok().map(|solution| outputs(&solution.quad))
+        };
+
+        // Zentrale Differenz, an Grenzen der Lösbarkeit einseitig
+        let deltas: Vec<f64> = match (shifted(step), shifted(-step)) {
+            (Some(plus), Some(minus)) => (0..10).map(|k| (plus[k] - minus[k]) / 2.0).collect(),
+            (Some(plus), None) => (0..10).map(|k| plus[k] - base[k]).collect(),
+            (None, Some(minus)) => (0..10).map(|k| base[k] - minus[k]).collect(),
+            (None, None) => {
+                return Err(
+                    "❌ Unsicherheit kann nicht berechnet werden: Schon kleine Messabweichungen \
+                    machen das Viereck unlösbar. Bitte Unsicherheit verringern."
+                        .to_string(),
+                )
+            }
+        };
+
+        for (k, delta) in deltas.iter().enumerate() {
+            sum_sq[k] += delta * delta;
+        }
+    }
+
+    let sigma: Vec<f64> = sum_sq.iter().map(|v| v.sqrt()).collect();
+    Ok(UncertaintyResult {
+        sides_mm: [sigma[0], sigma[1], sigma[2], sigma[3]],
+        angles_deg: [sigma[4], sigma[5], sigma[6], sigma[7]],
+        diagonals_mm: [sigma[8], sigma[9]],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_propagate_rectangle() {
+        let input = QuadInput {
+            sides_mm: [Some(4000.0), Some(3000.0), Some(4000.0), Some(3000.0)],
+            angles: [Some(90.0), None, None, None],
+            diagonals_mm: [None; 2],
+        };
+        let uncertainty = MeasurementUncertainty { side_mm: 2.0, angle_deg: 0.0 };
+        let result = propagate(&input, &uncertainty).unwrap();
+
+        // Gemessene Seiten behalten ihre eigene Unsicherheit
+        assert!((result.sides_mm[0] - 2.0).abs() < 0.01);
+        // Die Diagonale AC hängt von allen Seiten ab und ist unsicherer als 0
+        assert!(result.diagonals_mm[0] > 0.5);
+
+        let none = propagate(&input, &MeasurementUncertainty::default()).unwrap();
+        assert_eq!(none, UncertaintyResult::default());
+    }
+}
@@ -1,5 +1,6 @@
 // Hilfsfunktionen für geometrische Berechnungen
 
+use super::error::GeometryError;
 use super::types::Point;
 use std::f64::consts::PI;
 
@@ -19,6 +20,92 @@ pub fn distance_f64(p1: &Point, p2: &Point) -> f64 {
     (dx * dx + dy * dy).sqrt()
 }
 
+/// Berechnet den kürzesten Abstand eines Punktes zu einer Strecke (in µm als Float)
+pub fn distance_point_to_segment(p: &Point, seg_start: &Point, seg_end: &Point) -> f64 {
+    let dx = seg_end.x - seg_start.x;
+    let dy = seg_end.y - seg_start.y;
+    let len_sq = dx * dx + dy * dy;
+
+    if len_sq == 0.0 {
+        return distance_f64(p, seg_start);
+    }
+
+    let t = (((p.x - seg_start.x) * dx + (p.y - seg_start.y) * dy) / len_sq).clamp(0.0, 1.0);
+    let projection = Point::new(seg_start.x + t * dx, seg_start.y + t * dy);
+    distance_f64(p, &projection)
+}
+
+/// Schnitt eines Strahls (origin + t·dir, t ≥ 0) mit einer Strecke a→b
+/// Gibt (t, Position auf der Strecke 0.0 bis 1.0) zurück
+pub fn ray_segment_intersection(origin: &Point, dir: (f64, f64), a: &Point, b: &Point) -> Option<(f64, f64)> {
+    let seg_x = b.x - a.x;
+    let seg_y = b.y - a.y;
+    let denom = dir.0 * seg_y - dir.1 * seg_x;
+
+    // Parallel
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+
+    let diff_x = a.x - origin.x;
+    let diff_y = a.y - origin.y;
+    let t = (diff_x * seg_y - diff_y * seg_x) / denom;
+    let u = (diff_x * dir.1 - diff_y * dir.0) / denom;
+
+    if t >= 0.0 && (-1e-9..=1.0 + 1e-9).contains(&u) {
+        Some((t, u.clamp(0.0, 1.0)))
+    } else {
+        None
+    }
+}
+
+/// Schnitt der unbegrenzten Geraden p1→p2 mit einer Strecke a→b
+/// Gibt (t auf der Geraden, Position auf der Strecke 0.0 bis 1.0) zurück; t darf außerhalb von 0..1 liegen
+pub fn line_segment_intersection(p1: &Point, p2: &Point, a: &Point, b: &Point) -> Option<(f64, f64)> {
+    let (dx, dy) = (p2.x - p1.x, p2.y - p1.y);
+    let (sx, sy) = (b.x - a.x, b.y - a.y);
+    let denom = dx * sy - dy * sx;
+
+    // Parallel
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+
+    let (ox, oy) = (a.x - p1.x, a.y - p1.y);
+    let t = (ox * sy - oy * sx) / denom;
+    let u = (ox * dy - oy * dx) / denom;
+
+    if (-1e-9..=1.0 + 1e-9).contains(&u) {
+        Some((t, u.clamp(0.0, 1.0)))
+    } else {
+        None
+    }
+}
+
+/// Schnittpunkt zweier Strecken a1–a2 und b1–b2
+/// Gibt die Parameter (t auf a, u auf b) zurück; Berührungen an den Endpunkten zählen nicht
+pub fn segment_intersection(a1: &Point, a2: &Point, b1: &Point, b2: &Point) -> Option<(f64, f64)> {
+    const EPS: f64 = 1e-9;
+    let (ax, ay) = (a2.x - a1.x, a2.y - a1.y);
+    let (bx, by) = (b2.x - b1.x, b2.y - b1.y);
+    let denom = ax * by - ay * bx;
+
+    // Parallel oder deckungsgleich
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+
+    let (dx, dy) = (b1.x - a1.x, b1.y - a1.y);
+    let t = (dx * by - dy * bx) / denom;
+    let u = (dx * ay - dy * ax) / denom;
+
+    if t > EPS && t < 1.0 - EPS && u > EPS && u < 1.0 - EPS {
+        Some((t, u))
+    } else {
+        None
+    }
+}
+
 /// Berechnet den Innenwinkel an einem Vertex
 /// prev -> vertex -> next
 pub fn calculate_interior_angle(prev: &Point, vertex: &Point, next: &Point) -> f64 {
@@ -40,25 +127,6 @@ pub fn calculate_interior_angle(prev: &Point, vertex: &Point, next: &Point) -> f
     angle_deg
 }
 
-/// Formatiert eine Länge in µm konsistent als cm oder m
-pub fn format_length_um(um: i64, use_cm: bool) -> String {
-    let mm = um as f64 / 1000.0;
-    
-    if use_cm {
-        format!("{:.2} cm", mm / 10.0)
-    } else if mm >= 10000.0 {
-        format!("{:.3} m", mm / 1000.0)
-    } else {
-        format!("{:.2} cm", mm / 10.0)
-    }
-}
-
-/// Legacy-Funktion für Kompatibilität
-pub fn format_length_consistent(mm: f64, use_cm: bool) -> String {
-    let um = (mm * 1000.0).round() as i64;
-    format_length_um(um, use_cm)
-}
-
 /// Berechnet den Winkel zwischen zwei Vektoren (in Grad, 0-180°)
 /// v1: Vektor von p1 nach p2
 /// v2: Vektor von p1 nach p3
@@ -95,23 +163,22 @@ pub fn calculate_intersection_angle(
     
     angle_between_vectors(side_vx, side_vy, line_vx, line_vy)
 }
-/// Gibt den Punkt zurück, der ein konvexes Viereck ergibt
+/// Gibt den Punkt zurück, der ein konvexes Viereck ergibt: Schnittpunkt auf der von
+/// `opposite` (gegenüberliegende Ecke) abgewandten Seite der Mittelpunktslinie
 /// Arbeitet mit µm (als Float für trigonometrische Berechnungen)
 pub fn find_circle_intersection(
     center1: &Point,
     radius_um: f64, // in µm als Float
     center2: &Point,
     radius2_um: f64, // in µm als Float
-) -> Result<Point, String> {
+    opposite: &Point,
+) -> Result<Point, GeometryError> {
     let dx = center2.x - center1.x;
     let dy = center2.y - center1.y;
     let d = (dx * dx + dy * dy).sqrt();
 
     if d > radius_um + radius2_um || d < (radius_um - radius2_um).abs() {
-        return Err(
-            "❌ Geometrischer Konflikt: Die Kreise schneiden sich nicht!\n\
-            Die angegebenen Seitenlängen passen nicht zusammen.".to_string()
-        );
+        return Err(GeometryError::CirclesDontIntersect);
     }
 
     let a = (radius_um * radius_um - radius2_um * radius2_um + d * d) / (2.0 * d);
@@ -124,6 +191,24 @@ pub fn find_circle_intersection(
     let p1 = Point::new(px + h * dy / d, py - h * dx / d);
     let p2 = Point::new(px - h * dy / d, py + h * dx / d);
 
-    // Wähle den Punkt mit größerer y-Koordinate
-    Ok(if p1.y > p2.y { p1 } else { p2 })
-}
\ No newline at end of file
+    let side = |p: &Point| dx * (p.y - center1.y) - dy * (p.x - center1.x);
+    Ok(if side(&p1) * side(opposite) < 0.0 { p1 } else { p2 })
+}
+
+/// Schnittpunkte eines Strahls (Start, Richtung in rad) mit einem Kreis, nächster zuerst
+/// Leer, wenn der Strahl den Kreis verfehlt
+pub fn ray_circle_intersections(start: &Point, direction_rad: f64, center: &Point, radius_um: f64) -> Vec<Point> {
+    let (ux, uy) = (direction_rad.cos(), direction_rad.sin());
+    let (cx, cy) = (center.x - start.x, center.y - start.y);
+    let projection = ux * cx + uy * cy;
+    let discriminant = projection * projection - (cx * cx + cy * cy) + radius_um * radius_um;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    [projection - discriminant.sqrt(), projection + discriminant.sqrt()]
+        .iter()
+        .filter(|&&t| t > 0.0)
+        .map(|&t| Point::new(start.x + t * ux, start.y + t * uy))
+        .collect()
+}
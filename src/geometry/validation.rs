@@ -1,82 +1,237 @@
 // Validierungs- und Berechnungslogik
 
+use super::error::{GeometryError, PlausibilityWarning};
+use super::solver::QuadInput;
+use super::trace::ConstructionMethod;
 use super::types::Quadrilateral;
-use super::utils::calculate_interior_angle;
+use super::utils::{calculate_interior_angle, distance_um};
+use serde::{Deserialize, Serialize};
+
+/// Kürzeste sinnvolle Seite (1 mm)
+const MIN_SIDE_UM: i64 = 1_000;
+/// Winkelabstand zu 0° bzw. 180°, ab dem drei Eckpunkte als kollinear gelten
+const COLLINEAR_TOLERANCE_DEG: f64 = 0.5;
+/// Mindestfläche im Verhältnis zum Quadrat der längsten Seite
+const MIN_AREA_RATIO: f64 = 0.001;
+/// Abweichung der Winkelsumme von 360°, die ohne Warnung hingenommen wird
+const ANGLE_SUM_TOLERANCE_DEG: f64 = 0.5;
+/// Größte Längenabweichung, die ohne Warnung hingenommen wird (1 mm)
+const MAX_SILENT_DEVIATION_UM: i64 = 1_000;
+
+/// Warnbereich für überzählige Maße: Abweichungen bis hierhin ergeben eine Warnung und das
+/// Viereck wird trotzdem gezeichnet, größere Abweichungen brechen die Berechnung ab
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlausibilityTolerance {
+    pub length_mm: f64,     // überzählige Seite bzw. Diagonale
+    pub angle_sum_deg: f64, // Winkelsumme bei vier gemessenen Winkeln
+}
+
+impl PlausibilityTolerance {
+    /// Kein Warnbereich: jede Abweichung über der Rechentoleranz ist ein Fehler
+    pub const STRICT: Self = Self { length_mm: 0.0, angle_sum_deg: 0.0 };
+}
+
+impl Default for PlausibilityTolerance {
+    fn default() -> Self {
+        Self { length_mm: 5.0, angle_sum_deg: 1.0 }
+    }
+}
+
+/// Einzelner Messwert der Eingabe (Index wie in `QuadInput`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputValue {
+    Side(usize),
+    Angle(usize),
+    Diagonal(usize),
+}
+
+/// Kleinste Ergänzungen, mit denen die Eingabe lösbar wird
+#[derive(Clone, Debug, PartialEq)]
+pub struct MissingInput {
+    pub count: usize,                  // Anzahl der noch fehlenden Werte
+    pub options: Vec<Vec<InputValue>>, // Alle Ergänzungen mit genau `count` Werten
+}
+
+/// Lösbarkeitsmatrix: reichen die gegebenen Seiten, Winkel und Diagonalen für eine Konstruktion?
+fn is_solvable(sides: [bool; 4], angles: [bool; 4], diagonals: [bool; 2]) -> bool {
+    let count = |given: &[bool]| given.iter().filter(|&&g| g).count();
+    let adjacent_angles = (0..4).any(|i| angles[i] && angles[(i + 1) % 4]);
+    match (count(&sides), count(&angles), count(&diagonals)) {
+        (4, 1..=4, _) | (4, 0, 1..=2) => true,
+        (3, 2..=4, _) => adjacent_angles,
+        _ => false,
+    }
+}
+
+/// Welche Werte noch fehlen, bevor sich das Viereck berechnen lässt; None = schon lösbar
+/// Probiert alle Ergänzungen der leeren Felder, kleinste zuerst, gegen dieselbe Matrix wie `calculate`
+pub fn missing_input(input: &QuadInput) -> Option<MissingInput> {
+    let given = (
+        input.sides_mm.map(|s| s.is_some()),
+        input.angles.map(|a| a.is_some()),
+        input.diagonals_mm.map(|d| d.is_some()),
+    );
+    if is_solvable(given.0, given.1, given.2) {
+        return None;
+    }
+
+    let empty: Vec<InputValue> = (0..4)
+        .filter(|&i| !given.0[i])
+        .map(InputValue::Side)
+        .chain((0..4).filter(|&i| !given.1[i]).map(InputValue::Angle))
+        .chain((0..2).filter(|&i| !given.2[i]).map(InputValue::Diagonal))
+        .collect();
+    (1..=empty.len()).find_map(|count| {
+        let options: Vec<Vec<InputValue>> = (0_u32..1 << empty.len())
+            .filter(|mask| mask.count_ones() as usize == count)
+            .map(|mask| empty.iter().enumerate().filter(|(bit, _)| mask & (1 << bit) != 0).map(|(_, &value)| value).collect())
+            .filter(|added: &Vec<InputValue>| {
+                let (mut sides, mut angles, mut diagonals) = given;
+                for value in added {
+                    match *value {
+                        InputValue::Side(i) => sides[i] = true,
+                        InputValue::Angle(i) => angles[i] = true,
+                        InputValue::Diagonal(i) => diagonals[i] = true,
+                    }
+                }
+                is_solvable(sides, angles, diagonals)
+            })
+            .collect();
+        (!options.is_empty()).then_some(MissingInput { count, options })
+    })
+}
+
+/// Einordnung eines überzähligen Längenmaßes
+enum Deviation {
+    Within,
+    Warning,
+    Exceeded,
+}
+
+fn length_deviation(actual_um: i64, expected_um: i64, tolerance: &PlausibilityTolerance) -> Deviation {
+    if within_tolerance(actual_um, expected_um, tolerance) {
+        Deviation::Within
+    } else if (actual_um - expected_um).abs() as f64 <= tolerance.length_mm * 1000.0 {
+        Deviation::Warning
+    } else {
+        Deviation::Exceeded
+    }
+}
 
 impl Quadrilateral {
-    /// Hauptfunktion zur Berechnung des Vierecks
-    pub fn calculate(&mut self) -> Result<(), String> {
-        // Zähle gegebene Werte
-        let sides_given = [self.side_ab_um, self.side_bc_um, self.side_cd_um, self.side_da_um]
-            .iter()
-            .filter(|s| s.is_some())
-            .count();
-        let angles_given = [self.angle_a, self.angle_b, self.angle_c, self.angle_d]
-            .iter()
-            .filter(|a| a.is_some())
-            .count();
+    /// Hauptfunktion zur Berechnung des Vierecks, gibt den gewählten Konstruktionsweg zurück
+    /// Widersprüche im Warnbereich von `tolerance` landen als Warnung im Ergebnis statt als Fehler
+    pub fn calculate(
+        &mut self,
+        tolerance: &PlausibilityTolerance,
+    ) -> Result<(ConstructionMethod, Vec<PlausibilityWarning>), GeometryError> {
+        // Gegebene Werte
+        let sides = [self.side_ab_um, self.side_bc_um, self.side_cd_um, self.side_da_um].map(|s| s.is_some());
+        let angles = [self.angle_a, self.angle_b, self.angle_c, self.angle_d].map(|a| a.is_some());
+        let diagonals = [self.diagonal_ac_um, self.diagonal_bd_um].map(|d| d.is_some());
 
         // Validiere Mindestanforderungen
-        let is_solvable = match (sides_given, angles_given) {
-            (4, 1..=4) => true,
-            (3, 2..=4) => self.has_adjacent_angles(),
-            _ => false,
-        };
-
-        if !is_solvable {
-            return Err(format!(
-                "❌ Nicht genug Informationen für eindeutige Lösung!\n\n\
-                Gegeben: {} Seiten, {} Winkel\n\n\
-                Benötigt wird EINE der folgenden Kombinationen:\n\
-                • 4 Seiten + mindestens 1 Winkel\n\
-                • 3 Seiten + 2 benachbarte Winkel (z.B. A+B oder B+C)\n\n\
-                Tipp: Messen Sie einen weiteren Wert!",
-                sides_given, angles_given
-            ));
+        if !is_solvable(sides, angles, diagonals) {
+            let count = |given: &[bool]| given.iter().filter(|&&g| g).count();
+            return Err(GeometryError::MissingInput { sides: count(&sides), angles: count(&angles) });
         }
 
         // Berechne fehlende Winkel
-        self.calculate_missing_angles()?;
+        let mut warnings = Vec::new();
+        self.calculate_missing_angles(tolerance, &mut warnings)?;
 
         // Konstruiere das Viereck
-        self.construct_quadrilateral()?;
+        let method = self.construct_quadrilateral()?;
 
-        Ok(())
+        // Bei zwei benachbarten Winkeln ist die gegenüberliegende Seite überzählig
+        if let ConstructionMethod::AdjacentAngles { vertex } = method {
+            let side = (vertex + 2) % 4;
+            let expected = [self.side_ab_um, self.side_bc_um, self.side_cd_um, self.side_da_um][side].unwrap_or_default();
+            let actual = self.get_side_length_um(side);
+            match length_deviation(actual, expected, tolerance) {
+                Deviation::Within => {}
+                Deviation::Warning => warnings.push(PlausibilityWarning::SideDeviation { side, expected_um: expected, actual_um: actual }),
+                Deviation::Exceeded => return Err(GeometryError::SideMismatch { side, expected, actual }),
+            }
+        }
+
+        // Flache oder zusammengefallene Ergebnisse nicht als Viereck zeichnen
+        self.degeneracy_check()?;
+
+        // Zusätzlich gemessene Diagonalen müssen zur Konstruktion passen
+        for (diagonal, measured_um) in [self.diagonal_ac_um, self.diagonal_bd_um].into_iter().enumerate() {
+            if let Some(expected) = measured_um {
+                let actual = self.diagonal_um(diagonal);
+                match length_deviation(actual, expected, tolerance) {
+                    Deviation::Within => {}
+                    Deviation::Warning => {
+                        warnings.push(PlausibilityWarning::DiagonalDeviation { diagonal, expected_um: expected, actual_um: actual })
+                    }
+                    Deviation::Exceeded => {
+                        return Err(GeometryError::DiagonalMismatch { diagonal, expected, actual })
+                    }
+                }
+            }
+        }
+
+        Ok((method, warnings))
     }
 
-    /// Prüft ob mindestens 2 benachbarte Winkel gegeben sind
-    pub(crate) fn has_adjacent_angles(&self) -> bool {
-        let angles = [
-            self.angle_a.is_some(),
-            self.angle_b.is_some(),
-            self.angle_c.is_some(),
-            self.angle_d.is_some(),
-        ];
-
-        let adjacent_pairs = [
-            (angles[0], angles[1]), // A+B
-            (angles[1], angles[2]), // B+C
-            (angles[2], angles[3]), // C+D
-            (angles[3], angles[0]), // D+A
-        ];
-
-        adjacent_pairs.iter().any(|(a, b)| *a && *b)
+    /// Erkennt entartete Vierecke: zusammenfallende Eckpunkte, nahezu kollinare
+    /// Eckpunkte und verschwindend kleine Fläche
+    pub fn degeneracy_check(&self) -> Result<(), GeometryError> {
+        for side in 0..4 {
+            let length_um = distance_um(&self.vertices[side], &self.vertices[(side + 1) % 4]);
+            if length_um < MIN_SIDE_UM {
+                return Err(GeometryError::ZeroLengthSide { side, length_um });
+            }
+        }
+
+        for vertex in 0..4 {
+            let angle = calculate_interior_angle(
+                &self.vertices[(vertex + 3) % 4],
+                &self.vertices[vertex],
+                &self.vertices[(vertex + 1) % 4],
+            );
+            if !(COLLINEAR_TOLERANCE_DEG..=180.0 - COLLINEAR_TOLERANCE_DEG).contains(&angle) {
+                return Err(GeometryError::CollinearVertices { vertex, angle_deg: angle });
+            }
+        }
+
+        let longest_um = (0..4)
+            .map(|i| self.get_side_length_um(i))
+            .fold(0_i64, |a, b| a.max(b)) as f64;
+        let area_um2 = self.signed_area_um2().abs();
+        if area_um2 < MIN_AREA_RATIO * longest_um * longest_um {
+            return Err(GeometryError::ZeroArea { area_um2 });
+        }
+
+        Ok(())
     }
 
     /// Berechnet fehlende Winkel (Winkelsumme = 360°)
-    pub(crate) fn calculate_missing_angles(&mut self) -> Result<(), String> {
+    pub(crate) fn calculate_missing_angles(
+        &mut self,
+        tolerance: &PlausibilityTolerance,
+        warnings: &mut Vec<PlausibilityWarning>,
+    ) -> Result<(), GeometryError> {
         let angles = [self.angle_a, self.angle_b, self.angle_c, self.angle_d];
         let angles_given = angles.iter().filter(|a| a.is_some()).count();
 
         match angles_given {
             4 => {
                 let sum: f64 = angles.iter().filter_map(|&a| a).sum();
-                if (sum - 360.0).abs() > 0.5 {
-                    return Err(format!(
-                        "❌ Fehler: Winkelsumme muss 360° sein!\n\
-                        Ihre Summe: {:.2}° (Differenz: {:.2}°)",
-                        sum, sum - 360.0
-                    ));
+                let deviation = sum - 360.0;
+                if deviation.abs() > ANGLE_SUM_TOLERANCE_DEG {
+                    if deviation.abs() > tolerance.angle_sum_deg {
+                        return Err(GeometryError::AngleSumInvalid { sum_deg: sum });
+                    }
+                    // Winkelabschluss: Fehlbetrag gleichmäßig auf die vier Winkel verteilen
+                    for angle in [&mut self.angle_a, &mut self.angle_b, &mut self.angle_c, &mut self.angle_d] {
+                        *angle = angle.map(|a| a - deviation / 4.0);
+                    }
+                    warnings.push(PlausibilityWarning::AngleSum { sum_deg: sum });
                 }
             }
             3 => {
@@ -84,11 +239,7 @@ impl Quadrilateral {
                 let missing = 360.0 - sum;
 
                 if missing <= 0.0 || missing >= 360.0 {
-                    return Err(format!(
-                        "❌ Fehler: Die 3 Winkel summieren sich auf {:.1}°!\n\
-                        Der 4. Winkel müsste {:.1}° sein (ungültig).",
-                        sum, missing
-                    ));
+                    return Err(GeometryError::MissingAngleOutOfRange { sum_deg: sum, missing_deg: missing });
                 }
 
                 if self.angle_a.is_none() {
@@ -107,6 +258,16 @@ impl Quadrilateral {
         Ok(())
     }
 
+    /// Übernimmt alle vier Winkel aus den fertigen Vertices, auch die eingegebenen, damit
+    /// Abweichungen der Konstruktion nicht hinter den Eingabewerten verborgen bleiben
+    pub(crate) fn store_angles_from_vertices(&mut self) {
+        self.angle_a = None;
+        self.angle_b = None;
+        self.angle_c = None;
+        self.angle_d = None;
+        self.calculate_angles_from_vertices();
+    }
+
     /// Berechnet alle fehlenden Winkel aus den Vertices
     pub(crate) fn calculate_angles_from_vertices(&mut self) {
         if self.angle_a.is_none() {
@@ -139,34 +300,73 @@ impl Quadrilateral {
         }
     }
 
-    /// Validiert eine berechnete Seitenlänge gegen die Vorgabe
-    /// Arbeitet in Mikrometer (µm) für maximale Präzision
-    pub(crate) fn validate_length_um(
-        &self,
-        name: &str,
-        calculated_um: i64,
-        expected_um: i64,
-    ) -> Result<(), String> {
-        let diff_um = (calculated_um - expected_um).abs();
-        // Toleranz: 1µm oder 0.1% (was größer ist)
-        let tolerance_um = 1_i64.max((expected_um as f64 * 0.001) as i64);
-
-        if diff_um > tolerance_um {
-            let diff_mm = diff_um as f64 / 1000.0;
-            let expected_mm = expected_um as f64 / 1000.0;
-            let calculated_mm = calculated_um as f64 / 1000.0;
-            let diff_percent = (diff_um as f64 / expected_um as f64) * 100.0;
-            
-            return Err(format!(
-                "⚠️ WARNUNG: Seite {} passt nicht!\n\n\
-                • Seite {} (berechnet): {:.3} mm\n\
-                • Seite {} (vorgegeben): {:.3} mm\n\
-                • Abweichung: {:.3} mm ({:.2}%)\n\n\
-                Das Viereck kann so nicht gebaut werden!\n\
-                Bitte überprüfen Sie die Messungen.",
-                name, name, calculated_mm, name, expected_mm, diff_mm, diff_percent
-            ));
-        }
-        Ok(())
+}
+
+/// Toleranz für berechnete gegen gemessene Längen: 1µm oder 0.1% (was größer ist),
+/// höchstens aber 1 mm bzw. der Warnbereich, damit Messfehler langer Seiten nicht ohne Warnung durchgehen
+fn within_tolerance(calculated_um: i64, expected_um: i64, tolerance: &PlausibilityTolerance) -> bool {
+    let mut max_um = MAX_SILENT_DEVIATION_UM;
+    if tolerance.length_mm > 0.0 {
+        max_um = max_um.min((tolerance.length_mm * 1000.0) as i64);
+    }
+    let tolerance_um = 1_i64.max(((expected_um as f64 * 0.001) as i64).min(max_um));
+    (calculated_um - expected_um).abs() <= tolerance_um
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    fn quad_from(points: [(f64, f64); 4]) -> Quadrilateral {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = points.map(|(x, y)| Point::new(x, y));
+        quad
+    }
+
+    #[test]
+    fn test_degeneracy_check() {
+        let square = quad_from([(0.0, 0.0), (1e6, 0.0), (1e6, 1e6), (0.0, 1e6)]);
+        assert!(square.degeneracy_check().is_ok());
+
+        // C liegt auf der Verbindung von B nach D
+        let collinear = quad_from([(0.0, 0.0), (1e6, 0.0), (5e5, 5e5), (0.0, 1e6)]);
+        let error = collinear.degeneracy_check().unwrap_err();
+        assert!(matches!(error, GeometryError::CollinearVertices { vertex: 2, .. }));
+        assert!(error.to_string().contains("Winkel bei C"));
+
+        let sliver = quad_from([(0.0, 0.0), (1e6, 0.0), (2e6, 100.0), (1e6, 200.0)]);
+        assert!(sliver.degeneracy_check().is_err());
+    }
+
+    #[test]
+    fn test_missing_input() {
+        assert_eq!(missing_input(&QuadInput::rectangle(4000.0, 3000.0)), None);
+
+        // 4 Seiten: ein beliebiger Winkel oder eine Diagonale
+        let mut input = QuadInput { sides_mm: [Some(4000.0); 4], ..QuadInput::default() };
+        let missing = missing_input(&input).unwrap();
+        assert_eq!(missing.count, 1);
+        assert_eq!(missing.options.len(), 6);
+
+        // 3 Seiten + Winkel A: Seite DA oder ein Nachbarwinkel von A
+        input.sides_mm[3] = None;
+        input.angles[0] = Some(90.0);
+        let missing = missing_input(&input).unwrap();
+        assert_eq!(
+            missing.options,
+            vec![vec![InputValue::Side(3)], vec![InputValue::Angle(1)], vec![InputValue::Angle(3)]]
+        );
+
+        // Leere Eingabe: 4 Seiten + 1 Winkel oder 3 Seiten + 2 benachbarte Winkel
+        let missing = missing_input(&QuadInput::default()).unwrap();
+        assert_eq!(missing.count, 5);
+        assert!(missing.options.contains(&vec![
+            InputValue::Side(0),
+            InputValue::Side(1),
+            InputValue::Side(2),
+            InputValue::Side(3),
+            InputValue::Angle(0),
+        ]));
     }
-}
\ No newline at end of file
+}
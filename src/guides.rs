@@ -0,0 +1,188 @@
+// Fanghilfen beim Ziehen von Linienenden (Smart Guides)
+// Rechnet in Bildschirmkoordinaten: die Toleranz ist in Pixel gemeint, die Hilfslinien werden direkt gezeichnet
+
+use egui::{Pos2, Vec2};
+
+/// Abstand in Pixel, ab dem ein Linienende auf eine Hilfslinie springt
+pub const GUIDE_SNAP_PX: f32 = 6.0;
+
+/// Form einer Hilfslinie
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuideShape {
+    Line { origin: Pos2, direction: Vec2 }, // Unbegrenzte Gerade
+    Circle { center: Pos2, radius: f32 },
+}
+
+/// Warum die Hilfslinie angeboten wird
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuideKind {
+    Aligned,             // Waagerecht oder senkrecht zu einem vorhandenen Punkt
+    Collinear,           // Auf der Verlängerung einer Seite oder Linie
+    Equidistant,         // Gleich weit von einem Eckpunkt entfernt wie das andere Linienende
+    RoundLength(f64),    // Linie hätte genau diese Länge (mm)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guide {
+    pub kind: GuideKind,
+    pub shape: GuideShape,
+    pub reference: Pos2, // Bezugspunkt (ausgerichteter Punkt, Eckpunkt bzw. festes Linienende)
+}
+
+/// Vorhandene Geometrie, an der sich das gezogene Ende ausrichten kann
+pub struct GuideSources<'a> {
+    pub points: &'a [Pos2],             // Eckpunkte und Enden anderer Linien
+    pub segments: &'a [(Pos2, Pos2)],   // Seiten und andere Linien
+    pub vertices: &'a [Pos2],
+    pub fixed: Option<Pos2>,            // Anderes Ende der gezogenen Linie
+    pub px_per_mm: f32,
+    pub length_step_mm: f64,            // Raster für runde Längen
+}
+
+/// Alle Hilfslinien, die für die Position `pos` in Frage kommen
+pub fn candidate_guides(pos: Pos2, sources: &GuideSources) -> Vec<Guide> {
+    let mut guides = Vec::new();
+
+    for &point in sources.points {
+        for direction in [Vec2::X, Vec2::Y] {
+            guides.push(Guide { kind: GuideKind::Aligned, shape: GuideShape::Line { origin: point, direction }, reference: point });
+        }
+    }
+
+    for &(a, b) in sources.segments {
+        if (b - a).length_sq() > 0.0 {
+            guides.push(Guide {
+                kind: GuideKind::Collinear,
+                shape: GuideShape::Line { origin: a, direction: b - a },
+                reference: if (pos - a).length_sq() < (pos - b).length_sq() { a } else { b },
+            });
+        }
+    }
+
+    if let Some(fixed) = sources.fixed {
+        for &vertex in sources.vertices {
+            let radius = (fixed - vertex).length();
+            if radius > 0.0 {
+                guides.push(Guide { kind: GuideKind::Equidistant, shape: GuideShape::Circle { center: vertex, radius }, reference: vertex });
+            }
+        }
+
+        if sources.px_per_mm > 0.0 && sources.length_step_mm > 0.0 {
+            let length_mm = ((pos - fixed).length() / sources.px_per_mm) as f64;
+            let round_mm = (length_mm / sources.length_step_mm).round() * sources.length_step_mm;
+            if round_mm > 0.0 {
+                guides.push(Guide {
+                    kind: GuideKind::RoundLength(round_mm),
+                    shape: GuideShape::Circle { center: fixed, radius: round_mm as f32 * sources.px_per_mm },
+                    reference: fixed,
+                });
+            }
+        }
+    }
+
+    guides
+}
+
+/// Nächster Punkt auf der Hilfslinie
+fn project(pos: Pos2, shape: &GuideShape) -> Pos2 {
+    match *shape {
+        GuideShape::Line { origin, direction } => origin + direction * ((pos - origin).dot(direction) / direction.length_sq()),
+        GuideShape::Circle { center, radius } => {
+            let offset = pos - center;
+            if offset.length_sq() == 0.0 {
+                pos
+            } else {
+                center + offset.normalized() * radius
+            }
+        }
+    }
+}
+
+/// Schnittpunkte der Hilfslinie mit der Geraden durch a und b
+fn intersections(shape: &GuideShape, a: Pos2, b: Pos2) -> Vec<Pos2> {
+    let d = b - a;
+    match *shape {
+        GuideShape::Line { origin, direction } => {
+            let denom = d.x * direction.y - d.y * direction.x;
+            if denom.abs() < 1e-6 {
+                return Vec::new();
+            }
+            let w = origin - a;
+            let t = (w.x * direction.y - w.y * direction.x) / denom;
+            vec![a + d * t]
+        }
+        GuideShape::Circle { center, radius } => {
+            let f = a - center;
+            let (qa, qb, qc) = (d.length_sq(), 2.0 * f.dot(d), f.length_sq() - radius * radius);
+            let discriminant = qb * qb - 4.0 * qa * qc;
+            if qa == 0.0 || discriminant < 0.0 {
+                return Vec::new();
+            }
+            [-1.0, 1.0].iter().map(|sign| a + d * ((-qb + sign * discriminant.sqrt()) / (2.0 * qa))).collect()
+        }
+    }
+}
+
+/// Weiches Einrasten: die nächste Hilfslinie innerhalb von `tolerance_px`
+/// Mit `constraint` (Seite oder Linie, auf der das Ende gleitet) nur Schnittpunkte mit dieser Geraden
+pub fn snap_to_guides(pos: Pos2, guides: &[Guide], constraint: Option<(Pos2, Pos2)>, tolerance_px: f32) -> Option<(Pos2, Guide)> {
+    guides
+        .iter()
+        .flat_map(|guide| {
+            let targets = match constraint {
+                Some((a, b)) => intersections(&guide.shape, a, b),
+                None => vec![project(pos, &guide.shape)],
+            };
+            targets.into_iter().map(move |target| (target, *guide))
+        })
+        .map(|(target, guide)| ((target - pos).length(), target, guide))
+        .filter(|(dist, ..)| *dist <= tolerance_px)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, target, guide)| (target, guide))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources<'a>(points: &'a [Pos2], segments: &'a [(Pos2, Pos2)], fixed: Option<Pos2>) -> GuideSources<'a> {
+        GuideSources { points, segments, vertices: points, fixed, px_per_mm: 0.1, length_step_mm: 100.0 }
+    }
+
+    #[test]
+    fn test_aligned_and_collinear() {
+        let points = [Pos2::new(100.0, 100.0)];
+        let segments = [(Pos2::new(0.0, 0.0), Pos2::new(50.0, 50.0))];
+        let sources = sources(&points, &segments, None);
+
+        // 4 px unter der Waagerechten durch (100, 100)
+        let pos = Pos2::new(300.0, 104.0);
+        let (snapped, guide) = snap_to_guides(pos, &candidate_guides(pos, &sources), None, GUIDE_SNAP_PX).unwrap();
+        assert_eq!(guide.kind, GuideKind::Aligned);
+        assert!((snapped - Pos2::new(300.0, 100.0)).length() < 1e-3);
+
+        // Nahe der Verlängerung der Diagonale, weit weg von allen Punkten
+        let pos = Pos2::new(400.0, 403.0);
+        let (snapped, guide) = snap_to_guides(pos, &candidate_guides(pos, &sources), None, GUIDE_SNAP_PX).unwrap();
+        assert_eq!(guide.kind, GuideKind::Collinear);
+        assert!((snapped.x - snapped.y).abs() < 1e-3);
+
+        let pos = Pos2::new(300.0, 200.0);
+        assert!(snap_to_guides(pos, &candidate_guides(pos, &sources), None, GUIDE_SNAP_PX).is_none());
+    }
+
+    #[test]
+    fn test_round_length_on_constraint() {
+        // Festes Ende im Ursprung, 0,1 px/mm: 1000 mm entsprechen 100 px
+        let sources = sources(&[], &[], Some(Pos2::new(0.0, 0.0)));
+        let pos = Pos2::new(97.0, 30.0);
+        let guides = candidate_guides(pos, &sources);
+
+        // Ende gleitet auf der Waagerechten y = 30: Schnitt mit dem Kreis r = 100 px
+        let constraint = (Pos2::new(0.0, 30.0), Pos2::new(200.0, 30.0));
+        let (snapped, guide) = snap_to_guides(pos, &guides, Some(constraint), GUIDE_SNAP_PX).unwrap();
+        assert_eq!(guide.kind, GuideKind::RoundLength(1000.0));
+        assert!((snapped - Pos2::new(95.39392, 30.0)).length() < 1e-3);
+        assert!((snapped.to_vec2().length() - 100.0).abs() < 1e-3);
+    }
+}
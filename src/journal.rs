@@ -0,0 +1,88 @@
+// Sitzungsprotokoll: wann welcher Wert berechnet oder welche Linie eingezeichnet wurde
+// Belegt den zeitlichen Ablauf des Aufmaßes (z.B. bei Rückfragen zur Abrechnung), exportierbar als CSV
+
+use crate::export::{csv_delimiter, csv_field};
+use crate::format::NumberFormat;
+
+/// Art des Protokolleintrags
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JournalEvent {
+    Calculation,
+    LineAdded,
+}
+
+impl JournalEvent {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JournalEvent::Calculation => "Berechnung",
+            JournalEvent::LineAdded => "Linie",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    pub timestamp: String, // "TT.MM.JJJJ hh:mm:ss", Ortszeit
+    pub sheet: String,
+    pub event: JournalEvent,
+    pub description: String,
+}
+
+/// Einträge in zeitlicher Reihenfolge, gilt für die ganze Sitzung über alle Blätter
+#[derive(Clone, Debug, Default)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Hängt einen Eintrag mit der aktuellen Uhrzeit an
+    pub fn record(&mut self, sheet: &str, event: JournalEvent, description: String) {
+        self.entries.push(JournalEntry {
+            timestamp: chrono::Local::now().format("%d.%m.%Y %H:%M:%S").to_string(),
+            sheet: sheet.to_string(),
+            event,
+            description,
+        });
+    }
+
+    /// Protokoll als CSV, Trennzeichen passend zum Zahlenformat
+    pub fn to_csv(&self, number: &NumberFormat) -> String {
+        let d = csv_delimiter(number);
+
+        let mut csv = format!("Zeit{d}Blatt{d}Ereignis{d}Beschreibung\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{}{d}{}{d}{}{d}{}\n",
+                entry.timestamp,
+                csv_field(&entry.sheet, d),
+                entry.event.label(),
+                csv_field(&entry.description, d),
+            ));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_csv() {
+        let mut journal = Journal::default();
+        journal.record("Küche", JournalEvent::Calculation, "AB 4,000 m; A 90,0°".to_string());
+        journal.entries.push(JournalEntry {
+            timestamp: "15.10.2026 09:30:00".to_string(),
+            sheet: "Küche".to_string(),
+            event: JournalEvent::LineAdded,
+            description: "Linie 1, Länge 3,000 m".to_string(),
+        });
+        assert_eq!(journal.entries[0].timestamp.len(), 19);
+
+        let csv = journal.to_csv(&NumberFormat::new(2));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Zeit;Blatt;Ereignis;Beschreibung");
+        assert!(lines[1].ends_with(";Küche;Berechnung;\"AB 4,000 m; A 90,0°\""));
+        assert_eq!(lines[2], "15.10.2026 09:30:00;Küche;Linie;Linie 1, Länge 3,000 m");
+    }
+}
@@ -1,15 +1,70 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod api;
+#[cfg(not(target_arch = "wasm32"))]
+mod association;
+mod batch;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+#[cfg(not(target_arch = "wasm32"))]
+mod clipboard;
+mod config;
+mod costs;
+mod diagnostics;
+mod dxf;
+mod export;
+mod format;
 mod geometry;
+mod guides;
+mod journal;
+mod project;
+mod raster;
+mod report;
+mod scene;
+mod script;
+mod storage;
+mod tutorial;
 mod ui;
 mod updater;
+mod view;
+mod xlsx;
 
+use config::{AppConfig, ThemeMode};
+#[cfg(not(target_arch = "wasm32"))]
+use config::WindowMode;
 use eframe::egui;
 
+#[cfg(not(target_arch = "wasm32"))]
 #[tokio::main]
 async fn main() -> Result<(), eframe::Error> {
+    diagnostics::install_panic_hook();
+
+    // Kommandozeilen-Modus: Berechnen ohne GUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_cli_invocation(&args) {
+        std::process::exit(cli::run(&args));
+    }
+
+    // Projektdatei als erstes Argument, z.B. beim Doppelklick über die Dateiverknüpfung
+    let project_path = args.first().map(std::path::PathBuf::from);
+
+    // Gespeicherte Einstellungen bestimmen Fenstermodus und Farbschema beim Start
+    let config = AppConfig::load();
+
+    let viewport = egui::ViewportBuilder::default().with_title("Einfache CAD App für Vierecke");
+    let viewport = match config.window_mode {
+        WindowMode::Fullscreen => viewport.with_fullscreen(true),
+        WindowMode::Maximized => viewport.with_maximized(true),
+        WindowMode::Windowed => viewport.with_inner_size([1280.0, 800.0]),
+    };
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_fullscreen(true)
-            .with_title("Einfache CAD App für Vierecke"),
+        viewport,
+        follow_system_theme: config.theme == ThemeMode::System,
+        default_theme: if config.theme == ThemeMode::Dark {
+            eframe::Theme::Dark
+        } else {
+            eframe::Theme::Light
+        },
         ..Default::default()
     };
 
@@ -17,24 +72,63 @@ async fn main() -> Result<(), eframe::Error> {
         "CAD App",
         options,
         Box::new(|cc| {
-            // Größere Schrift global einstellen
-            let mut style = (*cc.egui_ctx.style()).clone();
-            style.text_styles = [
-                (egui::TextStyle::Heading, egui::FontId::proportional(32.0)),
-                (egui::TextStyle::Body, egui::FontId::proportional(20.0)),
-                (egui::TextStyle::Monospace, egui::FontId::proportional(18.0)),
-                (egui::TextStyle::Button, egui::FontId::proportional(22.0)),
-                (egui::TextStyle::Small, egui::FontId::proportional(16.0)),
-            ].into();
-            
-            // Größere Buttons und Inputs
-            style.spacing.button_padding = egui::vec2(12.0, 8.0);
-            style.spacing.item_spacing = egui::vec2(12.0, 10.0);
-            style.spacing.interact_size = egui::vec2(50.0, 30.0);
-            
-            cc.egui_ctx.set_style(style);
-            
-            Ok(Box::new(ui::CadApp::default()))
+            apply_style(&cc.egui_ctx);
+            let mut app = ui::CadApp::new(config);
+            if let Some(path) = project_path {
+                app.open_project_file(&path);
+            }
+            Ok(Box::new(app))
         }),
     )
+}
+
+/// Web-Version: läuft im Canvas "cad_canvas" der index.html (Build mit `trunk build --release`)
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    diagnostics::install_panic_hook();
+    let config = AppConfig::load();
+    let options = eframe::WebOptions {
+        follow_system_theme: config.theme == ThemeMode::System,
+        default_theme: if config.theme == ThemeMode::Dark {
+            eframe::Theme::Dark
+        } else {
+            eframe::Theme::Light
+        },
+        ..Default::default()
+    };
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = eframe::WebRunner::new()
+            .start(
+                "cad_canvas",
+                options,
+                Box::new(|cc| {
+                    apply_style(&cc.egui_ctx);
+                    Ok(Box::new(ui::CadApp::new(config)))
+                }),
+            )
+            .await;
+        if let Err(e) = result {
+            panic!("Web-App konnte nicht gestartet werden: {:?}", e);
+        }
+    });
+}
+
+fn apply_style(ctx: &egui::Context) {
+    // Größere Schrift global einstellen
+    let mut style = (*ctx.style()).clone();
+    style.text_styles = [
+        (egui::TextStyle::Heading, egui::FontId::proportional(32.0)),
+        (egui::TextStyle::Body, egui::FontId::proportional(20.0)),
+        (egui::TextStyle::Monospace, egui::FontId::proportional(18.0)),
+        (egui::TextStyle::Button, egui::FontId::proportional(22.0)),
+        (egui::TextStyle::Small, egui::FontId::proportional(16.0)),
+    ].into();
+    
+    // Größere Buttons und Inputs
+    style.spacing.button_padding = egui::vec2(12.0, 8.0);
+    style.spacing.item_spacing = egui::vec2(12.0, 10.0);
+    style.spacing.interact_size = egui::vec2(50.0, 30.0);
+    
+    ctx.set_style(style);
 }
\ No newline at end of file
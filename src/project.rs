@@ -0,0 +1,135 @@
+// Projektdatei: Metadaten und alle Arbeitsblätter mit Eingaben und Zeichnungselementen als JSON
+// Die Vierecke selbst werden beim Öffnen aus den Eingaben neu berechnet
+
+use crate::geometry::{AngleDimension, ConstructionLine, CustomCircle, CustomLine, QuadInput};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Dateiendung der Projektdateien
+pub const PROJECT_EXTENSION: &str = "cadz";
+
+/// Angaben für den Schriftkopf von Protokollen und Exporten
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectMetadata {
+    pub name: String,   // Projektname
+    pub client: String, // Bauherr
+    pub author: String, // Bearbeiter
+    pub date: String,   // Freitext, leer = Exportdatum
+    pub notes: String,
+}
+
+impl ProjectMetadata {
+    /// Zeilen für den Schriftkopf, leere Angaben werden ausgelassen
+    pub fn title_block(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Projekt", self.name.as_str()),
+            ("Bauherr", self.client.as_str()),
+            ("Bearbeiter", self.author.as_str()),
+            ("Datum", self.date.as_str()),
+        ]
+        .into_iter()
+        .map(|(label, value)| (label, value.trim()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+}
+
+/// Eingefrorener Messstand eines Blatts für den Vorher/Nachher-Vergleich, z.B. Rohbau gegen Ausbau
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeasurementSnapshot {
+    pub name: String,
+    pub date: String, // Zeitpunkt des Einfrierens
+    pub input: QuadInput,
+}
+
+impl MeasurementSnapshot {
+    /// Bezeichnung in Listen und Vergleichstabelle, z.B. "Rohbau (03.02.2026 14:05)"
+    pub fn title(&self) -> String {
+        match self.name.trim() {
+            "" => self.date.clone(),
+            name => format!("{} ({})", name, self.date),
+        }
+    }
+}
+
+/// Ein Arbeitsblatt (z.B. ein Raum) der Projektdatei
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSheet {
+    pub name: String,
+    pub input: QuadInput,
+    pub lines: Vec<CustomLine>,
+    pub circles: Vec<CustomCircle>,
+    pub angle_dimensions: Vec<AngleDimension>,
+    pub construction_lines: Vec<ConstructionLine>,
+    pub snapshots: Vec<MeasurementSnapshot>,
+}
+
+/// Inhalt einer Projektdatei
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectFile {
+    pub metadata: ProjectMetadata,
+    pub sheets: Vec<ProjectSheet>,
+}
+
+impl ProjectFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = storage::read_to_string(path)
+            .map_err(|e| format!("Projekt konnte nicht gelesen werden: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Ungültige Projektdatei: {}", e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Projekt konnte nicht gespeichert werden: {}", e))?;
+        storage::write(path, content.as_bytes()).map_err(|e| format!("Projekt konnte nicht gespeichert werden: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_block() {
+        let metadata = ProjectMetadata {
+            name: "Umbau Bad".to_string(),
+            author: "  ".to_string(),
+            date: "03.02.2026".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(metadata.title_block(), vec![("Projekt", "Umbau Bad"), ("Datum", "03.02.2026")]);
+    }
+
+    #[test]
+    fn test_project_roundtrip() {
+        let mut project = ProjectFile::default();
+        project.metadata.client = "Familie Muster".to_string();
+        let mut sheet = ProjectSheet { name: "Küche".to_string(), ..Default::default() };
+        sheet.input.sides_mm[0] = Some(4000.0);
+        sheet.snapshots.push(MeasurementSnapshot {
+            name: "Rohbau".to_string(),
+            date: "03.02.2026 14:05".to_string(),
+            input: QuadInput::rectangle(4000.0, 3000.0),
+        });
+        project.sheets.push(sheet);
+
+        let json = serde_json::to_string(&project).unwrap();
+        let back: ProjectFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.metadata, project.metadata);
+        assert_eq!(back.sheets.len(), 1);
+        assert_eq!(back.sheets[0].name, "Küche");
+        assert_eq!(back.sheets[0].input, project.sheets[0].input);
+        assert_eq!(back.sheets[0].snapshots, project.sheets[0].snapshots);
+        assert_eq!(back.sheets[0].snapshots[0].title(), "Rohbau (03.02.2026 14:05)");
+
+        // Fehlende Felder bekommen Standardwerte
+        let old: ProjectFile = serde_json::from_str("{\"metadata\": {\"name\": \"Alt\"}}").unwrap();
+        assert_eq!(old.metadata.name, "Alt");
+        assert!(old.sheets.is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+// Bildexport ohne Bildschirmfoto
+// Die Darstellungsliste wird in der gewünschten Auflösung in Dreiecke zerlegt und in Software gerastert,
+// unabhängig von Fenstergröße und Grafikkarte (z.B. für Großformatdrucke)
+
+use egui::epaint::text::Fonts;
+use egui::epaint::{ClippedShape, Color32, Mesh, Primitive, TessellationOptions, Tessellator, Vertex};
+use egui::{Pos2, Rect, Shape, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// Größte Bildseite in Pixel (A0 bei 300 dpi hat 14.043 px)
+pub const MAX_IMAGE_SIDE_PX: u32 = 20_000;
+
+/// Bildschirmauflösung, bei der Strichstärken und Schriften so groß wie am Bildschirm wirken
+const SCREEN_DPI: f32 = 96.0;
+
+/// Papierformate nach DIN 476
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaperSize {
+    A4,
+    A3,
+    A2,
+    A1,
+    A0,
+}
+
+impl PaperSize {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaperSize::A4 => "A4",
+            PaperSize::A3 => "A3",
+            PaperSize::A2 => "A2",
+            PaperSize::A1 => "A1",
+            PaperSize::A0 => "A0",
+        }
+    }
+
+    /// Breite und Höhe im Hochformat (mm)
+    pub fn size_mm(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::A2 => (420.0, 594.0),
+            PaperSize::A1 => (594.0, 841.0),
+            PaperSize::A0 => (841.0, 1189.0),
+        }
+    }
+}
+
+/// Wie die Bildgröße festgelegt wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageSizeMode {
+    Pixels, // Breite × Höhe direkt
+    Paper,  // Papierformat und Druckauflösung
+}
+
+/// Einstellungen für den Bildexport
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageExportSettings {
+    pub mode: ImageSizeMode,
+    pub width_px: u32,
+    pub height_px: u32,
+    pub paper: PaperSize,
+    pub landscape: bool,
+    pub dpi: u32,
+}
+
+impl Default for ImageExportSettings {
+    fn default() -> Self {
+        Self {
+            mode: ImageSizeMode::Paper,
+            width_px: 3840,
+            height_px: 2160,
+            paper: PaperSize::A3,
+            landscape: true,
+            dpi: 300,
+        }
+    }
+}
+
+/// Bildgröße in Pixel und Vergrößerung gegenüber der Zeichnung in Punkten
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageResolution {
+    pub width_px: u32,
+    pub height_px: u32,
+    pub pixels_per_point: f32,
+}
+
+impl ImageResolution {
+    /// Zeichenfläche in Punkten, darin wird die Darstellung aufgebaut
+    pub fn rect(&self) -> Rect {
+        Rect::from_min_size(Pos2::ZERO, Vec2::new(self.width_px as f32, self.height_px as f32) / self.pixels_per_point)
+    }
+}
+
+impl ImageExportSettings {
+    /// Auflösung des Bildes; `screen_width` ist die Breite der Zeichenfläche in Punkten,
+    /// bei fester Pixelgröße wirken Schriften und Strichstärken dann wie am Bildschirm
+    pub fn resolution(&self, screen_width: f32) -> Result<ImageResolution, String> {
+        let (width_px, height_px, pixels_per_point) = match self.mode {
+            ImageSizeMode::Pixels => (self.width_px, self.height_px, self.width_px as f32 / screen_width.max(1.0)),
+            ImageSizeMode::Paper => {
+                let (short, long) = self.paper.size_mm();
+                let (width_mm, height_mm) = if self.landscape { (long, short) } else { (short, long) };
+                let px = |mm: f32| (mm / 25.4 * self.dpi as f32).round() as u32;
+                (px(width_mm), px(height_mm), self.dpi as f32 / SCREEN_DPI)
+            }
+        };
+
+        if width_px == 0 || height_px == 0 {
+            return Err("Bildgröße muss größer als 0 sein".to_string());
+        }
+        if width_px.max(height_px) > MAX_IMAGE_SIDE_PX {
+            return Err(format!(
+                "Bild zu groß: {} × {} px (höchstens {} px je Seite)",
+                width_px, height_px, MAX_IMAGE_SIDE_PX
+            ));
+        }
+        Ok(ImageResolution { width_px, height_px, pixels_per_point: pixels_per_point.max(0.1) })
+    }
+}
+
+/// Rastert die Formen auf weißen Grund
+/// `fonts` muss mit `resolution.pixels_per_point` angelegt sein, sonst werden Texte unscharf
+pub fn render(shapes: Vec<Shape>, fonts: &Fonts, resolution: &ImageResolution) -> image::RgbaImage {
+    let clip_rect = resolution.rect();
+    let clipped = shapes.into_iter().map(|shape| ClippedShape { clip_rect, shape }).collect();
+    let prepared_discs = fonts.texture_atlas().lock().prepared_discs();
+    let primitives = Tessellator::new(resolution.pixels_per_point, TessellationOptions::default(), fonts.font_image_size(), prepared_discs)
+        .tessellate_shapes(clipped);
+
+    let font_image = fonts.image();
+    let texture = Texture { size: font_image.size, pixels: font_image.srgba_pixels(None).collect() };
+    let mut canvas = Canvas::new(resolution.width_px as usize, resolution.height_px as usize);
+    for primitive in primitives {
+        if let Primitive::Mesh(mesh) = primitive.primitive {
+            let clip = Rect::from_min_max(
+                (primitive.clip_rect.min.to_vec2() * resolution.pixels_per_point).to_pos2(),
+                (primitive.clip_rect.max.to_vec2() * resolution.pixels_per_point).to_pos2(),
+            );
+            canvas.draw_mesh(&mesh, resolution.pixels_per_point, clip, &texture);
+        }
+    }
+    canvas.into_image()
+}
+
+/// Schriftatlas; enthält auch das weiße Pixel für Flächen ohne Textur
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+}
+
+impl Texture {
+    fn sample(&self, uv: Pos2) -> [f32; 4] {
+        let x = ((uv.x * self.size[0] as f32) as usize).min(self.size[0] - 1);
+        let y = ((uv.y * self.size[1] as f32) as usize).min(self.size[1] - 1);
+        self.pixels[y * self.size[0] + x].to_array().map(|c| c as f32 / 255.0)
+    }
+}
+
+/// Bildpuffer mit vormultipliziertem Alpha, wie egui ihn erwartet
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height, pixels: vec![[1.0; 4]; width * height] }
+    }
+
+    fn draw_mesh(&mut self, mesh: &Mesh, pixels_per_point: f32, clip: Rect, texture: &Texture) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+            self.draw_triangle([a, b, c], pixels_per_point, clip, texture);
+        }
+    }
+
+    fn draw_triangle(&mut self, vertices: [&Vertex; 3], pixels_per_point: f32, clip: Rect, texture: &Texture) {
+        let [p0, p1, p2] = vertices.map(|v| (v.pos.to_vec2() * pixels_per_point).to_pos2());
+        let area = edge(p0, p1, p2);
+        if area.abs() < 1e-6 {
+            return;
+        }
+
+        let min_x = p0.x.min(p1.x).min(p2.x).max(clip.min.x).max(0.0).floor() as usize;
+        let min_y = p0.y.min(p1.y).min(p2.y).max(clip.min.y).max(0.0).floor() as usize;
+        let max_x = (p0.x.max(p1.x).max(p2.x).min(clip.max.x).ceil().max(0.0) as usize).min(self.width);
+        let max_y = (p0.y.max(p1.y).max(p2.y).min(clip.max.y).ceil().max(0.0) as usize).min(self.height);
+        let colors = vertices.map(|v| v.color.to_array().map(|c| c as f32 / 255.0));
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                // Pixelmitte, Gewichte der Eckpunkte (baryzentrisch)
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let w = [edge(p1, p2, p) / area, edge(p2, p0, p) / area, edge(p0, p1, p) / area];
+                if w.iter().any(|&w| w < 0.0) {
+                    continue;
+                }
+
+                let uv = Pos2::new(
+                    w[0] * vertices[0].uv.x + w[1] * vertices[1].uv.x + w[2] * vertices[2].uv.x,
+                    w[0] * vertices[0].uv.y + w[1] * vertices[1].uv.y + w[2] * vertices[2].uv.y,
+                );
+                let texel = texture.sample(uv);
+                let src: [f32; 4] =
+                    std::array::from_fn(|i| (w[0] * colors[0][i] + w[1] * colors[1][i] + w[2] * colors[2][i]) * texel[i]);
+
+                let dst = &mut self.pixels[y * self.width + x];
+                for i in 0..4 {
+                    dst[i] = src[i] + dst[i] * (1.0 - src[3]);
+                }
+            }
+        }
+    }
+
+    fn into_image(self) -> image::RgbaImage {
+        let bytes = self.pixels.iter().flat_map(|px| px.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)).collect();
+        image::RgbaImage::from_raw(self.width as u32, self.height as u32, bytes).expect("Puffergröße passt zum Bild")
+    }
+}
+
+/// Doppelte vorzeichenbehaftete Fläche des Dreiecks a, b, c
+fn edge(a: Pos2, b: Pos2, c: Pos2) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{Align2, FontDefinitions, FontId, Stroke};
+
+    #[test]
+    fn test_paper_resolution() {
+        let settings = ImageExportSettings { landscape: false, paper: PaperSize::A4, ..Default::default() };
+        let resolution = settings.resolution(1000.0).unwrap();
+        assert_eq!((resolution.width_px, resolution.height_px), (2480, 3508));
+        assert!((resolution.pixels_per_point - 3.125).abs() < 1e-6);
+
+        let pixels = ImageExportSettings { mode: ImageSizeMode::Pixels, width_px: 2000, height_px: 1000, ..Default::default() };
+        let resolution = pixels.resolution(1000.0).unwrap();
+        assert_eq!(resolution.pixels_per_point, 2.0);
+        assert_eq!(resolution.rect().size(), Vec2::new(1000.0, 500.0));
+
+        let huge = ImageExportSettings { paper: PaperSize::A0, dpi: 600, ..Default::default() };
+        assert!(huge.resolution(1000.0).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let resolution = ImageResolution { width_px: 40, height_px: 20, pixels_per_point: 2.0 };
+        let fonts = Fonts::new(resolution.pixels_per_point, 2048, FontDefinitions::default());
+        let red = Color32::from_rgb(200, 0, 0);
+        let text = fonts.layout_no_wrap("Test".to_string(), FontId::proportional(8.0), Color32::BLACK);
+        let shapes = vec![
+            Shape::rect_filled(Rect::from_min_size(Pos2::ZERO, Vec2::splat(5.0)), 0.0, red),
+            Shape::rect_stroke(Rect::from_min_size(Pos2::new(10.0, 0.0), Vec2::splat(8.0)), 0.0, Stroke::new(1.0, red)),
+            Shape::galley(Align2::LEFT_TOP.anchor_size(Pos2::new(10.0, 1.0), text.size()).min, text, Color32::BLACK),
+        ];
+
+        let image = render(shapes, &fonts, &resolution);
+        assert_eq!(image.dimensions(), (40, 20));
+        assert_eq!(image.get_pixel(4, 4).0, [200, 0, 0, 255]);
+        assert_eq!(image.get_pixel(39, 19).0, [255, 255, 255, 255]);
+        // Schrift erscheint als dunkle Pixel im Textbereich
+        assert!((20..40).any(|x| (2..18).any(|y| image.get_pixel(x, y).0[0] < 100)));
+    }
+}
@@ -0,0 +1,641 @@
+// Aufmaß-Protokoll als eigenständige HTML-Datei (im Browser ansehen oder als PDF drucken)
+// Zeichnung als eingebettetes SVG, damit keine Bilddateien mitgegeben werden müssen
+
+use crate::config::{AngleDisplay, FillPattern, FillSettings, LengthUnit, VertexLabels};
+use crate::costs::{self, CostItem};
+use crate::format::NumberFormat;
+use crate::geometry::{construction_segment, ConstructionLine, CustomCircle, CustomLine, LineStyle, Point, QuadInput, Quadrilateral};
+use crate::project::ProjectMetadata;
+use crate::view::ViewOrientation;
+use serde::{Deserialize, Serialize};
+
+const SVG_WIDTH: f64 = 640.0;
+const SVG_HEIGHT: f64 = 420.0;
+const SVG_PADDING: f64 = 40.0;
+/// Größte Logodatei; das Logo liegt eingebettet in den Einstellungen
+pub const MAX_LOGO_BYTES: usize = 512 * 1024;
+
+/// Firmenkopf für Protokolle: Logo und Anschrift, in den Einstellungen gespeichert
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompanyBranding {
+    pub company: String,
+    pub address: String,       // mehrzeilig: Straße, Ort, Telefon …
+    pub logo_data_uri: String, // "data:image/png;base64,…", leer = ohne Logo
+}
+
+impl CompanyBranding {
+    pub fn is_empty(&self) -> bool {
+        self.company.trim().is_empty() && self.address.trim().is_empty() && self.logo_data_uri.is_empty()
+    }
+
+    /// Übernimmt eine PNG-, JPEG- oder SVG-Datei als Logo
+    pub fn set_logo(&mut self, bytes: &[u8]) -> Result<(), String> {
+        if bytes.len() > MAX_LOGO_BYTES {
+            return Err(format!("Logo ist zu groß (höchstens {} KB)", MAX_LOGO_BYTES / 1024));
+        }
+        let mime = if bytes.starts_with(b"\x89PNG") {
+            "image/png"
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            "image/jpeg"
+        } else if String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).contains("<svg") {
+            "image/svg+xml"
+        } else {
+            return Err("Logo muss eine PNG-, JPEG- oder SVG-Datei sein".to_string());
+        };
+        self.logo_data_uri = format!("data:{};base64,{}", mime, base64(bytes));
+        Ok(())
+    }
+}
+
+/// Base64 nach RFC 4648 mit Auffüllung, für eingebettete Bilder
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Alles, was in das Protokoll kommt
+pub struct Report<'a> {
+    pub metadata: &'a ProjectMetadata,
+    pub date: String, // Exportdatum, falls im Projekt kein Datum steht
+    pub quad: &'a Quadrilateral,
+    pub input: Option<&'a QuadInput>, // Gemessene Werte, der Rest gilt als berechnet
+    pub lines: &'a [CustomLine],
+    pub circles: &'a [CustomCircle],
+    pub construction_lines: &'a [ConstructionLine], // Leer, solange Hilfslinien nicht exportiert werden sollen
+    pub labels: &'a VertexLabels,
+    pub reference_side: Option<usize>, // Seite, die waagerecht unten liegt (None = wie berechnet)
+    pub number: NumberFormat,
+    pub unit: LengthUnit,
+    pub angle_display: AngleDisplay, // Innen-, Außenwinkel oder beide
+    pub fill: &'a FillSettings,      // Füllung bzw. Schraffur der Fläche wie in der Zeichenfläche
+    pub triangle_diagonal: usize,
+    pub costs: &'a [CostItem],
+    pub branding: &'a CompanyBranding,
+}
+
+/// Maskiert Text für HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Weltkoordinaten (µm) → SVG-Koordinaten, eingepasst mit Rand
+struct SvgFit {
+    min_x: f64,
+    min_y: f64,
+    scale: f64,
+    orientation: ViewOrientation, // Umlaufsinn der Beschriftung und Bezugsseite wie in der Zeichenfläche
+}
+
+impl SvgFit {
+    fn new(points: &[Point], orientation: ViewOrientation) -> Self {
+        let points: Vec<Point> = points.iter().map(|p| orientation.apply(p)).collect();
+        let min_x = points.iter().map(|p| p.x).fold(f64::MAX, f64::min);
+        let max_x = points.iter().map(|p| p.x).fold(f64::MIN, f64::max);
+        let min_y = points.iter().map(|p| p.y).fold(f64::MAX, f64::min);
+        let max_y = points.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+        let scale = ((SVG_WIDTH - 2.0 * SVG_PADDING) / (max_x - min_x).max(1.0))
+            .min((SVG_HEIGHT - 2.0 * SVG_PADDING) / (max_y - min_y).max(1.0));
+        Self { min_x, min_y, scale, orientation }
+    }
+
+    fn map(&self, p: &Point) -> (f64, f64) {
+        let p = self.orientation.apply(p);
+        (
+            SVG_PADDING + (p.x - self.min_x) * self.scale,
+            SVG_PADDING + (p.y - self.min_y) * self.scale,
+        )
+    }
+
+    fn points(&self, points: &[Point]) -> String {
+        points
+            .iter()
+            .map(|p| {
+                let (x, y) = self.map(p);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Report<'_> {
+    fn length(&self, mm: f64) -> String {
+        self.number.length(mm, self.unit)
+    }
+
+    /// Umlaufsinn der Beschriftung und Bezugsseite wie in der Zeichenfläche
+    pub fn orientation(&self) -> ViewOrientation {
+        let orientation = ViewOrientation { mirror_x: self.quad.is_clockwise() != self.labels.clockwise, ..Default::default() };
+        match self.reference_side {
+            Some(side) => orientation.with_baseline(self.quad, side),
+            None => orientation,
+        }
+    }
+
+    /// Ausgeblendete Linien kommen wie im GeoJSON-Export nicht ins Protokoll
+    pub fn visible_lines(&self) -> impl Iterator<Item = &CustomLine> {
+        self.lines.iter().filter(|line| !line.hidden)
+    }
+
+    /// Füllung der Viereckfläche als SVG-Attribute; Schraffuren als Muster mit Abstand und
+    /// Strichstärke wie in der Zeichenfläche, das SVG schneidet es an der Fläche ab
+    fn area_fill(&self) -> (String, String) {
+        let [r, g, b] = self.fill.color;
+        let color = format!("rgb({},{},{})", r, g, b);
+        let opacity = self.fill.opacity as f64 / 255.0;
+        // Im gedrehten Muster ergibt die waagerechte Linie 45°, die senkrechte -45°
+        let mut strokes = vec![(0, 6, 12, 6)];
+        match self.fill.pattern {
+            FillPattern::None => return (String::new(), "fill=\"#e6ecff\"".to_string()),
+            FillPattern::Solid => return (String::new(), format!("fill=\"{}\" fill-opacity=\"{:.2}\"", color, opacity)),
+            FillPattern::Hatch => {}
+            FillPattern::CrossHatch => strokes.push((6, 0, 6, 12)),
+        }
+        let mut defs = String::from(
+            "<defs><pattern id=\"area_hatch\" patternUnits=\"userSpaceOnUse\" width=\"12\" height=\"12\" patternTransform=\"rotate(45)\">",
+        );
+        for (x1, y1, x2, y2) in strokes {
+            defs.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-opacity=\"{:.2}\" stroke-width=\"1.5\"/>",
+                x1, y1, x2, y2, color, opacity
+            ));
+        }
+        defs.push_str("</pattern></defs>\n");
+        (defs, "fill=\"url(#area_hatch)\"".to_string())
+    }
+
+    /// Zeichnung mit Viereck, Eckpunkten, Linien und Kreisen
+    fn drawing_svg(&self) -> String {
+        // Linien dürfen über das Viereck hinausreichen und sollen vollständig sichtbar sein
+        let points: Vec<Point> = self
+            .quad
+            .vertices
+            .iter()
+            .chain(self.visible_lines().flat_map(|line| [&line.start, &line.end]))
+            .cloned()
+            .collect();
+        let fit = SvgFit::new(&points, self.orientation());
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = SVG_WIDTH,
+            h = SVG_HEIGHT
+        );
+
+        // Hilfslinien unter dem Viereck; unbegrenzte reichen über die ganze Zeichnung und werden vom SVG beschnitten
+        let reach_mm = (SVG_WIDTH + SVG_HEIGHT) / fit.scale / 1000.0;
+        for line in self.construction_lines {
+            if let Some((start, end)) = construction_segment(self.quad, line, reach_mm) {
+                let (x1, y1) = fit.map(&start);
+                let (x2, y2) = fit.map(&end);
+                svg.push_str(&format!(
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#8c8c8c\" stroke-width=\"1\" stroke-dasharray=\"6 4\"/>\n",
+                    x1, y1, x2, y2
+                ));
+            }
+        }
+
+        let (defs, fill) = self.area_fill();
+        svg.push_str(&defs);
+        svg.push_str(&format!(
+            "<polygon points=\"{}\" {} stroke=\"#3232c8\" stroke-width=\"3\"/>\n",
+            fit.points(&self.quad.vertices),
+            fill
+        ));
+
+        for circle in self.circles {
+            let [r, g, b] = circle.color;
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"rgb({},{},{})\" stroke-width=\"{:.1}\"/>\n",
+                fit.points(&circle.outline(96)),
+                r,
+                g,
+                b,
+                circle.width
+            ));
+        }
+
+        for line in self.visible_lines() {
+            let (x1, y1) = fit.map(&line.start);
+            let (x2, y2) = fit.map(&line.end);
+            let [r, g, b] = line.color;
+            let dash = match line.style {
+                LineStyle::Solid => "",
+                LineStyle::Dashed => " stroke-dasharray=\"10 6\"",
+                LineStyle::Dotted => " stroke-dasharray=\"2 5\"",
+            };
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"rgb({},{},{})\" stroke-width=\"{:.1}\"{}/>\n",
+                x1, y1, x2, y2, r, g, b, line.width, dash
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"12\" text-anchor=\"middle\" fill=\"#383e42\">{}</text>\n",
+                (x1 + x2) / 2.0,
+                (y1 + y2) / 2.0 - 4.0,
+                escape(&line.name)
+            ));
+        }
+
+        // Eckpunkte mit Abstand nach außen beschriften
+        let (cx, cy) = self
+            .quad
+            .vertices
+            .iter()
+            .map(|p| fit.map(p))
+            .fold((0.0, 0.0), |acc, (x, y)| (acc.0 + x / 4.0, acc.1 + y / 4.0));
+        for (i, vertex) in self.quad.vertices.iter().enumerate() {
+            let (x, y) = fit.map(vertex);
+            let (dx, dy) = (x - cx, y - cy);
+            let len = (dx * dx + dy * dy).sqrt().max(1.0);
+            svg.push_str(&format!("<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"#c83232\"/>\n", x, y));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"18\" font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>\n",
+                x + dx / len * 18.0,
+                y + dy / len * 18.0,
+                escape(self.labels.vertex(i))
+            ));
+        }
+
+        // Winkel als Bogen, Wert auf der Winkelhalbierenden; Außenwinkel mit Bogen außerhalb
+        let angles = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
+        for (i, angle) in angles.iter().enumerate() {
+            let Some(angle) = *angle else {
+                continue;
+            };
+            let (x, y) = fit.map(&self.quad.vertices[i]);
+            let legs = [(i + 3) % 4, (i + 1) % 4].map(|j| {
+                let (px, py) = fit.map(&self.quad.vertices[j]);
+                let len = ((px - x).powi(2) + (py - y).powi(2)).sqrt().max(1.0);
+                ((px - x) / len, (py - y) / len, len)
+            });
+            let radius = (legs[0].2.min(legs[1].2) * 0.3).min(24.0);
+            let [(ax, ay, _), (bx, by, _)] = legs;
+            let reflex = (angle > 180.0) != (self.angle_display == AngleDisplay::Exterior);
+            // SVG: y nach unten, Sweep-Flag 1 = im Uhrzeigersinn auf dem Blatt
+            let clockwise = (ax * by - ay * bx > 0.0) != reflex;
+            let (mx, my) = if reflex { (-(ax + bx), -(ay + by)) } else { (ax + bx, ay + by) };
+            let mlen = (mx * mx + my * my).sqrt();
+            let (mx, my) = if mlen > 1e-9 { (mx / mlen, my / mlen) } else if clockwise { (-ay, ax) } else { (ay, -ax) };
+            svg.push_str(&format!(
+                "<path d=\"M {:.1} {:.1} A {r:.1} {r:.1} 0 {} {} {:.1} {:.1}\" fill=\"none\" stroke=\"#646464\" stroke-width=\"1.5\"/>\n",
+                x + ax * radius,
+                y + ay * radius,
+                reflex as u8,
+                clockwise as u8,
+                x + bx * radius,
+                y + by * radius,
+                r = radius
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" text-anchor=\"middle\" dominant-baseline=\"middle\" fill=\"#646464\">{}</text>\n",
+                x + mx * (radius + 16.0),
+                y + my * (radius + 16.0),
+                escape(&self.number.corner_angle(angle, self.angle_display))
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Tabelle mit Seiten, Winkeln, Diagonalen und Fläche
+    fn values_table(&self) -> String {
+        let input = self.input.cloned().unwrap_or_default();
+        let mut rows = String::new();
+
+        for i in 0..4 {
+            let length_mm = self.quad.get_side_length_mm(i);
+            rows.push_str(&format!(
+                "<tr><td>Seite {}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                escape(&self.labels.side(i)),
+                self.length(length_mm),
+                escape(&input.side_source(i, length_mm).describe(|measured| self.length(measured)))
+            ));
+        }
+
+        let angles = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
+        for (i, angle) in angles.iter().enumerate() {
+            let angle = angle.unwrap_or(0.0);
+            let status = input.angle_source(i, angle).describe(|measured| self.number.angle(measured));
+            for (suffix, degrees) in self.angle_display.corner_values(angle) {
+                rows.push_str(&format!(
+                    "<tr><td>Winkel {}{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                    escape(self.labels.vertex(i)),
+                    suffix,
+                    self.number.angle(degrees),
+                    escape(&status)
+                ));
+            }
+        }
+
+        for d in 0..2 {
+            let length_mm = self.quad.diagonal_um(d) as f64 / 1000.0;
+            rows.push_str(&format!(
+                "<tr><td>Diagonale {}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                escape(&self.labels.diagonal(d)),
+                self.length(length_mm),
+                escape(&input.diagonal_source(d, length_mm).describe(|measured| self.length(measured)))
+            ));
+        }
+
+        let perimeter_mm: f64 = (0..4).map(|i| self.quad.get_side_length_mm(i)).sum();
+        rows.push_str(&format!(
+            "<tr><td>Umfang</td><td class=\"num\">{}</td><td>berechnet</td></tr>\n",
+            self.length(perimeter_mm)
+        ));
+        rows.push_str(&format!(
+            "<tr><td>Fläche</td><td class=\"num\">{} m²</td><td>berechnet</td></tr>\n",
+            self.number.number(self.quad.area_mm2() / 1_000_000.0)
+        ));
+
+        format!("<table>\n<tr><th>Größe</th><th>Wert</th><th></th></tr>\n{}</table>\n", rows)
+    }
+
+    fn lines_table(&self) -> String {
+        if self.visible_lines().next().is_none() {
+            return String::new();
+        }
+        let mut rows = String::new();
+        for line in self.visible_lines() {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape(&line.name),
+                self.length(line.length_um as f64 / 1000.0),
+                self.number.angle(line.start_angle),
+                self.number.angle(line.end_angle)
+            ));
+        }
+        format!(
+            "<h2>Eingezeichnete Linien</h2>\n<table>\n<tr><th>Name</th><th>Länge</th><th>Winkel Start</th><th>Winkel Ende</th></tr>\n{}</table>\n",
+            rows
+        )
+    }
+
+    /// Zerlegung in zwei Dreiecke zum Abstecken mit dem Maßband
+    fn triangles_table(&self) -> String {
+        let mut rows = String::new();
+        for triangle in self.quad.split_triangles(self.triangle_diagonal) {
+            let [a, b, c] = triangle.vertices;
+            let name = format!("{}{}{}", self.labels.vertex(a), self.labels.vertex(b), self.labels.vertex(c));
+            let sides = (0..3)
+                .map(|i| {
+                    let (from, to) = (triangle.vertices[i], triangle.vertices[(i + 1) % 3]);
+                    format!("{} = {}", self.labels.segment(from, to), self.length(triangle.sides_mm[i]))
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let angles = (0..3)
+                .map(|i| format!("{} = {}", self.labels.vertex(triangle.vertices[i]), self.number.angle(triangle.angles_deg[i])))
+                .collect::<Vec<_>>()
+                .join(", ");
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape(&name),
+                escape(&sides),
+                escape(&angles)
+            ));
+        }
+        format!(
+            "<h2>Absteckung in Dreiecken (Diagonale {})</h2>\n<table>\n<tr><th>Dreieck</th><th>Seiten</th><th>Winkel</th></tr>\n{}</table>\n",
+            escape(&self.labels.diagonal(self.triangle_diagonal)),
+            rows
+        )
+    }
+
+    fn costs_table(&self) -> String {
+        if self.costs.is_empty() {
+            return String::new();
+        }
+        let mut rows = String::new();
+        for item in self.costs {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{} {}</td><td class=\"num\">{} €</td><td class=\"num\">{} €</td></tr>\n",
+                item.label,
+                self.number.number(item.quantity),
+                item.unit,
+                self.number.number(item.rate),
+                self.number.number(item.total())
+            ));
+        }
+        format!(
+            "<h2>Materialkosten (Schätzung)</h2>\n<table>\n<tr><th>Position</th><th>Menge</th><th>Preis</th><th>Summe</th></tr>\n{}\
+             <tr><th colspan=\"3\">Gesamt</th><th class=\"num\">{} €</th></tr>\n</table>\n",
+            rows,
+            self.number.number(costs::total_cost(self.costs))
+        )
+    }
+
+    /// Briefkopf mit Firmenlogo und Anschrift über dem Titel
+    fn letterhead(&self) -> String {
+        let branding = self.branding;
+        if branding.is_empty() {
+            return String::new();
+        }
+        let logo = if branding.logo_data_uri.is_empty() {
+            String::new()
+        } else {
+            format!("<img class=\"logo\" src=\"{}\" alt=\"Logo\">\n", escape(&branding.logo_data_uri))
+        };
+        let mut address: Vec<String> = Vec::new();
+        if !branding.company.trim().is_empty() {
+            address.push(format!("<strong>{}</strong>", escape(branding.company.trim())));
+        }
+        address.extend(branding.address.lines().map(str::trim).filter(|l| !l.is_empty()).map(escape));
+        format!("<div class=\"letterhead\">\n{}<div class=\"address\">{}</div>\n</div>\n", logo, address.join("<br>"))
+    }
+
+    /// Schriftkopf mit den Projektangaben, ohne eigenes Datum gilt das Exportdatum
+    fn title_block(&self) -> String {
+        let mut rows: String = self
+            .metadata
+            .title_block()
+            .into_iter()
+            .map(|(label, value)| format!("<tr><th>{}</th><td>{}</td></tr>\n", label, escape(value)))
+            .collect();
+        if self.metadata.date.trim().is_empty() {
+            rows.push_str(&format!("<tr><th>Datum</th><td>{}</td></tr>\n", escape(&self.date)));
+        }
+        format!("<table class=\"title-block\">\n{}</table>\n", rows)
+    }
+
+    /// Vollständiges HTML-Dokument
+    pub fn to_html(&self) -> String {
+        let project = self.metadata.name.trim();
+        let title = if project.is_empty() {
+            "Aufmaß-Protokoll".to_string()
+        } else {
+            format!("Aufmaß-Protokoll – {}", project)
+        };
+        let notes = self.metadata.notes.trim();
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!("<h2>Notizen</h2>\n<p class=\"notes\">{}</p>\n", escape(notes))
+        };
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n\
+             body {{ font-family: sans-serif; margin: 2em; color: #222; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 1.5em; }}\n\
+             th, td {{ border: 1px solid #bbb; padding: 4px 10px; text-align: left; }}\n\
+             td.num, th.num {{ text-align: right; }}\n\
+             .meta {{ color: #555; }}\n\
+             .notes {{ white-space: pre-wrap; }}\n\
+             .title-block th {{ background: #f2f2f2; }}\n\
+             .letterhead {{ display: flex; justify-content: space-between; align-items: flex-start; border-bottom: 2px solid #444; padding-bottom: 0.5em; }}\n\
+             .letterhead .logo {{ max-height: 80px; max-width: 260px; }}\n\
+             .letterhead .address {{ text-align: right; font-size: 0.9em; margin-left: auto; }}\n\
+             </style>\n</head>\n<body>\n\
+             {letterhead}<h1>{title}</h1>\n{title_block}\
+             {drawing}\n<h2>Maße</h2>\n{values}{lines}{triangles}{costs}{notes}\
+             <p class=\"meta\">Erstellt mit CAD-Zeichner {version}</p>\n</body>\n</html>\n",
+            title = escape(&title),
+            letterhead = self.letterhead(),
+            title_block = self.title_block(),
+            drawing = self.drawing_svg(),
+            values = self.values_table(),
+            lines = self.lines_table(),
+            triangles = self.triangles_table(),
+            costs = self.costs_table(),
+            notes = notes,
+            version = env!("CARGO_PKG_VERSION"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::LineAnchor;
+
+    #[test]
+    fn test_report_html() {
+        let mut quad = Quadrilateral::new();
+        quad.vertices = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(4_000_000.0, 3_000_000.0),
+            Point::new(0.0, 3_000_000.0),
+        ];
+        quad.angle_a = Some(90.0);
+        let labels = VertexLabels::default();
+        let metadata = ProjectMetadata {
+            name: "Bad <OG>".to_string(),
+            client: "Familie Muster".to_string(),
+            notes: "Fliesen bauseits".to_string(),
+            ..Default::default()
+        };
+        let report = Report {
+            metadata: &metadata,
+            date: "01.01.2026".to_string(),
+            quad: &quad,
+            input: None,
+            lines: &[],
+            circles: &[],
+            construction_lines: &[],
+            labels: &labels,
+            reference_side: Some(1),
+            number: NumberFormat::new(2),
+            unit: LengthUnit::Meter,
+            angle_display: AngleDisplay::Interior,
+            fill: &FillSettings::default(),
+            triangle_diagonal: 0,
+            costs: &[],
+            branding: &CompanyBranding::default(),
+        };
+
+        let html = report.to_html();
+        assert!(html.contains("<title>Aufmaß-Protokoll – Bad &lt;OG&gt;</title>"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("<path d=\"M "));
+        assert!(html.contains("<td>Winkel A</td><td class=\"num\">90,00°</td>"));
+        assert!(html.contains("Fliesen bauseits"));
+        assert!(html.contains("<tr><th>Bauherr</th><td>Familie Muster</td></tr>"));
+        assert!(html.contains("<tr><th>Datum</th><td>01.01.2026</td></tr>"));
+        assert!(html.contains("12,00 m²"));
+        assert!(!html.contains("Materialkosten"));
+        assert!(!html.contains("stroke-dasharray=\"6 4\""));
+
+        let html = Report { angle_display: AngleDisplay::Both, date: String::new(), ..report }.to_html();
+        assert!(html.contains("<td>Winkel A innen</td><td class=\"num\">90,00°</td>"));
+        assert!(html.contains("<td>Winkel A außen</td><td class=\"num\">270,00°</td>"));
+        assert!(html.contains(">90,00° / 270,00°</text>"));
+
+        assert!(html.contains("fill=\"#e6ecff\""));
+        let hatch = FillSettings { pattern: FillPattern::CrossHatch, ..Default::default() };
+        let html = Report { fill: &hatch, date: String::new(), ..report }.to_html();
+        assert!(html.contains("<pattern id=\"area_hatch\""));
+        assert!(html.contains("fill=\"url(#area_hatch)\""));
+        assert_eq!(html.matches("stroke-opacity=").count(), 2);
+
+        let mut line = CustomLine::new("Achse".to_string(), LineAnchor::Vertex(0), LineAnchor::Vertex(2), &quad, &[]);
+        line.width = 4.0;
+        let lines = [line];
+        let html = Report { lines: &lines, date: String::new(), ..report }.to_html();
+        assert!(html.contains("stroke-width=\"4.0\"/>"));
+        assert!(html.contains("<td>Achse</td>"));
+
+        let mut hidden = lines.clone();
+        hidden[0].hidden = true;
+        let html = Report { lines: &hidden, date: String::new(), ..report }.to_html();
+        assert!(!html.contains("Achse"));
+        assert!(!html.contains("Eingezeichnete Linien"));
+
+        let extension = [ConstructionLine { side: 0, extension_mm: Some(1000.0) }];
+        let html = Report { construction_lines: &extension, ..report }.to_html();
+        assert!(html.contains("stroke-dasharray=\"6 4\""));
+        assert!(!html.contains("class=\"letterhead\""));
+    }
+
+    #[test]
+    fn test_branding() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+
+        let mut branding = CompanyBranding { company: "Muster & Co".to_string(), address: "Hauptstr. 1\n\n12345 Ort".to_string(), ..Default::default() };
+        assert!(branding.set_logo(b"GIF89a").is_err());
+        branding.set_logo(b"\x89PNG\r\n").unwrap();
+        assert_eq!(branding.logo_data_uri, "data:image/png;base64,iVBORw0K");
+
+        let quad = Quadrilateral::new();
+        let labels = VertexLabels::default();
+        let metadata = ProjectMetadata::default();
+        let report = Report {
+            metadata: &metadata,
+            date: String::new(),
+            quad: &quad,
+            input: None,
+            lines: &[],
+            circles: &[],
+            construction_lines: &[],
+            labels: &labels,
+            reference_side: None,
+            number: NumberFormat::new(2),
+            unit: LengthUnit::Meter,
+            angle_display: AngleDisplay::Interior,
+            fill: &FillSettings::default(),
+            triangle_diagonal: 0,
+            costs: &[],
+            branding: &branding,
+        };
+        let letterhead = report.letterhead();
+        assert!(letterhead.contains("<img class=\"logo\" src=\"data:image/png;base64,iVBORw0K\""));
+        assert!(letterhead.contains("<strong>Muster &amp; Co</strong><br>Hauptstr. 1<br>12345 Ort"));
+    }
+}
@@ -0,0 +1,1009 @@
+// Darstellungsliste der Zeichenfläche
+// Formen und Beschriftungen werden einmal erzeugt und wiederverwendet, solange sich Geometrie,
+// Ansicht und Auswahl nicht ändern. Dazu die Bildschirmpositionen der Linien für die Trefferprüfung
+// und das Zeichnen der Darstellung selbst (Viereck, Linien, Kreise und alle Maße)
+
+use crate::config::{AngleDisplay, FillPattern, FillSettings, LengthUnit, VertexLabels};
+use crate::format::NumberFormat;
+use crate::geometry::utils::distance_um;
+use crate::geometry::{
+    anchor_segment, construction_segment, distance_f64, edge_angle, edge_segment, line_crossings, perimeter_stations,
+    AngleDimension, Baseline, ConstructionLine, CustomCircle, CustomLine, CutEdge, LineAnchor, LineStyle, LocalFrame,
+    Point, PointGridSpec, Quadrilateral,
+};
+use crate::view::{angle_arc, angle_arc_radius, hatch_segments, ViewTransform};
+use egui::epaint::text::Fonts;
+use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Shape, Stroke, Vec2};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Kantenlänge der Rasterzellen für die Trefferprüfung in Punkten
+const HIT_CELL_SIZE: f32 = 48.0;
+/// Rand um die Zeichenfläche, der noch über das Raster gesucht wird
+const HIT_MARGIN: f32 = 2.0 * HIT_CELL_SIZE;
+
+/// Fingerabdruck aller Eingaben, aus denen die Darstellung entsteht
+#[derive(Default)]
+pub struct SceneKey(DefaultHasher);
+
+impl SceneKey {
+    pub fn add(&mut self, value: impl Hash) {
+        value.hash(&mut self.0);
+    }
+
+    /// Für Daten ohne `Hash` (z.B. mit f64): über ihre JSON-Darstellung, ohne Zwischenspeicher
+    pub fn add_serialized(&mut self, value: &impl Serialize) {
+        let _ = serde_json::to_writer(HashWriter(&mut self.0), value);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+struct HashWriter<'a>(&'a mut DefaultHasher);
+
+impl std::io::Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sammelt Formen, statt sie sofort zu zeichnen (gleiche Aufrufe wie `egui::Painter`)
+/// Texte werden dabei einmal gesetzt und als fertige Galley gespeichert
+pub struct SceneBuilder {
+    fonts: Fonts, // Nur für das Setzen der Texte
+    shapes: Vec<Shape>,
+    lines: Vec<ScreenLine>,
+}
+
+impl SceneBuilder {
+    /// Für die Zeichenfläche, Texte mit den Schriften des Fensters
+    pub fn new(painter: &Painter) -> Self {
+        Self::with_fonts(painter.fonts(|fonts| fonts.clone()))
+    }
+
+    /// Eigene Schriften, z.B. für den Bildexport in höherer Auflösung
+    pub fn with_fonts(fonts: Fonts) -> Self {
+        Self { fonts, shapes: Vec::new(), lines: Vec::new() }
+    }
+
+    pub fn add(&mut self, shape: impl Into<Shape>) {
+        self.shapes.push(shape.into());
+    }
+
+    pub fn extend(&mut self, shapes: impl IntoIterator<Item = Shape>) {
+        self.shapes.extend(shapes);
+    }
+
+    pub fn line_segment(&mut self, points: [Pos2; 2], stroke: impl Into<Stroke>) {
+        self.add(Shape::line_segment(points, stroke.into()));
+    }
+
+    pub fn circle_filled(&mut self, center: Pos2, radius: f32, color: Color32) {
+        self.add(Shape::circle_filled(center, radius, color));
+    }
+
+    pub fn circle_stroke(&mut self, center: Pos2, radius: f32, stroke: impl Into<Stroke>) {
+        self.add(Shape::circle_stroke(center, radius, stroke));
+    }
+
+    pub fn text(&mut self, pos: Pos2, anchor: Align2, text: impl ToString, font_id: FontId, color: Color32) -> Rect {
+        let galley = self.fonts.layout_no_wrap(text.to_string(), font_id, color);
+        let rect = anchor.anchor_size(pos, galley.size());
+        if !galley.is_empty() {
+            self.add(Shape::galley(rect.min, galley, color));
+        }
+        rect
+    }
+
+    /// Sichtbare Linie für die Trefferprüfung vormerken
+    pub fn register_line(&mut self, index: usize, start: Pos2, end: Pos2) {
+        self.lines.push(ScreenLine { index, start, end });
+    }
+
+    pub fn into_shapes(self) -> Vec<Shape> {
+        self.shapes
+    }
+}
+
+/// Linie in Bildschirmkoordinaten
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenLine {
+    pub index: usize,
+    pub start: Pos2,
+    pub end: Pos2,
+}
+
+/// Räumlicher Index der sichtbaren Linien, damit Hover und Ziehen nicht jede Linie prüfen
+/// Gleichmäßiges Raster im Bildschirm, jede Linie steht in den Zellen, die sie durchquert;
+/// wird mit der Darstellung neu aufgebaut (Zoom, Verschieben, Geometrie)
+pub struct HitTester {
+    lines: Vec<ScreenLine>,                  // Zeichenreihenfolge, frühere Linien haben Vorrang
+    bounds: Rect,                            // Indizierter Bereich, außerhalb wird jede Linie geprüft
+    cells: HashMap<(i32, i32), Vec<usize>>, // Zelle → Positionen in `lines`
+}
+
+impl HitTester {
+    pub fn new(lines: Vec<ScreenLine>, bounds: Rect) -> Self {
+        // Zelle gehört zur Linie, wenn die Linie den Umkreis der Zelle schneidet
+        let cell_radius = HIT_CELL_SIZE * std::f32::consts::FRAC_1_SQRT_2;
+        let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (position, line) in lines.iter().enumerate() {
+            let area = Rect::from_two_pos(line.start, line.end).intersect(bounds);
+            if !area.is_finite() || area.width() < 0.0 || area.height() < 0.0 {
+                continue;
+            }
+            let (min, max) = (cell_of(area.min), cell_of(area.max));
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    let center = Pos2::new((x as f32 + 0.5) * HIT_CELL_SIZE, (y as f32 + 0.5) * HIT_CELL_SIZE);
+                    if point_to_line_distance(center, line.start, line.end) <= cell_radius {
+                        cells.entry((x, y)).or_default().push(position);
+                    }
+                }
+            }
+        }
+        Self { lines, bounds, cells }
+    }
+
+    /// Erste Linie, deren Anfang oder Ende näher als `radius` liegt, mit true = Anfang
+    pub fn line_end_at(&self, pos: Pos2, radius: f32) -> Option<(usize, bool)> {
+        self.first_hit(pos, radius, |line| {
+            let to_start = (pos - line.start).length();
+            let to_end = (pos - line.end).length();
+            (to_start < radius || to_end < radius).then_some(to_start < to_end)
+        })
+    }
+
+    /// Erste Linie, die näher als `radius` am Punkt vorbeiläuft
+    pub fn line_at(&self, pos: Pos2, radius: f32) -> Option<usize> {
+        self.first_hit(pos, radius, |line| (point_to_line_distance(pos, line.start, line.end) < radius).then_some(()))
+            .map(|(index, ())| index)
+    }
+
+    /// Linien, die ganz im Rechteck liegen (Auswahlrahmen)
+    pub fn lines_inside(&self, rect: Rect) -> impl Iterator<Item = usize> + '_ {
+        self.lines
+            .iter()
+            .filter(move |line| rect.contains(line.start) && rect.contains(line.end))
+            .map(|line| line.index)
+    }
+
+    /// Prüft nur die Linien aus den Zellen im Umkreis, in Zeichenreihenfolge
+    fn first_hit<T>(&self, pos: Pos2, radius: f32, test: impl Fn(&ScreenLine) -> Option<T>) -> Option<(usize, T)> {
+        let hit = |line: &ScreenLine| test(line).map(|value| (line.index, value));
+        let area = Rect::from_center_size(pos, Vec2::splat(2.0 * radius));
+        if !self.bounds.contains_rect(area) {
+            return self.lines.iter().find_map(hit);
+        }
+
+        let (min, max) = (cell_of(area.min), cell_of(area.max));
+        let mut candidates: Vec<usize> = (min.0..=max.0)
+            .flat_map(|x| (min.1..=max.1).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.into_iter().find_map(|position| hit(&self.lines[position]))
+    }
+}
+
+impl Default for HitTester {
+    fn default() -> Self {
+        Self { lines: Vec::new(), bounds: Rect::NOTHING, cells: HashMap::new() }
+    }
+}
+
+fn cell_of(pos: Pos2) -> (i32, i32) {
+    ((pos.x / HIT_CELL_SIZE).floor() as i32, (pos.y / HIT_CELL_SIZE).floor() as i32)
+}
+
+/// Abstand eines Punkts von der Strecke (nicht der unendlichen Geraden)
+pub fn point_to_line_distance(p: Pos2, line_start: Pos2, line_end: Pos2) -> f32 {
+    let line_vec = line_end - line_start;
+    let point_vec = p - line_start;
+    
+    let line_len_sq = line_vec.x * line_vec.x + line_vec.y * line_vec.y;
+    if line_len_sq == 0.0 {
+        return point_vec.length();
+    }
+    
+    let t = ((point_vec.x * line_vec.x + point_vec.y * line_vec.y) / line_len_sq).clamp(0.0, 1.0);
+    let projection = line_start + t * line_vec;
+    
+    (p - projection).length()
+}
+
+/// Zwischengespeicherte Darstellung, gültig solange der Schlüssel gleich bleibt
+#[derive(Default)]
+pub struct Scene {
+    key: Option<u64>,
+    shapes: Vec<Shape>,
+    pub hits: HitTester,
+}
+
+impl Scene {
+    pub fn is_current(&self, key: u64) -> bool {
+        self.key == Some(key)
+    }
+
+    /// `canvas` = Zeichenfläche, über die der Trefferindex aufgebaut wird
+    pub fn store(&mut self, key: u64, builder: SceneBuilder, canvas: Rect) {
+        self.key = Some(key);
+        self.shapes = builder.shapes;
+        self.hits = HitTester::new(builder.lines, canvas.expand(HIT_MARGIN));
+    }
+
+    /// Galleys sind per Arc geteilt, das Kopieren der Liste ist daher günstig
+    pub fn paint(&self, painter: &Painter) {
+        painter.extend(self.shapes.iter().cloned());
+    }
+}
+
+/// Alles, woraus die statische Darstellung entsteht; der Schlüssel umfasst genau diese Daten
+pub struct SceneSource<'a> {
+    pub quad: &'a Quadrilateral,
+    pub lines: &'a [CustomLine],
+    pub circles: &'a [CustomCircle],
+    pub angle_dimensions: &'a [AngleDimension],
+    pub construction_lines: &'a [ConstructionLine],
+    pub comparison: Option<(&'a Quadrilateral, &'a str)>, // Vergleichsviereck mit Zeitpunkt
+    pub comparison_side_by_side: bool,
+    pub measure_first: Option<CutEdge>,               // Nur während des Winkelmessens
+    pub measure_result: Option<&'a AngleDimension>,
+    pub baseline: Baseline,
+    pub hovered_line: Option<usize>,
+    pub selected_line: Option<usize>,
+    pub selected_endpoint: Option<bool>,
+    pub selected_circle: Option<usize>,
+    pub multi_selection: &'a [usize],
+    // Angezeigte Einstellungen, nicht die ganze Konfiguration
+    pub fmt: NumberFormat,
+    pub unit: LengthUnit,
+    pub labels: &'a VertexLabels,
+    pub angle_display: AngleDisplay,
+    pub fill: &'a FillSettings,
+    pub show_grid: bool,
+    pub grid_spacing_mm: f64,
+    pub show_stations: bool,
+    pub show_centroid: bool,
+    pub show_bimedians: bool,
+    pub show_point_grid: bool,
+    pub point_grid: &'a PointGridSpec,
+}
+
+impl SceneSource<'_> {
+    /// Fingerabdruck von allem, was `build` zeichnet
+    pub fn key(&self, view: &ViewTransform, rect: Rect, pixels_per_point: f32) -> u64 {
+        let mut key = SceneKey::default();
+        key.add(view);
+        key.add([rect.min.x, rect.min.y, rect.max.x, rect.max.y, pixels_per_point].map(f32::to_bits));
+        key.add_serialized(&self.quad.vertices);
+        key.add_serialized(&[self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d]);
+        key.add_serialized(&self.lines);
+        key.add_serialized(&self.circles);
+        key.add_serialized(&self.comparison.map(|(quad, time)| (&quad.vertices, time)));
+        key.add(self.comparison_side_by_side);
+        key.add_serialized(&self.angle_dimensions);
+        key.add_serialized(&self.construction_lines);
+        key.add_serialized(&(self.measure_first, self.measure_result));
+        key.add((self.baseline.origin, self.baseline.axis_to));
+        key.add((self.hovered_line, self.selected_line, self.selected_endpoint, self.selected_circle));
+        key.add(self.multi_selection);
+        key.add((self.fmt.decimals, self.fmt.decimal_char(), self.fmt.grouping));
+        key.add_serialized(&(self.unit, self.labels, self.angle_display, self.fill));
+        key.add_serialized(&(self.show_grid, self.grid_spacing_mm, self.show_stations, self.show_centroid, self.show_bimedians));
+        key.add_serialized(&(self.show_point_grid, self.point_grid));
+        key.finish()
+    }
+
+    /// Statische Darstellung: Viereck, Linien, Kreise und alle Maße
+    /// Auswahl und Hover gehören dazu, Vorschauen beim Zeichnen nicht
+    pub fn build(&self, scene: &mut SceneBuilder, view: &ViewTransform, rect: Rect) {
+        let fmt = self.fmt;
+        let to_screen = |p: &Point| -> Pos2 { view.to_screen(p) };
+
+        if self.show_grid {
+            self.draw_grid(scene, rect, view);
+        }
+
+        let screen_vertices: Vec<Pos2> = self.quad.vertices.iter().map(to_screen).collect();
+        
+        self.draw_fill(scene, &screen_vertices);
+        
+        // Vergleichsviereck halbtransparent überlagern, an A/AB ausgerichtet; nebeneinander rechts daneben
+        if let Some((compared, time)) = self.comparison {
+            let mut overlay: Vec<Pos2> = compared.aligned_to(self.quad).iter().map(to_screen).collect();
+            if self.comparison_side_by_side {
+                let max_x = |points: &[Pos2]| points.iter().map(|p| p.x).fold(f32::MIN, f32::max);
+                let min_x = overlay.iter().map(|p| p.x).fold(f32::MAX, f32::min);
+                let shift = Vec2::new(max_x(&screen_vertices) - min_x + 60.0, 0.0);
+                for point in &mut overlay {
+                    *point += shift;
+                }
+                let top = overlay.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+                scene.text(
+                    Pos2::new((min_x + shift.x + max_x(&overlay)) / 2.0, top - 16.0),
+                    Align2::CENTER_BOTTOM,
+                    time,
+                    FontId::proportional(16.0),
+                    Color32::from_rgb(230, 120, 0),
+                );
+            }
+            scene.add(Shape::convex_polygon(
+                overlay.clone(),
+                Color32::from_rgba_unmultiplied(230, 120, 0, 30),
+                Stroke::NONE,
+            ));
+            for i in 0..4 {
+                scene.extend(styled_line(
+                    overlay[i],
+                    overlay[(i + 1) % 4],
+                    LineStyle::Dashed,
+                    Stroke::new(2.5, Color32::from_rgba_unmultiplied(230, 120, 0, 180)),
+                ));
+            }
+        }
+        
+        // Hilfslinien dünn gestrichelt; unbegrenzte reichen bis über den Rand der Zeichenfläche
+        let corners = [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()].map(|c| view.to_world(c));
+        let reach_mm = self
+            .quad
+            .vertices
+            .iter()
+            .flat_map(|v| corners.iter().map(move |c| distance_f64(v, c)))
+            .fold(0.0, f64::max)
+            / 1000.0;
+        for line in self.construction_lines {
+            if let Some((start, end)) = construction_segment(self.quad, line, reach_mm) {
+                scene.extend(styled_line(
+                    to_screen(&start),
+                    to_screen(&end),
+                    LineStyle::Dashed,
+                    Stroke::new(1.0, Color32::from_rgb(140, 140, 140)),
+                ));
+            }
+        }
+
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            scene.line_segment(
+                [screen_vertices[i], screen_vertices[next]],
+                Stroke::new(4.0, Color32::from_rgb(50, 50, 200)),
+            );
+        }
+
+        let labels = self.labels;
+        let angles = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
+        
+        for i in 0..4 {
+            scene.circle_filled(screen_vertices[i], 8.0, Color32::from_rgb(200, 50, 50));
+            
+            let offset = Vec2::new(-25.0, -25.0);
+            scene.text(
+                screen_vertices[i] + offset,
+                Align2::CENTER_CENTER,
+                labels.vertex(i),
+                FontId::proportional(28.0),
+                Color32::BLACK,
+            );
+
+            // Winkel als Bogen, Wert auf der Winkelhalbierenden; Außenwinkel mit Bogen außerhalb
+            if let Some(angle) = angles[i] {
+                let vertex = screen_vertices[i];
+                let first = screen_vertices[(i + 3) % 4] - vertex;
+                let second = screen_vertices[(i + 1) % 4] - vertex;
+                let radius = angle_arc_radius(view.px_per_mm(), first.length().min(second.length()));
+                let reflex = (angle > 180.0) != (self.angle_display == AngleDisplay::Exterior);
+                let (arc, bisector) = angle_arc(vertex, first.normalized(), second.normalized(), radius, reflex);
+                let color = Color32::from_rgb(100, 100, 100);
+                scene.add(Shape::line(arc, Stroke::new(1.5, color)));
+                scene.text(
+                    vertex + bisector * (radius + 22.0),
+                    Align2::CENTER_CENTER,
+                    fmt.corner_angle(angle, self.angle_display),
+                    FontId::proportional(18.0),
+                    color,
+                );
+            }
+        }
+
+        
+        let unit = self.unit;
+        
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            let mid = Pos2::new(
+                (screen_vertices[i].x + screen_vertices[next].x) / 2.0,
+                (screen_vertices[i].y + screen_vertices[next].y) / 2.0,
+            );
+            
+            let length_mm = self.quad.get_side_length_mm(i);
+            let formatted = format!("{}: {}", labels.side(i), fmt.length(length_mm, unit));
+            
+            scene.text(
+                mid,
+                Align2::CENTER_CENTER,
+                formatted,
+                FontId::proportional(22.0),
+                Color32::from_rgb(0, 120, 0),
+            );
+        }
+
+        // Zeichne custom lines
+        for (idx, line) in self.lines.iter().enumerate() {
+            if line.hidden {
+                continue;
+            }
+            let start_screen = to_screen(&line.start);
+            let end_screen = to_screen(&line.end);
+            scene.register_line(idx, start_screen, end_screen);
+            
+            let is_hovered = self.hovered_line == Some(idx);
+            let is_selected = self.selected_line == Some(idx);
+            let in_multi_selection = self.multi_selection.contains(&idx);
+            let [r, g, b] = line.color;
+            let line_color = Color32::from_rgb(r, g, b);
+            let line_width = if is_hovered { line.width + 1.0 } else { line.width };
+            
+            // Ausgewählte Linie hinterlegen
+            if is_selected || in_multi_selection {
+                scene.line_segment(
+                    [start_screen, end_screen],
+                    Stroke::new(line_width + 8.0, Color32::from_rgba_unmultiplied(80, 160, 255, 90)),
+                );
+            }
+            
+            scene.extend(styled_line(start_screen, end_screen, line.style, Stroke::new(line_width, line_color)));
+
+            let mid = Pos2::new(
+                (start_screen.x + end_screen.x) / 2.0,
+                (start_screen.y + end_screen.y) / 2.0,
+            );
+            
+            let length_mm = line.length_um as f64 / 1000.0;
+            let formatted = fmt.length(length_mm, unit);
+            
+            scene.text(
+                mid,
+                Align2::CENTER_CENTER,
+                formatted,
+                FontId::proportional(20.0),
+                Color32::from_rgb(56, 62, 66),  //Anthrazit
+            );
+
+            // Teilflächen beiderseits der ausgewählten Trennlinie
+            if is_selected {
+                if let Some(parts) = self.quad.split_by_line(line) {
+                    for part in &parts {
+                        scene.text(
+                            to_screen(&part.label_point()),
+                            Align2::CENTER_CENTER,
+                            format!("{} m²", fmt.number(part.area_mm2 / 1_000_000.0)),
+                            FontId::proportional(16.0),
+                            Color32::from_rgb(80, 160, 255),
+                        );
+                    }
+                }
+            }
+
+            // Per Pfeiltasten verschiebbarer Endpunkt
+            if is_selected {
+                if let Some(at_start) = self.selected_endpoint {
+                    let pos = if at_start { start_screen } else { end_screen };
+                    scene.circle_stroke(pos, 9.0, Stroke::new(2.0, Color32::from_rgb(80, 160, 255)));
+                }
+            }
+
+            // Am Schwerpunkt und an freien Punkten gibt es keine Bezugsstrecke und damit keinen Winkel
+            scene.circle_filled(start_screen, 4.0, Color32::from_rgb(255, 200, 0));
+            if !matches!(line.start_anchor, LineAnchor::Centroid | LineAnchor::Free { .. }) {
+                scene.text(
+                    start_screen + Vec2::new(15.0, -15.0),
+                    Align2::LEFT_BOTTOM,
+                    fmt.angle(line.start_angle),
+                    FontId::proportional(16.0),
+                    Color32::from_rgb(56, 62, 66),  //Anthrazit
+                );
+            }
+
+            scene.circle_filled(end_screen, 4.0, Color32::from_rgb(255, 200, 0));
+            if !matches!(line.end_anchor, LineAnchor::Centroid | LineAnchor::Free { .. }) {
+                scene.text(
+                    end_screen + Vec2::new(15.0, -15.0),
+                    Align2::LEFT_BOTTOM,
+                    fmt.angle(line.end_angle),
+                    FontId::proportional(16.0),
+                    Color32::from_rgb(56, 62, 66),  //Anthrazit
+                );
+            }
+
+            // Teilstrecken auf der Bezugsstrecke (Seite oder Elternlinie)
+            let (start_ref, _) = anchor_segment(self.quad, self.lines, &line.start_anchor);
+            let start_ref_screen = to_screen(&start_ref);
+            let segment_start_length_um = distance_um(&start_ref, &line.start);
+            let segment_start_mm = segment_start_length_um as f64 / 1000.0;
+            let segment_start_formatted = fmt.length(segment_start_mm, unit);
+            
+            let segment_start_screen = Pos2::new(
+                (start_ref_screen.x + start_screen.x) / 2.0,
+                (start_ref_screen.y + start_screen.y) / 2.0,
+            );
+            
+            // An Eckpunkten, am Schwerpunkt und an freien Punkten gibt es keine Teilstrecke
+            if !matches!(line.start_anchor, LineAnchor::Vertex(_) | LineAnchor::Centroid | LineAnchor::Free { .. }) {
+                scene.text(
+                    segment_start_screen,
+                    Align2::CENTER_CENTER,
+                    segment_start_formatted,
+                    FontId::proportional(14.0),
+                    Color32::from_rgb(150, 150, 150),
+                );
+            }
+
+            let (_, end_ref) = anchor_segment(self.quad, self.lines, &line.end_anchor);
+            let end_ref_screen = to_screen(&end_ref);
+            let segment_end_length_um = distance_um(&line.end, &end_ref);
+            let segment_end_mm = segment_end_length_um as f64 / 1000.0;
+            let segment_end_formatted = fmt.length(segment_end_mm, unit);
+            
+            let segment_end_screen = Pos2::new(
+                (end_screen.x + end_ref_screen.x) / 2.0,
+                (end_screen.y + end_ref_screen.y) / 2.0,
+            );
+            
+            if !matches!(line.end_anchor, LineAnchor::Vertex(_) | LineAnchor::Centroid | LineAnchor::Free { .. }) {
+                scene.text(
+                    segment_end_screen,
+                    Align2::CENTER_CENTER,
+                    segment_end_formatted,
+                    FontId::proportional(14.0),
+                    Color32::from_rgb(150, 150, 150),
+                );
+            }
+        }
+
+        // Kreuzungen zwischen Linien: Schnittpunkt, Schnittwinkel und Teilstrecken
+        let crossings = line_crossings(self.lines);
+        for crossing in &crossings {
+            let pos = to_screen(&crossing.point);
+            let color = Color32::from_rgb(200, 50, 50);
+            scene.circle_stroke(pos, 6.0, Stroke::new(2.0, color));
+
+            // Bogen über den spitzen Schnittwinkel, auf den längsten Teilstrecken beider Linien
+            let parts = |line: &CustomLine, ratio: f64| {
+                let dir = to_screen(&line.end) - to_screen(&line.start);
+                [(dir, dir.length() * (1.0 - ratio as f32)), (-dir, dir.length() * ratio as f32)]
+            };
+            let first_parts = parts(&self.lines[crossing.lines.0], crossing.ratios.0);
+            let second_parts = parts(&self.lines[crossing.lines.1], crossing.ratios.1);
+            let Some((first_dir, second_dir, room)) = first_parts
+                .iter()
+                .flat_map(|a| second_parts.iter().map(move |b| (a.0, b.0, a.1.min(b.1))))
+                .filter(|(a, b, _)| a.dot(*b) >= 0.0)
+                .max_by(|x, y| x.2.total_cmp(&y.2))
+            else {
+                continue;
+            };
+            let radius = angle_arc_radius(view.px_per_mm(), room) * 0.7;
+            let (arc, bisector) = angle_arc(pos, first_dir.normalized(), second_dir.normalized(), radius, false);
+            scene.add(Shape::line(arc, Stroke::new(1.5, color)));
+            scene.text(
+                pos + bisector * (radius + 16.0),
+                Align2::CENTER_CENTER,
+                fmt.angle(crossing.angle_deg),
+                FontId::proportional(14.0),
+                color,
+            );
+        }
+
+        for (idx, line) in self.lines.iter().enumerate() {
+            let mut ratios: Vec<f64> = crossings
+                .iter()
+                .filter_map(|c| match c.lines {
+                    (first, _) if first == idx => Some(c.ratios.0),
+                    (_, second) if second == idx => Some(c.ratios.1),
+                    _ => None,
+                })
+                .collect();
+            if ratios.is_empty() {
+                continue;
+            }
+            ratios.push(0.0);
+            ratios.push(1.0);
+            ratios.sort_by(|a, b| a.total_cmp(b));
+
+            let start_screen = to_screen(&line.start);
+            let end_screen = to_screen(&line.end);
+            let normal = (end_screen - start_screen).normalized().rot90() * 12.0;
+            for pair in ratios.windows(2) {
+                let length_mm = line.length_um as f64 / 1000.0 * (pair[1] - pair[0]);
+                let mid = start_screen.lerp(end_screen, ((pair[0] + pair[1]) / 2.0) as f32);
+                scene.text(
+                    mid + normal,
+                    Align2::CENTER_CENTER,
+                    fmt.length(length_mm, unit),
+                    FontId::proportional(14.0),
+                    Color32::from_rgb(150, 150, 150),
+                );
+            }
+        }
+
+        // Zeichne Kreise und Kreisbögen
+        for (idx, circle) in self.circles.iter().enumerate() {
+            let outline: Vec<Pos2> = circle.outline(96).iter().map(to_screen).collect();
+            let [r, g, b] = circle.color;
+            
+            if self.selected_circle == Some(idx) {
+                scene.add(Shape::line(
+                    outline.clone(),
+                    Stroke::new(circle.width + 8.0, Color32::from_rgba_unmultiplied(80, 160, 255, 90)),
+                ));
+            }
+            scene.add(Shape::line(outline, Stroke::new(circle.width, Color32::from_rgb(r, g, b))));
+            
+            // Mittelpunkt und Radius
+            let center_screen = to_screen(&circle.center);
+            let cross = Stroke::new(1.0, Color32::from_rgb(r, g, b));
+            scene.line_segment([center_screen - Vec2::new(5.0, 0.0), center_screen + Vec2::new(5.0, 0.0)], cross);
+            scene.line_segment([center_screen - Vec2::new(0.0, 5.0), center_screen + Vec2::new(0.0, 5.0)], cross);
+            scene.text(
+                center_screen + Vec2::new(8.0, -8.0),
+                Align2::LEFT_BOTTOM,
+                format!("r = {}", fmt.length(circle.radius_um as f64 / 1000.0, unit)),
+                FontId::proportional(16.0),
+                Color32::from_rgb(56, 62, 66),
+            );
+        }
+
+        // Stationierung: fortlaufendes Maß entlang des Umfangs, nach außen versetzt
+        if self.show_stations {
+            let centroid = screen_vertices.iter().fold(Vec2::ZERO, |acc, p| acc + p.to_vec2()) / 4.0;
+            for station in perimeter_stations(self.quad, self.lines) {
+                let pos = to_screen(&station.point);
+                let outward = (pos - centroid.to_pos2()).normalized();
+                scene.circle_stroke(pos, 5.0, Stroke::new(1.5, Color32::from_rgb(0, 130, 130)));
+                scene.text(
+                    pos + outward * 22.0,
+                    Align2::CENTER_CENTER,
+                    fmt.length(station.station_mm, unit),
+                    FontId::proportional(14.0),
+                    Color32::from_rgb(0, 130, 130),
+                );
+            }
+        }
+
+        if self.show_centroid {
+            self.draw_centroid_midpoints(scene, view);
+        }
+
+        if self.show_bimedians {
+            self.draw_bimedians(scene, view);
+        }
+
+        // Punktraster als kleine Kreuze
+        if self.show_point_grid {
+            let stroke = Stroke::new(1.5, Color32::from_rgb(200, 110, 0));
+            for grid_point in self.quad.point_grid(self.point_grid).unwrap_or_default() {
+                let pos = view.to_screen(&grid_point.point);
+                scene.line_segment([pos - Vec2::new(4.0, 4.0), pos + Vec2::new(4.0, 4.0)], stroke);
+                scene.line_segment([pos - Vec2::new(4.0, -4.0), pos + Vec2::new(4.0, -4.0)], stroke);
+            }
+        }
+
+        // Winkelmaße: angeheftete und die aktuelle Messung
+        for dimension in self.angle_dimensions {
+            self.draw_angle_dimension(scene, view, dimension, Color32::from_rgb(30, 110, 60));
+        }
+        if let Some(edge) = self.measure_first {
+            if let Some((a, b)) = edge_segment(self.quad, self.lines, edge) {
+                scene.line_segment(
+                    [to_screen(&a), to_screen(&b)],
+                    Stroke::new(8.0, Color32::from_rgba_unmultiplied(30, 160, 80, 90)),
+                );
+            }
+        }
+        if let Some(dimension) = self.measure_result {
+            self.draw_angle_dimension(scene, view, dimension, Color32::from_rgb(30, 160, 80));
+        }
+    }
+
+    /// Winkelmaß mit Bogen am Scheitel und Wert in der Winkelmitte
+    fn draw_angle_dimension(&self, scene: &mut SceneBuilder, view: &ViewTransform, dimension: &AngleDimension, color: Color32) {
+        let Some(angle) = edge_angle(self.quad, self.lines, dimension.first, dimension.second) else {
+            return;
+        };
+        const RADIUS: f32 = 40.0;
+        let vertex = view.to_screen(&angle.vertex);
+        // Schenkelrichtung auf dem Bildschirm (berücksichtigt Drehung und Spiegelung der Ansicht)
+        let [first, second] = angle.directions.map(|(dx, dy)| {
+            let far = Point::new(angle.vertex.x + dx * 1_000_000.0, angle.vertex.y + dy * 1_000_000.0);
+            (view.to_screen(&far) - vertex).normalized()
+        });
+        
+        let stroke = Stroke::new(1.5, color);
+        scene.line_segment([vertex, vertex + first * (RADIUS + 12.0)], stroke);
+        scene.line_segment([vertex, vertex + second * (RADIUS + 12.0)], stroke);
+        let (arc, bisector) = angle_arc(vertex, first, second, RADIUS, false);
+        scene.add(Shape::line(arc, stroke));
+        
+        scene.text(
+            vertex + bisector * (RADIUS + 16.0),
+            Align2::CENTER_CENTER,
+            self.fmt.angle(angle.angle_deg),
+            FontId::proportional(15.0),
+            color,
+        );
+    }
+
+    /// Schwerpunkt und Seitenmitten mit Koordinaten im System der Absteckpunkte
+    fn draw_centroid_midpoints(&self, scene: &mut SceneBuilder, view: &ViewTransform) {
+        let fmt = self.fmt;
+        let unit = self.unit;
+        let frame = LocalFrame::new(self.quad, self.baseline);
+        let color = Color32::from_rgb(160, 40, 140);
+        let coordinates = |p: &Point| match &frame {
+            Some(frame) => {
+                let (x, y) = frame.to_local_mm(p);
+                format!("({} | {})", fmt.length(x, unit), fmt.length(y, unit))
+            }
+            None => String::new(),
+        };
+        
+        for side in 0..4 {
+            let point = self.quad.get_point_on_side(side, 0.5);
+            let pos = view.to_screen(&point);
+            scene.circle_stroke(pos, 4.0, Stroke::new(1.5, color));
+            scene.text(
+                pos + Vec2::new(8.0, 8.0),
+                Align2::LEFT_TOP,
+                format!("M{} {}", self.labels.side(side), coordinates(&point)),
+                FontId::proportional(12.0),
+                color,
+            );
+        }
+        
+        let centroid = self.quad.centroid();
+        let pos = view.to_screen(&centroid);
+        scene.line_segment([pos - Vec2::new(7.0, 0.0), pos + Vec2::new(7.0, 0.0)], Stroke::new(2.0, color));
+        scene.line_segment([pos - Vec2::new(0.0, 7.0), pos + Vec2::new(0.0, 7.0)], Stroke::new(2.0, color));
+        scene.text(
+            pos + Vec2::new(10.0, 10.0),
+            Align2::LEFT_TOP,
+            format!("S {}", coordinates(&centroid)),
+            FontId::proportional(13.0),
+            color,
+        );
+    }
+
+    /// Schwerlinien gestrichelt mit Länge, Symmetrieachsen strichpunktiert über das Viereck hinaus
+    fn draw_bimedians(&self, scene: &mut SceneBuilder, view: &ViewTransform) {
+        let fmt = self.fmt;
+        let unit = self.unit;
+        let color = Color32::from_rgb(160, 40, 140);
+        for index in 0..2 {
+            let bimedian = self.quad.bimedian(index);
+            let (start, end) = (view.to_screen(&bimedian.start), view.to_screen(&bimedian.end));
+            scene.extend(Shape::dashed_line(&[start, end], Stroke::new(1.5, color), 8.0, 5.0));
+            let center = start + (end - start) * if index == 0 { 0.3 } else { 0.7 };
+            scene.text(
+                center + Vec2::new(6.0, -6.0),
+                Align2::LEFT_BOTTOM,
+                fmt.length(bimedian.length_mm, unit),
+                FontId::proportional(12.0),
+                color,
+            );
+        }
+
+        let axis_color = Color32::from_rgb(40, 90, 200);
+        for axis in self.quad.symmetry_axes() {
+            let (start, end) = (view.to_screen(&axis.start), view.to_screen(&axis.end));
+            let overhang = (end - start) * 0.1;
+            let (start, end) = (start - overhang, end + overhang);
+            let direction = (end - start).normalized();
+            // Strichpunktlinie: langer Strich, Lücke, kurzer Punkt, Lücke
+            let length = (end - start).length();
+            let mut t = 0.0;
+            while t < length {
+                let dash_end = (t + 14.0).min(length);
+                scene.line_segment([start + direction * t, start + direction * dash_end], Stroke::new(1.2, axis_color));
+                let dot = t + 18.0;
+                if dot < length {
+                    scene.line_segment([start + direction * dot, start + direction * (dot + 2.0).min(length)], Stroke::new(1.2, axis_color));
+                }
+                t += 24.0;
+            }
+            scene.text(end + direction * 4.0, Align2::LEFT_CENTER, "Achse", FontId::proportional(12.0), axis_color);
+        }
+    }
+
+    /// Füllung bzw. Schraffur der Viereckfläche
+    fn draw_fill(&self, scene: &mut SceneBuilder, screen_vertices: &[Pos2]) {
+        let fill = self.fill;
+        let [r, g, b] = fill.color;
+        let color = Color32::from_rgba_unmultiplied(r, g, b, fill.opacity);
+        
+        match fill.pattern {
+            FillPattern::None => {}
+            FillPattern::Solid => {
+                // In zwei Dreiecke zerlegen, damit auch konkave Vierecke korrekt gefüllt werden
+                let split = (0..4).find(|&i| self.quad.is_reflex_vertex(i)).unwrap_or(0);
+                let opposite = (split + 2) % 4;
+                for middle in [(split + 1) % 4, (split + 3) % 4] {
+                    scene.add(Shape::convex_polygon(
+                        vec![screen_vertices[split], screen_vertices[middle], screen_vertices[opposite]],
+                        color,
+                        Stroke::NONE,
+                    ));
+                }
+            }
+            FillPattern::Hatch | FillPattern::CrossHatch => {
+                let stroke = Stroke::new(1.5, color);
+                let mut angles = vec![45.0];
+                if fill.pattern == FillPattern::CrossHatch {
+                    angles.push(-45.0);
+                }
+                for angle in angles {
+                    for segment in hatch_segments(screen_vertices, angle, 12.0) {
+                        scene.line_segment(segment, stroke);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hintergrundraster, ausgerichtet an Eckpunkt A und am Bildschirm
+    /// Feine Linien im gewählten Abstand, jede 10. Linie kräftiger
+    fn draw_grid(&self, scene: &mut SceneBuilder, rect: Rect, view: &ViewTransform) {
+        let spacing_mm = self.grid_spacing_mm.max(1.0);
+        let spacing_px = spacing_mm as f32 * view.px_per_mm();
+        let minor_stroke = Stroke::new(1.0, Color32::from_rgb(235, 235, 235));
+        let major_stroke = Stroke::new(1.0, Color32::from_rgb(205, 205, 205));
+        let origin = view.world_to_view(&self.quad.vertices[0]);
+        let world_min = view.screen_to_view(rect.min);
+        let world_max = view.screen_to_view(rect.max);
+
+        // Zu dichte feine Linien weglassen
+        let show_minor = spacing_px >= 6.0;
+        if !show_minor && spacing_px * 10.0 < 6.0 {
+            return;
+        }
+
+        let first = ((world_min.x - origin.x) / 1000.0 / spacing_mm).floor() as i64;
+        let last = ((world_max.x - origin.x) / 1000.0 / spacing_mm).ceil() as i64;
+        for k in first..=last {
+            let major = k.rem_euclid(10) == 0;
+            if !major && !show_minor {
+                continue;
+            }
+            let x = view.view_to_screen(&Point::new(origin.x + k as f64 * spacing_mm * 1000.0, origin.y)).x;
+            scene.line_segment(
+                [Pos2::new(x, rect.min.y), Pos2::new(x, rect.max.y)],
+                if major { major_stroke } else { minor_stroke },
+            );
+        }
+
+        let first = ((world_min.y - origin.y) / 1000.0 / spacing_mm).floor() as i64;
+        let last = ((world_max.y - origin.y) / 1000.0 / spacing_mm).ceil() as i64;
+        for k in first..=last {
+            let major = k.rem_euclid(10) == 0;
+            if !major && !show_minor {
+                continue;
+            }
+            let y = view.view_to_screen(&Point::new(origin.x, origin.y + k as f64 * spacing_mm * 1000.0)).y;
+            scene.line_segment(
+                [Pos2::new(rect.min.x, y), Pos2::new(rect.max.x, y)],
+                if major { major_stroke } else { minor_stroke },
+            );
+        }
+    }
+}
+
+/// Formen einer Linie in der gewählten Strichart
+pub fn styled_line(start: Pos2, end: Pos2, style: LineStyle, stroke: Stroke) -> Vec<Shape> {
+    match style {
+        LineStyle::Solid => vec![Shape::line_segment([start, end], stroke)],
+        LineStyle::Dashed => Shape::dashed_line(&[start, end], stroke, 4.0 * stroke.width, 2.5 * stroke.width),
+        LineStyle::Dotted => Shape::dotted_line(&[start, end], stroke.color, 3.0 * stroke.width, stroke.width / 2.0 + 0.5),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::ViewOrientation;
+
+    #[test]
+    fn test_scene_key() {
+        let key = |values: &[f64], hidden: bool| {
+            let mut key = SceneKey::default();
+            key.add_serialized(&values);
+            key.add(hidden);
+            key.finish()
+        };
+        assert_eq!(key(&[1.0, 2.5], false), key(&[1.0, 2.5], false));
+        assert_ne!(key(&[1.0, 2.5], false), key(&[1.0, 2.6], false));
+        assert_ne!(key(&[1.0, 2.5], false), key(&[1.0, 2.5], true));
+    }
+
+    #[test]
+    fn test_scene_source_key() {
+        let quad = Quadrilateral::new();
+        let labels = VertexLabels::default();
+        let fill = FillSettings::default();
+        let point_grid = PointGridSpec::default();
+        let source = |angle_display| SceneSource {
+            quad: &quad,
+            lines: &[],
+            circles: &[],
+            angle_dimensions: &[],
+            construction_lines: &[],
+            comparison: None,
+            comparison_side_by_side: false,
+            measure_first: None,
+            measure_result: None,
+            baseline: Baseline::default(),
+            hovered_line: None,
+            selected_line: None,
+            selected_endpoint: None,
+            selected_circle: None,
+            multi_selection: &[],
+            fmt: NumberFormat::new(1),
+            unit: LengthUnit::Centimeter,
+            labels: &labels,
+            angle_display,
+            fill: &fill,
+            show_grid: false,
+            grid_spacing_mm: 100.0,
+            show_stations: false,
+            show_centroid: false,
+            show_bimedians: false,
+            show_point_grid: false,
+            point_grid: &point_grid,
+        };
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let view = ViewTransform::fit(rect, &quad.vertices, ViewOrientation::default(), 40.0, 1.0, Vec2::ZERO);
+        let key = |display| source(display).key(&view, rect, 1.0);
+        assert_eq!(key(AngleDisplay::Interior), key(AngleDisplay::Interior));
+        assert_ne!(key(AngleDisplay::Interior), key(AngleDisplay::Exterior));
+    }
+
+    #[test]
+    fn test_hit_tester_matches_linear_search() {
+        // Fächer aus langen und kurzen Linien, teils über den Rand hinaus
+        let lines: Vec<ScreenLine> = (0..300)
+            .map(|i| {
+                let t = i as f32;
+                let start = Pos2::new((t * 37.0) % 900.0 - 50.0, (t * 53.0) % 700.0);
+                let end = start + Vec2::angled(t * 0.7) * (20.0 + (t * 13.0) % 600.0);
+                ScreenLine { index: i, start, end }
+            })
+            .collect();
+        let bounds = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let hits = HitTester::new(lines.clone(), bounds);
+
+        for x in (-40..840).step_by(7) {
+            for y in (-40..640).step_by(11) {
+                let pos = Pos2::new(x as f32, y as f32);
+                let expected_line = lines.iter().find(|l| point_to_line_distance(pos, l.start, l.end) < 15.0).map(|l| l.index);
+                assert_eq!(hits.line_at(pos, 15.0), expected_line, "{:?}", pos);
+
+                let expected_end = lines.iter().find_map(|l| {
+                    let (to_start, to_end) = ((pos - l.start).length(), (pos - l.end).length());
+                    (to_start < 12.0 || to_end < 12.0).then_some((l.index, to_start < to_end))
+                });
+                assert_eq!(hits.line_end_at(pos, 12.0), expected_end, "{:?}", pos);
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+// Skript-Schnittstelle (Rhai): Vierecke berechnen, Linien einzeichnen, Maße abfragen und exportieren
+// Skripte arbeiten auf einem eigenen Zustand, die App übernimmt das Ergebnis erst nach fehlerfreiem Lauf
+
+use crate::export;
+use crate::geometry::{solve, CustomLine, LineAnchor, QuadInput, Quadrilateral};
+use rhai::{Dynamic, Engine, EvalAltResult, Map, INT};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Dateiendung der Skriptdateien
+pub const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Dateitypen, die Skripte mit save() schreiben dürfen
+const SAVE_EXTENSIONS: [&str; 3] = ["csv", "txt", "json"];
+
+/// Höchstzahl an Rechenschritten, damit Endlosschleifen die Oberfläche nicht blockieren
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Beispiel für das Skriptfenster
+pub const EXAMPLE_SCRIPT: &str = r#"// Rechteck 4,00 × 3,00 m mit Diagonalen
+rectangle(4000, 3000);
+vertex_line(0, 2);
+vertex_line(1, 3);
+print("Diagonale AC: " + diagonal(0) + " mm");
+
+// Trennwand 1,20 m von A auf AB, parallel zu DA
+let wall = side_line(0, 1200.0 / side(0), 2, 1.0 - 1200.0 / side(2));
+print("Trennwand: " + line_length(wall) + " mm");
+"#;
+
+/// Ergebnis eines Skriptlaufs
+#[derive(Debug, Default)]
+pub struct ScriptResult {
+    pub input: Option<QuadInput>, // Eingaben des zuletzt berechneten Vierecks
+    pub lines: Vec<CustomLine>,
+    pub log: Vec<String>,         // Ausgaben von print() und Exporten
+    pub error: Option<String>,    // Abbruchgrund; Ausgaben bis dahin bleiben erhalten
+}
+
+type ScriptError = Box<EvalAltResult>;
+
+/// Zustand, auf den die registrierten Funktionen gemeinsam zugreifen
+#[derive(Default)]
+struct ScriptState {
+    input: Option<QuadInput>,
+    quad: Option<Quadrilateral>,
+    lines: Vec<CustomLine>,
+    log: Vec<String>,
+}
+
+impl ScriptState {
+    fn quad(&self) -> Result<&Quadrilateral, ScriptError> {
+        self.quad
+            .as_ref()
+            .ok_or_else(|| "Zuerst ein Viereck berechnen (quad oder rectangle)".into())
+    }
+
+    /// Berechnet ein neues Viereck, bisherige Linien entfallen
+    fn solve(&mut self, input: QuadInput) -> Result<(), ScriptError> {
+        self.quad = Some(solve(&input).map_err(|e| e.to_string())?.quad);
+        self.input = Some(input);
+        self.lines.clear();
+        Ok(())
+    }
+
+    fn add_line(&mut self, start: LineAnchor, end: LineAnchor) -> Result<INT, ScriptError> {
+        let quad = self
+            .quad
+            .as_ref()
+            .ok_or("Zuerst ein Viereck berechnen (quad oder rectangle)")?;
+        let name = format!("Linie {}", self.lines.len() + 1);
+        let line = CustomLine::new(name, start, end, quad, &self.lines);
+        self.lines.push(line);
+        Ok(self.lines.len() as INT - 1)
+    }
+
+    fn line(&self, idx: INT) -> Result<&CustomLine, ScriptError> {
+        usize::try_from(idx)
+            .ok()
+            .and_then(|idx| self.lines.get(idx))
+            .ok_or_else(|| format!("Linie {} gibt es nicht", idx).into())
+    }
+}
+
+/// Zahl aus einem Skriptwert, ganze Zahlen und Kommazahlen sind gleichwertig
+fn number(value: &Dynamic) -> Result<f64, ScriptError> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|i| i as f64))
+        .map_err(|_| format!("Zahl erwartet, erhalten: {}", value.type_name()).into())
+}
+
+/// Index einer Seite bzw. eines Eckpunkts (0–3)
+fn corner_index(idx: INT) -> Result<usize, ScriptError> {
+    usize::try_from(idx)
+        .ok()
+        .filter(|&i| i < 4)
+        .ok_or_else(|| format!("Index {} ungültig, erlaubt sind 0 bis 3", idx).into())
+}
+
+fn ratio(value: &Dynamic) -> Result<f64, ScriptError> {
+    let ratio = number(value)?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(ratio)
+    } else {
+        Err(format!("Position {} ungültig, erlaubt sind 0 bis 1", ratio).into())
+    }
+}
+
+/// Prüft Dateiname und -typ für save(), damit Skripte nur Textdateien auf dem Desktop ablegen
+fn save_target(prefix: &str, extension: &str) -> Result<(), ScriptError> {
+    if prefix.is_empty() || prefix.contains(['/', '\\', ':']) || prefix.contains("..") {
+        return Err(format!("Dateiname \"{}\" ungültig, Pfade sind nicht erlaubt", prefix).into());
+    }
+    if !SAVE_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
+        return Err(format!(
+            "Dateityp \"{}\" nicht erlaubt, möglich sind {}",
+            extension,
+            SAVE_EXTENSIONS.join(", ")
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Eingaben aus einer Map wie #{ab: 4000, bc: 3000, cd: 4000, da: 3000, a: 90}
+fn quad_input(values: Map) -> Result<QuadInput, ScriptError> {
+    let mut input = QuadInput::default();
+    for (key, value) in values {
+        let value = Some(number(&value)?);
+        match key.to_lowercase().as_str() {
+            "ab" => input.sides_mm[0] = value,
+            "bc" => input.sides_mm[1] = value,
+            "cd" => input.sides_mm[2] = value,
+            "da" => input.sides_mm[3] = value,
+            "a" => input.angles[0] = value,
+            "b" => input.angles[1] = value,
+            "c" => input.angles[2] = value,
+            "d" => input.angles[3] = value,
+            "ac" => input.diagonals_mm[0] = value,
+            "bd" => input.diagonals_mm[1] = value,
+            other => return Err(format!("Unbekannter Wert '{}' (erlaubt: ab, bc, cd, da, a, b, c, d, ac, bd)", other).into()),
+        }
+    }
+    Ok(input)
+}
+
+fn build_engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let s = state.clone();
+    engine.on_print(move |text| s.borrow_mut().log.push(text.to_string()));
+
+    // === Viereck ===
+    let s = state.clone();
+    engine.register_fn("quad", move |values: Map| -> Result<(), ScriptError> {
+        s.borrow_mut().solve(quad_input(values)?)
+    });
+    let s = state.clone();
+    engine.register_fn("rectangle", move |width: Dynamic, height: Dynamic| -> Result<(), ScriptError> {
+        s.borrow_mut().solve(QuadInput::rectangle(number(&width)?, number(&height)?))
+    });
+
+    // === Abfragen (Längen in mm, Winkel in Grad, Fläche in m²) ===
+    let s = state.clone();
+    engine.register_fn("side", move |idx: INT| -> Result<f64, ScriptError> {
+        Ok(s.borrow().quad()?.get_side_length_mm(corner_index(idx)?))
+    });
+    let s = state.clone();
+    engine.register_fn("angle", move |idx: INT| -> Result<f64, ScriptError> {
+        let state = s.borrow();
+        let quad = state.quad()?;
+        let angles = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d];
+        angles[corner_index(idx)?].ok_or_else(|| "Winkel nicht berechnet".into())
+    });
+    let s = state.clone();
+    engine.register_fn("diagonal", move |idx: INT| -> Result<f64, ScriptError> {
+        let idx = usize::try_from(idx).ok().filter(|&i| i < 2).ok_or("Diagonale 0 (AC) oder 1 (BD) angeben")?;
+        Ok(s.borrow().quad()?.diagonal_um(idx) as f64 / 1000.0)
+    });
+    let s = state.clone();
+    engine.register_fn("area", move || -> Result<f64, ScriptError> {
+        Ok(s.borrow().quad()?.area_mm2() / 1_000_000.0)
+    });
+
+    // === Linien ===
+    let s = state.clone();
+    engine.register_fn(
+        "side_line",
+        move |from: INT, from_ratio: Dynamic, to: INT, to_ratio: Dynamic| -> Result<INT, ScriptError> {
+            let start = LineAnchor::Side { side: corner_index(from)?, ratio: ratio(&from_ratio)? };
+            let end = LineAnchor::Side { side: corner_index(to)?, ratio: ratio(&to_ratio)? };
+            s.borrow_mut().add_line(start, end)
+        },
+    );
+    let s = state.clone();
+    engine.register_fn("vertex_line", move |from: INT, to: INT| -> Result<INT, ScriptError> {
+        let (start, end) = (LineAnchor::Vertex(corner_index(from)?), LineAnchor::Vertex(corner_index(to)?));
+        s.borrow_mut().add_line(start, end)
+    });
+    let s = state.clone();
+    engine.register_fn("line_length", move |idx: INT| -> Result<f64, ScriptError> {
+        Ok(s.borrow().line(idx)?.length_um as f64 / 1000.0)
+    });
+    let s = state.clone();
+    engine.register_fn("line_name", move |idx: INT, name: &str| -> Result<(), ScriptError> {
+        let mut state = s.borrow_mut();
+        state.line(idx)?;
+        state.lines[idx as usize].name = name.to_string();
+        Ok(())
+    });
+
+    // === Export ===
+    let s = state.clone();
+    engine.register_fn("save", move |prefix: &str, extension: &str, content: &str| -> Result<String, ScriptError> {
+        save_target(prefix, extension)?;
+        let path = export::save_to_desktop(prefix, extension, content.as_bytes())?;
+        let path = path.display().to_string();
+        s.borrow_mut().log.push(format!("Gespeichert: {}", path));
+        Ok(path)
+    });
+
+    engine
+}
+
+/// Führt ein Skript aus; bei einem Fehler enthält das Ergebnis die Ausgaben bis zum Abbruch
+pub fn run(source: &str) -> ScriptResult {
+    let state = Rc::new(RefCell::new(ScriptState::default()));
+    let error = build_engine(&state).run(source).err().map(|e| e.to_string());
+
+    let state = state.take();
+    ScriptResult {
+        input: state.input,
+        lines: state.lines,
+        log: state.log,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_script() {
+        let result = run(EXAMPLE_SCRIPT);
+        assert_eq!(result.error, None);
+        assert_eq!(result.input, Some(QuadInput::rectangle(4000.0, 3000.0)));
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.log[0], "Diagonale AC: 5000.0 mm");
+        assert!((result.lines[2].length_um as f64 - 3_000_000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_quad_and_queries() {
+        let result = run(
+            r#"
+            quad(#{ab: 4000, bc: 3000, cd: 4000, da: 3000, a: 90});
+            print(angle(2).round());
+            print(area().round());
+            "#,
+        );
+        assert_eq!(result.error, None);
+        assert_eq!(result.log, vec!["90.0", "12.0"]);
+    }
+
+    #[test]
+    fn test_script_errors() {
+        // Ausgaben vor dem Fehler bleiben erhalten
+        let result = run("print(\"vorher\"); side(0);");
+        assert_eq!(result.log, vec!["vorher"]);
+        assert!(result.error.unwrap().contains("Zuerst ein Viereck"));
+
+        let result = run("rectangle(4000, 3000); side_line(0, 1.5, 2, 0.5);");
+        assert!(result.error.is_some());
+
+        let result = run("loop {}");
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn test_save_target() {
+        assert!(save_target("Aufmass", "csv").is_ok());
+        assert!(save_target("Aufmass", "JSON").is_ok());
+        assert!(save_target("../Aufmass", "csv").is_err());
+        assert!(save_target("Ordner/Aufmass", "txt").is_err());
+        assert!(save_target("C:\\Windows\\Aufmass", "txt").is_err());
+        assert!(save_target("", "txt").is_err());
+        assert!(save_target("Aufmass", "exe").is_err());
+        assert!(save_target("Aufmass", "csv/../x").is_err());
+
+        // Abgelehnte Dateien brechen das Skript ab
+        let result = run(r#"save("../Aufmass", "csv", "a;b");"#);
+        assert!(result.error.unwrap().contains("Pfade sind nicht erlaubt"));
+    }
+}
@@ -0,0 +1,180 @@
+// Plattformabhängiger Dateizugriff
+// Desktop: Dateisystem und Konfigurationsordner
+// Web: Einstellungen im Browser-Speicher, Schreiben als Download, Lesen nur von auf das Fenster gezogenen Dateien
+
+use std::path::PathBuf;
+
+/// Ordner bzw. Schlüssel-Präfix der gespeicherten Einstellungen
+const SETTINGS_DIR_NAME: &str = "CAD-Zeichner";
+
+pub use platform::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use super::SETTINGS_DIR_NAME;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    pub fn read_to_string(path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    pub fn write(path: &Path, content: &[u8]) -> io::Result<()> {
+        std::fs::write(path, content)
+    }
+
+    /// Zielordner für Exporte und Screenshots
+    pub fn desktop_dir() -> PathBuf {
+        dirs::desktop_dir().unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Pfad einer auf das Fenster gezogenen Datei
+    pub fn dropped_file(file: &egui::DroppedFile) -> Option<PathBuf> {
+        file.path.clone()
+    }
+
+    fn settings_path(name: &str) -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(SETTINGS_DIR_NAME).join(name))
+    }
+
+    /// Liest eine Einstellungsdatei, None wenn sie fehlt oder unlesbar ist
+    pub fn load_setting(name: &str) -> Option<String> {
+        settings_path(name).and_then(|path| std::fs::read_to_string(path).ok())
+    }
+
+    pub fn save_setting(name: &str, content: &str) -> Result<(), String> {
+        let path = settings_path(name).ok_or("Konfigurationsverzeichnis nicht gefunden")?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Konfigurationsordner konnte nicht erstellt werden: {}", e))?;
+        }
+
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Einstellungen konnten nicht gespeichert werden: {}", e))
+    }
+
+    /// Hängt an eine Einstellungsdatei an, ohne sie neu zu schreiben; legt sie bei Bedarf an
+    pub fn append_setting(name: &str, content: &str) -> Result<(), String> {
+        use std::io::Write;
+        let path = settings_path(name).ok_or("Konfigurationsverzeichnis nicht gefunden")?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Konfigurationsordner konnte nicht erstellt werden: {}", e))?;
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| format!("{} konnte nicht geschrieben werden: {}", name, e))
+    }
+
+    /// Größe einer Einstellungsdatei in Bytes, 0 wenn sie fehlt
+    pub fn setting_len(name: &str) -> usize {
+        settings_path(name)
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map_or(0, |meta| meta.len() as usize)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use super::SETTINGS_DIR_NAME;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use wasm_bindgen::JsCast;
+
+    thread_local! {
+        // Inhalt der auf das Fenster gezogenen Dateien, nach Dateiname
+        static DROPPED: RefCell<HashMap<PathBuf, Arc<[u8]>>> = RefCell::new(HashMap::new());
+    }
+
+    fn js_error(e: wasm_bindgen::JsValue) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, format!("{:?}", e))
+    }
+
+    /// Der Browser hat keinen Dateizugriff: gelesen werden nur zuvor auf das Fenster gezogene Dateien
+    pub fn read_to_string(path: &Path) -> io::Result<String> {
+        String::from_utf8(read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+        DROPPED
+            .with(|dropped| dropped.borrow().get(path).map(|bytes| bytes.to_vec()))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Datei bitte auf das Fenster ziehen"))
+    }
+
+    /// Bietet den Inhalt als Download unter dem Dateinamen des Pfads an
+    pub fn write(path: &Path, content: &[u8]) -> io::Result<()> {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(content));
+        let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(js_error)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Kein Browserfenster"))?;
+        let anchor: web_sys::HtmlAnchorElement = document
+            .create_element("a")
+            .map_err(js_error)?
+            .dyn_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Download-Link konnte nicht erstellt werden"))?;
+        anchor.set_href(&url);
+        anchor.set_download(&name);
+        anchor.click();
+
+        web_sys::Url::revoke_object_url(&url).map_err(js_error)
+    }
+
+    /// Downloads tragen nur den Dateinamen
+    pub fn desktop_dir() -> PathBuf {
+        PathBuf::new()
+    }
+
+    /// Merkt sich den Inhalt der Datei, damit `read_to_string` ihn über den Dateinamen findet
+    pub fn dropped_file(file: &egui::DroppedFile) -> Option<PathBuf> {
+        let bytes = file.bytes.clone()?;
+        let path = PathBuf::from(&file.name);
+        DROPPED.with(|dropped| dropped.borrow_mut().insert(path.clone(), bytes));
+        Some(path)
+    }
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn load_setting(name: &str) -> Option<String> {
+        local_storage()?.get_item(&format!("{}/{}", SETTINGS_DIR_NAME, name)).ok()?
+    }
+
+    pub fn save_setting(name: &str, content: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("Browser-Speicher nicht verfügbar")?
+            .set_item(&format!("{}/{}", SETTINGS_DIR_NAME, name), content)
+            .map_err(|e| format!("Einstellungen konnten nicht gespeichert werden: {:?}", e))
+    }
+
+    /// Der Browser-Speicher kennt kein Anhängen, der Eintrag wird neu geschrieben
+    pub fn append_setting(name: &str, content: &str) -> Result<(), String> {
+        save_setting(name, &(load_setting(name).unwrap_or_default() + content))
+    }
+
+    pub fn setting_len(name: &str) -> usize {
+        load_setting(name).map_or(0, |content| content.len())
+    }
+}
+
+/// Pfad einer auf das Fenster gezogenen Datei (im Web nur der Dateiname)
+pub fn first_dropped_file(ctx: &egui::Context) -> Option<PathBuf> {
+    ctx.input(|i| i.raw.dropped_files.iter().find_map(dropped_file))
+}
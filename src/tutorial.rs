@@ -0,0 +1,87 @@
+// Einführung für neue Benutzer: Beispielraum eingeben, berechnen, Trennwand einzeichnen
+// Die Oberfläche hebt zu jedem Schritt den passenden Bereich hervor
+
+use crate::geometry::QuadInput;
+
+/// Bereich der Oberfläche, der in einem Schritt hervorgehoben wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TutorialTarget {
+    InputPanel,
+    CalculateButton,
+    Canvas,
+}
+
+/// Aufgabe eines Schritts, die Oberfläche prüft sie am Zustand der App
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialGoal {
+    None,
+    InputsFilled,
+    Calculated,
+    LineDrawn,
+}
+
+pub struct TutorialStep {
+    pub title: &'static str,
+    pub text: &'static str,
+    pub target: Option<TutorialTarget>,
+    pub goal: TutorialGoal,
+}
+
+/// Beispielraum 5,00 × 4,00 m mit rechtem Winkel bei A
+pub const EXAMPLE_ROOM: QuadInput = QuadInput {
+    sides_mm: [Some(5000.0), Some(4000.0), Some(5000.0), Some(4000.0)],
+    angles: [Some(90.0), None, None, None],
+    diagonals_mm: [None; 2],
+};
+
+pub const STEPS: [TutorialStep; 5] = [
+    TutorialStep {
+        title: "Willkommen",
+        text: "Diese kurze Einführung zeigt an einem Beispielraum, wie aus gemessenen Maßen ein \
+               Grundriss entsteht und wie eine Trennwand eingezeichnet wird.",
+        target: None,
+        goal: TutorialGoal::None,
+    },
+    TutorialStep {
+        title: "Maße eingeben",
+        text: "Links werden die gemessenen Seitenlängen in mm und die Innenwinkel eingetragen. \
+               Nötig sind 4 Seiten + 1 Winkel oder 3 Seiten + 2 Winkel. \
+               „Beispiel eintragen“ füllt einen Raum mit 5,00 × 4,00 m aus.",
+        target: Some(TutorialTarget::InputPanel),
+        goal: TutorialGoal::InputsFilled,
+    },
+    TutorialStep {
+        title: "Berechnen",
+        text: "Ein Klick auf „🔢 Berechnen“ (oder Enter in einem Eingabefeld) prüft die Maße \
+               und zeichnet den Grundriss maßstäblich.",
+        target: Some(TutorialTarget::CalculateButton),
+        goal: TutorialGoal::Calculated,
+    },
+    TutorialStep {
+        title: "Trennwand einzeichnen",
+        text: "Mit gedrückter Maustaste von einer Seite zur gegenüberliegenden ziehen. \
+               Für einen exakten Abstand doppelt auf die Seite klicken, z.B. 1,5 m eingeben \
+               und dann den Endpunkt anklicken.",
+        target: Some(TutorialTarget::Canvas),
+        goal: TutorialGoal::LineDrawn,
+    },
+    TutorialStep {
+        title: "Geschafft",
+        text: "Linien lassen sich links in der Linienliste umbenennen, sperren, verschieben oder \
+               löschen. Weitere Tipps stehen unter „❓ Hilfe“, dort startet auch diese Einführung erneut.",
+        target: None,
+        goal: TutorialGoal::None,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::solve;
+
+    #[test]
+    fn test_example_room_solves() {
+        let quad = solve(&EXAMPLE_ROOM).unwrap().quad;
+        assert!((quad.area_mm2() / 1_000_000.0 - 20.0).abs() < 1e-6);
+    }
+}
@@ -1,16 +1,228 @@
 use crate::geometry::*;
-use crate::geometry::utils::{distance_um, calculate_intersection_angle};
-use crate::updater::{self, UpdateInfo};
+use crate::geometry::utils::distance_um;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::association;
+use crate::batch;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::clipboard;
+use crate::costs::{self, CostItem};
+use crate::diagnostics::{self, LogLevel};
+use crate::dxf;
+use crate::export::{self, PointRecord};
+use crate::format::{NumberFormat, MAX_DECIMAL_PLACES};
+use crate::guides::{candidate_guides, snap_to_guides, Guide, GuideKind, GuideShape, GuideSources, GUIDE_SNAP_PX};
+use crate::journal::{Journal, JournalEvent};
+use crate::project::{MeasurementSnapshot, ProjectFile, ProjectMetadata, ProjectSheet, PROJECT_EXTENSION};
+use crate::raster::{self, ImageSizeMode, PaperSize, MAX_IMAGE_SIDE_PX};
+use crate::report::Report;
+use crate::scene::{point_to_line_distance, styled_line, Scene, SceneBuilder, SceneSource};
+use crate::script::{self, EXAMPLE_SCRIPT, SCRIPT_EXTENSION};
+use crate::storage;
+use crate::tutorial::{TutorialGoal, TutorialTarget, EXAMPLE_ROOM, STEPS};
+use crate::config::{AngleDisplay, AppConfig, DecimalSeparator, InputTemplate, FillPattern, Language, LengthUnit, ThemeMode, VertexLabels, WindowMode};
+use crate::updater::{self, SharedUpdateState, UpdateChannel, UpdateInfo, UpdateState};
+use crate::xlsx;
+use crate::view::{format_ruler_label, nice_step_mm, ViewOrientation, ViewTransform};
 use eframe::egui;
+use egui::epaint::text::Fonts;
 use egui::{Color32, Pos2, Stroke, Vec2};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+/// Aktives Zeichenwerkzeug auf der Zeichenfläche
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DrawTool {
+    Line,
+    Circle,
+    Arc,
+    Measure, // Winkel zwischen zwei Kanten messen
+}
+
+/// Element der Zeichnung unter dem Cursor
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Hit {
+    LineEnd { line: usize, at_start: bool },
+    Line(usize),
+    Vertex(usize),
+    Circle(usize),
+    Quad,
+}
+
+/// Offene Abstandseingabe für den Startpunkt einer neuen Linie (nach Doppelklick auf Seite oder Linie)
+struct DistanceInput {
+    anchor: LineAnchor, // Bezugsstrecke, die Position wird aus dem eingegebenen Abstand berechnet
+    pos: Pos2,          // Bildschirmposition des Doppelklicks
+    text: String,
+    error: Option<String>,
+    request_focus: bool,
+}
+
+/// Tastaturführung durch die Maßeingaben: Tab springt in der Reihenfolge AB … DA, A … D, AC, BD
+/// ohne Umweg über Überschriften und Schalter; nach einem Fehler steht der Cursor im ersten fehlerhaften Feld
+#[derive(Default)]
+struct InputFocus {
+    shown: Vec<(usize, egui::Id)>,   // In diesem Frame angezeigte Felder (Index wie input_errors)
+    tab_from: Option<(usize, bool)>, // Feld, in dem Tab gedrückt wurde; true = mit Shift (rückwärts)
+    pending: Option<usize>,          // Feld, das beim nächsten Anzeigen den Fokus bekommt
+}
+
+impl InputFocus {
+    fn begin_frame(&mut self) {
+        self.shown.clear();
+        self.tab_from = None;
+    }
+
+    /// Nach allen Feldern: Tab-Sprung ausführen; ein nicht angezeigtes Zielfeld (eingeklappt) verfällt
+    fn end_frame(&mut self, ctx: &egui::Context) {
+        if let Some((field, backwards)) = self.tab_from {
+            if let Some(pos) = self.shown.iter().position(|&(f, _)| f == field) {
+                let n = self.shown.len();
+                let next = if backwards { (pos + n - 1) % n } else { (pos + 1) % n };
+                let id = self.shown[next].1;
+                ctx.memory_mut(|mem| mem.request_focus(id));
+            }
+        }
+        if self.pending.is_some_and(|field| !self.shown.iter().any(|&(f, _)| f == field)) {
+            self.pending = None;
+        }
+    }
+}
+
+/// Linie ohne Maus: Start und Ende jeweils als Abstand auf einer Seite oder Linie
+struct KeyboardLine {
+    start: LineAnchor, // Bezugsstrecke (ratio 0), der Abstand zählt ab ihrem Anfang
+    start_text: String,
+    end: LineAnchor,
+    end_text: String,
+    error: Option<String>,
+}
+
+impl Default for KeyboardLine {
+    fn default() -> Self {
+        Self {
+            start: LineAnchor::Side { side: 0, ratio: 0.0 },
+            start_text: String::new(),
+            end: LineAnchor::Side { side: 2, ratio: 0.0 },
+            end_text: String::new(),
+            error: None,
+        }
+    }
+}
+
+/// Form der Beschriftungsskizze, solange die Eingaben noch kein Viereck ergeben
+/// Bewusst unregelmäßig, damit keine zwei Seiten gleich aussehen
+const SKETCH_FALLBACK: QuadInput = QuadInput {
+    sides_mm: [Some(5000.0), Some(3500.0), Some(4200.0), Some(3000.0)],
+    angles: [Some(85.0), None, None, None],
+    diagonals_mm: [None; 2],
+};
+
+/// Fehlermeldung für unlesbare Abstände
+const INVALID_DISTANCE: &str = "Ungültige Länge (z.B. 1250, 125 cm oder 1,25 m)";
+
+/// Anzahl der Berechnungen im Sitzungs-Verlauf
+const HISTORY_LEN: usize = 10;
+
+/// Eintrag im Sitzungs-Verlauf: Eingaben und berechnetes Viereck
+#[derive(Clone, Debug)]
+struct CalculationRecord {
+    time: String,
+    input: QuadInput,
+    quad: Quadrilateral,
+}
+
+/// Linien vor dem letzten Sammel-Löschen, wiederherstellbar bis zur nächsten neuen Linie
+struct DeletedLines {
+    lines: Vec<CustomLine>,
+    angle_dimensions: Vec<AngleDimension>,
+}
+
+/// Zustand eines Arbeitsblatts (z.B. ein Raum)
+/// Das aktive Blatt liegt direkt in den Feldern von `CadApp`, die übrigen werden hier geparkt
+struct Worksheet {
+    name: String,
+    quad: Quadrilateral,
+    calculated: bool,
+    custom_lines: Vec<CustomLine>,
+    custom_circles: Vec<CustomCircle>,
+    angle_dimensions: Vec<AngleDimension>,
+    construction_lines: Vec<ConstructionLine>,
+    inputs: [String; 10], // AB, BC, CD, DA, A, B, C, D, AC, BD
+    input_errors: [Option<String>; 10],
+    computed_inputs: [bool; 10],
+    last_input: Option<QuadInput>,
+    history: Vec<CalculationRecord>,
+    comparison: Option<CalculationRecord>,
+    snapshots: Vec<MeasurementSnapshot>,
+    uncertainty: Option<Result<UncertaintyResult, String>>,
+    tile_estimate: Option<Result<TileEstimate, String>>,
+    construction_info: Option<String>,
+    selected_line: Option<usize>,
+    selected_circle: Option<usize>,
+    next_line_number: usize,
+    next_circle_number: usize,
+    triangle_diagonal: usize,
+    zoom: f32,
+    pan: Vec2,
+}
+
+impl Worksheet {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            quad: Quadrilateral::new(),
+            calculated: false,
+            custom_lines: Vec::new(),
+            custom_circles: Vec::new(),
+            angle_dimensions: Vec::new(),
+            construction_lines: Vec::new(),
+            inputs: Default::default(),
+            input_errors: Default::default(),
+            computed_inputs: [false; 10],
+            last_input: None,
+            history: Vec::new(),
+            comparison: None,
+            snapshots: Vec::new(),
+            uncertainty: None,
+            tile_estimate: None,
+            construction_info: None,
+            selected_line: None,
+            selected_circle: None,
+            next_line_number: 1,
+            next_circle_number: 1,
+            triangle_diagonal: 0,
+            zoom: 1.0,
+            pan: Vec2::ZERO,
+        }
+    }
+}
+
+/// Ab dieser Änderung gilt die Form beim Übertragen der Linien als stark verändert
+const RESHAPE_SIDE_RATIO: f64 = 0.1;
+const RESHAPE_ANGLE_DEG: f64 = 10.0;
+
+/// Farben zur Schnellauswahl im Kontextmenü
+const QUICK_COLORS: [[u8; 3]; 6] = [
+    [200, 100, 0],
+    [200, 50, 50],
+    [50, 50, 200],
+    [0, 140, 70],
+    [130, 60, 160],
+    [56, 62, 66],
+];
+
 pub struct CadApp {
     quad: Quadrilateral,
     calculated: bool,
     error_message: Option<String>,
     custom_lines: Vec<CustomLine>,
+    custom_circles: Vec<CustomCircle>,
+    angle_dimensions: Vec<AngleDimension>, // Angeheftete Winkelmaße
+    construction_lines: Vec<ConstructionLine>, // Hilfslinien (Seitenverlängerungen)
+    input_construction_extension: String,     // Überstand neuer Hilfslinien, leer = unbegrenzt
     
     // Eingabefelder
     input_ab: String,
@@ -21,21 +233,127 @@ pub struct CadApp {
     input_angle_b: String,
     input_angle_c: String,
     input_angle_d: String,
+    input_diagonal_ac: String,
+    input_diagonal_bd: String,
+    input_errors: [Option<String>; 10], // Fehlerhinweis je Feld: AB, BC, CD, DA, A, B, C, D, AC, BD
+    computed_inputs: [bool; 10],        // Feld enthält einen berechneten Wert und zählt nicht als Messung
     
     // UI State
     show_help: bool,
+    show_settings: bool,
+    show_batch: bool,
+    input_batch_path: String,
+    batch_message: Option<Result<String, String>>, // Ergebnis der letzten Stapelberechnung
+    show_script: bool,
+    script_source: String,
+    input_script_path: String,
+    script_log: Vec<String>,      // Ausgaben des letzten Skriptlaufs
+    script_error: Option<String>, // Abbruchgrund des letzten Skriptlaufs oder Dateifehler
+    show_log: bool,
+    log_text: Option<String>,    // Beim Öffnen gelesenes Fehlerprotokoll
+    log_message: Option<String>, // Ergebnis des letzten Exports bzw. Leerens
+    #[cfg(not(target_arch = "wasm32"))]
+    api_server: Option<tokio::task::JoinHandle<()>>, // Laufende lokale HTTP-Schnittstelle
+    #[cfg(not(target_arch = "wasm32"))]
+    api_error: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    api_tolerance: api::SharedTolerance, // Warnbereich für Anfragen an die Schnittstelle
+    #[cfg(not(target_arch = "wasm32"))]
+    association_message: Option<Result<String, String>>, // Ergebnis der Registrierung der Dateiverknüpfung
+    #[cfg(not(target_arch = "wasm32"))]
+    copy_image_pending: bool, // Bildschirmfoto angefordert, Zeichnung geht danach in die Zwischenablage
+    #[cfg(not(target_arch = "wasm32"))]
+    clipboard_message: Option<Result<String, String>>,
+    canvas_rect: egui::Rect, // Lage der Zeichenfläche im letzten Frame
+    show_project: bool,
+    show_templates: bool,              // Fenster "Vorlagen verwalten"
+    input_template_name: String,
+    template_message: Option<Result<String, String>>, // Ergebnis des letzten Speicherns/Ladens einer Vorlage
+    show_prism: bool,
+    prism_view: PrismView,
+    input_project_path: String,
+    project_message: Option<Result<String, String>>, // Ergebnis des letzten Speicherns/Öffnens
+    sheets: Vec<Worksheet>, // Alle Arbeitsblätter, der Eintrag des aktiven Blatts trägt nur den Namen
+    active_sheet: usize,
     drawing_line: bool,
-    line_start: Option<(usize, f64, Pos2)>,
+    line_start: Option<(LineAnchor, Pos2)>,
     preview_end: Option<Pos2>,
+    line_click_end: bool, // Start per Abstandseingabe gesetzt: Linie endet mit einem Klick statt durch Ziehen
+    distance_input: Option<DistanceInput>,
+    reset_undo: Option<Worksheet>, // Blatt vor "Neu", wiederherstellbar bis zur nächsten Berechnung
+    deleted_lines: Option<DeletedLines>, // Vor dem Löschen mehrerer Linien, für Strg+Z
+    confirm_clear_lines: bool,           // Rückfrage "Alle Linien entfernen" ist offen
+    confirm_remove_sheet: bool,          // Rückfrage "Arbeitsblatt entfernen" ist offen
+    replay_error: Option<String>,  // Grundmaße aus dem Konstruktionsverlauf ergeben kein Viereck
+    replay_unlogged: bool,         // Neu berechnet, der Eintrag ins Messprotokoll folgt nach Ende der Eingabe
+    keyboard_line: KeyboardLine,
     dragging_line_idx: Option<usize>,
     drag_offset: Vec2,
+    translating_line: Option<(usize, CustomLine, Point)>, // Alt+Ziehen: Linie, Zustand und Weltposition beim Start
+    active_guide: Option<Guide>, // Hilfslinie, an der das gezogene Linienende gerade eingerastet ist
     hovered_line: Option<usize>,
+    selected_line: Option<usize>,
+    selected_endpoint: Option<bool>, // Endpunkt der ausgewählten Linie für Pfeiltasten: true = Start, false = Ende
+    multi_selection: Vec<usize>,     // Mehrfachauswahl (Strg+Klick, Shift+Ziehen) zum Gruppieren und gemeinsamen Bearbeiten
+    selection_box: Option<Pos2>,     // Startpunkt des Auswahlrahmens
+    scene: Scene,                    // Zwischengespeicherte Darstellung der Zeichenfläche
+    input_group_move: String,        // Schrittweite beim Verschieben der Auswahl (mm)
+    context_target: Option<Hit>,     // Rechtsklick-Ziel des offenen Kontextmenüs
+    next_line_number: usize, // Für automatisch vergebene Namen ("Linie 1", "Linie 2", ...)
+    construction_info: Option<String>, // Ergebnis des letzten Konstruktionswerkzeugs
+    recalc_warning: Option<String>,    // Hinweis nach dem Übertragen der Linien auf eine stark veränderte Form
+    plausibility_warnings: Vec<PlausibilityWarning>, // Kleine Widersprüche der letzten Berechnung
+    check_diagonal: usize,             // Kontrollmaß: 0 = AC, 1 = BD
+    triangle_diagonal: usize,          // Zerlegung in Dreiecke: 0 = AC, 1 = BD
+    baseline: Baseline,                // Bezugssystem der Absteckpunkte
+    export_message: Option<String>,    // Ergebnis des letzten Exports
+    project: ProjectMetadata,
+    report_message: Option<String>,    // Ergebnis des letzten Protokoll-Exports
+    input_logo_path: String,           // Logodatei für den Firmenkopf
+    branding_message: Option<String>,  // Ergebnis des letzten Logo-Ladens
+    image_export_message: Option<String>, // Ergebnis des letzten Bildexports
+    geojson_message: Option<String>,      // Ergebnis des letzten GeoJSON-Exports
+    tile_estimate: Option<Result<TileEstimate, String>>, // Zwischengespeichert, Neuberechnung bei Änderungen
+    input_check_diagonal: String,
+    inverse_target: usize,   // Rückwärtsrechnung: 0 = AC, 1 = BD, 2 = Fläche
+    input_inverse: String,   // Zielmaß der Rückwärtsrechnung (Eingabeeinheit bzw. m²)
+    inverse_result: Option<Result<Vec<InverseSolution>, String>>,
+    input_line_offset: String, // Versatz für parallele Kopien (mm)
+    last_input: Option<QuadInput>, // Eingaben der letzten erfolgreichen Berechnung
+    history: Vec<CalculationRecord>, // Neueste zuerst, höchstens HISTORY_LEN Einträge
+    journal: Journal,                // Sitzungsprotokoll über alle Blätter, wird nicht mit dem Projekt gespeichert
+    journal_message: Option<String>, // Ergebnis des letzten Protokoll-Exports (CSV)
+    comparison: Option<CalculationRecord>, // Überlagertes Vergleichsviereck (z.B. Soll gegen Ist)
+    comparison_side_by_side: bool,         // Vergleichsviereck neben statt über dem aktuellen zeichnen
+    snapshots: Vec<MeasurementSnapshot>,   // Eingefrorene Messstände des Blatts, werden mit dem Projekt gespeichert
+    input_snapshot_name: String,
+    uncertainty: Option<Result<UncertaintyResult, String>>, // None = keine Unsicherheit angegeben
+    tool: DrawTool,
+    circle_center: Option<Point>, // Mittelpunkt während ein Kreis aufgezogen wird
+    measure_first: Option<CutEdge>,         // Erste angeklickte Kante beim Winkelmessen
+    measure_result: Option<AngleDimension>, // Letzte Messung, noch nicht angeheftet
+    arc_sweep_deg: f64,           // Bogenwinkel für neue Kreisbögen
+    selected_circle: Option<usize>,
+    next_circle_number: usize,
+    zoom: f32,
+    pan: Vec2,
+    orientation: ViewOrientation, // Drehung/Spiegelung, nur Darstellung
+    
+    // Einstellungen
+    config: AppConfig,
     
-    // Update State
-    update_info: Arc<Mutex<Option<UpdateInfo>>>,
-    checking_update: bool,
-    show_update_dialog: bool,
-    update_status: String,
+    // Update State (wird vom Hintergrund-Task aktualisiert)
+    update_state: SharedUpdateState,
+    startup_check_done: bool,
+    silent_update_check: bool, // Automatische Prüfung: nur bei neuer Version melden
+    pending_update: Option<String>, // Geladene Version, die beim Beenden installiert wird
+    exit_prompt: bool,              // Nachfrage beim Beenden mit geladenem Update offen
+    exit_confirmed: bool,           // Beenden bestätigt, Schließen nicht mehr abfangen
+    
+    // Einführung
+    tutorial_step: Option<usize>, // Offener Schritt, None = Einführung geschlossen
+    tutorial_rects: HashMap<TutorialTarget, egui::Rect>, // Lage der hervorzuhebenden Bereiche im letzten Frame
+    input_focus: InputFocus,
 }
 
 impl Default for CadApp {
@@ -45,6 +363,10 @@ impl Default for CadApp {
             calculated: false,
             error_message: None,
             custom_lines: Vec::new(),
+            custom_circles: Vec::new(),
+            angle_dimensions: Vec::new(),
+            construction_lines: Vec::new(),
+            input_construction_extension: String::new(),
             input_ab: String::new(),
             input_bc: String::new(),
             input_cd: String::new(),
@@ -53,34 +375,415 @@ impl Default for CadApp {
             input_angle_b: String::new(),
             input_angle_c: String::new(),
             input_angle_d: String::new(),
+            input_diagonal_ac: String::new(),
+            input_diagonal_bd: String::new(),
+            input_errors: Default::default(),
+            computed_inputs: [false; 10],
             show_help: false,
+            show_settings: false,
+            show_batch: false,
+            input_batch_path: String::new(),
+            show_script: false,
+            show_log: false,
+            log_text: None,
+            log_message: None,
+            script_source: EXAMPLE_SCRIPT.to_string(),
+            input_script_path: String::new(),
+            script_log: Vec::new(),
+            script_error: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            api_server: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            api_error: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            api_tolerance: Arc::new(Mutex::new(PlausibilityTolerance::default())),
+            #[cfg(not(target_arch = "wasm32"))]
+            association_message: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            copy_image_pending: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            clipboard_message: None,
+            canvas_rect: egui::Rect::NOTHING,
+            batch_message: None,
+            show_project: false,
+            show_templates: false,
+            input_template_name: String::new(),
+            template_message: None,
+            show_prism: false,
+            prism_view: PrismView::default(),
+            input_project_path: String::new(),
+            project_message: None,
+            sheets: vec![Worksheet::new("Blatt 1".to_string())],
+            active_sheet: 0,
             drawing_line: false,
             line_start: None,
             preview_end: None,
+            line_click_end: false,
+            distance_input: None,
+            reset_undo: None,
+            deleted_lines: None,
+            confirm_clear_lines: false,
+            confirm_remove_sheet: false,
+            replay_error: None,
+            replay_unlogged: false,
+            keyboard_line: KeyboardLine::default(),
             dragging_line_idx: None,
+            translating_line: None,
+            active_guide: None,
             drag_offset: Vec2::ZERO,
             hovered_line: None,
-            update_info: Arc::new(Mutex::new(None)),
-            checking_update: false,
-            show_update_dialog: false,
-            update_status: String::new(),
+            selected_line: None,
+            selected_endpoint: None,
+            multi_selection: Vec::new(),
+            selection_box: None,
+            scene: Scene::default(),
+            input_group_move: "100".to_string(),
+            context_target: None,
+            next_line_number: 1,
+            construction_info: None,
+            recalc_warning: None,
+            plausibility_warnings: Vec::new(),
+            check_diagonal: 0,
+            triangle_diagonal: 0,
+            baseline: Baseline::default(),
+            export_message: None,
+            project: ProjectMetadata::default(),
+            report_message: None,
+            input_logo_path: String::new(),
+            branding_message: None,
+            image_export_message: None,
+            geojson_message: None,
+            tile_estimate: None,
+            input_check_diagonal: String::new(),
+            inverse_target: 0,
+            input_inverse: String::new(),
+            inverse_result: None,
+            input_line_offset: "100".to_string(),
+            last_input: None,
+            history: Vec::new(),
+            journal: Journal::default(),
+            journal_message: None,
+            comparison: None,
+            comparison_side_by_side: false,
+            snapshots: Vec::new(),
+            input_snapshot_name: String::new(),
+            uncertainty: None,
+            tool: DrawTool::Line,
+            circle_center: None,
+            measure_first: None,
+            measure_result: None,
+            arc_sweep_deg: 90.0,
+            selected_circle: None,
+            next_circle_number: 1,
+            zoom: 1.0,
+            pan: Vec2::ZERO,
+            orientation: ViewOrientation::default(),
+            config: AppConfig::default(),
+            update_state: Arc::new(Mutex::new(UpdateState::Idle)),
+            startup_check_done: false,
+            silent_update_check: false,
+            pending_update: None,
+            exit_prompt: false,
+            exit_confirmed: false,
+            tutorial_step: None,
+            tutorial_rects: HashMap::new(),
+            input_focus: InputFocus::default(),
         }
     }
 }
 
-// ========== HILFSFUNKTION: KOMMA-FORMATIERUNG ==========
-fn format_with_comma(value: f64) -> String {
-    format!("{:.3}", value).replace('.', ",")
-}
+impl CadApp {
+    /// Erstellt die App mit den beim Start geladenen Einstellungen
+    pub fn new(config: AppConfig) -> Self {
+        Self {
+            // Beim ersten Start führt die Einführung durch die Bedienung
+            tutorial_step: (!config.tutorial_seen).then_some(0),
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Öffnet eine Projektdatei (Startargument, Dateiverknüpfung oder Drag & Drop)
+    pub fn open_project_file(&mut self, path: &std::path::Path) {
+        self.input_project_path = path.display().to_string();
+        self.project_message = Some(self.load_project());
+        self.show_project = true;
+    }
+
+    // === Arbeitsblätter ===
+
+    /// Nimmt den Zustand des aktiven Blatts aus den Feldern der App
+    fn take_sheet_state(&mut self) -> Worksheet {
+        Worksheet {
+            name: self.sheets[self.active_sheet].name.clone(),
+            quad: std::mem::replace(&mut self.quad, Quadrilateral::new()),
+            calculated: std::mem::take(&mut self.calculated),
+            custom_lines: std::mem::take(&mut self.custom_lines),
+            custom_circles: std::mem::take(&mut self.custom_circles),
+            angle_dimensions: std::mem::take(&mut self.angle_dimensions),
+            construction_lines: std::mem::take(&mut self.construction_lines),
+            inputs: [
+                std::mem::take(&mut self.input_ab),
+                std::mem::take(&mut self.input_bc),
+                std::mem::take(&mut self.input_cd),
+                std::mem::take(&mut self.input_da),
+                std::mem::take(&mut self.input_angle_a),
+                std::mem::take(&mut self.input_angle_b),
+                std::mem::take(&mut self.input_angle_c),
+                std::mem::take(&mut self.input_angle_d),
+                std::mem::take(&mut self.input_diagonal_ac),
+                std::mem::take(&mut self.input_diagonal_bd),
+            ],
+            input_errors: std::mem::take(&mut self.input_errors),
+            computed_inputs: std::mem::take(&mut self.computed_inputs),
+            last_input: self.last_input.take(),
+            history: std::mem::take(&mut self.history),
+            comparison: self.comparison.take(),
+            snapshots: std::mem::take(&mut self.snapshots),
+            uncertainty: self.uncertainty.take(),
+            tile_estimate: self.tile_estimate.take(),
+            construction_info: self.construction_info.take(),
+            selected_line: self.selected_line.take(),
+            selected_circle: self.selected_circle.take(),
+            next_line_number: self.next_line_number,
+            next_circle_number: self.next_circle_number,
+            triangle_diagonal: self.triangle_diagonal,
+            zoom: self.zoom,
+            pan: self.pan,
+        }
+    }
+
+    /// Lädt ein Blatt in die Felder der App, laufende Zeichenaktionen werden abgebrochen
+    fn put_sheet_state(&mut self, sheet: Worksheet) {
+        let [ab, bc, cd, da, a, b, c, d, ac, bd] = sheet.inputs;
+        self.input_ab = ab;
+        self.input_bc = bc;
+        self.input_cd = cd;
+        self.input_da = da;
+        self.input_angle_a = a;
+        self.input_angle_b = b;
+        self.input_angle_c = c;
+        self.input_angle_d = d;
+        self.input_diagonal_ac = ac;
+        self.input_diagonal_bd = bd;
+        self.quad = sheet.quad;
+        self.calculated = sheet.calculated;
+        self.custom_lines = sheet.custom_lines;
+        self.custom_circles = sheet.custom_circles;
+        self.angle_dimensions = sheet.angle_dimensions;
+        self.construction_lines = sheet.construction_lines;
+        self.input_errors = sheet.input_errors;
+        self.computed_inputs = sheet.computed_inputs;
+        self.last_input = sheet.last_input;
+        self.history = sheet.history;
+        self.comparison = sheet.comparison;
+        self.snapshots = sheet.snapshots;
+        self.uncertainty = sheet.uncertainty;
+        self.tile_estimate = sheet.tile_estimate;
+        self.construction_info = sheet.construction_info;
+        self.selected_line = sheet.selected_line;
+        self.selected_circle = sheet.selected_circle;
+        self.next_line_number = sheet.next_line_number;
+        self.next_circle_number = sheet.next_circle_number;
+        self.triangle_diagonal = sheet.triangle_diagonal;
+        self.zoom = sheet.zoom;
+        self.pan = sheet.pan;
+        
+        self.drawing_line = false;
+        self.line_start = None;
+        self.preview_end = None;
+        self.line_click_end = false;
+        self.distance_input = None;
+        self.reset_undo = None;
+        self.deleted_lines = None;
+        self.confirm_clear_lines = false;
+        self.confirm_remove_sheet = false;
+        self.dragging_line_idx = None;
+        self.translating_line = None;
+        self.hovered_line = None;
+        self.selected_endpoint = None;
+        self.multi_selection.clear();
+        self.selection_box = None;
+        self.context_target = None;
+        self.circle_center = None;
+        self.measure_first = None;
+        self.measure_result = None;
+        self.export_message = None;
+        self.geojson_message = None;
+        self.recalc_warning = None;
+        self.plausibility_warnings.clear();
+        self.inverse_result = None;
+    }
+
+    fn switch_sheet(&mut self, idx: usize) {
+        if idx == self.active_sheet || idx >= self.sheets.len() {
+            return;
+        }
+        let current = self.take_sheet_state();
+        self.sheets[self.active_sheet] = current;
+        let name = self.sheets[idx].name.clone();
+        let target = std::mem::replace(&mut self.sheets[idx], Worksheet::new(name));
+        self.active_sheet = idx;
+        self.put_sheet_state(target);
+    }
+
+    /// Leert Eingaben, Ergebnisse und Zeichnung des aktiven Blatts, Verlauf und Messstände bleiben erhalten
+    fn reset_sheet(&mut self) {
+        let previous = self.take_sheet_state();
+        let mut fresh = Worksheet::new(previous.name.clone());
+        fresh.history = previous.history.clone();
+        fresh.snapshots = previous.snapshots.clone();
+        self.put_sheet_state(fresh);
+        self.reset_undo = Some(previous);
+    }
+
+    /// Stellt das Blatt vor dem letzten "Neu" wieder her
+    fn undo_reset(&mut self) {
+        if let Some(previous) = self.reset_undo.take() {
+            self.put_sheet_state(previous);
+        }
+    }
+
+    fn add_sheet(&mut self) {
+        let mut number = self.sheets.len() + 1;
+        while self.sheets.iter().any(|s| s.name == format!("Blatt {}", number)) {
+            number += 1;
+        }
+        self.sheets.push(Worksheet::new(format!("Blatt {}", number)));
+        self.switch_sheet(self.sheets.len() - 1);
+    }
+
+    /// Entfernt das aktive Blatt, das letzte verbleibende Blatt bleibt erhalten
+    fn remove_active_sheet(&mut self) {
+        if self.sheets.len() < 2 {
+            return;
+        }
+        let removed = self.active_sheet;
+        let next = if removed + 1 < self.sheets.len() { removed + 1 } else { removed - 1 };
+        self.switch_sheet(next);
+        self.sheets.remove(removed);
+        if self.active_sheet > removed {
+            self.active_sheet -= 1;
+        }
+    }
+
+    /// Reiterleiste über der Zeichenfläche
+    fn show_sheet_tabs(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to = None;
+        let mut add = false;
+        let mut remove = false;
+        
+        ui.horizontal(|ui| {
+            for (idx, sheet) in self.sheets.iter().enumerate() {
+                if ui.selectable_label(idx == self.active_sheet, &sheet.name).clicked() {
+                    switch_to = Some(idx);
+                }
+            }
+            if icon_button(ui, egui::Button::new("➕"), "Neues Arbeitsblatt").clicked() {
+                add = true;
+            }
+            
+            ui.separator();
+            ui.label("Name:");
+            ui.add(egui::TextEdit::singleline(&mut self.sheets[self.active_sheet].name).desired_width(140.0));
+            if self.sheets.len() > 1 && icon_button(ui, egui::Button::new("🗑"), "Arbeitsblatt entfernen").clicked() {
+                remove = true;
+            }
+        });
+        
+        if let Some(idx) = switch_to {
+            self.switch_sheet(idx);
+        }
+        if add {
+            self.add_sheet();
+        }
+        if remove {
+            self.confirm_remove_sheet = true;
+        }
+    }
 
-fn format_angle_with_comma(value: f64) -> String {
-    format!("{:.3}", value).replace('.', ",")
+    /// Rückfrage vor dem Entfernen des aktiven Arbeitsblatts
+    fn show_remove_sheet_dialog(&mut self, ctx: &egui::Context) {
+        let (mut confirm, mut cancel) = (false, false);
+        egui::Window::new("🗑 Arbeitsblatt entfernen?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let sheet = &self.sheets[self.active_sheet];
+                ui.label(format!("\"{}\" wird mit Eingaben, Linien, Verlauf und Messständen entfernt.", sheet.name));
+                ui.label(egui::RichText::new("Das lässt sich nicht rückgängig machen.").small());
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    confirm = ui.button("Entfernen").clicked();
+                    cancel = ui.button("Abbrechen").clicked();
+                });
+            });
+        if cancel || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.confirm_remove_sheet = false;
+        } else if confirm {
+            self.confirm_remove_sheet = false;
+            self.remove_active_sheet();
+        }
+    }
 }
 
+const STATUS_BAR_HEIGHT: f32 = 28.0;
+
+/// Rand um die eingepasste Zeichnung (Punkte), auch beim Bildexport
+const VIEW_PADDING: f32 = 120.0;
+
+
 impl eframe::App for CadApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // Automatische Update-Prüfung beim Start (opt-in)
+        if !self.startup_check_done {
+            self.startup_check_done = true;
+            self.update_api_server();
+            if self.config.check_updates_on_startup {
+                self.check_for_updates();
+                self.silent_update_check = true;
+            }
+        }
+        
+        // Auf das Fenster gezogene Projektdatei direkt öffnen
+        let dropped_project = storage::first_dropped_file(ctx)
+            .filter(|path| path.extension().is_some_and(|e| e == PROJECT_EXTENSION));
+        if let Some(path) = dropped_project {
+            self.open_project_file(&path);
+        }
+        
+        let labels = self.config.labels.clone();
+        let fmt = self.number_format();
+        
+        // Strg+Z nimmt "Neu" zurück, in Textfeldern bleibt es beim Rückgängig der Eingabe
+        if self.reset_undo.is_some()
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Z))
+        {
+            self.undo_reset();
+        } else if self.deleted_lines.is_some()
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Z))
+        {
+            self.undo_delete_lines();
+        }
+
+        // Entf löscht die Mehrfachauswahl bzw. die ausgewählte Linie
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::Delete)) {
+            if !self.multi_selection.is_empty() {
+                let selection = self.multi_selection.clone();
+                self.delete_lines(&selection);
+            } else if let Some(idx) = self.selected_line {
+                self.delete_line(idx);
+            }
+        }
+
+        // Enter in einem Eingabefeld berechnet wie der Berechnen-Button
+        let mut submit = false;
+        
         // Linkes Panel für Eingaben mit Scrollbar
-        egui::SidePanel::left("input_panel")
+        let input_panel = egui::SidePanel::left("input_panel")
             .min_width(380.0)
             .max_width(420.0)
             .resizable(true)
@@ -94,50 +797,118 @@ impl eframe::App for CadApp {
                         // === EINGABE SECTION ===
                         ui.add_space(5.0);
                         
-                        egui::CollapsingHeader::new("📏 Seitenlängen (in mm)")
+                        if ui
+                            .checkbox(&mut self.config.rectangle_mode, "▭ Rechteck-Schnellmodus")
+                            .on_hover_text("Nur Breite × Höhe eingeben, Diagonalen werden gleich eingezeichnet")
+                            .changed()
+                        {
+                            self.save_config();
+                        }
+                        self.show_template_bar(ui);
+                        ui.add_space(5.0);
+
+                        egui::CollapsingHeader::new("🧭 Beschriftung")
                             .default_open(true)
                             .show(ui, |ui| {
-                                ui.add_space(3.0);
-                                ui.horizontal(|ui| {
-                                    ui.label("Seite AB:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_ab).desired_width(120.0));
+                                self.show_label_sketch(ui, &labels);
+                            });
+                        ui.add_space(5.0);
+
+                        self.input_focus.begin_frame();
+                        let input_unit = self.config.input_unit;
+                        if self.config.rectangle_mode {
+                            egui::CollapsingHeader::new(format!("▭ Rechteck (in {})", input_unit.label()))
+                                .id_source("rectangle_inputs")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    ui.add_space(3.0);
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 0, label: &format!("Breite {}:", labels.side(0)), text: &mut self.input_ab, error: &mut self.input_errors[0], computed: &mut self.computed_inputs[0] }, |t| parse_length_with_unit(t, &fmt, input_unit));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 1, label: &format!("Höhe {}:", labels.side(1)), text: &mut self.input_bc, error: &mut self.input_errors[1], computed: &mut self.computed_inputs[1] }, |t| parse_length_with_unit(t, &fmt, input_unit));
+                                    if self.calculated {
+                                        let unit = self.length_unit();
+                                        for diagonal in 0..2 {
+                                            let mm = self.quad.diagonal_um(diagonal) as f64 / 1000.0;
+                                            ui.label(format!("  Kontrollmaß {}: {}", labels.diagonal(diagonal), fmt.length(mm, unit)));
+                                        }
+                                    }
                                 });
-                                ui.horizontal(|ui| {
-                                    ui.label("Seite BC:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_bc).desired_width(120.0));
+                        } else {
+                            egui::CollapsingHeader::new(format!("📏 Seitenlängen (in {})", input_unit.label()))
+                                .id_source("side_inputs")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    ui.add_space(3.0);
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 0, label: &format!("Seite {}:", labels.side(0)), text: &mut self.input_ab, error: &mut self.input_errors[0], computed: &mut self.computed_inputs[0] }, |t| parse_length_with_unit(t, &fmt, input_unit));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 1, label: &format!("Seite {}:", labels.side(1)), text: &mut self.input_bc, error: &mut self.input_errors[1], computed: &mut self.computed_inputs[1] }, |t| parse_length_with_unit(t, &fmt, input_unit));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 2, label: &format!("Seite {}:", labels.side(2)), text: &mut self.input_cd, error: &mut self.input_errors[2], computed: &mut self.computed_inputs[2] }, |t| parse_length_with_unit(t, &fmt, input_unit));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 3, label: &format!("Seite {}:", labels.side(3)), text: &mut self.input_da, error: &mut self.input_errors[3], computed: &mut self.computed_inputs[3] }, |t| parse_length_with_unit(t, &fmt, input_unit));
                                 });
-                                ui.horizontal(|ui| {
-                                    ui.label("Seite CD:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_cd).desired_width(120.0));
+
+                            ui.add_space(10.0);
+                        
+                            egui::CollapsingHeader::new("📐 Innenwinkel (in Grad)")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    ui.add_space(3.0);
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 4, label: &format!("Winkel {}:", labels.vertex(0)), text: &mut self.input_angle_a, error: &mut self.input_errors[4], computed: &mut self.computed_inputs[4] }, |t| parse_angle_input(t, &fmt));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 5, label: &format!("Winkel {}:", labels.vertex(1)), text: &mut self.input_angle_b, error: &mut self.input_errors[5], computed: &mut self.computed_inputs[5] }, |t| parse_angle_input(t, &fmt));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 6, label: &format!("Winkel {}:", labels.vertex(2)), text: &mut self.input_angle_c, error: &mut self.input_errors[6], computed: &mut self.computed_inputs[6] }, |t| parse_angle_input(t, &fmt));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 7, label: &format!("Winkel {}:", labels.vertex(3)), text: &mut self.input_angle_d, error: &mut self.input_errors[7], computed: &mut self.computed_inputs[7] }, |t| parse_angle_input(t, &fmt));
                                 });
-                                ui.horizontal(|ui| {
-                                    ui.label("Seite DA:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_da).desired_width(120.0));
+
+                            ui.add_space(10.0);
+
+                            egui::CollapsingHeader::new(format!("⤢ Diagonalen (in {})", input_unit.label()))
+                                .id_source("diagonal_inputs")
+                                .default_open(false)
+                                .show(ui, |ui| {
+                                    ui.label(egui::RichText::new("Ohne Winkelmessung: 4 Seiten + 1 Diagonale genügen, die zweite dient als Kontrolle").small());
+                                    ui.add_space(3.0);
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 8, label: &format!("Diagonale {}:", labels.diagonal(0)), text: &mut self.input_diagonal_ac, error: &mut self.input_errors[8], computed: &mut self.computed_inputs[8] }, |t| parse_length_with_unit(t, &fmt, input_unit));
+                                    submit |= validated_input_row(ui, &mut self.input_focus, InputField { field: 9, label: &format!("Diagonale {}:", labels.diagonal(1)), text: &mut self.input_diagonal_bd, error: &mut self.input_errors[9], computed: &mut self.computed_inputs[9] }, |t| parse_length_with_unit(t, &fmt, input_unit));
                                 });
-                            });
+
+                            // Live-Hinweis, welche Werte zur Lösung noch fehlen
+                            if let Some(missing) = missing_input(&self.entered_input()) {
+                                ui.add_space(5.0);
+                                ui.colored_label(Color32::from_rgb(60, 110, 170), format!("💡 {}", missing_input_text(&missing, &labels)));
+                            }
+                        }
+                        self.input_focus.end_frame(ui.ctx());
 
                         ui.add_space(10.0);
                         
-                        egui::CollapsingHeader::new("📐 Innenwinkel (in Grad)")
-                            .default_open(true)
+                        egui::CollapsingHeader::new("± Messunsicherheit")
+                            .default_open(false)
                             .show(ui, |ui| {
-                                ui.add_space(3.0);
-                                ui.horizontal(|ui| {
-                                    ui.label("Winkel A:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_angle_a).desired_width(120.0));
-                                });
-                                ui.horizontal(|ui| {
-                                    ui.label("Winkel B:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_angle_b).desired_width(120.0));
-                                });
+                                let mut changed = false;
                                 ui.horizontal(|ui| {
-                                    ui.label("Winkel C:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_angle_c).desired_width(120.0));
+                                    ui.label("Je Seite:");
+                                    changed |= ui.add(
+                                        egui::DragValue::new(&mut self.config.uncertainty.side_mm)
+                                            .speed(0.1)
+                                            .range(0.0..=100.0)
+                                            .prefix("± ")
+                                            .suffix(" mm"),
+                                    ).changed();
                                 });
                                 ui.horizontal(|ui| {
-                                    ui.label("Winkel D:");
-                                    ui.add(egui::TextEdit::singleline(&mut self.input_angle_d).desired_width(120.0));
+                                    ui.label("Je Winkel:");
+                                    changed |= ui.add(
+                                        egui::DragValue::new(&mut self.config.uncertainty.angle_deg)
+                                            .speed(0.05)
+                                            .range(0.0..=10.0)
+                                            .prefix("± ")
+                                            .suffix("°"),
+                                    ).changed();
                                 });
+                                if let Some(Err(e)) = &self.uncertainty {
+                                    ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                                }
+                                if changed {
+                                    self.update_uncertainty();
+                                    self.save_config();
+                                }
                             });
 
                         ui.add_space(15.0);
@@ -150,9 +921,57 @@ impl eframe::App for CadApp {
                         .min_size(egui::vec2(250.0, 45.0))
                         .fill(Color32::from_rgb(50, 120, 200));
                         
-                        if ui.add(calc_button).clicked() {
-                            self.calculate_quadrilateral();
+                        let calc_shortcut = ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Enter));
+                        let calc_response = ui
+                            .add(calc_button)
+                            .on_hover_text("Enter in einem Eingabefeld oder Strg+Enter");
+                        self.tutorial_rects.insert(TutorialTarget::CalculateButton, calc_response.rect);
+                        let calc_clicked = calc_response.clicked();
+                        if calc_clicked || calc_shortcut || submit {
+                            if self.config.rectangle_mode {
+                                self.calculate_rectangle();
+                            } else {
+                                self.calculate_quadrilateral();
+                            }
+                            self.input_focus.pending = self.input_errors.iter().position(Option::is_some);
+                        }
+                        if ui
+                            .checkbox(&mut self.config.keep_lines_on_recalc, "Linien bei Neuberechnung behalten")
+                            .on_hover_text("Linien bleiben an ihren Seiten und Eckpunkten verankert und wandern mit")
+                            .changed()
+                        {
+                            self.save_config();
+                        }
+                        if let Some(warning) = &self.recalc_warning {
+                            ui.colored_label(Color32::from_rgb(200, 120, 0), warning);
+                        }
+                        if !self.plausibility_warnings.is_empty() {
+                            // Im Warnbereich: gezeichnet wird trotzdem, die Abweichung bleibt sichtbar
+                            egui::Frame::none()
+                                .fill(Color32::from_rgb(255, 243, 176))
+                                .rounding(4.0)
+                                .inner_margin(6.0)
+                                .show(ui, |ui| {
+                                    ui.colored_label(Color32::from_rgb(120, 90, 0), "⚠ Maße widersprechen sich leicht – bestmögliche Lösung gezeichnet:");
+                                    for warning in &self.plausibility_warnings {
+                                        ui.colored_label(Color32::from_rgb(120, 90, 0), format!("• {}", warning.message(self.config.language, &fmt, &self.config.labels)));
+                                    }
+                                });
                         }
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("🆕 Neu")
+                                .on_hover_text("Eingaben, Ergebnisse und Linien dieses Blatts leeren")
+                                .clicked()
+                            {
+                                self.reset_sheet();
+                            }
+                            if self.reset_undo.is_some()
+                                && ui.button("↩ Rückgängig").on_hover_text("Strg+Z").clicked()
+                            {
+                                self.undo_reset();
+                            }
+                        });
 
                         // === BERECHNETE WERTE SECTION ===
                         if self.calculated {
@@ -162,193 +981,5338 @@ impl eframe::App for CadApp {
                             egui::CollapsingHeader::new("📊 Berechnete Werte")
                                 .default_open(true)
                                 .show(ui, |ui| {
+                                    if ui
+                                        .button("📥 Werte übernehmen")
+                                        .on_hover_text("Eingabefelder auf das berechnete Viereck zurücksetzen")
+                                        .clicked()
+                                    {
+                                        self.write_back_inputs();
+                                    }
+                                    self.show_construction_trace(ui, &labels);
                                     egui::ScrollArea::vertical()
                                         .max_height(250.0)
                                         .show(ui, |ui| {
                                             ui.label("✅ Geometrisch korrekte Werte:");
                                             ui.add_space(8.0);
                                             
-                                            let max_length_um = [
-                                                self.quad.side_ab_um.unwrap_or(0),
-                                                self.quad.side_bc_um.unwrap_or(0),
-                                                self.quad.side_cd_um.unwrap_or(0),
-                                                self.quad.side_da_um.unwrap_or(0),
-                                            ].iter().fold(0_i64, |a, &b| a.max(b));
-                                            
-                                            let use_cm = max_length_um < 10_000_000;
+                                            let unit = self.length_unit();
+                                            let input = self.last_input.clone().unwrap_or_default();
                                             
+                                            // Seiten und Winkel stammen nach der Berechnung alle aus den Eckpunkten, die Herkunft steht dahinter
                                             ui.group(|ui| {
                                                 ui.label(egui::RichText::new("Seitenlängen:").strong());
-                                                if let Some(mm) = self.quad.get_side_mm("AB") {
-                                                    let formatted = if use_cm {
-                                                        format!("{} cm", format_with_comma(mm / 10.0))
-                                                    } else {
-                                                        format!("{} m", format_with_comma(mm / 1000.0))
+                                                for (side, name) in SIDE_NAMES.iter().enumerate() {
+                                                    let Some(mm) = self.quad.get_side_mm(name) else {
+                                                        continue;
                                                     };
-                                                    ui.label(format!("  AB: {}", formatted));
+                                                    ui.horizontal(|ui| {
+                                                        let formatted = fmt.length(mm, unit);
+                                                        ui.label(format!("  {}: {}{}", labels.side(side), formatted, self.length_uncertainty_text(side)));
+                                                        value_source_label(ui, input.side_source(side, mm).describe(|measured| fmt.length(measured, unit)));
+                                                    });
                                                 }
-                                                if let Some(mm) = self.quad.get_side_mm("BC") {
-                                                    let formatted = if use_cm {
-                                                        format!("{} cm", format_with_comma(mm / 10.0))
-                                                    } else {
-                                                        format!("{} m", format_with_comma(mm / 1000.0))
+                                            });
+                                            
+                                            ui.add_space(8.0);
+                                            
+                                            ui.group(|ui| {
+                                                let display = self.config.angle_display;
+                                                ui.label(egui::RichText::new(format!("{}:", display.label())).strong());
+                                                let angles = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
+                                                for (vertex, angle) in angles.into_iter().enumerate() {
+                                                    let Some(angle) = angle else {
+                                                        continue;
                                                     };
-                                                    ui.label(format!("  BC: {}", formatted));
+                                                    for (suffix, degrees) in display.corner_values(angle) {
+                                                        ui.horizontal(|ui| {
+                                                            ui.label(format!("  {}{}: {}°{}", labels.vertex(vertex), suffix, fmt.number(degrees), self.angle_uncertainty_text(vertex)));
+                                                            value_source_label(ui, input.angle_source(vertex, angle).describe(|measured| fmt.angle(measured)));
+                                                        });
+                                                    }
                                                 }
-                                                if let Some(mm) = self.quad.get_side_mm("CD") {
-                                                    let formatted = if use_cm {
-                                                        format!("{} cm", format_with_comma(mm / 10.0))
-                                                    } else {
-                                                        format!("{} m", format_with_comma(mm / 1000.0))
-                                                    };
-                                                    ui.label(format!("  CD: {}", formatted));
+                                            });
+
+                                            ui.add_space(8.0);
+
+                                            ui.group(|ui| {
+                                                ui.label(egui::RichText::new("Außenmaße:").strong())
+                                                    .on_hover_text("Umschließendes Rechteck, z.B. für Türdurchgang oder Transport");
+                                                let mut changed = false;
+                                                ui.horizontal(|ui| {
+                                                    ui.label("  bezogen auf");
+                                                    egui::ComboBox::from_id_source("extent_side")
+                                                        .selected_text(format!("Seite {}", labels.side(self.config.extent_side)))
+                                                        .show_ui(ui, |ui| {
+                                                            for side in 0..4 {
+                                                                changed |= ui.selectable_value(&mut self.config.extent_side, side, format!("Seite {}", labels.side(side))).changed();
+                                                            }
+                                                        });
+                                                });
+                                                if changed {
+                                                    self.save_config();
                                                 }
-                                                if let Some(mm) = self.quad.get_side_mm("DA") {
-                                                    let formatted = if use_cm {
-                                                        format!("{} cm", format_with_comma(mm / 10.0))
-                                                    } else {
-                                                        format!("{} m", format_with_comma(mm / 1000.0))
-                                                    };
-                                                    ui.label(format!("  DA: {}", formatted));
+                                                if let Some((width, height)) = self.quad.extent_mm(self.config.extent_side) {
+                                                    ui.label(format!("  Breite: {}", fmt.length(width, unit)));
+                                                    ui.label(format!("  Höhe: {}", fmt.length(height, unit)));
                                                 }
                                             });
-                                            
+
                                             ui.add_space(8.0);
-                                            
+
                                             ui.group(|ui| {
-                                                ui.label(egui::RichText::new("Innenwinkel:").strong());
-                                                if let Some(a) = self.quad.angle_a {
-                                                    ui.label(format!("  A: {}°", format_angle_with_comma(a)));
-                                                }
-                                                if let Some(b) = self.quad.angle_b {
-                                                    ui.label(format!("  B: {}°", format_angle_with_comma(b)));
+                                                ui.label(egui::RichText::new("Schwerlinien:").strong())
+                                                    .on_hover_text("Verbindung gegenüberliegender Seitenmitten, Schnittpunkt = Mitte des Vierecks");
+                                                for index in 0..2 {
+                                                    ui.label(format!(
+                                                        "  M{} – M{}: {}",
+                                                        labels.side(index),
+                                                        labels.side(index + 2),
+                                                        fmt.length(self.quad.bimedian(index).length_mm, unit)
+                                                    ));
                                                 }
-                                                if let Some(c) = self.quad.angle_c {
-                                                    ui.label(format!("  C: {}°", format_angle_with_comma(c)));
+                                                let axes = self.quad.symmetry_axes();
+                                                if axes.is_empty() {
+                                                    ui.label("  Keine Symmetrieachse");
                                                 }
-                                                if let Some(d) = self.quad.angle_d {
-                                                    ui.label(format!("  D: {}°", format_angle_with_comma(d)));
+                                                for axis in axes {
+                                                    let through = match axis.through {
+                                                        AxisThrough::Diagonal(d) => format!("entlang {}", labels.diagonal(d)),
+                                                        AxisThrough::Bimedian(i) => format!("durch M{} und M{}", labels.side(i), labels.side(i + 2)),
+                                                    };
+                                                    ui.label(format!("  Symmetrieachse {}: {}", through, fmt.length(axis.length_mm, unit)))
+                                                        .on_hover_text(format!(
+                                                            "Größte Abweichung gespiegelter Eckpunkte: {}",
+                                                            fmt.length(axis.deviation_mm, unit)
+                                                        ));
                                                 }
                                             });
                                         });
                                 });
                         }
 
-                        // === AKTIONEN ===
-                        ui.add_space(20.0);
-                        ui.separator();
-                        
-                        if ui.button("📸 Screenshot erstellen").clicked() {
-                            self.take_screenshot();
+                        // === VERLAUF ===
+                        if !self.history.is_empty() {
+                            ui.add_space(10.0);
+                            self.show_history(ui, &labels);
                         }
 
-                        ui.add_space(10.0);
-                        
-                        if self.checking_update {
-                            ui.add(egui::Spinner::new());
-                            ui.label("Prüfe Updates...");
-                        } else {
-                            if ui.button("🔄 Nach Updates suchen").clicked() {
-                                self.check_for_updates();
-                            }
+                        // === MESSPROTOKOLL ===
+                        if !self.journal.entries.is_empty() {
+                            ui.add_space(10.0);
+                            self.show_journal(ui);
                         }
 
-                        ui.add_space(10.0);
-                        if ui.button("❓ Hilfe").clicked() {
-                            self.show_help = !self.show_help;
+                        // === MESSSTÄNDE (VORHER/NACHHER) ===
+                        if self.calculated || !self.snapshots.is_empty() {
+                            ui.add_space(10.0);
+                            self.show_snapshots(ui);
                         }
-                        
-                        ui.add_space(20.0);
-                        ui.separator();
-                        
-                        ui.add_space(10.0);
-                        let close_button = egui::Button::new(
-                            egui::RichText::new("❌ App schließen")
-                                .size(24.0)
-                                .color(Color32::WHITE)
-                        )
-                        .fill(Color32::from_rgb(180, 40, 40))
-                        .min_size(egui::vec2(200.0, 50.0));
-                        
-                        if ui.add(close_button).clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+
+                        // === VERGLEICH ===
+                        if self.calculated && self.comparison.is_some() {
+                            ui.add_space(10.0);
+                            self.show_comparison(ui, &labels);
                         }
-                    });
-            });
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.calculated {
-                self.draw_quadrilateral(ui);
-            } else {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(250.0);
-                    ui.heading("👈 Bitte Werte eingeben und 'Berechnen' klicken");
-                });
-            }
-        });
+                        // === SEHNEN-/TANGENTENVIERECK ===
+                        if self.calculated {
+                            self.show_special_circles(ui);
+                        }
 
-        // Fehler-Dialog
-        if self.error_message.is_some() {
-            let error_text = self.error_message.clone().unwrap();
-            
-            egui::Window::new("⚠️ Fehler bei der Berechnung")
-                .collapsible(false)
-                .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                .show(ctx, |ui| {
-                    ui.set_min_width(400.0);
-                    
-                    egui::ScrollArea::vertical()
-                        .max_height(400.0)
-                        .show(ui, |ui| {
-                            ui.colored_label(Color32::from_rgb(200, 50, 50), &error_text);
-                        });
-                    
-                    ui.add_space(15.0);
-                    ui.separator();
-                    ui.add_space(10.0);
-                    
-                    if ui.button("OK - Eingaben überprüfen").clicked() {
-                        self.error_message = None;
-                    }
-                });
-        }
+                        // === DREIECKSZERLEGUNG ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_triangulation(ui, &labels);
+                        }
 
-        // Hilfe-Dialog
-        if self.show_help {
-            egui::Window::new("❓ Hilfe")
-                .collapsible(false)
+                        // === ABSTECKPUNKTE ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_stakeout_points(ui, &labels);
+                            ui.add_space(10.0);
+                            self.show_geojson_export(ui, &labels);
+                        }
+
+                        // === PLATTENBEDARF ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_tile_calculator(ui);
+                        }
+
+                        // === PUNKTRASTER ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_point_grid(ui, &labels);
+                        }
+
+                        // === MATERIALKOSTEN ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_cost_estimate(ui);
+                            self.show_quote_quantities(ui);
+                        }
+
+                        // === RAUM (3D) ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_room_measures(ui, &labels);
+                        }
+
+                        // === KONTROLLMASS ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            self.show_diagonal_check(ui, &labels);
+                            self.show_inverse_solver(ui, &labels);
+                        }
+
+                        // === WERKZEUG ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            egui::CollapsingHeader::new("✏️ Werkzeug")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.radio_value(&mut self.tool, DrawTool::Line, "📏 Linie");
+                                        ui.radio_value(&mut self.tool, DrawTool::Circle, "⭕ Kreis");
+                                        ui.radio_value(&mut self.tool, DrawTool::Arc, "◠ Bogen");
+                                        ui.radio_value(&mut self.tool, DrawTool::Measure, "📐 Winkel");
+                                    });
+                                    if self.tool == DrawTool::Arc {
+                                        ui.horizontal(|ui| {
+                                            ui.label("Bogenwinkel:");
+                                            ui.add(egui::DragValue::new(&mut self.arc_sweep_deg).range(1.0..=359.0).suffix("°"));
+                                        });
+                                    }
+                                    if self.tool == DrawTool::Measure {
+                                        self.show_angle_measure(ui, &labels);
+                                    }
+                                    if self.tool == DrawTool::Line {
+                                        self.show_keyboard_line(ui);
+                                    }
+                                });
+                        }
+
+                        // === KONSTRUKTION ===
+                        if self.calculated {
+                            ui.add_space(10.0);
+                            egui::CollapsingHeader::new("🔧 Konstruktion")
+                                .default_open(false)
+                                .show(ui, |ui| {
+                                    ui.label("Winkelhalbierende von:");
+                                    ui.horizontal(|ui| {
+                                        for vertex in 0..4 {
+                                            if ui.button(labels.vertex(vertex)).clicked() {
+                                                self.construct_bisector(vertex);
+                                            }
+                                        }
+                                    });
+                                    if let Some(info) = &self.construction_info {
+                                        ui.add_space(5.0);
+                                        ui.label(egui::RichText::new(info).small());
+                                    }
+                                    ui.add_space(5.0);
+                                    self.show_construction_lines(ui, &labels);
+                                });
+                        }
+
+                        // === LINIENLISTE ===
+                        if self.calculated && !self.custom_lines.is_empty() {
+                            ui.add_space(10.0);
+                            self.show_line_list(ui);
+                            self.show_line_groups(ui);
+                        }
+
+                        // === KONSTRUKTIONSVERLAUF ===
+                        if self.calculated && self.last_input.is_some() {
+                            ui.add_space(10.0);
+                            self.show_construction_history(ui, &labels);
+                        }
+
+                        // === LINIEN-EIGENSCHAFTEN ===
+                        let mut cut_edge = None;
+                        let mut offset_side = None;
+                        let earlier_lines: Vec<String> = match self.selected_line {
+                            Some(idx) => self.custom_lines.iter().take(idx).map(|l| l.name.clone()).collect(),
+                            None => Vec::new(),
+                        };
+                        let unit = self.length_unit();
+                        let partition = self.selected_line.and_then(|idx| self.quad.split_by_line(&self.custom_lines[idx]));
+                        if let Some(line) = self.selected_line.and_then(|idx| self.custom_lines.get_mut(idx)) {
+                            ui.add_space(10.0);
+                            egui::CollapsingHeader::new("🎨 Linien-Eigenschaften")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Name:");
+                                        ui.add(egui::TextEdit::singleline(&mut line.name).desired_width(180.0));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Strichart:");
+                                        egui::ComboBox::from_id_source("line_style")
+                                            .selected_text(line.style.label())
+                                            .show_ui(ui, |ui| {
+                                                for style in [LineStyle::Solid, LineStyle::Dashed, LineStyle::Dotted] {
+                                                    ui.selectable_value(&mut line.style, style, style.label());
+                                                }
+                                            });
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Stärke:");
+                                        ui.add(egui::Slider::new(&mut line.width, 1.0..=10.0).suffix(" px"));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Farbe:");
+                                        egui::color_picker::color_edit_button_srgb(ui, &mut line.color);
+                                    });
+                                    if let Some(parts) = &partition {
+                                        ui.label("Teilflächen:");
+                                        for (i, part) in parts.iter().enumerate() {
+                                            ui.label(format!(
+                                                "  {}) {} m², Umfang {}",
+                                                i + 1,
+                                                fmt.number(part.area_mm2 / 1_000_000.0),
+                                                fmt.length(part.perimeter_mm, unit)
+                                            ));
+                                        }
+                                    }
+                                    ui.checkbox(&mut line.locked, "🔒 Gesperrt");
+                                    ui.horizontal(|ui| {
+                                        ui.label("Parallel kopieren:");
+                                        ui.add(egui::TextEdit::singleline(&mut self.input_line_offset).desired_width(60.0));
+                                        ui.label("mm");
+                                        if icon_button(ui, egui::Button::new("◀").small(), "Kopie links der Laufrichtung").clicked() {
+                                            offset_side = Some(-1.0);
+                                        }
+                                        if icon_button(ui, egui::Button::new("▶").small(), "Kopie rechts der Laufrichtung").clicked() {
+                                            offset_side = Some(1.0);
+                                        }
+                                    });
+                                    ui.add_space(5.0);
+                                    ui.label("✂ Trimmen/Verlängern bis:");
+                                    ui.horizontal_wrapped(|ui| {
+                                        for side in 0..4 {
+                                            if ui.button(labels.side(side)).clicked() {
+                                                cut_edge = Some(CutEdge::Side(side));
+                                            }
+                                        }
+                                        for (other, name) in earlier_lines.iter().enumerate() {
+                                            if ui.button(name).clicked() {
+                                                cut_edge = Some(CutEdge::Line(other));
+                                            }
+                                        }
+                                    });
+                                });
+                        }
+                        if let (Some(idx), Some(edge)) = (self.selected_line, cut_edge) {
+                            self.cut_line(idx, edge);
+                        }
+                        if let (Some(idx), Some(sign)) = (self.selected_line, offset_side) {
+                            self.duplicate_line_offset(idx, sign);
+                        }
+
+                        // === KREIS-EIGENSCHAFTEN ===
+                        if self.selected_circle.is_some() {
+                            ui.add_space(10.0);
+                            self.show_circle_properties(ui);
+                        }
+
+                        // === ANSICHT ===
+                        ui.add_space(10.0);
+                        egui::CollapsingHeader::new("🎨 Flächenfüllung")
+                            .default_open(false)
+                            .show(ui, |ui| {
+                                let mut changed = false;
+                                ui.horizontal(|ui| {
+                                    ui.label("Muster:");
+                                    egui::ComboBox::from_id_source("fill_pattern")
+                                        .selected_text(self.config.fill.pattern.label())
+                                        .show_ui(ui, |ui| {
+                                            for pattern in [FillPattern::None, FillPattern::Solid, FillPattern::Hatch, FillPattern::CrossHatch] {
+                                                changed |= ui.selectable_value(&mut self.config.fill.pattern, pattern, pattern.label()).changed();
+                                            }
+                                        });
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Farbe:");
+                                    changed |= egui::color_picker::color_edit_button_srgb(ui, &mut self.config.fill.color).changed();
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Deckkraft:");
+                                    changed |= ui.add(egui::Slider::new(&mut self.config.fill.opacity, 10..=255)).changed();
+                                });
+                                if changed {
+                                    self.save_config();
+                                }
+                            });
+
+                        egui::CollapsingHeader::new("🏷 Beschriftung")
+                            .default_open(false)
+                            .show(ui, |ui| {
+                                let mut changed = false;
+                                egui::Grid::new("vertex_labels").num_columns(2).show(ui, |ui| {
+                                    for (i, name) in self.config.labels.names.iter_mut().enumerate() {
+                                        ui.label(format!("Eckpunkt {}:", i + 1));
+                                        changed |= ui.add(egui::TextEdit::singleline(name).desired_width(120.0)).changed();
+                                        ui.end_row();
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    changed |= ui.radio_value(&mut self.config.labels.clockwise, true, "Im Uhrzeigersinn").changed();
+                                    changed |= ui.radio_value(&mut self.config.labels.clockwise, false, "Gegen den Uhrzeigersinn").changed();
+                                });
+                                changed |= ui
+                                    .checkbox(&mut self.config.show_stations, "Stationierung entlang des Umfangs")
+                                    .on_hover_text("Fortlaufende Maße ab A, auch für alle Linienenden auf den Seiten")
+                                    .changed();
+                                changed |= ui
+                                    .checkbox(&mut self.config.show_centroid, "Schwerpunkt und Seitenmitten")
+                                    .on_hover_text("Mit Koordinaten im System der Absteckpunkte, auch im CSV-Export")
+                                    .changed();
+                                changed |= ui
+                                    .checkbox(&mut self.config.show_bimedians, "Schwerlinien und Symmetrieachsen")
+                                    .on_hover_text("Verbindungen gegenüberliegender Seitenmitten, z.B. zum Mittig-Ausrichten")
+                                    .changed();
+                                if changed {
+                                    self.save_config();
+                                }
+                            });
+
+                        egui::CollapsingHeader::new("🧭 Ausrichtung")
+                            .default_open(false)
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("Bezugsseite unten:");
+                                    let selected = match self.config.reference_side {
+                                        Some(side) => labels.side(side),
+                                        None => "Wie berechnet".to_string(),
+                                    };
+                                    let mut changed = false;
+                                    egui::ComboBox::from_id_source("reference_side")
+                                        .selected_text(selected)
+                                        .show_ui(ui, |ui| {
+                                            changed |= ui.selectable_value(&mut self.config.reference_side, None, "Wie berechnet").changed();
+                                            for side in 0..4 {
+                                                changed |= ui.selectable_value(&mut self.config.reference_side, Some(side), labels.side(side)).changed();
+                                            }
+                                        });
+                                    if changed {
+                                        self.save_config();
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Diese Seite liegt in Zeichnung, Bildexport und Protokoll waagerecht unten, egal wie konstruiert wurde");
+                                // Mit Bezugsseite ergibt sich die Drehung daraus
+                                ui.add_enabled_ui(self.config.reference_side.is_none(), |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Drehung:");
+                                        ui.add(
+                                            egui::DragValue::new(&mut self.orientation.rotation_deg)
+                                                .speed(0.5)
+                                                .suffix("°")
+                                                .range(-360.0..=360.0),
+                                        );
+                                        if ui.button("⟲ 90°").clicked() {
+                                            self.orientation.rotation_deg = (self.orientation.rotation_deg - 90.0).rem_euclid(360.0);
+                                        }
+                                        if ui.button("⟳ 90°").clicked() {
+                                            self.orientation.rotation_deg = (self.orientation.rotation_deg + 90.0).rem_euclid(360.0);
+                                        }
+                                    });
+                                    if self.calculated && ui.button("AB waagerecht ausrichten").clicked() {
+                                        let a = &self.quad.vertices[0];
+                                        let b = &self.quad.vertices[1];
+                                        self.orientation.rotation_deg = -(b.y - a.y).atan2(b.x - a.x).to_degrees();
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(&mut self.orientation.mirror_x, "↔ Spiegeln");
+                                    ui.checkbox(&mut self.orientation.mirror_y, "↕ Spiegeln");
+                                });
+                                if !self.orientation.is_identity() && ui.button("Ausrichtung zurücksetzen").clicked() {
+                                    self.orientation = ViewOrientation::default();
+                                }
+                            });
+
+                        egui::CollapsingHeader::new("🔲 Raster")
+                            .default_open(false)
+                            .show(ui, |ui| {
+                                let mut changed = ui.checkbox(&mut self.config.show_grid, "Raster anzeigen").changed();
+                                ui.horizontal(|ui| {
+                                    ui.label("Abstand:");
+                                    let spacings = [(10.0, "1 cm"), (50.0, "5 cm"), (100.0, "10 cm"), (500.0, "50 cm"), (1000.0, "1 m")];
+                                    let selected = spacings
+                                        .iter()
+                                        .find(|(mm, _)| *mm == self.config.grid_spacing_mm)
+                                        .map(|(_, label)| *label)
+                                        .unwrap_or("–");
+                                    egui::ComboBox::from_id_source("grid_spacing")
+                                        .selected_text(selected)
+                                        .show_ui(ui, |ui| {
+                                            for (mm, label) in spacings {
+                                                changed |= ui.selectable_value(&mut self.config.grid_spacing_mm, mm, label).changed();
+                                            }
+                                        });
+                                });
+                                if changed {
+                                    self.save_config();
+                                }
+                            });
+
+                        // === AKTIONEN ===
+                        ui.add_space(20.0);
+                        ui.separator();
+                        
+                        if self.calculated {
+                            self.show_report_export(ui);
+                            self.show_image_export(ui);
+                        }
+                        
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui.button("📸 Screenshot erstellen").clicked() {
+                            self.take_screenshot();
+                        }
+                        
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if self.calculated {
+                            if ui
+                                .button("📋 Bild kopieren")
+                                .on_hover_text("Zeichnung als Bild in die Zwischenablage, z.B. zum Einfügen in E-Mail oder Word")
+                                .clicked()
+                            {
+                                self.copy_image_pending = true;
+                                self.clipboard_message = None;
+                                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Screenshot);
+                            }
+                            match &self.clipboard_message {
+                                Some(Ok(message)) => {
+                                    ui.colored_label(Color32::from_rgb(0, 130, 0), message);
+                                }
+                                Some(Err(e)) => {
+                                    ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                                }
+                                None => {}
+                            }
+                        }
+                        
+                        if self.calculated && ui.button("🔍 Ansicht zurücksetzen").clicked() {
+                            self.zoom = 1.0;
+                            self.pan = Vec2::ZERO;
+                        }
+
+                        ui.add_space(10.0);
+                        
+                        let update_busy = !matches!(
+                            *self.update_state.lock().unwrap(),
+                            UpdateState::Idle
+                                | UpdateState::Result(_)
+                                | UpdateState::Ready(_)
+                                | UpdateState::RolledBack(_)
+                                | UpdateState::Failed(_)
+                        );
+                        if update_busy {
+                            ui.horizontal(|ui| {
+                                ui.add(egui::Spinner::new());
+                                ui.label("Update wird geladen...");
+                            });
+                        } else if let Some(version) = &self.pending_update {
+                            ui.label(format!("⬇ Update {} bereit", version))
+                                .on_hover_text("Wird beim Beenden der App installiert");
+                        } else if ui.button("🔄 Nach Updates suchen").clicked() {
+                            self.check_for_updates();
+                            self.silent_update_check = false;
+                        }
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Kanal:");
+                            let previous_channel = self.config.update_channel;
+                            egui::ComboBox::from_id_source("update_channel")
+                                .selected_text(self.config.update_channel.label())
+                                .show_ui(ui, |ui| {
+                                    for channel in [UpdateChannel::Stable, UpdateChannel::Beta] {
+                                        ui.selectable_value(&mut self.config.update_channel, channel, channel.label());
+                                    }
+                                });
+                            if self.config.update_channel != previous_channel {
+                                self.save_config();
+                            }
+                        });
+                        
+                        if ui.checkbox(&mut self.config.check_updates_on_startup, "Beim Start nach Updates suchen").changed() {
+                            self.save_config();
+                        }
+
+                        ui.add_space(10.0);
+                        if ui.button("⚙ Einstellungen").clicked() {
+                            self.show_settings = !self.show_settings;
+                        }
+                        if ui.button("🗂 Projekt").clicked() {
+                            self.show_project = !self.show_project;
+                        }
+                        if ui.button("📂 Stapelberechnung (CSV)").clicked() {
+                            self.show_batch = !self.show_batch;
+                        }
+                        if ui.button("📜 Skript").clicked() {
+                            self.show_script = !self.show_script;
+                        }
+                        if ui.button("🩺 Protokoll").on_hover_text("Fehlerprotokoll anzeigen und exportieren").clicked() {
+                            self.show_log = !self.show_log;
+                            self.log_text = None;
+                        }
+                        if ui.button("❓ Hilfe").clicked() {
+                            self.show_help = !self.show_help;
+                        }
+                        
+                        ui.add_space(20.0);
+                        ui.separator();
+                        
+                        ui.add_space(10.0);
+                        let close_button = egui::Button::new(
+                            egui::RichText::new("❌ App schließen")
+                                .size(24.0)
+                                .color(Color32::WHITE)
+                        )
+                        .fill(Color32::from_rgb(180, 40, 40))
+                        .min_size(egui::vec2(200.0, 50.0));
+                        
+                        if ui.add(close_button).clicked() {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+                    });
+            });
+
+        self.tutorial_rects.insert(TutorialTarget::InputPanel, input_panel.response.rect);
+
+        egui::TopBottomPanel::top("sheet_tabs").show(ctx, |ui| {
+            self.show_sheet_tabs(ui);
+        });
+
+        let canvas = egui::CentralPanel::default().show(ctx, |ui| {
+            if self.calculated {
+                self.draw_quadrilateral(ui);
+            } else {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(250.0);
+                    ui.heading("👈 Bitte Werte eingeben und 'Berechnen' klicken");
+                });
+            }
+        });
+        self.canvas_rect = canvas.response.rect;
+        self.tutorial_rects.insert(TutorialTarget::Canvas, self.canvas_rect);
+
+        // Angefordertes Bildschirmfoto auf die Zeichenfläche zuschneiden und kopieren
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.copy_image_pending {
+            let screenshot = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(image) = screenshot {
+                self.copy_image_pending = false;
+                let canvas = image.region(&self.canvas_rect, Some(ctx.pixels_per_point()));
+                self.clipboard_message = Some(
+                    clipboard::copy_image(&canvas).map(|_| "✅ Zeichnung in die Zwischenablage kopiert".to_string()),
+                );
+            }
+        }
+
+        if self.confirm_clear_lines {
+            self.show_clear_lines_dialog(ctx);
+        }
+        if self.confirm_remove_sheet {
+            self.show_remove_sheet_dialog(ctx);
+        }
+
+        // Fehler-Dialog
+        if self.error_message.is_some() {
+            let error_text = self.error_message.clone().unwrap();
+            
+            egui::Window::new("⚠️ Fehler bei der Berechnung")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.set_min_width(400.0);
+                    
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            ui.colored_label(Color32::from_rgb(200, 50, 50), &error_text);
+                        });
+                    
+                    ui.add_space(15.0);
+                    ui.separator();
+                    ui.add_space(10.0);
+                    
+                    if ui.button("OK - Eingaben überprüfen").clicked() {
+                        self.error_message = None;
+                    }
+                });
+        }
+
+        // Hilfe-Dialog
+        if self.show_help {
+            egui::Window::new("❓ Hilfe")
+                .collapsible(false)
                 .show(ctx, |ui| {
                     ui.label("📏 Linien zeichnen:");
                     ui.label("  Klicken & Ziehen von Seite zu Seite");
+                    ui.label("  Doppelklick auf Seite/Linie: Start mit exaktem Abstand (z.B. 1,25 m), dann Endpunkt anklicken");
+                    ui.label("  Abseits von Seiten und Linien entsteht ein freier Punkt, auch außerhalb des Vierecks");
+                    ui.add_space(5.0);
+                    
+                    ui.label("⌨ Ohne Maus:");
+                    ui.label("  Tab/Shift+Tab wechselt zwischen Feldern, Enter berechnet");
+                    ui.label("  Linie per Eingabe (Werkzeug): Start/Ende als Abstand auf Seite oder Linie");
+                    ui.label("  Linie in der Liste wählen, Start/Ende wählen, dann Pfeiltasten");
+                    ui.add_space(5.0);
+                    
+                    ui.label("📍 Eckpunkte:");
+                    ui.label("  Linien rasten an A–D ein (z.B. Diagonalen)");
+                    ui.add_space(5.0);
+                    
+                    ui.label("⁘ Punktraster:");
+                    ui.label("  Raster mit festen Abständen im Viereck, z.B. für Dübel oder Stützen, Export über 📍 Absteckpunkte");
+                    ui.add_space(5.0);
+                    
+                    ui.label("⭕ Kreise/Bögen:");
+                    ui.label("  Werkzeug wählen, vom Mittelpunkt aus aufziehen");
+                    ui.add_space(5.0);
+                    
+                    ui.label("📐 Winkel messen:");
+                    ui.label("  Werkzeug wählen, zwei Seiten/Linien anklicken, 📌 heftet das Maß an");
                     ui.add_space(5.0);
                     
                     ui.label("✏️ Linien verschieben:");
                     ui.label("  Endpunkt anklicken & ziehen");
+                    ui.label("  Ganze Linie parallel: Alt + Linienmitte ziehen");
+                    ui.label("  Magenta Hilfslinien: Ende fluchtet, hat gleichen Abstand oder eine runde Länge");
+                    ui.label("  Feinjustieren: Endpunkt anklicken, Pfeiltasten (Shift = ×10)");
+                    ui.label("  🔒 Gesperrte Linien bleiben fest");
+                    ui.label("  Mehrfachauswahl: Strg+Klick oder Rahmen mit Shift+Ziehen, dann 🔗 Gruppieren");
+                    ui.label("  Auswahl/Gruppe ohne gewählten Endpunkt mit Pfeiltasten verschieben");
+                    ui.label("  Entf löscht die Auswahl, Strg+Z holt gelöschte Linien zurück");
                     ui.add_space(5.0);
                     
                     ui.label("🔢 Eingabe:");
                     ui.label("  4 Seiten + 1 Winkel");
                     ui.label("  oder 3 Seiten + 2 Winkel");
+                    ui.label("  oder ▭ Rechteck-Schnellmodus: nur Breite × Höhe");
+                    ui.label("  Tab/Shift+Tab springt von Maß zu Maß, Enter berechnet");
+                    ui.label("  Maße auch mit Einheit, z.B. 2,5 m oder 250 cm");
+                    ui.label("  ⭐ Vorlagen speichern wiederkehrende Maße unter einem Namen");
+                    ui.label("  🪜 Herleitung unter 📊 Berechnete Werte zeigt den Rechenweg");
+                    ui.label("  Schwerlinien und Symmetrieachsen einblenden unter 🏷 Beschriftung");
+                    ui.label("  🔁 Rückwärtsrechnung: Winkel A für Soll-Diagonale oder -Fläche");
+                    ui.label("  Kleine Widersprüche (Warnbereich in ⚙) werden gelb gemeldet statt abzubrechen");
+                    ui.label("  🆕 Neu leert das Blatt, ↩ bzw. Strg+Z holt es zurück");
+                    ui.add_space(5.0);
+                    
+                    ui.label("🌳 Konstruktionsverlauf:");
+                    ui.label("  Grundmaße und Ankerabstände dort ändern, abhängige Linien wandern mit");
+                    ui.add_space(5.0);
+                    
+                    ui.label("📜 Skript:");
+                    ui.label("  Vierecke und Linien per Rhai-Skript erzeugen, Maße abfragen");
+                    ui.add_space(5.0);
+                    
+                    ui.label("🗂 Arbeitsblätter:");
+                    ui.label("  ➕ über der Zeichnung legt ein Blatt je Raum an");
+                    ui.label("  Alle Blätter werden zusammen im Projekt gespeichert");
                     
                     ui.add_space(10.0);
-                    if ui.button("Schließen").clicked() {
-                        self.show_help = false;
-                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("🎓 Einführung starten").clicked() {
+                            self.tutorial_step = Some(0);
+                            self.show_help = false;
+                        }
+                        if ui.button("Schließen").clicked() {
+                            self.show_help = false;
+                        }
+                    });
                 });
         }
 
+        // Einstellungen-Dialog
+        if self.show_settings {
+            self.show_settings_window(ctx, frame);
+        }
+
+        // Stapelberechnung
+        if self.show_batch {
+            self.show_batch_window(ctx);
+        }
+
+        // Skriptkonsole
+        if self.show_script {
+            self.show_script_window(ctx);
+        }
+
+        if self.show_log {
+            self.show_log_window(ctx);
+        }
+
+        // Projektangaben und Projektdatei
+        if self.show_project {
+            self.show_project_window(ctx);
+        }
+
+        if self.show_templates {
+            self.show_templates_window(ctx);
+        }
+
+        // 3D-Vorschau
+        if self.show_prism && self.calculated {
+            self.show_prism_window(ctx);
+        }
+
+        // Einführung
+        if self.tutorial_step.is_some() {
+            self.show_tutorial(ctx);
+        }
+
         // Update-Dialog
-        if self.show_update_dialog {
-            egui::Window::new("🔄 Update verfügbar")
-                .collapsible(false)
-                .resizable(false)
-                .show(ctx, |ui| {
-                    let update_info_guard = self.update_info.lock().unwrap();
-                    let info_clone = update_info_guard.clone();
-                    drop(update_info_guard);
-                    
-                    if let Some(ref info) = info_clone {
+        let update_state = self.update_state.lock().unwrap().clone();
+        if let UpdateState::Ready(version) = &update_state {
+            self.pending_update = Some(version.clone());
+        }
+        let mut suppress_dialog = false;
+        if self.silent_update_check {
+            // Automatische Prüfung: Nur neue, nicht übersprungene Versionen anzeigen
+            match &update_state {
+                UpdateState::Checking => {
+                    suppress_dialog = true;
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                UpdateState::Result(info)
+                    if !info.available || self.config.is_version_skipped(&info.latest_version) =>
+                {
+                    suppress_dialog = true;
+                }
+                UpdateState::Failed(_) => suppress_dialog = true,
+                _ => {}
+            }
+            if suppress_dialog && !matches!(update_state, UpdateState::Checking) {
+                updater::set_state(&self.update_state, UpdateState::Idle);
+                self.silent_update_check = false;
+            }
+        }
+        if !suppress_dialog && !matches!(update_state, UpdateState::Idle) {
+            // Ab hier verhält sich der Dialog wie bei einer manuellen Prüfung
+            self.silent_update_check = false;
+            self.show_update_window(ctx, update_state);
+        }
+        self.handle_close_request(ctx);
+    }
+}
+
+impl CadApp {
+    /// Konstruktionsverlauf als Baum: Grundmaße und Ankerabstände sind editierbar,
+    /// jede Änderung spielt die abhängigen Linien erneut ab
+    fn show_construction_history(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let Some(mut input) = self.last_input.clone() else {
+            return;
+        };
+        let mut base_changed = false;
+        let mut base_committed = false;
+        let mut anchor_edit = None;
+        let mut select = None;
+
+        egui::CollapsingHeader::new("🌳 Konstruktionsverlauf")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Grundviereck").strong());
+                egui::Grid::new("history_base").num_columns(2).show(ui, |ui| {
+                    for (i, side) in input.sides_mm.iter_mut().enumerate() {
+                        if let Some(mm) = side {
+                            ui.label(format!("  Seite {}", labels.side(i)));
+                            let response = ui.add(egui::DragValue::new(mm).speed(1.0).range(1.0..=1_000_000.0).suffix(" mm"));
+                            base_changed |= response.changed();
+                            base_committed |= response.lost_focus() || response.drag_stopped();
+                            ui.end_row();
+                        }
+                    }
+                    for (i, angle) in input.angles.iter_mut().enumerate() {
+                        if let Some(deg) = angle {
+                            ui.label(format!("  Winkel {}", labels.vertex(i)));
+                            let response = ui.add(egui::DragValue::new(deg).speed(0.1).range(0.1..=359.9).suffix("°"));
+                            base_changed |= response.changed();
+                            base_committed |= response.lost_focus() || response.drag_stopped();
+                            ui.end_row();
+                        }
+                    }
+                });
+                if let Some(e) = &self.replay_error {
+                    ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                }
+
+                for node in construction_tree(&self.custom_lines) {
+                    let line = &self.custom_lines[node.line];
+                    ui.horizontal(|ui| {
+                        ui.add_space(node.depth as f32 * 14.0);
+                        let name = format!("{}{}", line.name, if line.locked { " 🔒" } else { "" });
+                        if ui.selectable_label(self.selected_line == Some(node.line), name).clicked() {
+                            select = Some(node.line);
+                        }
+                    });
+                    for (at_start, anchor) in [(true, line.start_anchor), (false, line.end_anchor)] {
+                        ui.horizontal(|ui| {
+                            ui.add_space(node.depth as f32 * 14.0 + 20.0);
+                            ui.label(if at_start { "Start" } else { "Ende" });
+                            let reference = match anchor {
+                                LineAnchor::Side { side, .. } => format!("auf {} ab {}", labels.side(side), labels.vertex(side)),
+                                LineAnchor::Line { line, .. } => format!(
+                                    "auf {} ab Start",
+                                    self.custom_lines.get(line).map_or("", |l| l.name.as_str())
+                                ),
+                                LineAnchor::Vertex(vertex) => format!("an Ecke {}", labels.vertex(vertex)),
+                                LineAnchor::Centroid => "im Schwerpunkt".to_string(),
+                                LineAnchor::Free { side, .. } => format!("frei, bezogen auf {}", labels.side(side)),
+                            };
+                            if !matches!(anchor, LineAnchor::Side { .. } | LineAnchor::Line { .. }) {
+                                ui.label(reference);
+                                return;
+                            }
+                            let (a, b) = anchor_segment(&self.quad, &self.custom_lines, &anchor);
+                            let length_mm = distance_f64(&a, &b) / 1000.0;
+                            let mut distance_mm = anchor.ratio() * length_mm;
+                            let response = ui.add_enabled(
+                                !line.locked && length_mm > 0.0,
+                                egui::DragValue::new(&mut distance_mm).speed(1.0).range(0.0..=length_mm).suffix(" mm"),
+                            );
+                            ui.label(reference);
+                            if response.changed() {
+                                anchor_edit = Some((node.line, at_start, anchor.with_ratio(distance_mm / length_mm)));
+                            }
+                        });
+                    }
+                }
+            });
+
+        if let Some(idx) = select {
+            self.selected_line = if self.selected_line == Some(idx) { None } else { Some(idx) };
+            self.selected_endpoint = None;
+        }
+        if let Some((idx, at_start, anchor)) = anchor_edit {
+            let line = &mut self.custom_lines[idx];
+            if at_start {
+                line.start_anchor = anchor;
+            } else {
+                line.end_anchor = anchor;
+            }
+            update_all_lines(&self.quad, &mut self.custom_lines);
+        }
+        if base_changed {
+            self.replay_construction(input);
+        }
+        // Erst den fertig eingestellten Wert protokollieren, nicht jeden Zwischenschritt beim Ziehen
+        if base_committed && self.replay_unlogged {
+            self.replay_unlogged = false;
+            if let Some(input) = self.last_input.clone() {
+                let quad = self.quad.clone();
+                self.log_calculation(&input, &quad);
+            }
+        }
+    }
+
+    /// Berechnet das Viereck mit geänderten Grundmaßen neu, alle Linien bleiben an ihren Ankern
+    /// Anders als "Berechnen" ohne Verlaufseintrag, damit Ziehen an einem Wert die Liste nicht füllt;
+    /// ins Messprotokoll kommt das Ergebnis, sobald die Eingabe abgeschlossen ist
+    fn replay_construction(&mut self, input: QuadInput) {
+        match solve_with_tolerance(&input, &self.config.plausibility) {
+            Ok(QuadSolution { quad, warnings, .. }) => {
+                self.quad = quad;
+                self.plausibility_warnings = warnings;
+                self.last_input = Some(input);
+                self.replay_error = None;
+                self.replay_unlogged = true;
+                self.reset_undo = None;
+                self.tile_estimate = None;
+                if !self.quad.diagonal_inside(self.triangle_diagonal) {
+                    self.triangle_diagonal = 1 - self.triangle_diagonal;
+                }
+                self.write_back_inputs();
+                update_all_lines(&self.quad, &mut self.custom_lines);
+                self.update_uncertainty();
+            }
+            Err(e) => self.replay_error = Some(e.message(self.config.language, &self.number_format(), &self.config.labels)),
+        }
+    }
+
+    /// Liste aller eingezeichneten Linien mit Auswahl, Duplizieren und Löschen
+    fn show_line_list(&mut self, ui: &mut egui::Ui) {
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        let mut duplicate_idx = None;
+        let mut delete_idx = None;
+        let mut toggle_lock_idx = None;
+        let mut toggle_hidden_idx = None;
+        let mut toggle_multi_idx = None;
+        
+        egui::CollapsingHeader::new(format!("📋 Linien ({})", self.custom_lines.len()))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.custom_lines.is_empty(), egui::Button::new("🗑 Alle Linien entfernen")).clicked() {
+                        self.confirm_clear_lines = true;
+                    }
+                    if self.deleted_lines.is_some() && ui.button("↩ Rückgängig").on_hover_text("Strg+Z").clicked() {
+                        self.undo_delete_lines();
+                    }
+                });
+                for (idx, line) in self.custom_lines.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let is_selected = self.selected_line == Some(idx);
+                        let highlighted = is_selected || self.multi_selection.contains(&idx);
+                        let label = ui
+                            .selectable_label(highlighted, &line.name)
+                            .on_hover_text("Strg+Klick: zur Mehrfachauswahl hinzufügen");
+                        if label.clicked() && ui.input(|i| i.modifiers.command) {
+                            toggle_multi_idx = Some(idx);
+                        } else if label.clicked() {
+                            self.selected_line = if is_selected { None } else { Some(idx) };
+                            self.selected_endpoint = None;
+                        }
+                        if let Some(group) = &line.group {
+                            ui.label(egui::RichText::new(format!("📁 {}", group)).small());
+                        }
+                        let (hidden_icon, hidden_hint) = if line.hidden { ("🚫", "Einblenden") } else { ("👁", "Ausblenden") };
+                        if icon_button(ui, egui::Button::new(hidden_icon).small(), hidden_hint).clicked() {
+                            toggle_hidden_idx = Some(idx);
+                        }
+                        let (lock_icon, lock_hint) = if line.locked { ("🔒", "Entsperren") } else { ("🔓", "Sperren") };
+                        if icon_button(ui, egui::Button::new(lock_icon).small(), lock_hint).clicked() {
+                            toggle_lock_idx = Some(idx);
+                        }
+                        if icon_button(ui, egui::Button::new("📄").small(), "Duplizieren").clicked() {
+                            duplicate_idx = Some(idx);
+                        }
+                        if icon_button(ui, egui::Button::new("🗑").small(), "Löschen").clicked() {
+                            delete_idx = Some(idx);
+                        }
+                    });
+                    if self.selected_line == Some(idx) {
+                        // Endpunkt für die Feinjustierung ohne Maus wählen
+                        ui.horizontal(|ui| {
+                            ui.label("  Pfeiltasten bewegen:");
+                            ui.selectable_value(&mut self.selected_endpoint, Some(true), "Start");
+                            ui.selectable_value(&mut self.selected_endpoint, Some(false), "Ende");
+                        });
+                    }
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "  {}   ∠ {}° / {}°",
+                            fmt.length(line.length_um as f64 / 1000.0, unit),
+                            fmt.number(line.start_angle),
+                            fmt.number(line.end_angle),
+                        ))
+                        .small(),
+                    );
+                }
+            });
+        
+        if let Some(idx) = toggle_multi_idx {
+            self.toggle_multi_selection(idx);
+        }
+        if let Some(idx) = toggle_hidden_idx {
+            self.custom_lines[idx].hidden = !self.custom_lines[idx].hidden;
+        }
+        if let Some(idx) = toggle_lock_idx {
+            self.custom_lines[idx].locked = !self.custom_lines[idx].locked;
+        }
+        if let Some(idx) = duplicate_idx {
+            self.duplicate_line(idx);
+        }
+        if let Some(idx) = delete_idx {
+            self.delete_line(idx);
+        }
+    }
+
+    /// Nimmt eine Linie in die Mehrfachauswahl auf oder entfernt sie daraus
+    fn toggle_multi_selection(&mut self, idx: usize) {
+        if let Some(pos) = self.multi_selection.iter().position(|&i| i == idx) {
+            self.multi_selection.remove(pos);
+        } else {
+            self.multi_selection.push(idx);
+        }
+    }
+
+    /// Mehrfachauswahl und Gruppen: gruppieren, gemeinsam verschieben, ausblenden, gestalten und löschen
+    fn show_line_groups(&mut self, ui: &mut egui::Ui) {
+        let groups = group_names(&self.custom_lines);
+        if groups.is_empty() && self.multi_selection.is_empty() {
+            return;
+        }
+        let mut group_selection = false;
+        let mut ungroup_selection = false;
+        let mut move_steps = 0.0;
+        let mut select_group = None;
+        let mut delete_group = None;
+        let mut ungroup = None;
+        let mut restyle: Option<(String, LineStyle, [u8; 3], f32)> = None;
+        let mut toggle_hidden = None;
+
+        egui::CollapsingHeader::new(format!("📁 Gruppen ({})", groups.len()))
+            .default_open(true)
+            .show(ui, |ui| {
+                if !self.multi_selection.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} ausgewählt", self.multi_selection.len()));
+                        if ui.add_enabled(self.multi_selection.len() > 1, egui::Button::new("🔗 Gruppieren")).clicked() {
+                            group_selection = true;
+                        }
+                        if ui.button("Gruppe lösen").clicked() {
+                            ungroup_selection = true;
+                        }
+                        if icon_button(ui, egui::Button::new("🗑").small(), "Auswahl löschen (Entf)").clicked() {
+                            delete_group = Some(self.multi_selection.clone());
+                        }
+                        if icon_button(ui, egui::Button::new("✖").small(), "Auswahl aufheben").clicked() {
+                            self.multi_selection.clear();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Verschieben:");
+                        ui.add(egui::TextEdit::singleline(&mut self.input_group_move).desired_width(60.0));
+                        ui.label("mm");
+                        if icon_button(ui, egui::Button::new("◀").small(), "Zum Anfang der Bezugsstrecken").clicked() {
+                            move_steps = -1.0;
+                        }
+                        if icon_button(ui, egui::Button::new("▶").small(), "Zum Ende der Bezugsstrecken").clicked() {
+                            move_steps = 1.0;
+                        }
+                    });
+                    ui.add_space(5.0);
+                }
+
+                for group in &groups {
+                    let members = group_members(&self.custom_lines, group);
+                    let first = &self.custom_lines[members[0]];
+                    let (mut style, mut color, mut width) = (first.style, first.color, first.width);
+                    let all_hidden = members.iter().all(|&i| self.custom_lines[i].hidden);
+                    ui.horizontal(|ui| {
+                        let selected = members.iter().all(|i| self.multi_selection.contains(i));
+                        if ui.selectable_label(selected, format!("📁 {} ({})", group, members.len())).clicked() {
+                            select_group = Some(members.clone());
+                        }
+                        let (hidden_icon, hidden_hint) = if all_hidden { ("🚫", "Gruppe einblenden") } else { ("👁", "Gruppe ausblenden") };
+                        if icon_button(ui, egui::Button::new(hidden_icon).small(), hidden_hint).clicked() {
+                            toggle_hidden = Some((group.clone(), !all_hidden));
+                        }
+                        let mut changed = egui::color_picker::color_edit_button_srgb(ui, &mut color).changed();
+                        egui::ComboBox::from_id_source(("group_style", group))
+                            .selected_text(style.label())
+                            .width(100.0)
+                            .show_ui(ui, |ui| {
+                                for option in [LineStyle::Solid, LineStyle::Dashed, LineStyle::Dotted] {
+                                    changed |= ui.selectable_value(&mut style, option, option.label()).changed();
+                                }
+                            });
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut width).speed(0.1).range(1.0..=10.0).suffix(" px"))
+                            .on_hover_text("Strichstärke der Gruppe")
+                            .changed();
+                        if changed {
+                            restyle = Some((group.clone(), style, color, width));
+                        }
+                        if icon_button(ui, egui::Button::new("✂").small(), "Gruppe auflösen").clicked() {
+                            ungroup = Some(group.clone());
+                        }
+                        if icon_button(ui, egui::Button::new("🗑").small(), "Gruppe löschen").clicked() {
+                            delete_group = Some(members.clone());
+                        }
+                    });
+                }
+            });
+
+        if group_selection {
+            let name = next_group_name(&self.custom_lines);
+            for &idx in &self.multi_selection {
+                self.custom_lines[idx].group = Some(name.clone());
+            }
+        }
+        if ungroup_selection {
+            for &idx in &self.multi_selection {
+                self.custom_lines[idx].group = None;
+            }
+        }
+        if move_steps != 0.0 {
+            let fmt = self.number_format();
+            match parse_length_input(&self.input_group_move, &fmt) {
+                Ok(Some(step_mm)) => {
+                    move_lines(&self.quad, &mut self.custom_lines, &self.multi_selection, move_steps * step_mm);
+                }
+                Ok(None) => self.error_message = Some("❌ Bitte eine Schrittweite eingeben.".to_string()),
+                Err(e) => self.error_message = Some(format!("❌ Schrittweite: {}", e)),
+            }
+        }
+        if let Some(members) = select_group {
+            self.multi_selection = members;
+        }
+        if let Some((group, hidden)) = toggle_hidden {
+            for line in self.custom_lines.iter_mut().filter(|l| l.group.as_ref() == Some(&group)) {
+                line.hidden = hidden;
+            }
+        }
+        if let Some((group, style, color, width)) = restyle {
+            for line in self.custom_lines.iter_mut().filter(|l| l.group.as_ref() == Some(&group)) {
+                line.style = style;
+                line.color = color;
+                line.width = width;
+            }
+        }
+        if let Some(group) = ungroup {
+            for line in self.custom_lines.iter_mut().filter(|l| l.group.as_ref() == Some(&group)) {
+                line.group = None;
+            }
+        }
+        if let Some(members) = delete_group {
+            self.delete_lines(&members);
+        }
+    }
+
+    /// Hängt eine neue Linie an und vermerkt sie im Messprotokoll
+    fn add_line(&mut self, line: CustomLine) {
+        let description = format!(
+            "{}, Länge {}",
+            line.name,
+            self.number_format().length(line.length_um as f64 / 1000.0, self.length_unit())
+        );
+        self.journal.record(&self.sheets[self.active_sheet].name, JournalEvent::LineAdded, description);
+        self.custom_lines.push(line);
+        self.deleted_lines = None;
+    }
+
+    /// Vermerkt eine erfolgreiche Berechnung mit den gemessenen Werten im Messprotokoll
+    fn log_calculation(&mut self, input: &QuadInput, quad: &Quadrilateral) {
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        let labels = &self.config.labels;
+        let mut values: Vec<String> = Vec::new();
+        for (i, side) in input.sides_mm.iter().enumerate() {
+            if let Some(mm) = side {
+                values.push(format!("{} {}", labels.side(i), fmt.length(*mm, unit)));
+            }
+        }
+        for (i, angle) in input.angles.iter().enumerate() {
+            if let Some(deg) = angle {
+                values.push(format!("{} {}", labels.vertex(i), fmt.angle(*deg)));
+            }
+        }
+        for (i, diagonal) in input.diagonals_mm.iter().enumerate() {
+            if let Some(mm) = diagonal {
+                values.push(format!("{} {}", labels.diagonal(i), fmt.length(*mm, unit)));
+            }
+        }
+        let description = format!("{} → Fläche {} m²", values.join(", "), fmt.number(quad.area_mm2() / 1_000_000.0));
+        self.journal.record(&self.sheets[self.active_sheet].name, JournalEvent::Calculation, description);
+    }
+
+    fn duplicate_line(&mut self, idx: usize) {
+        let mut copy = self.custom_lines[idx].clone();
+        copy.name = format!("{} (Kopie)", copy.name);
+        copy.locked = false;
+        self.add_line(copy);
+        self.selected_line = Some(self.custom_lines.len() - 1);
+        self.selected_endpoint = None;
+    }
+
+    /// Kopiert eine Linie parallel versetzt, Darstellung bleibt erhalten
+    /// `sign` wählt die Seite: +1 rechts, -1 links der Laufrichtung
+    fn duplicate_line_offset(&mut self, idx: usize, sign: f64) {
+        let fmt = self.number_format();
+        let offset_mm = match parse_length_input(&self.input_line_offset, &fmt) {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                self.error_message = Some("❌ Bitte einen Versatz eingeben.".to_string());
+                return;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("❌ Versatz: {}", e));
+                return;
+            }
+        };
+        
+        match offset_anchors(&self.quad, &self.custom_lines, idx, sign * offset_mm) {
+            Ok((start, end)) => {
+                let original = &self.custom_lines[idx];
+                let mut copy = CustomLine::new(
+                    format!("{} (Kopie)", original.name),
+                    start,
+                    end,
+                    &self.quad,
+                    &self.custom_lines,
+                );
+                copy.style = original.style;
+                copy.width = original.width;
+                copy.color = original.color;
+                self.add_line(copy);
+                self.selected_line = Some(self.custom_lines.len() - 1);
+                self.selected_endpoint = None;
+                self.error_message = None;
+            }
+            Err(e) => self.error_message = Some(format!("❌ {}", e)),
+        }
+    }
+
+    /// Eigenschaften des ausgewählten Kreises bzw. Bogens
+    fn show_circle_properties(&mut self, ui: &mut egui::Ui) {
+        let Some(idx) = self.selected_circle.filter(|&idx| idx < self.custom_circles.len()) else {
+            self.selected_circle = None;
+            return;
+        };
+        let mut delete = false;
+        
+        egui::CollapsingHeader::new("⭕ Kreis-Eigenschaften")
+            .default_open(true)
+            .show(ui, |ui| {
+                let circle = &mut self.custom_circles[idx];
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.add(egui::TextEdit::singleline(&mut circle.name).desired_width(180.0));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Radius:");
+                    let mut radius_mm = circle.radius_um as f64 / 1000.0;
+                    if ui.add(egui::DragValue::new(&mut radius_mm).range(0.1..=1_000_000.0).suffix(" mm")).changed() {
+                        circle.radius_um = Quadrilateral::mm_to_um(radius_mm);
+                    }
+                });
+                if let Some(arc) = &mut circle.arc {
+                    ui.horizontal(|ui| {
+                        ui.label("Start:");
+                        ui.add(egui::DragValue::new(&mut arc.start_deg).suffix("°"));
+                        ui.label("Winkel:");
+                        ui.add(egui::DragValue::new(&mut arc.sweep_deg).range(1.0..=359.0).suffix("°"));
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Stärke:");
+                    ui.add(egui::Slider::new(&mut circle.width, 1.0..=10.0).suffix(" px"));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Farbe:");
+                    egui::color_picker::color_edit_button_srgb(ui, &mut circle.color);
+                });
+                if ui.button("🗑 Löschen").clicked() {
+                    delete = true;
+                }
+            });
+        
+        if delete {
+            self.custom_circles.remove(idx);
+            self.selected_circle = None;
+        }
+    }
+
+    /// Zerlegung in zwei Dreiecke, damit das Viereck nur mit dem Maßband abgesteckt werden kann
+    fn show_triangulation(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        
+        egui::CollapsingHeader::new("🔺 Absteckung in Dreiecken")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Diagonale:");
+                    for diagonal in 0..2 {
+                        ui.radio_value(&mut self.triangle_diagonal, diagonal, labels.diagonal(diagonal));
+                    }
+                });
+                if !self.quad.diagonal_inside(self.triangle_diagonal) {
+                    ui.colored_label(
+                        Color32::from_rgb(200, 120, 0),
+                        "⚠ Diagonale verläuft außerhalb des Vierecks",
+                    );
+                }
+                
+                for triangle in self.quad.split_triangles(self.triangle_diagonal) {
+                    let [a, b, c] = triangle.vertices;
+                    ui.group(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Dreieck {}{}{}",
+                                labels.vertex(a),
+                                labels.vertex(b),
+                                labels.vertex(c)
+                            ))
+                            .strong(),
+                        );
+                        for i in 0..3 {
+                            let (from, to) = (triangle.vertices[i], triangle.vertices[(i + 1) % 3]);
+                            ui.label(format!(
+                                "  {}: {}",
+                                labels.segment(from, to),
+                                fmt.length(triangle.sides_mm[i], unit)
+                            ));
+                        }
+                        for i in 0..3 {
+                            ui.label(format!(
+                                "  Winkel {}: {}",
+                                labels.vertex(triangle.vertices[i]),
+                                fmt.angle(triangle.angles_deg[i])
+                            ));
+                        }
+                    });
+                }
+            });
+    }
+
+    /// Umkreis (Sehnenviereck) bzw. Inkreis (Tangentenviereck), nur wenn vorhanden
+    fn show_special_circles(&mut self, ui: &mut egui::Ui) {
+        let circumcircle = self.quad.circumcircle();
+        let incircle = self.quad.incircle();
+        if circumcircle.is_none() && incircle.is_none() {
+            return;
+        }
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        let frame = LocalFrame::new(&self.quad, self.baseline);
+        let mut add_circle = None;
+        
+        ui.add_space(10.0);
+        egui::CollapsingHeader::new("⭕ Sehnen-/Tangentenviereck")
+            .default_open(true)
+            .show(ui, |ui| {
+                let entries = [
+                    ("Sehnenviereck", "Umkreis", circumcircle),
+                    ("Tangentenviereck", "Inkreis", incircle),
+                ];
+                for (kind, name, circle) in entries {
+                    let Some(circle) = circle else {
+                        continue;
+                    };
+                    ui.label(egui::RichText::new(format!("✅ {}", kind)).strong());
+                    ui.label(format!("  {}: r = {}", name, fmt.length(circle.radius_mm, unit)));
+                    if let Some(frame) = &frame {
+                        let (x, y) = frame.to_local_mm(&circle.center);
+                        ui.label(format!("  Mittelpunkt: X = {}, Y = {}", fmt.length(x, unit), fmt.length(y, unit)));
+                    }
+                    if circle.deviation > 0.0 {
+                        let deviation = if kind == "Sehnenviereck" {
+                            format!("{}°", fmt.number(circle.deviation))
+                        } else {
+                            fmt.length(circle.deviation, unit)
+                        };
+                        ui.label(egui::RichText::new(format!("  nahezu, Abweichung {}", deviation)).small());
+                    }
+                    if ui.small_button(format!("⭕ {} einzeichnen", name)).clicked() {
+                        add_circle = Some((name, circle));
+                    }
+                    ui.add_space(5.0);
+                }
+                ui.label(egui::RichText::new("Mittelpunkt im System der Absteckpunkte").small());
+            });
+        
+        if let Some((name, circle)) = add_circle {
+            let radius_um = Quadrilateral::mm_to_um(circle.radius_mm);
+            self.custom_circles.push(CustomCircle::new(name.to_string(), circle.center, radius_um, None));
+            self.selected_circle = Some(self.custom_circles.len() - 1);
+            self.selected_line = None;
+        }
+    }
+
+    /// Alle Punkte (Eckpunkte, ggf. Seitenmitten und Schwerpunkt, Linienenden) im lokalen System der Basislinie
+    fn stakeout_points(&self) -> Vec<PointRecord> {
+        let Some(frame) = LocalFrame::new(&self.quad, self.baseline) else {
+            return Vec::new();
+        };
+        let record = |name: String, p: &Point| {
+            let (x_mm, y_mm) = frame.to_local_mm(p);
+            PointRecord { name, x_mm, y_mm }
+        };
+        
+        let mut points: Vec<PointRecord> = (0..4)
+            .map(|i| record(self.config.labels.vertex(i).to_string(), &self.quad.vertices[i]))
+            .collect();
+        if self.config.show_centroid {
+            for side in 0..4 {
+                points.push(record(format!("Mitte {}", self.config.labels.side(side)), &self.quad.get_point_on_side(side, 0.5)));
+            }
+            points.push(record("Schwerpunkt".to_string(), &self.quad.centroid()));
+        }
+        if self.config.show_point_grid {
+            for grid_point in self.quad.point_grid(&self.config.point_grid).unwrap_or_default() {
+                points.push(record(format!("Raster Z{} S{}", grid_point.row, grid_point.column), &grid_point.point));
+            }
+        }
+        for line in &self.custom_lines {
+            points.push(record(format!("{} Anfang", line.name), &line.start));
+            points.push(record(format!("{} Ende", line.name), &line.end));
+        }
+        points
+    }
+
+    /// Hilfslinien: Seiten über ihre Eckpunkte hinaus verlängern
+    fn show_construction_lines(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+
+        ui.label("Seite verlängern:");
+        ui.horizontal(|ui| {
+            ui.label("Überstand:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.input_construction_extension)
+                    .desired_width(60.0)
+                    .hint_text("∞"),
+            );
+        });
+        let text = self.input_construction_extension.trim();
+        let extension_mm = if text.is_empty() {
+            Ok(None)
+        } else {
+            fmt.parse_length_mm(text).filter(|mm| *mm > 0.0).map(Some).ok_or(INVALID_DISTANCE)
+        };
+        ui.horizontal(|ui| {
+            for side in 0..4 {
+                if ui.add_enabled(extension_mm.is_ok(), egui::Button::new(labels.side(side))).clicked() {
+                    if let Ok(extension_mm) = extension_mm {
+                        self.construction_lines.push(ConstructionLine { side, extension_mm });
+                    }
+                }
+            }
+        });
+        if let Err(e) = extension_mm {
+            ui.colored_label(Color32::RED, egui::RichText::new(e).small());
+        }
+
+        let mut remove = None;
+        for (idx, line) in self.construction_lines.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let reach = line.extension_mm.map_or("unbegrenzt".to_string(), |mm| format!("± {}", fmt.length(mm, unit)));
+                ui.label(format!("⋯ {} {}", labels.side(line.side), reach));
+                if icon_button(ui, egui::Button::new("🗑").small(), "Hilfslinie entfernen").clicked() {
+                    remove = Some(idx);
+                }
+            });
+        }
+        if let Some(idx) = remove {
+            self.construction_lines.remove(idx);
+        }
+        if !self.construction_lines.is_empty()
+            && ui.checkbox(&mut self.config.export_construction_lines, "Hilfslinien im Protokoll zeichnen").changed()
+        {
+            self.save_config();
+        }
+    }
+
+    /// Messergebnis und angeheftete Winkelmaße im Werkzeug-Bereich
+    fn show_angle_measure(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        let edge_name = |edge: CutEdge| match edge {
+            CutEdge::Side(side) => labels.side(side),
+            CutEdge::Line(line) => self.custom_lines.get(line).map(|l| l.name.clone()).unwrap_or_default(),
+        };
+        let dimension_text = |dimension: &AngleDimension| {
+            let names = format!("{} ∠ {}", edge_name(dimension.first), edge_name(dimension.second));
+            match edge_angle(&self.quad, &self.custom_lines, dimension.first, dimension.second) {
+                Some(angle) => format!("{}: {}", names, fmt.angle(angle.angle_deg)),
+                None => format!("{}: parallel", names),
+            }
+        };
+        
+        match (self.measure_first, &self.measure_result) {
+            (Some(first), _) => {
+                ui.label(format!("Zweite Kante anklicken (erste: {})", edge_name(first)));
+            }
+            (None, Some(dimension)) => {
+                ui.label(egui::RichText::new(dimension_text(dimension)).strong());
+            }
+            (None, None) => {
+                ui.label("Zwei Seiten oder Linien nacheinander anklicken");
+            }
+        }
+        
+        let mut pin = false;
+        if let Some(dimension) = &self.measure_result {
+            let parallel = edge_angle(&self.quad, &self.custom_lines, dimension.first, dimension.second).is_none();
+            pin = ui.add_enabled(!parallel, egui::Button::new("📌 Als Winkelmaß anheften")).clicked();
+        }
+        
+        let mut remove = None;
+        for (idx, dimension) in self.angle_dimensions.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(dimension_text(dimension));
+                if icon_button(ui, egui::Button::new("🗑").small(), "Winkelmaß entfernen").clicked() {
+                    remove = Some(idx);
+                }
+            });
+        }
+        
+        if pin {
+            if let Some(dimension) = self.measure_result.take() {
+                self.angle_dimensions.push(dimension);
+            }
+        }
+        if let Some(idx) = remove {
+            self.angle_dimensions.remove(idx);
+        }
+    }
+
+    /// Kante (Linie oder Seite) unter dem Cursor; Linien haben Vorrang
+    fn find_edge_at(&self, pos: Pos2, view: &ViewTransform) -> Option<CutEdge> {
+        let lines = self.custom_lines.iter().enumerate().map(|(idx, line)| (CutEdge::Line(idx), &line.start, &line.end));
+        let sides = (0..4).map(|side| (CutEdge::Side(side), &self.quad.vertices[side], &self.quad.vertices[(side + 1) % 4]));
+        lines
+            .chain(sides)
+            .find(|(_, a, b)| point_to_line_distance(pos, view.to_screen(a), view.to_screen(b)) < 12.0)
+            .map(|(edge, _, _)| edge)
+    }
+
+    /// Punktliste für die Absteckung mit wählbarem Ursprung und Bezugsachse, CSV-Export
+    fn show_stakeout_points(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        
+        egui::CollapsingHeader::new("📍 Absteckpunkte")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Ursprung:");
+                    let previous = self.baseline.origin;
+                    egui::ComboBox::from_id_source("stakeout_origin")
+                        .selected_text(labels.vertex(self.baseline.origin))
+                        .show_ui(ui, |ui| {
+                            for vertex in 0..4 {
+                                ui.selectable_value(&mut self.baseline.origin, vertex, labels.vertex(vertex));
+                            }
+                        });
+                    if self.baseline.origin != previous {
+                        self.baseline.axis_to = (self.baseline.origin + 1) % 4;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("X-Achse:");
+                    let origin = self.baseline.origin;
+                    for neighbor in [(origin + 1) % 4, (origin + 3) % 4] {
+                        ui.radio_value(&mut self.baseline.axis_to, neighbor, labels.segment(origin, neighbor));
+                    }
+                });
+                
+                let points = self.stakeout_points();
+                egui::Grid::new("stakeout_grid").striped(true).show(ui, |ui| {
+                    ui.label(egui::RichText::new("Punkt").strong());
+                    ui.label(egui::RichText::new("X (mm)").strong());
+                    ui.label(egui::RichText::new("Y (mm)").strong());
+                    ui.end_row();
+                    for point in &points {
+                        ui.label(&point.name);
+                        ui.label(fmt.number(point.x_mm));
+                        ui.label(fmt.number(point.y_mm));
+                        ui.end_row();
+                    }
+                });
+                
+                // Maßbandlauf entlang des Umfangs: Eckpunkte und Linienenden in Reihenfolge der Station
+                if self.config.show_stations {
+                    ui.add_space(5.0);
+                    ui.label(format!("Stationierung ab {}:", labels.vertex(0)));
+                    egui::Grid::new("station_grid").striped(true).show(ui, |ui| {
+                        ui.label(egui::RichText::new("Station (mm)").strong());
+                        ui.label(egui::RichText::new("Punkt").strong());
+                        ui.end_row();
+                        for station in perimeter_stations(&self.quad, &self.custom_lines) {
+                            ui.label(fmt.number(station.station_mm));
+                            ui.label(match station.source {
+                                StationSource::Vertex(i) => labels.vertex(i).to_string(),
+                                StationSource::LineEnd { line, at_start } => {
+                                    format!("{} {}", self.custom_lines[line].name, if at_start { "Anfang" } else { "Ende" })
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                }
+                
+                ui.add_space(5.0);
+                if ui.button("💾 Als CSV exportieren").clicked() {
+                    let csv = export::points_csv(&points, &fmt);
+                    self.export_message = Some(match export::save_to_desktop("absteckpunkte", "csv", csv.as_bytes()) {
+                        Ok(path) => format!("✅ Gespeichert: {}", path.display()),
+                        Err(e) => format!("❌ {}", e),
+                    });
+                }
+                if let Some(message) = &self.export_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+            });
+    }
+
+    /// GeoJSON-Export mit Georeferenzierung: bekannter Eckpunkt und Richtung der abgehenden Seite
+    fn show_geojson_export(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        egui::CollapsingHeader::new("🌍 GeoJSON / Lageplan")
+            .default_open(false)
+            .show(ui, |ui| {
+                let reference = &mut self.config.geo_reference;
+                let mut changed = false;
+                egui::Grid::new("geo_reference_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Bekannter Punkt:");
+                    egui::ComboBox::from_id_source("geo_origin")
+                        .selected_text(labels.vertex(reference.origin_vertex))
+                        .show_ui(ui, |ui| {
+                            for vertex in 0..4 {
+                                changed |= ui.selectable_value(&mut reference.origin_vertex, vertex, labels.vertex(vertex)).changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Rechtswert (Ost):");
+                    changed |= ui.add(egui::DragValue::new(&mut reference.easting_m).speed(0.1).suffix(" m")).changed();
+                    ui.end_row();
+
+                    ui.label("Hochwert (Nord):");
+                    changed |= ui.add(egui::DragValue::new(&mut reference.northing_m).speed(0.1).suffix(" m")).changed();
+                    ui.end_row();
+
+                    ui.label(format!("Richtung {}:", labels.side(reference.origin_vertex)))
+                        .on_hover_text("Richtungswinkel von Nord im Uhrzeigersinn, 90° = Osten");
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut reference.azimuth_deg)
+                                .speed(0.1)
+                                .range(0.0..=360.0)
+                                .suffix("°"),
+                        )
+                        .changed();
+                    ui.end_row();
+
+                    let mut with_epsg = reference.epsg.is_some();
+                    if ui.checkbox(&mut with_epsg, "EPSG-Code:").on_hover_text("z.B. 25832 für ETRS89 / UTM Zone 32N").changed() {
+                        reference.epsg = with_epsg.then_some(25832);
+                        changed = true;
+                    }
+                    if let Some(epsg) = &mut reference.epsg {
+                        changed |= ui.add(egui::DragValue::new(epsg).range(1..=999_999)).changed();
+                    }
+                    ui.end_row();
+                });
+                if changed {
+                    self.save_config();
+                }
+
+                ui.add_space(5.0);
+                if ui
+                    .button("💾 Als GeoJSON exportieren")
+                    .on_hover_text("Eckpunkte, Viereck und eingeblendete Linien in Lageplan-Koordinaten")
+                    .clicked()
+                {
+                    let json = export::geojson(&self.quad, &self.custom_lines, labels, &self.config.geo_reference);
+                    self.geojson_message = Some(match export::save_to_desktop("lageplan", "geojson", json.as_bytes()) {
+                        Ok(path) => format!("✅ Gespeichert: {}", path.display()),
+                        Err(e) => format!("❌ {}", e),
+                    });
+                }
+                if let Some(message) = &self.geojson_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+            });
+    }
+
+    /// Raumhöhe, Volumen und Wandflächen des extrudierten Grundrisses
+    fn show_room_measures(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        
+        egui::CollapsingHeader::new("🧊 Raum / 3D")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Raumhöhe:");
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.config.room_height_mm)
+                            .speed(5.0)
+                            .range(100.0..=20_000.0)
+                            .suffix(" mm"),
+                    );
+                    if response.changed() {
+                        self.save_config();
+                    }
+                });
+                
+                let measures = self.quad.prism_measures(self.config.room_height_mm);
+                ui.add_space(5.0);
+                ui.label(egui::RichText::new(format!("Volumen: {} m³", fmt.number(measures.volume_m3))).strong());
+                ui.label(format!("Boden/Decke: je {} m²", fmt.number(measures.floor_m2)));
+                for (i, area) in measures.wall_areas_m2.iter().enumerate() {
+                    ui.label(format!("Wand {}: {} m²", labels.side(i), fmt.number(*area)));
+                }
+                ui.label(format!("Wände gesamt: {} m²", fmt.number(measures.total_wall_area_m2())));
+                ui.label(egui::RichText::new("Ohne Abzug von Türen und Fenstern").small());
+                
+                ui.add_space(5.0);
+                if ui.button("🧊 3D-Vorschau").clicked() {
+                    self.show_prism = !self.show_prism;
+                }
+            });
+    }
+
+    /// Drehbare Vorschau des Raums als Prisma, Ziehen mit der Maus dreht die Ansicht
+    fn show_prism_window(&mut self, ctx: &egui::Context) {
+        let labels = self.config.labels.clone();
+        let mut open = self.show_prism;
+        
+        egui::Window::new("🧊 3D-Vorschau")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let (response, painter) = ui.allocate_painter(Vec2::new(480.0, 360.0), egui::Sense::drag());
+                let rect = response.rect;
+                painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+                
+                if response.dragged() {
+                    let delta = response.drag_delta();
+                    self.prism_view.yaw_deg = (self.prism_view.yaw_deg - delta.x as f64 * 0.5).rem_euclid(360.0);
+                    self.prism_view.pitch_deg = (self.prism_view.pitch_deg + delta.y as f64 * 0.5).clamp(0.0, 90.0);
+                }
+                
+                let points = self.quad.project_prism(self.config.room_height_mm, &self.prism_view);
+                
+                // In den Zeichenbereich einpassen
+                let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+                let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+                let padding = 30.0;
+                let scale = ((rect.width() as f64 - 2.0 * padding) / (max_x - min_x).max(1.0))
+                    .min((rect.height() as f64 - 2.0 * padding) / (max_y - min_y).max(1.0));
+                let center = rect.center();
+                let to_screen = |i: usize| {
+                    Pos2::new(
+                        center.x + ((points[i].x - (min_x + max_x) / 2.0) * scale) as f32,
+                        center.y - ((points[i].y - (min_y + max_y) / 2.0) * scale) as f32,
+                    )
+                };
+                
+                // Wände von hinten nach vorn zeichnen
+                let mut walls: Vec<[usize; 4]> = (0..4).map(|i| [i, (i + 1) % 4, 4 + (i + 1) % 4, 4 + i]).collect();
+                walls.sort_by(|a, b| {
+                    let depth = |face: &[usize; 4]| face.iter().map(|&i| points[i].depth).sum::<f64>();
+                    depth(a).total_cmp(&depth(b))
+                });
+                let edge = Stroke::new(1.5, Color32::from_rgb(40, 60, 90));
+                for wall in &walls {
+                    painter.add(egui::Shape::convex_polygon(
+                        wall.iter().map(|&i| to_screen(i)).collect(),
+                        Color32::from_rgba_unmultiplied(100, 150, 220, 110),
+                        edge,
+                    ));
+                }
+                
+                for i in 0..4 {
+                    painter.text(
+                        to_screen(i),
+                        egui::Align2::CENTER_TOP,
+                        labels.vertex(i),
+                        egui::FontId::proportional(14.0),
+                        ui.visuals().text_color(),
+                    );
+                }
+                
+                ui.label(egui::RichText::new("Ziehen mit der Maus dreht die Ansicht").small());
+                ui.horizontal(|ui| {
+                    if ui.button("Draufsicht").clicked() {
+                        self.prism_view = PrismView { yaw_deg: 0.0, pitch_deg: 90.0 };
+                    }
+                    if ui.button("Zurücksetzen").clicked() {
+                        self.prism_view = PrismView::default();
+                    }
+                });
+            });
+        self.show_prism = open;
+    }
+
+    /// Platten-/Fliesenrechner: Stückzahl und Verschnitt für das gewählte Format
+    fn show_tile_calculator(&mut self, ui: &mut egui::Ui) {
+        let fmt = self.number_format();
+        
+        egui::CollapsingHeader::new("🧱 Platten-/Fliesenrechner")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut changed = false;
+                let tiles = &mut self.config.tiles;
+                egui::Grid::new("tile_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Breite:");
+                    changed |= ui.add(egui::DragValue::new(&mut tiles.width_mm).speed(1.0).range(10.0..=5000.0).suffix(" mm")).changed();
+                    ui.end_row();
+                    ui.label("Höhe:");
+                    changed |= ui.add(egui::DragValue::new(&mut tiles.height_mm).speed(1.0).range(10.0..=5000.0).suffix(" mm")).changed();
+                    ui.end_row();
+                    ui.label("Fuge:");
+                    changed |= ui.add(egui::DragValue::new(&mut tiles.joint_mm).speed(0.1).range(0.0..=50.0).suffix(" mm")).changed();
+                    ui.end_row();
+                });
+                
+                if changed {
+                    self.tile_estimate = None;
+                    self.save_config();
+                }
+                
+                let estimate = self
+                    .tile_estimate
+                    .get_or_insert_with(|| self.quad.estimate_tiles(&self.config.tiles));
+                
+                ui.add_space(5.0);
+                ui.label(format!("Fläche: {} m²", fmt.number(self.quad.area_mm2() / 1_000_000.0)));
+                match estimate {
+                    Ok(estimate) => {
+                        ui.label(format!("Ganze Platten: {}", estimate.full_tiles));
+                        ui.label(format!("Zuschnitte: {}", estimate.cut_tiles));
+                        ui.label(egui::RichText::new(format!("Bedarf: {} Stück", estimate.total_tiles())).strong());
+                        ui.label(format!("Verschnitt: {} %", fmt.number(estimate.waste_percent)));
+                        ui.label(egui::RichText::new("Verlegung ab dem ersten Eckpunkt entlang der ersten Seite, ohne Wiederverwendung von Reststücken").small());
+                    }
+                    Err(e) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e.as_str());
+                    }
+                }
+            });
+    }
+
+    /// Regelmäßiges Punktraster im Viereck, die Punkte erscheinen auch bei den Absteckpunkten
+    fn show_point_grid(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        egui::CollapsingHeader::new("⁘ Punktraster")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut changed = ui
+                    .checkbox(&mut self.config.show_point_grid, "Raster anzeigen")
+                    .on_hover_text("Die Rasterpunkte werden mit den Absteckpunkten als CSV exportiert")
+                    .changed();
+                let spec = &mut self.config.point_grid;
+                egui::Grid::new("point_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Ausrichtung:");
+                    egui::ComboBox::from_id_source("point_grid_side")
+                        .selected_text(format!("parallel zu {}", labels.side(spec.side)))
+                        .show_ui(ui, |ui| {
+                            for side in 0..4 {
+                                changed |= ui.selectable_value(&mut spec.side, side, format!("parallel zu {}", labels.side(side))).changed();
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("Abstand entlang:");
+                    changed |= ui.add(egui::DragValue::new(&mut spec.spacing_along_mm).speed(10.0).range(10.0..=100_000.0).suffix(" mm")).changed();
+                    ui.end_row();
+                    ui.label("Abstand quer:");
+                    changed |= ui.add(egui::DragValue::new(&mut spec.spacing_across_mm).speed(10.0).range(10.0..=100_000.0).suffix(" mm")).changed();
+                    ui.end_row();
+                    ui.label("Randabstand:");
+                    changed |= ui.add(egui::DragValue::new(&mut spec.margin_mm).speed(10.0).range(0.0..=100_000.0).suffix(" mm")).changed();
+                    ui.end_row();
+                });
+
+                if changed {
+                    self.save_config();
+                }
+
+                ui.add_space(5.0);
+                match self.quad.point_grid(&self.config.point_grid) {
+                    Ok(points) => {
+                        let rows = points.iter().map(|p| p.row).max().unwrap_or(0);
+                        let columns = points.iter().map(|p| p.column).max().unwrap_or(0);
+                        ui.label(format!("{} Punkte in {} Zeilen × {} Spalten", points.len(), rows, columns));
+                    }
+                    Err(e) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e.as_str());
+                    }
+                }
+            });
+    }
+
+    /// Kostentabelle für Fläche, Umfang und eingezeichnete Linien
+    fn cost_items(&self) -> Vec<CostItem> {
+        let (area_m2, perimeter_m, lines_m) = self.measured_quantities();
+        costs::estimate_costs(&self.config.costs, area_m2, perimeter_m, lines_m)
+    }
+
+    /// Exakte Mengen: Fläche (m²), Umfang (m) und Summe der eingezeichneten Linien (m)
+    fn measured_quantities(&self) -> (f64, f64, f64) {
+        let perimeter_m = (0..4).map(|i| self.quad.get_side_length_mm(i)).sum::<f64>() / 1000.0;
+        let lines_m = self.custom_lines.iter().map(|l| l.length_um as f64).sum::<f64>() / 1_000_000.0;
+        (self.quad.area_mm2() / 1_000_000.0, perimeter_m, lines_m)
+    }
+
+    /// Exakte und kaufmännisch aufgerundete Mengen nebeneinander
+    fn show_quote_quantities(&mut self, ui: &mut egui::Ui) {
+        let fmt = self.number_format();
+
+        egui::CollapsingHeader::new("🧾 Angebotsmengen")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut changed = false;
+                let rules = &mut self.config.quote;
+                egui::Grid::new("quote_rules_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Fläche aufrunden auf:");
+                    changed |= ui.add(egui::DragValue::new(&mut rules.area_step_m2).speed(0.01).range(0.0..=10.0).suffix(" m²")).changed();
+                    ui.end_row();
+                    ui.label("Längen aufrunden auf:");
+                    changed |= ui.add(egui::DragValue::new(&mut rules.length_step_m).speed(0.01).range(0.0..=10.0).suffix(" m")).changed();
+                    ui.end_row();
+                    ui.label("Verschnitt:");
+                    changed |= ui.add(egui::DragValue::new(&mut rules.waste_percent).speed(0.5).range(0.0..=100.0).suffix(" %")).changed();
+                    ui.end_row();
+                });
+                ui.label(egui::RichText::new("0 = nicht runden; der Verschnitt wird vor dem Runden aufgeschlagen").small());
+                if changed {
+                    self.save_config();
+                }
+
+                let rules = self.config.quote;
+                let (area_m2, perimeter_m, lines_m) = self.measured_quantities();
+                let mut rows = vec![
+                    ("Fläche", area_m2, rules.area_m2(area_m2), "m²"),
+                    ("Umfang", perimeter_m, rules.length_m(perimeter_m), "m"),
+                ];
+                if !self.custom_lines.is_empty() {
+                    rows.push(("Linien", lines_m, rules.length_m(lines_m), "m"));
+                }
+
+                ui.add_space(5.0);
+                egui::Grid::new("quote_table").striped(true).show(ui, |ui| {
+                    for header in ["Position", "Exakt", "Angebot"] {
+                        ui.label(egui::RichText::new(header).strong());
+                    }
+                    ui.end_row();
+                    for (label, exact, quoted, unit) in rows {
+                        ui.label(label);
+                        ui.label(format!("{} {}", fmt.number(exact), unit));
+                        ui.label(egui::RichText::new(format!("{} {}", fmt.number(quoted), unit)).strong());
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    /// Aufmaß-Protokoll mit Zeichnung und allen Werten als HTML, die Messwerte als XLSX bzw. die Zeichnung als DXF auf den Desktop
+    fn show_report_export(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("📄 Aufmaß-Protokoll")
+            .default_open(false)
+            .show(ui, |ui| {
+                if self.project.name.trim().is_empty() {
+                    ui.label(egui::RichText::new("Projektangaben für den Schriftkopf unter 🗂 Projekt").small());
+                } else {
+                    ui.label(format!("Projekt: {}", self.project.name.trim()));
+                }
+                if ui.button("🗂 Projektangaben bearbeiten").clicked() {
+                    self.show_project = true;
+                }
+                
+                let (export_html, export_xlsx, export_dxf) = ui
+                    .horizontal(|ui| {
+                        (
+                            ui.button("💾 Protokoll (HTML)").clicked(),
+                            ui.button("📊 Excel (XLSX)").clicked(),
+                            ui.button("📐 CAD (DXF)").on_hover_text("Zeichnung in mm mit Füllung bzw. Schraffur").clicked(),
+                        )
+                    })
+                    .inner;
+                if export_html || export_xlsx || export_dxf {
+                    let costs = self.cost_items();
+                    let report = Report {
+                        metadata: &self.project,
+                        date: chrono::Local::now().format("%d.%m.%Y %H:%M").to_string(),
+                        quad: &self.quad,
+                        input: self.last_input.as_ref(),
+                        lines: &self.custom_lines,
+                        circles: &self.custom_circles,
+                        construction_lines: if self.config.export_construction_lines { &self.construction_lines } else { &[] },
+                        labels: &self.config.labels,
+                        reference_side: self.config.reference_side,
+                        number: self.number_format(),
+                        unit: self.length_unit(),
+                        angle_display: self.config.angle_display,
+                        fill: &self.config.fill,
+                        triangle_diagonal: self.triangle_diagonal,
+                        costs: &costs,
+                        branding: &self.config.branding,
+                    };
+                    let saved = if export_html {
+                        export::save_to_desktop("aufmass_protokoll", "html", report.to_html().as_bytes())
+                    } else if export_xlsx {
+                        export::save_to_desktop("aufmass", "xlsx", &xlsx::report_workbook(&report))
+                    } else {
+                        export::save_to_desktop("zeichnung", "dxf", dxf::report_drawing(&report).as_bytes())
+                    };
+                    self.report_message = Some(match saved {
+                        Ok(path) => format!("✅ Gespeichert: {}", path.display()),
+                        Err(e) => format!("❌ {}", e),
+                    });
+                }
+                ui.label(egui::RichText::new("HTML im Browser öffnen, zum Weitergeben als PDF drucken").small());
+                if let Some(message) = &self.report_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+                self.show_branding(ui);
+            });
+    }
+
+    /// Firmenkopf der Protokolle: Name, Anschrift und Logo, bleibt in den Einstellungen gespeichert
+    fn show_branding(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🏢 Firmenkopf")
+            .default_open(false)
+            .show(ui, |ui| {
+                // Auf das Fenster gezogene Bilddatei als Logo vormerken
+                if let Some(path) = storage::first_dropped_file(ui.ctx()) {
+                    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+                    if matches!(extension.as_str(), "png" | "jpg" | "jpeg" | "svg") {
+                        self.input_logo_path = path.display().to_string();
+                    }
+                }
+
+                let branding = &mut self.config.branding;
+                let mut changed = false;
+                egui::Grid::new("branding_grid").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+                    ui.label("Firma:");
+                    changed |= ui.add(egui::TextEdit::singleline(&mut branding.company).desired_width(220.0)).changed();
+                    ui.end_row();
+                    ui.label("Anschrift:");
+                    changed |= ui
+                        .add(egui::TextEdit::multiline(&mut branding.address).desired_rows(3).desired_width(220.0))
+                        .changed();
+                    ui.end_row();
+                    ui.label("Logo:");
+                    ui.add(egui::TextEdit::singleline(&mut self.input_logo_path).desired_width(220.0).hint_text("PNG, JPEG oder SVG"));
+                    ui.end_row();
+                });
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.input_logo_path.trim().is_empty(), egui::Button::new("🖼 Logo laden")).clicked() {
+                        let loaded = storage::read(std::path::Path::new(self.input_logo_path.trim()))
+                            .map_err(|e| format!("Datei konnte nicht gelesen werden: {}", e))
+                            .and_then(|bytes| branding.set_logo(&bytes));
+                        self.branding_message = Some(match loaded {
+                            Ok(()) => "✅ Logo übernommen".to_string(),
+                            Err(e) => format!("❌ {}", e),
+                        });
+                        changed = true;
+                    }
+                    if ui.add_enabled(!branding.logo_data_uri.is_empty(), egui::Button::new("🗑 Logo entfernen")).clicked() {
+                        branding.logo_data_uri.clear();
+                        self.branding_message = None;
+                        changed = true;
+                    }
+                });
+                if !branding.logo_data_uri.is_empty() {
+                    // Base64: 4 Zeichen je 3 Byte
+                    let kilobytes = branding.logo_data_uri.len() * 3 / 4 / 1024;
+                    ui.label(egui::RichText::new(format!("Logo eingebettet (ca. {} KB)", kilobytes.max(1))).small());
+                }
+                ui.label(egui::RichText::new("Erscheint oben im HTML-Protokoll, Firma und Anschrift auch im XLSX").small());
+                if let Some(message) = &self.branding_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+                if changed {
+                    self.save_config();
+                }
+            });
+    }
+
+    /// Materialkosten: Preise eingeben und Schätzung anzeigen
+    fn show_cost_estimate(&mut self, ui: &mut egui::Ui) {
+        let fmt = self.number_format();
+        
+        egui::CollapsingHeader::new("💶 Materialkosten")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut changed = false;
+                let rates = &mut self.config.costs;
+                egui::Grid::new("cost_rates_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Preis Fläche:");
+                    changed |= ui.add(egui::DragValue::new(&mut rates.per_m2).speed(0.5).range(0.0..=100_000.0).suffix(" €/m²")).changed();
+                    ui.end_row();
+                    ui.label("Preis Umfang:");
+                    changed |= ui.add(egui::DragValue::new(&mut rates.per_m_perimeter).speed(0.1).range(0.0..=100_000.0).suffix(" €/m")).changed();
+                    ui.end_row();
+                    ui.label("Preis Linien:");
+                    changed |= ui.add(egui::DragValue::new(&mut rates.per_m_lines).speed(0.1).range(0.0..=100_000.0).suffix(" €/m")).changed();
+                    ui.end_row();
+                });
+                if changed {
+                    self.save_config();
+                }
+                
+                let items = self.cost_items();
+                if items.is_empty() {
+                    ui.label(egui::RichText::new("Preise eingeben, um die Kosten zu schätzen").small());
+                    return;
+                }
+                
+                let money = NumberFormat { decimals: 2, ..fmt };
+                ui.add_space(5.0);
+                egui::Grid::new("cost_table").striped(true).show(ui, |ui| {
+                    for header in ["Position", "Menge", "Preis", "Betrag"] {
+                        ui.label(egui::RichText::new(header).strong());
+                    }
+                    ui.end_row();
+                    for item in &items {
+                        ui.label(item.label);
+                        ui.label(format!("{} {}", fmt.number(item.quantity), item.unit));
+                        ui.label(format!("{} €/{}", money.number(item.rate), item.unit));
+                        ui.label(format!("{} €", money.number(item.total())));
+                        ui.end_row();
+                    }
+                    ui.label(egui::RichText::new("Summe").strong());
+                    ui.label("");
+                    ui.label("");
+                    ui.label(egui::RichText::new(format!("{} €", money.number(costs::total_cost(&items)))).strong());
+                    ui.end_row();
+                });
+            });
+    }
+
+    /// Messprotokoll der Sitzung mit Zeitstempeln, neueste Einträge oben
+    fn show_journal(&mut self, ui: &mut egui::Ui) {
+        let fmt = self.number_format();
+        egui::CollapsingHeader::new(format!("📝 Messprotokoll ({})", self.journal.entries.len()))
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(200.0).id_source("journal_scroll").show(ui, |ui| {
+                    for entry in self.journal.entries.iter().rev() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(egui::RichText::new(&entry.timestamp).small().strong());
+                            ui.label(egui::RichText::new(format!("{} · {}", entry.sheet, entry.event.label())).small());
+                            ui.label(egui::RichText::new(&entry.description).small());
+                        });
+                    }
+                });
+
+                ui.add_space(5.0);
+                if ui.button("💾 Als CSV exportieren").clicked() {
+                    let csv = self.journal.to_csv(&fmt);
+                    self.journal_message = Some(match export::save_to_desktop("messprotokoll", "csv", csv.as_bytes()) {
+                        Ok(path) => format!("✅ Gespeichert: {}", path.display()),
+                        Err(e) => format!("❌ {}", e),
+                    });
+                }
+                if let Some(message) = &self.journal_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+            });
+    }
+
+    /// Letzte Berechnungen der Sitzung zum Vergleichen von Varianten
+    fn show_history(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        let mut restore = None;
+        let mut compare = None;
+        
+        egui::CollapsingHeader::new(format!("🕘 Verlauf ({})", self.history.len()))
+            .default_open(false)
+            .show(ui, |ui| {
+                for (idx, record) in self.history.iter().enumerate() {
+                    ui.group(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(&record.time).strong());
+                            ui.label(format!("{} m²", fmt.number(record.quad.area_mm2() / 1_000_000.0)));
+                            if ui.small_button("↩ Wiederherstellen").clicked() {
+                                restore = Some(idx);
+                            }
+                            if icon_button(ui, egui::Button::new("⚖").small(), "Als Vergleich überlagern").clicked() {
+                                compare = Some(idx);
+                            }
+                        });
+                        
+                        // Gemessene Werte fett, berechnete normal
+                        ui.horizontal_wrapped(|ui| {
+                            for i in 0..4 {
+                                let text = format!("{} {}", labels.side(i), fmt.length(record.quad.get_side_length_mm(i), unit));
+                                let text = egui::RichText::new(text).small();
+                                ui.label(if record.input.sides_mm[i].is_some() { text.strong() } else { text });
+                            }
+                        });
+                        ui.horizontal_wrapped(|ui| {
+                            let angles = [record.quad.angle_a, record.quad.angle_b, record.quad.angle_c, record.quad.angle_d];
+                            for (i, angle) in angles.iter().enumerate() {
+                                let text = format!("{} {}", labels.vertex(i), fmt.angle(angle.unwrap_or(0.0)));
+                                let text = egui::RichText::new(text).small();
+                                ui.label(if record.input.angles[i].is_some() { text.strong() } else { text });
+                            }
+                        });
+                    });
+                }
+                ui.label(egui::RichText::new("Fett = gemessen").small());
+            });
+        
+        if let Some(idx) = restore {
+            self.restore_history(idx);
+        }
+        if let Some(idx) = compare {
+            self.comparison = Some(self.history[idx].clone());
+        }
+    }
+
+    /// Eingefrorene Messstände des Blatts: einfrieren, später neu messen und vergleichen
+    fn show_snapshots(&mut self, ui: &mut egui::Ui) {
+        let mut compare = None;
+        let mut delete = None;
+        
+        egui::CollapsingHeader::new(format!("📸 Messstände ({})", self.snapshots.len()))
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.input_snapshot_name).hint_text("z.B. Rohbau").desired_width(120.0));
+                    let can_freeze = self.calculated && self.last_input.is_some();
+                    if ui
+                        .add_enabled(can_freeze, egui::Button::new("📸 Einfrieren"))
+                        .on_hover_text("Aktuelle Messung speichern, um sie nach erneutem Aufmaß zu vergleichen")
+                        .clicked()
+                    {
+                        self.freeze_snapshot();
+                    }
+                });
+                for (idx, snapshot) in self.snapshots.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(snapshot.title());
+                        if self.calculated
+                            && icon_button(ui, egui::Button::new("⚖").small(), "Mit der aktuellen Messung vergleichen").clicked()
+                        {
+                            compare = Some(idx);
+                        }
+                        if icon_button(ui, egui::Button::new("🗑").small(), "Messstand löschen").clicked() {
+                            delete = Some(idx);
+                        }
+                    });
+                }
+                if !self.snapshots.is_empty() {
+                    ui.label(egui::RichText::new("Messstände werden mit dem Projekt gespeichert").small());
+                }
+            });
+        
+        if let Some(idx) = compare {
+            let snapshot = &self.snapshots[idx];
+            match solve_with_tolerance(&snapshot.input, &self.config.plausibility) {
+                Ok(solution) => {
+                    self.comparison = Some(CalculationRecord {
+                        time: snapshot.title(),
+                        input: snapshot.input.clone(),
+                        quad: solution.quad,
+                    });
+                }
+                Err(e) => self.error_message = Some(format!("❌ {}: {}", snapshot.title(), e.message(self.config.language, &self.number_format(), &self.config.labels))),
+            }
+        }
+        if let Some(idx) = delete {
+            self.snapshots.remove(idx);
+        }
+    }
+
+    /// Speichert die Eingaben der letzten Berechnung als Messstand
+    fn freeze_snapshot(&mut self) {
+        let Some(input) = self.last_input.clone() else {
+            return;
+        };
+        self.snapshots.push(MeasurementSnapshot {
+            name: std::mem::take(&mut self.input_snapshot_name).trim().to_string(),
+            date: chrono::Local::now().format("%d.%m.%Y %H:%M").to_string(),
+            input,
+        });
+    }
+
+    /// Vergleichstabelle: aktuelles Viereck gegen das überlagerte Vergleichsviereck
+    fn show_comparison(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let Some(record) = &self.comparison else {
+            return;
+        };
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        let diff = self.quad.compare(&record.quad);
+        let mut close = false;
+        
+        egui::CollapsingHeader::new(format!("⚖ Vergleich mit {}", record.time))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("comparison_table").striped(true).num_columns(4).show(ui, |ui| {
+                    ui.label(egui::RichText::new("").strong());
+                    ui.label(egui::RichText::new("Aktuell").strong());
+                    ui.label(egui::RichText::new("Vergleich").strong());
+                    ui.label(egui::RichText::new("Abweichung").strong());
+                    ui.end_row();
+                    
+                    for i in 0..4 {
+                        ui.label(labels.side(i));
+                        ui.label(fmt.length(self.quad.get_side_length_mm(i), unit));
+                        ui.label(fmt.length(record.quad.get_side_length_mm(i), unit));
+                        ui.label(format!("{} mm", fmt.signed(diff.sides_mm[i])));
+                        ui.end_row();
+                    }
+                    
+                    let own = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
+                    let other = [record.quad.angle_a, record.quad.angle_b, record.quad.angle_c, record.quad.angle_d];
+                    for i in 0..4 {
+                        ui.label(format!("∠ {}", labels.vertex(i)));
+                        ui.label(fmt.angle(own[i].unwrap_or(0.0)));
+                        ui.label(fmt.angle(other[i].unwrap_or(0.0)));
+                        ui.label(format!("{}°", fmt.signed(diff.angles_deg[i])));
+                        ui.end_row();
+                    }
+                    
+                    ui.label("Fläche");
+                    ui.label(format!("{} m²", fmt.number(self.quad.area_mm2() / 1_000_000.0)));
+                    ui.label(format!("{} m²", fmt.number(record.quad.area_mm2() / 1_000_000.0)));
+                    ui.label(format!("{} m²", fmt.signed(diff.area_m2)));
+                    ui.end_row();
+                });
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.comparison_side_by_side, false, "Überlagert");
+                    ui.selectable_value(&mut self.comparison_side_by_side, true, "Nebeneinander");
+                });
+                if !self.comparison_side_by_side {
+                    ui.label(egui::RichText::new("Überlagert an A und Seite AB ausgerichtet").small());
+                }
+                if ui.button("Vergleich beenden").clicked() {
+                    close = true;
+                }
+            });
+        
+        if close {
+            self.comparison = None;
+        }
+    }
+
+    /// Übernimmt die Eingaben eines Verlaufseintrags und rechnet neu
+    fn restore_history(&mut self, idx: usize) {
+        let input = self.history[idx].input.clone();
+        self.load_inputs(&input);
+    }
+
+    /// Füllt die Eingabefelder mit gemessenen Werten und rechnet neu
+    fn load_inputs(&mut self, input: &QuadInput) {
+        self.fill_inputs(input);
+        self.calculate_quadrilateral();
+    }
+
+    /// Gültige Werte der Eingabefelder, fehlerhafte Felder bleiben leer
+    /// Wie beim Berechnen zählen zurückgeschriebene Rechenwerte nicht als Eingabe
+    fn field_input(&self) -> QuadInput {
+        let fmt = self.number_format();
+        let side_inputs = [&self.input_ab, &self.input_bc, &self.input_cd, &self.input_da];
+        let angle_inputs = [&self.input_angle_a, &self.input_angle_b, &self.input_angle_c, &self.input_angle_d];
+        let diagonal_inputs = [&self.input_diagonal_ac, &self.input_diagonal_bd];
+
+        let mut input = QuadInput::default();
+        for i in 0..4 {
+            if !self.computed_inputs[i] {
+                input.sides_mm[i] = parse_length_with_unit(side_inputs[i], &fmt, self.config.input_unit).ok().flatten();
+            }
+            if !self.computed_inputs[4 + i] {
+                input.angles[i] = parse_angle_input(angle_inputs[i], &fmt).ok().flatten();
+            }
+        }
+        for (i, text) in diagonal_inputs.iter().enumerate() {
+            if !self.computed_inputs[8 + i] {
+                input.diagonals_mm[i] = parse_length_with_unit(text, &fmt, self.config.input_unit).ok().flatten();
+            }
+        }
+        if !self.config.rectangle_mode {
+            return input;
+        }
+        match (input.sides_mm[0], input.sides_mm[1]) {
+            (Some(width), Some(height)) => QuadInput::rectangle(width, height),
+            _ => QuadInput::default(),
+        }
+    }
+
+    /// Vorlagen für wiederkehrende Maße: laden, aktuelle Eingaben speichern, verwalten
+    fn show_template_bar(&mut self, ui: &mut egui::Ui) {
+        let mut load = None;
+        let mut store = false;
+        egui::CollapsingHeader::new(format!("⭐ Vorlagen ({})", self.config.templates.len()))
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("input_template")
+                        .selected_text("Vorlage laden …")
+                        .width(200.0)
+                        .show_ui(ui, |ui| {
+                            if self.config.templates.is_empty() {
+                                ui.label(egui::RichText::new("Noch keine Vorlagen gespeichert").small());
+                            }
+                            for (idx, template) in self.config.templates.iter().enumerate() {
+                                if ui.selectable_label(false, &template.name).clicked() {
+                                    load = Some(idx);
+                                }
+                            }
+                        });
+                    if icon_button(ui, egui::Button::new("⚙").small(), "Vorlagen umbenennen oder löschen").clicked() {
+                        self.show_templates = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.input_template_name)
+                            .hint_text("z.B. Standard-Garage 6×3")
+                            .desired_width(200.0),
+                    );
+                    store = ui
+                        .add_enabled(!self.input_template_name.trim().is_empty(), egui::Button::new("💾 Speichern"))
+                        .on_hover_text("Aktuelle Eingaben als Vorlage speichern; gleicher Name ersetzt die Vorlage")
+                        .clicked();
+                });
+                match &self.template_message {
+                    Some(Ok(message)) => {
+                        ui.label(egui::RichText::new(message).small());
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                    }
+                    None => {}
+                }
+            });
+
+        if let Some(idx) = load {
+            let InputTemplate { name, input } = self.config.templates[idx].clone();
+            self.load_inputs(&input);
+            self.template_message = Some(Ok(format!("✅ Vorlage \"{}\" geladen", name)));
+        }
+        if store {
+            let input = self.field_input();
+            self.template_message = Some(if input == QuadInput::default() {
+                Err("Keine gültigen Maße eingegeben".to_string())
+            } else {
+                let name = self.input_template_name.trim().to_string();
+                let replaced = self.config.store_template(&name, input);
+                self.save_config();
+                self.input_template_name.clear();
+                Ok(format!("✅ Vorlage \"{}\" {}", name, if replaced { "ersetzt" } else { "gespeichert" }))
+            });
+        }
+    }
+
+    /// Vorlagen umbenennen und löschen
+    fn show_templates_window(&mut self, ctx: &egui::Context) {
+        let fmt = self.number_format();
+        let labels = self.config.labels.clone();
+        let mut open = self.show_templates;
+        let mut changed = false;
+        let mut delete = None;
+        egui::Window::new("⭐ Vorlagen verwalten")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.config.templates.is_empty() {
+                    ui.label("Noch keine Vorlagen gespeichert");
+                }
+                egui::Grid::new("templates_grid").num_columns(3).striped(true).show(ui, |ui| {
+                    for (idx, template) in self.config.templates.iter_mut().enumerate() {
+                        changed |= ui.add(egui::TextEdit::singleline(&mut template.name).desired_width(180.0)).changed();
+                        let sides: Vec<String> = template
+                            .input
+                            .sides_mm
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(i, side)| side.map(|mm| format!("{} {}", labels.side(i), fmt.number(mm))))
+                            .collect();
+                        ui.label(egui::RichText::new(sides.join(" · ")).small());
+                        if icon_button(ui, egui::Button::new("🗑").small(), "Vorlage löschen").clicked() {
+                            delete = Some(idx);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        self.show_templates = open;
+
+        if let Some(idx) = delete {
+            self.config.templates.remove(idx);
+            changed = true;
+        }
+        if changed {
+            self.save_config();
+        }
+    }
+
+    /// Skizze mit Ecken, Seiten und Winkeln, die Proportionen folgen den bisherigen Eingaben
+    fn show_label_sketch(&self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let input = self.field_input();
+        let fallback = if self.config.rectangle_mode { QuadInput::rectangle(4000.0, 3000.0) } else { SKETCH_FALLBACK };
+        let Ok(QuadSolution { quad, .. }) = solve(&input).or_else(|_| solve(&fallback)) else {
+            return;
+        };
+
+        let size = Vec2::new(ui.available_width().min(320.0), 150.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let mut orientation = self.orientation;
+        if quad.is_clockwise() != labels.clockwise {
+            orientation.mirror_x = !orientation.mirror_x;
+        }
+        if let Some(side) = self.config.reference_side {
+            orientation = orientation.with_baseline(&quad, side);
+        }
+        let view = ViewTransform::fit(response.rect, &quad.vertices, orientation, 30.0, 1.0, Vec2::ZERO);
+        let corners: Vec<Pos2> = quad.vertices.iter().map(|p| view.to_screen(p)).collect();
+        let center = corners.iter().fold(Vec2::ZERO, |sum, p| sum + p.to_vec2()) / 4.0;
+
+        let accent = Color32::from_rgb(50, 120, 200);
+        let text_color = ui.visuals().text_color();
+        painter.add(egui::Shape::convex_polygon(
+            corners.clone(),
+            Color32::from_rgba_unmultiplied(50, 120, 200, 30),
+            Stroke::new(2.0, accent),
+        ));
+
+        // Nach außen versetzt, damit Beschriftungen nicht auf den Kanten liegen
+        let outward = |p: Pos2, distance: f32| p + (p.to_vec2() - center).normalized() * distance;
+        for i in 0..4 {
+            let corner = corners[i];
+            let middle = corner + (corners[(i + 1) % 4] - corner) / 2.0;
+            painter.circle_filled(corner, 3.0, accent);
+            painter.text(outward(corner, 12.0), egui::Align2::CENTER_CENTER, labels.vertex(i), egui::FontId::proportional(15.0), text_color);
+            painter.text(outward(corner, -18.0), egui::Align2::CENTER_CENTER, format!("∠{}", labels.vertex(i)), egui::FontId::proportional(11.0), accent);
+            painter.text(outward(middle, 12.0), egui::Align2::CENTER_CENTER, labels.side(i), egui::FontId::proportional(12.0), text_color);
+        }
+    }
+
+    /// Schreibt Eingaben in die Felder, ohne zu berechnen
+    fn fill_inputs(&mut self, input: &QuadInput) {
+        let fmt = self.number_format();
+        let mm_per_unit = self.config.input_unit.mm_per_unit();
+        let fields = [
+            &mut self.input_ab,
+            &mut self.input_bc,
+            &mut self.input_cd,
+            &mut self.input_da,
+            &mut self.input_angle_a,
+            &mut self.input_angle_b,
+            &mut self.input_angle_c,
+            &mut self.input_angle_d,
+            &mut self.input_diagonal_ac,
+            &mut self.input_diagonal_bd,
+        ];
+        for (i, field) in fields.into_iter().enumerate() {
+            let value = match i {
+                0..=3 => input.sides_mm[i].map(|mm| mm / mm_per_unit),
+                4..=7 => input.angles[i - 4],
+                _ => input.diagonals_mm[i - 8].map(|mm| mm / mm_per_unit),
+            };
+            *field = value.map(|v| fmt.number(v)).unwrap_or_default();
+        }
+        self.computed_inputs = [false; 10];
+        self.input_errors = Default::default();
+    }
+
+    /// Rechnet die Längenfelder nach dem Wechsel der Eingabeeinheit um, damit die Maße gleich bleiben
+    fn convert_length_inputs(&mut self, previous: LengthUnit) {
+        let fmt = self.number_format();
+        let mm_per_unit = self.config.input_unit.mm_per_unit();
+        let fields = [
+            &mut self.input_ab,
+            &mut self.input_bc,
+            &mut self.input_cd,
+            &mut self.input_da,
+            &mut self.input_diagonal_ac,
+            &mut self.input_diagonal_bd,
+        ];
+        for field in fields {
+            if let Ok(Some(mm)) = parse_length_with_unit(field, &fmt, previous) {
+                *field = fmt.number(mm / mm_per_unit);
+            }
+        }
+    }
+
+    /// Fenster der Einführung mit Schrittnavigation, der zugehörige Bereich wird umrandet
+    fn show_tutorial(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.tutorial_step else {
+            return;
+        };
+        let step = &STEPS[index];
+        let done = match step.goal {
+            TutorialGoal::None => true,
+            TutorialGoal::InputsFilled => {
+                let sides = [&self.input_ab, &self.input_bc, &self.input_cd, &self.input_da];
+                let angles = [&self.input_angle_a, &self.input_angle_b, &self.input_angle_c, &self.input_angle_d];
+                let filled = |fields: &[&String]| fields.iter().filter(|f| !f.trim().is_empty()).count();
+                filled(&sides) + filled(&angles) >= 5 && filled(&angles) >= 1
+            }
+            TutorialGoal::Calculated => self.calculated,
+            TutorialGoal::LineDrawn => !self.custom_lines.is_empty(),
+        };
+
+        if let Some(rect) = step.target.and_then(|target| self.tutorial_rects.get(&target)) {
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("tutorial_highlight")));
+            painter.rect_stroke(rect.shrink(2.0), 6.0, Stroke::new(4.0, Color32::from_rgb(255, 140, 0)));
+        }
+
+        let (mut back, mut next, mut close, mut fill_example) = (false, false, false, false);
+        egui::Window::new(format!("🎓 Einführung ({}/{})", index + 1, STEPS.len()))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-20.0, -20.0])
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                ui.heading(step.title);
+                ui.add_space(5.0);
+                ui.label(step.text);
+                ui.add_space(8.0);
+
+                if step.goal == TutorialGoal::InputsFilled && ui.button("📝 Beispiel eintragen").clicked() {
+                    fill_example = true;
+                }
+                if step.goal != TutorialGoal::None {
+                    if done {
+                        ui.colored_label(Color32::from_rgb(0, 130, 0), "✔ Erledigt");
+                    } else {
+                        ui.label(egui::RichText::new("Noch offen – „Weiter“ überspringt den Schritt").small().italics());
+                    }
+                }
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(index > 0, egui::Button::new("◀ Zurück")).clicked() {
+                        back = true;
+                    }
+                    let last = index + 1 == STEPS.len();
+                    if ui.button(if last { "✔ Fertig" } else { "Weiter ▶" }).clicked() {
+                        next = true;
+                    }
+                    if !last && ui.button("Überspringen").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if fill_example {
+            self.config.rectangle_mode = false;
+            self.fill_inputs(&EXAMPLE_ROOM);
+        }
+        if back {
+            self.tutorial_step = Some(index - 1);
+        } else if next && index + 1 < STEPS.len() {
+            self.tutorial_step = Some(index + 1);
+        } else if next || close {
+            self.tutorial_step = None;
+            self.config.tutorial_seen = true;
+            self.save_config();
+        }
+    }
+
+    /// Konstruktionsweg der letzten Berechnung, auf Wunsch mit Herleitung Schritt für Schritt
+    fn show_construction_trace(&self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let Some(QuadSolution { trace, .. }) =
+            self.last_input.as_ref().and_then(|input| solve_with_tolerance(input, &self.config.plausibility).ok())
+        else {
+            return;
+        };
+        let fmt = self.number_format();
+        let unit = self.length_unit();
+        ui.label(format!("🛠 Konstruktion: {}", method_text(&trace.method, labels)));
+        egui::CollapsingHeader::new("🪜 Herleitung")
+            .default_open(false)
+            .show(ui, |ui| {
+                for (idx, step) in trace.steps.iter().enumerate() {
+                    ui.label(format!("{}. {}", idx + 1, step_text(step, labels, &fmt, unit)));
+                }
+            });
+    }
+
+    /// Kontrollmaß: vor Ort gemessene Diagonale mit der berechneten vergleichen
+    fn show_diagonal_check(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        egui::CollapsingHeader::new("📐 Kontrollmaß")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Diagonale:");
+                    for diagonal in 0..2 {
+                        ui.radio_value(&mut self.check_diagonal, diagonal, labels.diagonal(diagonal));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(format!("Gemessen ({}):", self.config.input_unit.label()));
+                    ui.add(egui::TextEdit::singleline(&mut self.input_check_diagonal).desired_width(120.0));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Toleranz:");
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.config.check_tolerance_mm)
+                            .speed(0.5)
+                            .range(0.0..=1000.0)
+                            .prefix("± ")
+                            .suffix(" mm"),
+                    );
+                    if response.changed() {
+                        self.save_config();
+                    }
+                });
+                
+                let computed_mm = self.quad.diagonal_um(self.check_diagonal) as f64 / 1000.0;
+                ui.label(format!(
+                    "Berechnet: {} mm{}",
+                    fmt.number(computed_mm),
+                    self.length_uncertainty_text(4 + self.check_diagonal)
+                ));
+                
+                match parse_length_with_unit(&self.input_check_diagonal, &fmt, self.config.input_unit) {
+                    Ok(Some(measured_mm)) => {
+                        let check = self.quad.check_diagonal(self.check_diagonal, measured_mm, self.config.check_tolerance_mm);
+                        ui.label(format!(
+                            "Abweichung: {} mm ({} %)",
+                            fmt.signed(check.deviation_mm),
+                            fmt.signed(check.deviation_percent)
+                        ));
+                        if check.within_tolerance {
+                            ui.colored_label(Color32::from_rgb(30, 140, 30), "✅ Innerhalb der Toleranz");
+                        } else {
+                            ui.colored_label(Color32::from_rgb(200, 40, 40), "❌ Außerhalb der Toleranz – Messungen prüfen");
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                    }
+                }
+            });
+    }
+
+    /// Rückwärtsrechnung: Seiten bleiben, gesucht ist der Winkel bei A für eine gewünschte Diagonale oder Fläche
+    fn show_inverse_solver(&mut self, ui: &mut egui::Ui, labels: &VertexLabels) {
+        let fmt = self.number_format();
+        let mut apply_angle = None;
+        egui::CollapsingHeader::new(format!("🔁 Rückwärtsrechnung (Winkel {})", labels.vertex(0)))
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Seiten bleiben fest, gesucht ist der Winkel für das Zielmaß").small());
+                ui.horizontal(|ui| {
+                    ui.label("Ziel:");
+                    for diagonal in 0..2 {
+                        ui.radio_value(&mut self.inverse_target, diagonal, labels.diagonal(diagonal));
+                    }
+                    ui.radio_value(&mut self.inverse_target, 2, "Fläche");
+                });
+                let submitted = ui
+                    .horizontal(|ui| {
+                        if self.inverse_target == 2 {
+                            ui.label("Soll (m²):");
+                        } else {
+                            ui.label(format!("Soll ({}):", self.config.input_unit.label()));
+                        }
+                        let response = ui.add(egui::TextEdit::singleline(&mut self.input_inverse).desired_width(120.0));
+                        let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        ui.button("Winkel berechnen").clicked() || enter
+                    })
+                    .inner;
+
+                if submitted {
+                    let target = if self.inverse_target == 2 {
+                        fmt.parse(self.input_inverse.trim())
+                            .filter(|area| *area > 0.0)
+                            .map(|area_m2| InverseTarget::Area { area_m2 })
+                            .ok_or_else(|| "Keine gültige Fläche".to_string())
+                    } else {
+                        match parse_length_with_unit(&self.input_inverse, &fmt, self.config.input_unit) {
+                            Ok(Some(length_mm)) => Ok(InverseTarget::Diagonal { diagonal: self.inverse_target, length_mm }),
+                            Ok(None) => Err("Bitte ein Sollmaß eingeben".to_string()),
+                            Err(e) => Err(e),
+                        }
+                    };
+                    let sides_mm = [0, 1, 2, 3].map(|side| self.quad.get_side_length_mm(side));
+                    self.inverse_result = Some(target.and_then(|target| angle_for_target(sides_mm, &target)));
+                }
+
+                match &self.inverse_result {
+                    Some(Ok(solutions)) => {
+                        let unit = self.length_unit();
+                        for solution in solutions {
+                            ui.horizontal(|ui| {
+                                ui.strong(format!("{} = {}", labels.vertex(0), fmt.angle(solution.angle_a_deg)));
+                                ui.label(format!(
+                                    "({} {}, {} {}, {} m²)",
+                                    labels.diagonal(0),
+                                    fmt.length(solution.diagonals_mm[0], unit),
+                                    labels.diagonal(1),
+                                    fmt.length(solution.diagonals_mm[1], unit),
+                                    fmt.number(solution.area_m2)
+                                ));
+                                if ui.button("Übernehmen").on_hover_text("Winkel eintragen und neu berechnen").clicked() {
+                                    apply_angle = Some(solution.angle_a_deg);
+                                }
+                            });
+                        }
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                    }
+                    None => {}
+                }
+            });
+
+        if let Some(angle) = apply_angle {
+            // Nur Seiten und der gefundene Winkel, sonst wäre die Eingabe überbestimmt
+            let input = QuadInput {
+                sides_mm: [0, 1, 2, 3].map(|side| Some(self.quad.get_side_length_mm(side))),
+                angles: [Some(angle), None, None, None],
+                ..QuadInput::default()
+            };
+            self.fill_inputs(&input);
+            self.calculate_quadrilateral();
+            self.inverse_result = None;
+        }
+    }
+
+    /// Zeichnet die Winkelhalbierende am Eckpunkt bis zur gegenüberliegenden Seite ein
+    fn construct_bisector(&mut self, vertex: usize) {
+        let fmt = self.number_format();
+        let Some((end_anchor, hit)) = self.quad.angle_bisector(vertex) else {
+            self.error_message = Some(format!(
+                "❌ Die Winkelhalbierende bei {} konnte nicht konstruiert werden.",
+                self.config.labels.vertex(vertex)
+            ));
+            return;
+        };
+        
+        let line = CustomLine::new(
+            format!("Winkelhalbierende {}", self.config.labels.vertex(vertex)),
+            LineAnchor::Vertex(vertex),
+            end_anchor,
+            &self.quad,
+            &self.custom_lines,
+        );
+        
+        if let LineAnchor::Side { side, .. } = end_anchor {
+            let unit = self.length_unit();
+            let from_vertex_mm = distance_um(&self.quad.vertices[side], &hit) as f64 / 1000.0;
+            self.construction_info = Some(format!(
+                "{}: Länge {}, trifft {} bei {} ab {}",
+                line.name,
+                fmt.length(line.length_um as f64 / 1000.0, unit),
+                self.config.labels.side(side),
+                fmt.length(from_vertex_mm, unit),
+                self.config.labels.vertex(side),
+            ));
+        }
+        
+        self.add_line(line);
+        self.selected_line = Some(self.custom_lines.len() - 1);
+        self.selected_endpoint = None;
+    }
+
+    /// Trimmt oder verlängert eine Linie bis zur gewählten Seite oder Linie
+    fn cut_line(&mut self, idx: usize, edge: CutEdge) {
+        if self.custom_lines[idx].locked {
+            self.error_message = Some(format!("🔒 {} ist gesperrt.", self.custom_lines[idx].name));
+            return;
+        }
+        match trim_extend_line(&self.quad, &mut self.custom_lines, idx, edge) {
+            Ok(edit) => {
+                let fmt = self.number_format();
+                let line = &self.custom_lines[idx];
+                self.construction_info = Some(format!(
+                    "{} {}: neue Länge {}",
+                    line.name,
+                    match edit {
+                        LineEdit::Trimmed => "getrimmt",
+                        LineEdit::Extended => "verlängert",
+                    },
+                    fmt.length(line.length_um as f64 / 1000.0, self.length_unit()),
+                ));
+                self.error_message = None;
+            }
+            Err(e) => self.error_message = Some(format!("❌ {}", e)),
+        }
+    }
+
+    /// Löscht eine Linie; daran hängende Linien werden mitgelöscht
+    fn delete_line(&mut self, idx: usize) {
+        if self.custom_lines[idx].locked {
+            self.error_message = Some(format!("🔒 {} ist gesperrt.", self.custom_lines[idx].name));
+            return;
+        }
+        let removed = remove_line_cascade(&mut self.custom_lines, idx);
+        
+        // Indizes, die auf spätere Linien zeigen, nachziehen
+        self.selected_line = remap_index(self.selected_line, &removed);
+        self.hovered_line = remap_index(self.hovered_line, &removed);
+        self.dragging_line_idx = remap_index(self.dragging_line_idx, &removed);
+        self.translating_line = None;
+        self.multi_selection = self.multi_selection.iter().filter_map(|&i| remap_index(Some(i), &removed)).collect();
+        self.angle_dimensions = self.angle_dimensions.iter().filter_map(|d| d.remap(&removed)).collect();
+        self.measure_result = self.measure_result.and_then(|d| d.remap(&removed));
+        self.measure_first = None;
+    }
+
+    /// Löscht mehrere Linien auf einmal, gesperrte bleiben stehen; mit Strg+Z rückgängig
+    fn delete_lines(&mut self, indices: &[usize]) {
+        let mut indices: Vec<usize> = indices.iter().copied().filter(|&idx| idx < self.custom_lines.len()).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        let locked = indices.iter().filter(|&&idx| self.custom_lines[idx].locked).count();
+        if locked == indices.len() {
+            if locked > 0 {
+                self.error_message = Some("🔒 Alle gewählten Linien sind gesperrt.".to_string());
+            }
+            return;
+        }
+
+        self.deleted_lines = Some(DeletedLines {
+            lines: self.custom_lines.clone(),
+            angle_dimensions: self.angle_dimensions.clone(),
+        });
+        // Von hinten löschen: abhängige Linien liegen immer dahinter, die übrigen Indizes bleiben gültig
+        for &idx in indices.iter().rev() {
+            if idx < self.custom_lines.len() && !self.custom_lines[idx].locked {
+                self.delete_line(idx);
+            }
+        }
+        self.multi_selection.clear();
+        if locked > 0 {
+            self.error_message = Some(format!("🔒 {} gesperrte Linie(n) wurden nicht gelöscht.", locked));
+        }
+    }
+
+    /// Stellt die Linien vor dem letzten Sammel-Löschen wieder her
+    fn undo_delete_lines(&mut self) {
+        if let Some(deleted) = self.deleted_lines.take() {
+            self.custom_lines = deleted.lines;
+            self.angle_dimensions = deleted.angle_dimensions;
+            update_all_lines(&self.quad, &mut self.custom_lines);
+            self.selected_line = None;
+            self.hovered_line = None;
+            self.selected_endpoint = None;
+            self.multi_selection.clear();
+        }
+    }
+
+    /// Rückfrage vor "Alle Linien entfernen"
+    fn show_clear_lines_dialog(&mut self, ctx: &egui::Context) {
+        let (mut confirm, mut cancel) = (false, false);
+        egui::Window::new("🗑 Alle Linien entfernen?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let locked = self.custom_lines.iter().filter(|l| l.locked).count();
+                ui.label(format!("{} Linie(n) werden gelöscht.", self.custom_lines.len() - locked));
+                if locked > 0 {
+                    ui.label(format!("🔒 {} gesperrte Linie(n) bleiben erhalten.", locked));
+                }
+                ui.label(egui::RichText::new("Mit ↩ Rückgängig bzw. Strg+Z wiederherstellbar").small());
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    confirm = ui.button("Entfernen").clicked();
+                    cancel = ui.button("Abbrechen").clicked();
+                });
+            });
+        if cancel || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.confirm_clear_lines = false;
+        } else if confirm {
+            self.confirm_clear_lines = false;
+            let all: Vec<usize> = (0..self.custom_lines.len()).collect();
+            self.delete_lines(&all);
+        }
+    }
+
+    /// Speichert die Einstellungen; ein Fehler landet im Fehlerprotokoll statt nur auf stderr
+    fn save_config(&self) {
+        if let Err(e) = self.config.save() {
+            diagnostics::log(LogLevel::Error, "Einstellungen", &e);
+        }
+    }
+
+    /// Zahlenformat aus den Einstellungen (Nachkommastellen, Trennzeichen)
+    fn number_format(&self) -> NumberFormat {
+        NumberFormat::from_config(&self.config)
+    }
+
+    /// Einheit für Längenangaben; "Automatisch" zeigt cm, solange keine Seite 10 m oder länger ist
+    fn length_unit(&self) -> LengthUnit {
+        if self.config.length_unit != LengthUnit::Auto {
+            return self.config.length_unit;
+        }
+        let max_length_um = (0..4)
+            .map(|i| self.quad.get_side_length_um(i))
+            .fold(0_i64, |a, b| a.max(b));
+        if max_length_um < 10_000_000 {
+            LengthUnit::Centimeter
+        } else {
+            LengthUnit::Meter
+        }
+    }
+
+    /// Bisher eingetragene Messwerte; leere, fehlerhafte und berechnete Felder zählen nicht
+    fn entered_input(&self) -> QuadInput {
+        let fmt = self.number_format();
+        let unit = self.config.input_unit;
+        let length = |field: usize, text: &str| {
+            (!self.computed_inputs[field]).then(|| parse_length_with_unit(text, &fmt, unit).ok().flatten()).flatten()
+        };
+        let angle = |field: usize, text: &str| (!self.computed_inputs[field]).then(|| parse_angle_input(text, &fmt).ok().flatten()).flatten();
+        QuadInput {
+            sides_mm: [
+                length(0, &self.input_ab),
+                length(1, &self.input_bc),
+                length(2, &self.input_cd),
+                length(3, &self.input_da),
+            ],
+            angles: [
+                angle(4, &self.input_angle_a),
+                angle(5, &self.input_angle_b),
+                angle(6, &self.input_angle_c),
+                angle(7, &self.input_angle_d),
+            ],
+            diagonals_mm: [length(8, &self.input_diagonal_ac), length(9, &self.input_diagonal_bd)],
+        }
+    }
+
+    fn calculate_quadrilateral(&mut self) {
+        let fmt = self.number_format();
+        self.reset_undo = None;
+        self.replay_error = None;
+        self.error_message = None;
+        self.recalc_warning = None;
+        self.plausibility_warnings.clear();
+        
+        let side_inputs = [&self.input_ab, &self.input_bc, &self.input_cd, &self.input_da];
+        let angle_inputs = [
+            &self.input_angle_a,
+            &self.input_angle_b,
+            &self.input_angle_c,
+            &self.input_angle_d,
+        ];
+        
+        // Alle Felder prüfen; fehlerhafte Felder werden rot markiert
+        // Zurückgeschriebene Rechenwerte bleiben außen vor, sonst wäre die Eingabe überbestimmt
+        let mut input = QuadInput::default();
+        for i in 0..4 {
+            if self.computed_inputs[i] {
+                self.input_errors[i] = None;
+            } else {
+                match parse_length_with_unit(side_inputs[i], &fmt, self.config.input_unit) {
+                    Ok(value) => {
+                        input.sides_mm[i] = value;
+                        self.input_errors[i] = None;
+                    }
+                    Err(e) => self.input_errors[i] = Some(e),
+                }
+            }
+            if self.computed_inputs[4 + i] {
+                self.input_errors[4 + i] = None;
+                continue;
+            }
+            match parse_angle_input(angle_inputs[i], &fmt) {
+                Ok(value) => {
+                    input.angles[i] = value;
+                    self.input_errors[4 + i] = None;
+                }
+                Err(e) => self.input_errors[4 + i] = Some(e),
+            }
+        }
+        let diagonal_inputs = [&self.input_diagonal_ac, &self.input_diagonal_bd];
+        for (i, text) in diagonal_inputs.iter().enumerate() {
+            if self.computed_inputs[8 + i] {
+                self.input_errors[8 + i] = None;
+                continue;
+            }
+            match parse_length_with_unit(text, &fmt, self.config.input_unit) {
+                Ok(value) => {
+                    input.diagonals_mm[i] = value;
+                    self.input_errors[8 + i] = None;
+                }
+                Err(e) => self.input_errors[8 + i] = Some(e),
+            }
+        }
+        
+        if self.input_errors.iter().any(|e| e.is_some()) {
+            return;
+        }
+
+        match solve_with_tolerance(&input, &self.config.plausibility) {
+            Ok(QuadSolution { quad, warnings, .. }) => {
+                self.log_calculation(&input, &quad);
+                self.plausibility_warnings = warnings;
+                // Unveränderte Wiederholung nicht erneut in den Verlauf aufnehmen
+                if self.history.first().is_none_or(|last| last.input != input) {
+                    self.history.insert(0, CalculationRecord {
+                        time: chrono::Local::now().format("%H:%M:%S").to_string(),
+                        input: input.clone(),
+                        quad: quad.clone(),
+                    });
+                    self.history.truncate(HISTORY_LEN);
+                }
+                let keep_lines = self.config.keep_lines_on_recalc && self.calculated && !self.custom_lines.is_empty();
+                let previous = std::mem::replace(&mut self.quad, quad);
+                self.calculated = true;
+                self.last_input = Some(input);
+                self.tile_estimate = None;
+                if !self.quad.diagonal_inside(self.triangle_diagonal) {
+                    self.triangle_diagonal = 1 - self.triangle_diagonal;
+                }
+                self.update_uncertainty();
+                self.write_back_inputs();
+                self.zoom = 1.0;
+                self.pan = Vec2::ZERO;
+                self.construction_info = None;
+                if keep_lines {
+                    // Anker (Seite + Verhältnis bzw. Eckpunkt) bleiben, nur die Lage wird neu bestimmt
+                    update_all_lines(&self.quad, &mut self.custom_lines);
+                    if self.is_reshaped(&previous) {
+                        self.recalc_warning = Some("⚠ Die Form hat sich stark verändert – bitte die übertragenen Linien prüfen.".to_string());
+                    }
+                } else {
+                    // Winkelmaße zwischen Seiten bleiben, solche an Linien entfallen mit den Linien
+                    let sides_only = |d: &AngleDimension| matches!((d.first, d.second), (CutEdge::Side(_), CutEdge::Side(_)));
+                    self.angle_dimensions.retain(sides_only);
+                    self.measure_result = self.measure_result.filter(sides_only);
+                    self.measure_first = None;
+                    self.custom_lines.clear();
+                    self.selected_line = None;
+                    self.multi_selection.clear();
+                    self.next_line_number = 1;
+                    self.custom_circles.clear();
+                    self.selected_circle = None;
+                    self.next_circle_number = 1;
+                }
+            }
+            Err(e) => {
+                let input_json = serde_json::to_string(&input).unwrap_or_default();
+                diagnostics::log(LogLevel::Error, "Berechnung", &format!("{} – Eingabe: {}", e, input_json));
+                self.error_message = Some(e.message(self.config.language, &self.number_format(), &self.config.labels));
+                self.calculated = false;
+            }
+        }
+    }
+
+    /// Rechteck-Schnellmodus: Breite (AB) und Höhe (BC) genügen, beide Diagonalen werden eingezeichnet
+    fn calculate_rectangle(&mut self) {
+        let fmt = self.number_format();
+        let width = parse_length_with_unit(&self.input_ab, &fmt, self.config.input_unit);
+        let height = parse_length_with_unit(&self.input_bc, &fmt, self.config.input_unit);
+        self.input_errors[0] = width.clone().err();
+        self.input_errors[1] = height.clone().err();
+
+        match (width, height) {
+            (Ok(Some(width_mm)), Ok(Some(height_mm))) => {
+                self.load_inputs(&QuadInput::rectangle(width_mm, height_mm));
+                if self.calculated {
+                    self.add_diagonal_lines();
+                }
+            }
+            (Ok(_), Ok(_)) => {
+                self.error_message = Some("Für das Rechteck Breite und Höhe angeben".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    /// Zeichnet die Diagonalen AC und BD als Linien ein, sofern noch nicht vorhanden
+    fn add_diagonal_lines(&mut self) {
+        for diagonal in 0..2 {
+            let (start, end) = (LineAnchor::Vertex(diagonal), LineAnchor::Vertex(diagonal + 2));
+            if self.custom_lines.iter().any(|l| l.start_anchor == start && l.end_anchor == end) {
+                continue;
+            }
+            let name = format!("Diagonale {}", self.config.labels.diagonal(diagonal));
+            let line = CustomLine::new(name, start, end, &self.quad, &self.custom_lines);
+            self.add_line(line);
+        }
+    }
+
+    /// Weicht eine Seite um mehr als RESHAPE_SIDE_RATIO oder ein Winkel um mehr als RESHAPE_ANGLE_DEG ab?
+    fn is_reshaped(&self, previous: &Quadrilateral) -> bool {
+        let comparison = self.quad.compare(previous);
+        (0..4).any(|i| comparison.sides_mm[i].abs() > previous.get_side_length_mm(i) * RESHAPE_SIDE_RATIO)
+            || comparison.angles_deg.iter().any(|a| a.abs() > RESHAPE_ANGLE_DEG)
+    }
+
+    /// Schreibt die Werte des Modells in die Eingabefelder zurück
+    /// Gemessene Felder behalten ihren Status, alle anderen werden als "berechnet" markiert
+    fn write_back_inputs(&mut self) {
+        let Some(input) = self.last_input.clone() else {
+            return;
+        };
+        let fmt = self.number_format();
+        let mm_per_unit = self.config.input_unit.mm_per_unit();
+        let angles = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
+        let fields = [
+            &mut self.input_ab,
+            &mut self.input_bc,
+            &mut self.input_cd,
+            &mut self.input_da,
+            &mut self.input_angle_a,
+            &mut self.input_angle_b,
+            &mut self.input_angle_c,
+            &mut self.input_angle_d,
+            &mut self.input_diagonal_ac,
+            &mut self.input_diagonal_bd,
+        ];
+        
+        for (i, field) in fields.into_iter().enumerate() {
+            let (measured, model) = match i {
+                0..=3 => (input.sides_mm[i], Some(self.quad.get_side_length_mm(i))),
+                4..=7 => (input.angles[i - 4], angles[i - 4]),
+                _ => (input.diagonals_mm[i - 8], Some(self.quad.diagonal_um(i - 8) as f64 / 1000.0)),
+            };
+            if let Some(value) = measured.or(model) {
+                // Längen in der Eingabeeinheit, Winkel in Grad
+                *field = fmt.number(if (4..=7).contains(&i) { value } else { value / mm_per_unit });
+            }
+            self.computed_inputs[i] = measured.is_none();
+            self.input_errors[i] = None;
+        }
+    }
+
+    /// Berechnet die Fehlerfortpflanzung für die letzte Eingabe neu
+    fn update_uncertainty(&mut self) {
+        self.uncertainty = match &self.last_input {
+            Some(input) if self.calculated && !self.config.uncertainty.is_zero() => {
+                Some(propagate(input, &self.config.uncertainty))
+            }
+            _ => None,
+        };
+    }
+
+    /// " ± 4,0 mm" für eine Seite (0–3) bzw. Diagonale (4–5), leer ohne Unsicherheit
+    fn length_uncertainty_text(&self, index: usize) -> String {
+        match &self.uncertainty {
+            Some(Ok(result)) => {
+                let value = if index < 4 { result.sides_mm[index] } else { result.diagonals_mm[index - 4] };
+                format!(" ± {} mm", self.number_format().number(value))
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// " ± 0,35°" für einen Winkel, leer ohne Unsicherheit
+    fn angle_uncertainty_text(&self, vertex: usize) -> String {
+        match &self.uncertainty {
+            Some(Ok(result)) => format!(" ± {}", self.number_format().angle(result.angles_deg[vertex])),
+            _ => String::new(),
+        }
+    }
+
+    /// Gewählten Umlaufsinn der Beschriftung durch Spiegeln der Ansicht herstellen
+    /// Mit Bezugsseite ersetzt deren Lage die eingestellte Drehung
+    fn view_orientation(&self) -> ViewOrientation {
+        let mut orientation = self.orientation;
+        if self.quad.is_clockwise() != self.config.labels.clockwise {
+            orientation.mirror_x = !orientation.mirror_x;
+        }
+        match self.config.reference_side {
+            Some(side) if self.calculated => orientation.with_baseline(&self.quad, side),
+            _ => orientation,
+        }
+    }
+
+    /// Punkte, die ins Bild passen müssen: Eckpunkte und Linienenden (auch außerhalb des Vierecks)
+    fn fit_points(&self, moving: Option<usize>) -> Vec<Point> {
+        self.quad
+            .vertices
+            .iter()
+            .chain(
+                self.custom_lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| Some(*idx) != moving)
+                    .flat_map(|(_, line)| [&line.start, &line.end]),
+            )
+            .cloned()
+            .collect()
+    }
+
+    /// Zeichnung in der eingestellten Auflösung rendern und als PNG speichern
+    /// Ausschnitt wie "Ansicht zurücksetzen", unabhängig von Zoom und Fenstergröße
+    fn export_image(&self) -> Result<std::path::PathBuf, String> {
+        let resolution = self.config.image_export.resolution(self.canvas_rect.width())?;
+        let rect = resolution.rect();
+        let view = ViewTransform::fit(rect, &self.fit_points(None), self.view_orientation(), VIEW_PADDING, 1.0, Vec2::ZERO);
+
+        let fonts = Fonts::new(resolution.pixels_per_point, 8192, egui::FontDefinitions::default());
+        let mut scene = SceneBuilder::with_fonts(fonts.clone());
+        self.scene_source().build(&mut scene, &view, rect);
+        let image = raster::render(scene.into_shapes(), &fonts, &resolution);
+
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| format!("PNG konnte nicht erstellt werden: {}", e))?;
+        export::save_to_desktop("zeichnung", "png", png.get_ref())
+    }
+
+    /// Einstellungen und Knopf für den Bildexport
+    fn show_image_export(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🖼 Bildexport")
+            .default_open(false)
+            .show(ui, |ui| {
+                let settings = &mut self.config.image_export;
+                let before = settings.clone();
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut settings.mode, ImageSizeMode::Paper, "Papierformat");
+                    ui.radio_value(&mut settings.mode, ImageSizeMode::Pixels, "Pixel");
+                });
+                match settings.mode {
+                    ImageSizeMode::Paper => {
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("image_paper")
+                                .selected_text(settings.paper.label())
+                                .show_ui(ui, |ui| {
+                                    for paper in [PaperSize::A4, PaperSize::A3, PaperSize::A2, PaperSize::A1, PaperSize::A0] {
+                                        ui.selectable_value(&mut settings.paper, paper, paper.label());
+                                    }
+                                });
+                            ui.checkbox(&mut settings.landscape, "Querformat");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Auflösung:");
+                            ui.add(egui::DragValue::new(&mut settings.dpi).speed(10).range(72..=1200).suffix(" dpi"));
+                        });
+                    }
+                    ImageSizeMode::Pixels => {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut settings.width_px).speed(10).range(100..=MAX_IMAGE_SIDE_PX));
+                            ui.label("×");
+                            ui.add(egui::DragValue::new(&mut settings.height_px).speed(10).range(100..=MAX_IMAGE_SIDE_PX));
+                            ui.label("px");
+                        });
+                    }
+                }
+                if *settings != before {
+                    self.save_config();
+                }
+
+                match self.config.image_export.resolution(self.canvas_rect.width()) {
+                    Ok(resolution) => {
+                        ui.label(egui::RichText::new(format!("{} × {} px", resolution.width_px, resolution.height_px)).small());
+                        if ui.button("💾 Bild (PNG)").clicked() {
+                            self.image_export_message = Some(match self.export_image() {
+                                Ok(path) => format!("✅ Gespeichert: {}", path.display()),
+                                Err(e) => format!("❌ {}", e),
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                    }
+                }
+                if let Some(message) = &self.image_export_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+            });
+    }
+
+    fn draw_quadrilateral(&mut self, ui: &mut egui::Ui) {
+        let available_size = ui.available_size();
+        let (response, painter) = ui.allocate_painter(available_size, egui::Sense::click_and_drag());
+
+        let padding = VIEW_PADDING;
+        let orientation = self.view_orientation();
+        // Die gerade gezogene Linie zählt nicht, sonst liefe die Ansicht dem Cursor davon
+        let moving = self.dragging_line_idx.or(self.translating_line.as_ref().map(|(idx, ..)| *idx));
+        let fit_points = self.fit_points(moving);
+        let mut view = ViewTransform::fit(response.rect, &fit_points, orientation, padding, self.zoom, self.pan);
+
+        // Zoom mit dem Mausrad, der Punkt unter dem Cursor bleibt dabei stehen
+        if let Some(hover_pos) = response.hover_pos() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                let world_at_cursor = view.to_world(hover_pos);
+                self.zoom = (self.zoom * (scroll * 0.002).exp()).clamp(0.1, 50.0);
+                view = ViewTransform::fit(response.rect, &fit_points, orientation, padding, self.zoom, self.pan);
+                self.pan += hover_pos - view.to_screen(&world_at_cursor);
+                view = ViewTransform::fit(response.rect, &fit_points, orientation, padding, self.zoom, self.pan);
+            }
+        }
+
+        let to_screen = |p: &Point| -> Pos2 { view.to_screen(p) };
+
+        // Statische Darstellung nur bei Änderungen neu aufbauen
+        let source = self.scene_source();
+        let key = source.key(&view, response.rect, ui.ctx().pixels_per_point());
+        if !self.scene.is_current(key) {
+            let mut scene = SceneBuilder::new(&painter);
+            source.build(&mut scene, &view, response.rect);
+            self.scene.store(key, scene, response.rect);
+        }
+        self.scene.paint(&painter);
+
+        self.draw_rulers(&painter, response.rect, &view);
+        self.draw_status_bar(&painter, response.rect, &view, response.hover_pos());
+
+        // ========== LINIEN-INTERAKTION: HOVER UND VERSCHIEBEN ==========
+        let pointer_pos = response.interact_pointer_pos();
+        
+        // Hover-Erkennung für Linien-Endpunkte
+        if let Some(pos) = pointer_pos {
+            let hit = self.hit_test(pos, &view);
+            self.hovered_line = None;
+            
+            if !self.drawing_line && self.dragging_line_idx.is_none() && self.translating_line.is_none() {
+                // Gesperrte Linien werden nicht hervorgehoben, da sie sich nicht verschieben lassen
+                if let Some(Hit::LineEnd { line, .. } | Hit::Line(line)) = hit {
+                    self.hovered_line = Some(line).filter(|&idx| !self.custom_lines[idx].locked);
+                }
+            }
+
+            // Strg+Klick ergänzt die Mehrfachauswahl, ein normaler Klick wählt die Linie samt ihrer Gruppe
+            let hit_line = match hit {
+                Some(Hit::LineEnd { line, .. } | Hit::Line(line)) => Some(line),
+                _ => None,
+            };
+            if response.clicked() && ui.input(|i| i.modifiers.command) {
+                if let Some(line) = hit_line {
+                    self.toggle_multi_selection(line);
+                }
+            } else if response.clicked() {
+                self.multi_selection = hit_line
+                    .and_then(|line| self.custom_lines[line].group.clone())
+                    .map(|group| group_members(&self.custom_lines, &group))
+                    .unwrap_or_default();
+                self.selected_line = match hit {
+                    Some(Hit::LineEnd { line, .. } | Hit::Line(line)) => Some(line),
+                    _ => None,
+                };
+                self.selected_endpoint = match hit {
+                    Some(Hit::LineEnd { at_start, .. }) => Some(at_start),
+                    _ => None,
+                };
+                self.selected_circle = match hit {
+                    Some(Hit::Circle(idx)) => Some(idx),
+                    _ => None,
+                };
+            }
+
+            // ========== AUSWAHLRAHMEN: Shift + Ziehen, mit Strg zur Auswahl hinzufügen ==========
+            if response.drag_started() && ui.input(|i| i.modifiers.shift) {
+                self.selection_box = Some(pos);
+            }
+            let box_selecting = self.selection_box.is_some();
+            if let Some(origin) = self.selection_box {
+                let rect = egui::Rect::from_two_pos(origin, pos);
+                painter.rect(
+                    rect,
+                    0.0,
+                    Color32::from_rgba_unmultiplied(80, 160, 255, 30),
+                    Stroke::new(1.0, Color32::from_rgb(80, 160, 255)),
+                );
+                if response.drag_stopped() {
+                    if !ui.input(|i| i.modifiers.command) {
+                        self.multi_selection.clear();
+                    }
+                    let inside: Vec<usize> = self.scene.hits.lines_inside(rect).collect();
+                    for idx in inside {
+                        if !self.multi_selection.contains(&idx) {
+                            self.multi_selection.push(idx);
+                        }
+                    }
+                    self.selection_box = None;
+                }
+            }
+
+            // ========== KREISE UND BÖGEN AUFZIEHEN ==========
+            if matches!(self.tool, DrawTool::Circle | DrawTool::Arc) && !box_selecting {
+                if response.drag_started() {
+                    self.circle_center = Some(self.snap_point(pos, &view));
+                }
+                
+                if let Some(center) = self.circle_center.clone() {
+                    let center_screen = to_screen(&center);
+                    painter.circle_stroke(
+                        center_screen,
+                        (pos - center_screen).length(),
+                        Stroke::new(2.0, Color32::from_rgba_unmultiplied(120, 60, 160, 128)),
+                    );
+                    
+                    if response.drag_stopped() {
+                        let edge = view.to_world(pos);
+                        let radius_um = distance_um(&center, &edge);
+                        if radius_um > 0 {
+                            let (arc, prefix) = if self.tool == DrawTool::Arc {
+                                let start_deg = (edge.y - center.y).atan2(edge.x - center.x).to_degrees();
+                                (Some(CustomArc { start_deg, sweep_deg: self.arc_sweep_deg }), "Bogen")
+                            } else {
+                                (None, "Kreis")
+                            };
+                            let name = format!("{} {}", prefix, self.next_circle_number);
+                            self.next_circle_number += 1;
+                            self.custom_circles.push(CustomCircle::new(name, center, radius_um, arc));
+                            self.selected_circle = Some(self.custom_circles.len() - 1);
+                            self.selected_line = None;
+                        }
+                        self.circle_center = None;
+                    }
+                }
+            }
+
+            // ========== WINKEL MESSEN: zwei Kanten nacheinander anklicken ==========
+            if self.tool == DrawTool::Measure && response.clicked() {
+                if let Some(edge) = self.find_edge_at(pos, &view) {
+                    match self.measure_first.take() {
+                        Some(first) if first != edge => {
+                            self.measure_result = Some(AngleDimension { first, second: edge });
+                        }
+                        Some(_) => {}
+                        None => {
+                            self.measure_first = Some(edge);
+                            self.measure_result = None;
+                        }
+                    }
+                }
+            }
+
+            // ========== GANZE LINIE PARALLEL VERSCHIEBEN: Alt + Ziehen an der Linienmitte ==========
+            if response.drag_started() && ui.input(|i| i.modifiers.alt) && !self.drawing_line && self.tool == DrawTool::Line && !box_selecting {
+                if let Some(Hit::Line(line)) = hit {
+                    if !self.custom_lines[line].locked {
+                        self.translating_line = Some((line, self.custom_lines[line].clone(), view.to_world(pos)));
+                        self.selected_line = Some(line);
+                        self.selected_endpoint = None;
+                    }
+                }
+            }
+
+            if let Some((idx, original, origin)) = self.translating_line.clone() {
+                if response.dragged() {
+                    // Versatz senkrecht zur Linie; die Richtung bleibt, die Enden gleiten auf ihren Bezugsstrecken
+                    let current = view.to_world(pos);
+                    let (dx, dy) = (original.end.x - original.start.x, original.end.y - original.start.y);
+                    let len = (dx * dx + dy * dy).sqrt();
+                    if len > 0.0 {
+                        let offset_mm = ((current.x - origin.x) * -dy + (current.y - origin.y) * dx) / len / 1000.0;
+                        let previous = std::mem::replace(&mut self.custom_lines[idx], original);
+                        match translate_anchors(&self.quad, &self.custom_lines, idx, offset_mm) {
+                            Ok((start, end)) => {
+                                self.custom_lines[idx].start_anchor = start;
+                                self.custom_lines[idx].end_anchor = end;
+                                update_all_lines(&self.quad, &mut self.custom_lines);
+                            }
+                            // Zu weit verschoben: Linie bleibt an der letzten gültigen Lage
+                            Err(_) => self.custom_lines[idx] = previous,
+                        }
+                    }
+                }
+                if response.drag_stopped() {
+                    self.translating_line = None;
+                }
+            }
+
+            // ========== DRAG START: Endpunkt zum Verschieben auswählen ==========
+            if response.drag_started() && !self.drawing_line && self.tool == DrawTool::Line && !box_selecting {
+                if let Some(Hit::LineEnd { line, at_start }) = hit {
+                    if !self.custom_lines[line].locked {
+                        self.dragging_line_idx = Some(line);
+                        self.selected_line = Some(line);
+                        self.selected_endpoint = Some(at_start);
+                        // Merke welcher Endpunkt verschoben wird
+                        self.drag_offset = if at_start {
+                            Vec2::new(0.0, 0.0) // Start-Punkt wird verschoben
+                        } else {
+                            Vec2::new(1.0, 0.0) // End-Punkt wird verschoben (x=1 als Flag)
+                        };
+                    }
+                }
+            }
+
+            // ========== WÄHREND DES VERSCHIEBENS ==========
+            if let Some(drag_idx) = self.dragging_line_idx {
+                if response.dragged() {
+                    let moving_start = self.drag_offset.x == 0.0; // true = Start, false = End
+                    
+                    // Nächster Anker: Seite oder eine Linie mit kleinerem Index, abseits davon ein freier Punkt
+                    let line = &self.custom_lines[drag_idx];
+                    let fixed = if moving_start { line.end.clone() } else { line.start.clone() };
+                    let (target, guide) = self.guided_position(pos, &view, drag_idx, &fixed);
+                    self.active_guide = guide;
+                    let anchor = self.place_anchor(target, &view, drag_idx);
+                    let line = &mut self.custom_lines[drag_idx];
+                    if moving_start {
+                        line.start_anchor = anchor;
+                    } else {
+                        line.end_anchor = anchor;
+                    }
+                    
+                    // Linie und alle abhängigen Linien neu berechnen
+                    update_all_lines(&self.quad, &mut self.custom_lines);
+                }
+            }
+
+            if let (Some(guide), Some(drag_idx)) = (self.active_guide, self.dragging_line_idx) {
+                let line = &self.custom_lines[drag_idx];
+                let end = if self.drag_offset.x == 0.0 { &line.start } else { &line.end };
+                self.draw_guide(&painter, &guide, to_screen(end));
+            }
+
+            if response.drag_stopped() {
+                self.dragging_line_idx = None;
+                self.active_guide = None;
+            }
+
+            // ========== ZEICHNEN NEUER LINIEN ==========
+            if self.dragging_line_idx.is_none() && self.translating_line.is_none() && self.tool == DrawTool::Line && !box_selecting {
+                let all_lines = self.custom_lines.len();
+                
+                if response.drag_started() && !self.drawing_line {
+                    self.line_start = Some((self.place_anchor(pos, &view, all_lines), pos));
+                    self.drawing_line = true;
+                }
+
+                // Doppelklick auf Seite oder Linie: Startpunkt über exakten Abstand setzen
+                if response.double_clicked() && !self.drawing_line {
+                    if let Some((anchor, dist)) = self.find_anchor(pos, &view, all_lines) {
+                        if dist < 10.0 && matches!(anchor, LineAnchor::Side { .. } | LineAnchor::Line { .. }) {
+                            self.distance_input = Some(DistanceInput {
+                                anchor,
+                                pos,
+                                text: String::new(),
+                                error: None,
+                                request_focus: true,
+                            });
+                        }
+                    }
+                }
+
+                if self.drawing_line {
+                    self.preview_end = Some(pos);
+                    
+                    if let Some((start_anchor, _)) = self.line_start {
+                        let start_point = anchor_point(&self.quad, &self.custom_lines, &start_anchor);
+                        let start_screen = to_screen(&start_point);
+                        
+                        painter.line_segment(
+                            [start_screen, pos],
+                            Stroke::new(3.0, Color32::from_rgba_unmultiplied(200, 100, 0, 128)),
+                        );
+                    }
+                }
+
+                let click_end = self.line_click_end && response.clicked();
+                if (response.drag_stopped() || click_end) && self.drawing_line {
+                    if let Some((start_anchor, _)) = self.line_start {
+                        let end_anchor = self.place_anchor(pos, &view, all_lines);
+                        let line = CustomLine::new(String::new(), start_anchor, end_anchor, &self.quad, &self.custom_lines);
+                        if line.length_um > 0 {
+                            let name = format!("Linie {}", self.next_line_number);
+                            self.next_line_number += 1;
+                            self.add_line(CustomLine { name, ..line });
+                            self.selected_line = Some(self.custom_lines.len() - 1);
+                            self.selected_endpoint = None;
+                        }
+                    }
+                    
+                    self.drawing_line = false;
+                    self.line_start = None;
+                    self.preview_end = None;
+                    self.line_click_end = false;
+                }
+            }
+        }
+
+        // Start aus der Abstandseingabe: Vorschau folgt dem Cursor bis zum abschließenden Klick, Esc bricht ab
+        if self.drawing_line && self.line_click_end {
+            if let (Some((start_anchor, _)), Some(hover)) = (self.line_start, response.hover_pos()) {
+                let start_point = anchor_point(&self.quad, &self.custom_lines, &start_anchor);
+                painter.line_segment(
+                    [to_screen(&start_point), hover],
+                    Stroke::new(3.0, Color32::from_rgba_unmultiplied(200, 100, 0, 128)),
+                );
+            }
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.drawing_line = false;
+                self.line_start = None;
+                self.preview_end = None;
+                self.line_click_end = false;
+            }
+        }
+        self.show_distance_input(ui.ctx());
+
+        // ========== LUPE ==========
+        if let Some(pos) = pointer_pos {
+            let placing = self.drawing_line || self.dragging_line_idx.is_some();
+            if placing && self.config.snap.magnifier {
+                self.draw_magnifier(&painter, response.rect, &view, pos);
+            }
+        }
+
+        // ========== FEINJUSTIERUNG PER PFEILTASTEN ==========
+        if let (Some(idx), Some(at_start)) = (self.selected_line, self.selected_endpoint) {
+            if !ui.ctx().wants_keyboard_input() {
+                let (steps, shift) = arrow_key_steps(ui);
+                if steps != 0.0 && self.custom_lines.get(idx).is_some_and(|l| !l.locked) {
+                    let factor = if shift { 10.0 } else { 1.0 };
+                    let delta_mm = steps * factor * self.config.snap.nudge_step_mm;
+                    let (parents, rest) = self.custom_lines.split_at_mut(idx);
+                    let line = &mut rest[0];
+                    let anchor = if at_start { &mut line.start_anchor } else { &mut line.end_anchor };
+                    *anchor = nudge_anchor(&self.quad, parents, anchor, delta_mm);
+                    update_all_lines(&self.quad, &mut self.custom_lines);
+                }
+            }
+        } else if !self.multi_selection.is_empty() && !ui.ctx().wants_keyboard_input() {
+            // Ohne gewählten Endpunkt verschieben die Pfeiltasten die ganze Auswahl
+            let (steps, shift) = arrow_key_steps(ui);
+            if steps != 0.0 {
+                let factor = if shift { 10.0 } else { 1.0 };
+                let delta_mm = steps * factor * self.config.snap.nudge_step_mm;
+                move_lines(&self.quad, &mut self.custom_lines, &self.multi_selection, delta_mm);
+            }
+        }
+
+        // ========== KONTEXTMENÜ ==========
+        if response.secondary_clicked() {
+            self.context_target = response.hover_pos().and_then(|pos| self.hit_test(pos, &view));
+        }
+        response.context_menu(|ui| self.show_context_menu(ui));
+    }
+
+    /// Daten der statischen Darstellung, Winkelmessung nur solange das Werkzeug aktiv ist
+    fn scene_source(&self) -> SceneSource<'_> {
+        let measuring = self.tool == DrawTool::Measure;
+        SceneSource {
+            quad: &self.quad,
+            lines: &self.custom_lines,
+            circles: &self.custom_circles,
+            angle_dimensions: &self.angle_dimensions,
+            construction_lines: &self.construction_lines,
+            comparison: self.comparison.as_ref().map(|record| (&record.quad, record.time.as_str())),
+            comparison_side_by_side: self.comparison_side_by_side,
+            measure_first: self.measure_first.filter(|_| measuring),
+            measure_result: self.measure_result.as_ref().filter(|_| measuring),
+            baseline: self.baseline,
+            hovered_line: self.hovered_line,
+            selected_line: self.selected_line,
+            selected_endpoint: self.selected_endpoint,
+            selected_circle: self.selected_circle,
+            multi_selection: &self.multi_selection,
+            fmt: self.number_format(),
+            unit: self.length_unit(),
+            labels: &self.config.labels,
+            angle_display: self.config.angle_display,
+            fill: &self.config.fill,
+            show_grid: self.config.show_grid,
+            grid_spacing_mm: self.config.grid_spacing_mm,
+            show_stations: self.config.show_stations,
+            show_centroid: self.config.show_centroid,
+            show_bimedians: self.config.show_bimedians,
+            show_point_grid: self.config.show_point_grid,
+            point_grid: &self.config.point_grid,
+        }
+    }
+
+    /// Sucht das oberste Element unter dem Cursor
+    /// Reihenfolge: Linienenden, Linien, Eckpunkte, Kreise, Fläche
+    fn hit_test(&self, pos: Pos2, view: &ViewTransform) -> Option<Hit> {
+        // Sichtbare Linien über den Trefferindex der Darstellungsliste
+        if let Some((line, at_start)) = self.scene.hits.line_end_at(pos, 12.0) {
+            return Some(Hit::LineEnd { line, at_start });
+        }
+        
+        if let Some(line) = self.scene.hits.line_at(pos, 15.0) {
+            return Some(Hit::Line(line));
+        }
+        
+        let vertices: Vec<Pos2> = self.quad.vertices.iter().map(|p| view.to_screen(p)).collect();
+        if let Some(vertex) = (0..4).find(|&i| (pos - vertices[i]).length() < 12.0) {
+            return Some(Hit::Vertex(vertex));
+        }
+        
+        if let Some(idx) = self.find_circle_at(pos, view) {
+            return Some(Hit::Circle(idx));
+        }
+        
+        if self.calculated && point_in_polygon(pos, &vertices) {
+            return Some(Hit::Quad);
+        }
+        None
+    }
+
+    /// Inhalt des Rechtsklick-Menüs für das zuletzt getroffene Element
+    fn show_context_menu(&mut self, ui: &mut egui::Ui) {
+        match self.context_target {
+            Some(Hit::LineEnd { line: idx, .. } | Hit::Line(idx)) if idx < self.custom_lines.len() => {
+                ui.label(egui::RichText::new(&self.custom_lines[idx].name).strong());
+                ui.separator();
+                if ui.button("🎨 Eigenschaften").clicked() {
+                    self.selected_line = Some(idx);
+                    self.selected_endpoint = None;
+                    self.selected_circle = None;
+                    ui.close_menu();
+                }
+                if let Some(color) = quick_color_buttons(ui) {
+                    self.custom_lines[idx].color = color;
+                    ui.close_menu();
+                }
+                let locked = self.custom_lines[idx].locked;
+                if ui.button(if locked { "🔓 Entsperren" } else { "🔒 Sperren" }).clicked() {
+                    self.custom_lines[idx].locked = !locked;
+                    ui.close_menu();
+                }
+                if ui.button("📄 Duplizieren").clicked() {
+                    self.duplicate_line(idx);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!locked, egui::Button::new("🗑 Löschen")).clicked() {
+                    self.delete_line(idx);
+                    self.context_target = None;
+                    ui.close_menu();
+                }
+            }
+            Some(Hit::Circle(idx)) if idx < self.custom_circles.len() => {
+                ui.label(egui::RichText::new(&self.custom_circles[idx].name).strong());
+                ui.separator();
+                if ui.button("🎨 Eigenschaften").clicked() {
+                    self.selected_circle = Some(idx);
+                    self.selected_line = None;
+                    ui.close_menu();
+                }
+                if let Some(color) = quick_color_buttons(ui) {
+                    self.custom_circles[idx].color = color;
+                    ui.close_menu();
+                }
+                if ui.button("📄 Duplizieren").clicked() {
+                    let mut copy = self.custom_circles[idx].clone();
+                    copy.name = format!("{} (Kopie)", copy.name);
+                    self.custom_circles.push(copy);
+                    self.selected_circle = Some(self.custom_circles.len() - 1);
+                    ui.close_menu();
+                }
+                if ui.button("🗑 Löschen").clicked() {
+                    self.custom_circles.remove(idx);
+                    self.selected_circle = None;
+                    self.context_target = None;
+                    ui.close_menu();
+                }
+            }
+            Some(Hit::Vertex(vertex)) => {
+                ui.label(egui::RichText::new(format!("Eckpunkt {}", self.config.labels.vertex(vertex))).strong());
+                ui.separator();
+                if ui.button("📐 Winkelhalbierende einzeichnen").clicked() {
+                    self.construct_bisector(vertex);
+                    ui.close_menu();
+                }
+                if ui.button("📍 Als Ursprung der Absteckung").clicked() {
+                    self.baseline = Baseline { origin: vertex, axis_to: (vertex + 1) % 4 };
+                    ui.close_menu();
+                }
+            }
+            _ => {
+                ui.label(egui::RichText::new("Zeichnung").strong());
+                ui.separator();
+                if self.calculated {
+                    ui.label("Füllfarbe:");
+                    if let Some(color) = quick_color_buttons(ui) {
+                        self.config.fill.color = color;
+                        if self.config.fill.pattern == FillPattern::None {
+                            self.config.fill.pattern = FillPattern::Solid;
+                        }
+                        self.save_config();
+                        ui.close_menu();
+                    }
+                }
+                if ui.button("🔍 Ansicht einpassen").clicked() {
+                    self.zoom = 1.0;
+                    self.pan = Vec2::ZERO;
+                    ui.close_menu();
+                }
+                if ui.button("⚙ Einstellungen").clicked() {
+                    self.show_settings = true;
+                    ui.close_menu();
+                }
+            }
+        }
+    }
+
+    /// Vergrößerte Teilansicht um den Cursor, damit Punkte auf langen Seiten genau getroffen werden
+    fn draw_magnifier(&self, painter: &egui::Painter, rect: egui::Rect, view: &ViewTransform, pos: Pos2) {
+        let size = Vec2::splat(180.0);
+        let mut min = pos + Vec2::new(30.0, -30.0 - size.y);
+        min.x = min.x.min(rect.max.x - size.x).max(rect.min.x);
+        min.y = min.y.min(rect.max.y - size.y).max(rect.min.y);
+        let inset = egui::Rect::from_min_size(min, size);
+        
+        let lens = view.magnified(pos, self.config.snap.magnifier_zoom, inset.center());
+        let clipped = painter.with_clip_rect(inset);
+        clipped.rect_filled(inset, 4.0, Color32::from_rgb(250, 250, 250));
+        self.draw_geometry(&clipped, &lens);
+        
+        // Vorschau der entstehenden Linie
+        if let Some((start_anchor, _)) = self.line_start.filter(|_| self.drawing_line) {
+            let start = anchor_point(&self.quad, &self.custom_lines, &start_anchor);
+            clipped.line_segment(
+                [lens.to_screen(&start), inset.center()],
+                Stroke::new(2.0, Color32::from_rgba_unmultiplied(200, 100, 0, 128)),
+            );
+        }
+        
+        // Fadenkreuz an der Cursorposition
+        let cross = Stroke::new(1.0, Color32::from_rgb(200, 50, 50));
+        let c = inset.center();
+        clipped.line_segment([c - Vec2::new(12.0, 0.0), c + Vec2::new(12.0, 0.0)], cross);
+        clipped.line_segment([c - Vec2::new(0.0, 12.0), c + Vec2::new(0.0, 12.0)], cross);
+        clipped.text(
+            inset.left_bottom() + Vec2::new(6.0, -4.0),
+            egui::Align2::LEFT_BOTTOM,
+            format!("{}×", self.number_format().number(self.config.snap.magnifier_zoom as f64)),
+            egui::FontId::proportional(12.0),
+            Color32::GRAY,
+        );
+        painter.rect_stroke(inset, 4.0, Stroke::new(1.5, Color32::from_rgb(80, 80, 80)));
+    }
+
+    /// Nur die Geometrie (Seiten, Eckpunkte, Linien, Kreise) ohne Beschriftung
+    /// Für Teilansichten mit eigener Transformation wie die Lupe
+    fn draw_geometry(&self, painter: &egui::Painter, view: &ViewTransform) {
+        let vertices: Vec<Pos2> = self.quad.vertices.iter().map(|p| view.to_screen(p)).collect();
+        for i in 0..4 {
+            painter.line_segment(
+                [vertices[i], vertices[(i + 1) % 4]],
+                Stroke::new(2.0, Color32::from_rgb(50, 50, 200)),
+            );
+            painter.circle_filled(vertices[i], 4.0, Color32::from_rgb(200, 50, 50));
+        }
+        
+        for line in &self.custom_lines {
+            let [r, g, b] = line.color;
+            painter.extend(styled_line(
+                view.to_screen(&line.start),
+                view.to_screen(&line.end),
+                line.style,
+                Stroke::new(line.width, Color32::from_rgb(r, g, b)),
+            ));
+            painter.circle_filled(view.to_screen(&line.start), 3.0, Color32::from_rgb(255, 200, 0));
+            painter.circle_filled(view.to_screen(&line.end), 3.0, Color32::from_rgb(255, 200, 0));
+        }
+        
+        for circle in &self.custom_circles {
+            let outline: Vec<Pos2> = circle.outline(96).iter().map(|p| view.to_screen(p)).collect();
+            let [r, g, b] = circle.color;
+            painter.add(egui::Shape::line(outline, Stroke::new(circle.width, Color32::from_rgb(r, g, b))));
+        }
+    }
+
+    /// Rastet auf Eckpunkte, Seiten und Linien ein, sonst freie Position (in µm)
+    fn snap_point(&self, pos: Pos2, view: &ViewTransform) -> Point {
+        if !self.config.snap.circle_centers {
+            return view.to_world(pos);
+        }
+        match self.find_anchor(pos, view, self.custom_lines.len()) {
+            Some((anchor, dist)) if dist < self.config.snap.radius_px => anchor_point(&self.quad, &self.custom_lines, &anchor),
+            _ => view.to_world(pos),
+        }
+    }
+
+    /// Sucht den Kreis bzw. Bogen unter dem Cursor
+    fn find_circle_at(&self, pos: Pos2, view: &ViewTransform) -> Option<usize> {
+        let world = view.to_world(pos);
+        let tolerance_um = 10.0 / view.px_per_mm() as f64 * 1000.0;
+        
+        self.custom_circles
+            .iter()
+            .enumerate()
+            .map(|(idx, circle)| (idx, circle.distance_to(&world)))
+            .filter(|(_, dist)| *dist < tolerance_um)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Eingabefeld für den Abstand des Linienstarts vom Anfang der Seite bzw. Linie
+    fn show_distance_input(&mut self, ctx: &egui::Context) {
+        let Some(input) = self.distance_input.as_mut() else {
+            return;
+        };
+        let labels = &self.config.labels;
+        let prompt = match input.anchor {
+            LineAnchor::Line { line, .. } => format!(
+                "Abstand vom Start von {}:",
+                self.custom_lines.get(line).map_or("", |l| l.name.as_str())
+            ),
+            LineAnchor::Side { side, .. } | LineAnchor::Vertex(side) => {
+                format!("Abstand von {} auf {}:", labels.vertex(side), labels.side(side))
+            }
+            LineAnchor::Centroid | LineAnchor::Free { .. } => String::new(),
+        };
+
+        let (mut confirm, mut cancel) = (false, false);
+        egui::Area::new(egui::Id::new("distance_input"))
+            .fixed_pos(input.pos + Vec2::new(12.0, 12.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(prompt);
+                    ui.horizontal(|ui| {
+                        let edit = ui.add(
+                            egui::TextEdit::singleline(&mut input.text)
+                                .hint_text("z.B. 1,250 m")
+                                .desired_width(110.0),
+                        );
+                        if input.request_focus {
+                            edit.request_focus();
+                            input.request_focus = false;
+                        }
+                        if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            confirm = true;
+                        }
+                        if icon_button(ui, egui::Button::new("✔"), "Startpunkt setzen").clicked() {
+                            confirm = true;
+                        }
+                        if icon_button(ui, egui::Button::new("✖"), "Abbrechen").clicked() {
+                            cancel = true;
+                        }
+                    });
+                    if let Some(error) = &input.error {
+                        ui.colored_label(Color32::RED, error);
+                    }
+                });
+            });
+        if cancel || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.distance_input = None;
+            return;
+        }
+        if !confirm {
+            return;
+        }
+
+        let (anchor, pos, text) = (input.anchor, input.pos, input.text.clone());
+        let result = self
+            .number_format()
+            .parse_length_mm(&text)
+            .ok_or_else(|| INVALID_DISTANCE.to_string())
+            .and_then(|distance_mm| anchor_at_distance(&self.quad, &self.custom_lines, &anchor, distance_mm));
+        match result {
+            Ok(start_anchor) => {
+                self.line_start = Some((start_anchor, pos));
+                self.drawing_line = true;
+                self.line_click_end = true;
+                self.distance_input = None;
+            }
+            Err(e) => {
+                if let Some(input) = self.distance_input.as_mut() {
+                    input.error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Linie per Tastatur: Bezugsstrecke und Abstand für Start und Ende, Enter fügt hinzu
+    fn show_keyboard_line(&mut self, ui: &mut egui::Ui) {
+        let labels = &self.config.labels;
+        let mut references: Vec<(LineAnchor, String)> = (0..4)
+            .map(|side| {
+                let name = format!("Seite {} (ab {})", labels.side(side), labels.vertex(side));
+                (LineAnchor::Side { side, ratio: 0.0 }, name)
+            })
+            .collect();
+        references.extend(
+            self.custom_lines
+                .iter()
+                .enumerate()
+                .map(|(line, l)| (LineAnchor::Line { line, ratio: 0.0 }, format!("{} (ab Start)", l.name))),
+        );
+
+        let input = &mut self.keyboard_line;
+        let mut add = false;
+        ui.add_space(5.0);
+        ui.label("⌨ Linie per Eingabe:");
+        let rows = [
+            ("Start:", "keyboard_line_start", &mut input.start, &mut input.start_text),
+            ("Ende:", "keyboard_line_end", &mut input.end, &mut input.end_text),
+        ];
+        for (caption, id, reference, text) in rows {
+            // Gelöschte Linien fallen auf die erste Seite zurück
+            if !references.iter().any(|(r, _)| r == reference) {
+                *reference = references[0].0;
+            }
+            ui.horizontal(|ui| {
+                let label = ui.label(caption);
+                let selected = references.iter().find(|(r, _)| r == reference).map_or("", |(_, name)| name.as_str());
+                egui::ComboBox::from_id_source(id)
+                    .selected_text(selected)
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        for (anchor, name) in &references {
+                            ui.selectable_value(reference, *anchor, name.as_str());
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+                let edit = ui
+                    .add(egui::TextEdit::singleline(text).hint_text("z.B. 1,25 m").desired_width(80.0))
+                    .labelled_by(label.id);
+                if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    add = true;
+                }
+            });
+        }
+        if ui.button("➕ Linie hinzufügen").clicked() {
+            add = true;
+        }
+        if let Some(error) = &input.error {
+            ui.colored_label(Color32::from_rgb(200, 40, 40), error);
+        }
+
+        if add {
+            self.add_keyboard_line();
+        }
+    }
+
+    fn add_keyboard_line(&mut self) {
+        let fmt = self.number_format();
+        let input = &self.keyboard_line;
+        let anchor = |reference: &LineAnchor, text: &str| {
+            fmt.parse_length_mm(text)
+                .ok_or_else(|| INVALID_DISTANCE.to_string())
+                .and_then(|distance_mm| anchor_at_distance(&self.quad, &self.custom_lines, reference, distance_mm))
+        };
+        let result = anchor(&input.start, &input.start_text)
+            .and_then(|start| Ok((start, anchor(&input.end, &input.end_text)?)));
+
+        match result {
+            Ok((start, end)) => {
+                let name = format!("Linie {}", self.next_line_number);
+                self.next_line_number += 1;
+                let line = CustomLine::new(name, start, end, &self.quad, &self.custom_lines);
+                self.add_line(line);
+                self.selected_line = Some(self.custom_lines.len() - 1);
+                self.selected_endpoint = None;
+                self.keyboard_line.error = None;
+            }
+            Err(e) => self.keyboard_line.error = Some(e),
+        }
+    }
+
+    /// Sucht den nächsten Anker (Eckpunkt, Seite oder Linie) zur Bildschirmposition
+    /// Nur Linien mit Index < `max_line` kommen in Frage (verhindert zirkuläre Abhängigkeiten)
+    /// Position des gezogenen Linienendes nach dem weichen Einrasten an Hilfslinien
+    /// Gleitet das Ende auf einer Seite oder Linie, bleibt es darauf; an Eckpunkten gibt es keine Hilfslinien
+    fn guided_position(&self, pos: Pos2, view: &ViewTransform, drag_idx: usize, fixed: &Point) -> (Pos2, Option<Guide>) {
+        if !self.config.snap.smart_guides {
+            return (pos, None);
+        }
+        let constraint = match self.find_anchor(pos, view, drag_idx) {
+            Some((anchor @ (LineAnchor::Side { .. } | LineAnchor::Line { .. }), dist)) if dist < 10.0 => {
+                let (a, b) = anchor_segment(&self.quad, &self.custom_lines, &anchor);
+                Some((view.to_screen(&a), view.to_screen(&b)))
+            }
+            Some((_, dist)) if dist < 10.0 => return (pos, None),
+            _ => None,
+        };
+
+        let vertices: Vec<Pos2> = self.quad.vertices.iter().map(|v| view.to_screen(v)).collect();
+        let others: Vec<(Pos2, Pos2)> = self
+            .custom_lines
+            .iter()
+            .enumerate()
+            .filter(|(idx, line)| *idx != drag_idx && !line.hidden)
+            .map(|(_, line)| (view.to_screen(&line.start), view.to_screen(&line.end)))
+            .collect();
+        let points: Vec<Pos2> = vertices.iter().copied().chain(others.iter().flat_map(|&(a, b)| [a, b])).collect();
+        let segments: Vec<(Pos2, Pos2)> = (0..4).map(|i| (vertices[i], vertices[(i + 1) % 4])).chain(others).collect();
+        let sources = GuideSources {
+            points: &points,
+            segments: &segments,
+            vertices: &vertices,
+            fixed: Some(view.to_screen(fixed)),
+            px_per_mm: view.px_per_mm(),
+            length_step_mm: nice_step_mm(view.px_per_mm(), 60.0),
+        };
+
+        match snap_to_guides(pos, &candidate_guides(pos, &sources), constraint, GUIDE_SNAP_PX) {
+            Some((snapped, guide)) => (snapped, Some(guide)),
+            None => (pos, None),
+        }
+    }
+
+    /// Hilfslinie vom Bezugspunkt zum eingerasteten Linienende, bei runder Länge mit Wert
+    fn draw_guide(&self, painter: &egui::Painter, guide: &Guide, end: Pos2) {
+        let stroke = Stroke::new(1.0, Color32::from_rgb(230, 0, 140));
+        match guide.shape {
+            GuideShape::Line { .. } => {
+                painter.extend(egui::Shape::dashed_line(&[guide.reference, end], stroke, 6.0, 4.0));
+            }
+            GuideShape::Circle { center, radius } => {
+                painter.circle_stroke(center, radius, stroke);
+                painter.line_segment([center, end], stroke);
+            }
+        }
+        painter.circle_stroke(end, 6.0, stroke);
+
+        let text = match guide.kind {
+            GuideKind::Aligned => "ausgerichtet".to_string(),
+            GuideKind::Collinear => "in Flucht".to_string(),
+            GuideKind::Equidistant => "gleicher Abstand".to_string(),
+            GuideKind::RoundLength(mm) => self.number_format().length(mm, self.length_unit()),
+        };
+        painter.text(end + Vec2::new(12.0, 12.0), egui::Align2::LEFT_TOP, text, egui::FontId::proportional(13.0), stroke.color);
+    }
+
+    /// Anker für ein Linienende: einrastend auf Ecke, Seite oder Linie, sonst ein freier Punkt
+    /// (auch außerhalb des Vierecks, bezogen auf die nächste Seite)
+    fn place_anchor(&self, pos: Pos2, view: &ViewTransform, max_line: usize) -> LineAnchor {
+        match self.find_anchor(pos, view, max_line) {
+            Some((anchor, dist)) if dist < 10.0 => anchor,
+            _ => free_anchor(&self.quad, &view.to_world(pos)),
+        }
+    }
+
+    fn find_anchor(&self, pos: Pos2, view: &ViewTransform, max_line: usize) -> Option<(LineAnchor, f32)> {
+        // Eckpunkte rasten bevorzugt ein
+        if self.config.snap.vertices {
+            for vertex in 0..4 {
+                let dist = (pos - view.to_screen(&self.quad.vertices[vertex])).length();
+                if dist < self.config.snap.radius_px {
+                    return Some((LineAnchor::Vertex(vertex), dist));
+                }
+            }
+        }
+        
+        // Danach Seitenmitten und Schwerpunkt, sofern eingeblendet
+        if self.config.show_centroid && self.config.snap.centroid_midpoints {
+            let targets = (0..4)
+                .map(|side| LineAnchor::Side { side, ratio: 0.5 })
+                .chain(std::iter::once(LineAnchor::Centroid));
+            let nearest = targets
+                .map(|anchor| {
+                    let point = anchor_point(&self.quad, &self.custom_lines, &anchor);
+                    (anchor, (pos - view.to_screen(&point)).length())
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((anchor, dist)) = nearest.filter(|(_, dist)| *dist < self.config.snap.radius_px) {
+                return Some((anchor, dist));
+            }
+        }
+        
+        let mut best: Option<(LineAnchor, f32)> = None;
+        let mut consider = |anchor: LineAnchor, dist: f32| {
+            if best.is_none_or(|(_, best_dist)| dist < best_dist) {
+                best = Some((anchor, dist));
+            }
+        };
+        
+        for side in 0..4 {
+            let a = view.to_screen(&self.quad.vertices[side]);
+            let b = view.to_screen(&self.quad.vertices[(side + 1) % 4]);
+            let ratio = project_point_on_line(pos, a, b);
+            consider(LineAnchor::Side { side, ratio }, point_to_line_distance(pos, a, b));
+        }
+        
+        for (idx, line) in self.custom_lines.iter().enumerate().take(max_line) {
+            let a = view.to_screen(&line.start);
+            let b = view.to_screen(&line.end);
+            let ratio = project_point_on_line(pos, a, b);
+            consider(LineAnchor::Line { line: idx, ratio }, point_to_line_distance(pos, a, b));
+        }
+        
+        best
+    }
+
+    /// Zeichnet Lineale (oben/links, relativ zu Eckpunkt A) und eine Maßstabsleiste
+    fn draw_rulers(&self, painter: &egui::Painter, rect: egui::Rect, view: &ViewTransform) {
+        let decimal = self.number_format().decimal_char();
+        const TOP_HEIGHT: f32 = 26.0;
+        const LEFT_WIDTH: f32 = 56.0;
+        let background = Color32::from_rgba_unmultiplied(240, 240, 240, 230);
+        let tick_color = Color32::from_rgb(80, 80, 80);
+        let font = egui::FontId::proportional(13.0);
+        let tick_stroke = Stroke::new(1.0, tick_color);
+        let origin = view.world_to_view(&self.quad.vertices[0]);
+
+        let top = egui::Rect::from_min_max(rect.min, Pos2::new(rect.max.x, rect.min.y + TOP_HEIGHT));
+        let left = egui::Rect::from_min_max(rect.min, Pos2::new(rect.min.x + LEFT_WIDTH, rect.max.y));
+        painter.rect_filled(top, 0.0, background);
+        painter.rect_filled(left, 0.0, background);
+
+        let step_mm = nice_step_mm(view.px_per_mm(), 80.0);
+        let minor_mm = step_mm / 2.0;
+        let world_min = view.screen_to_view(rect.min);
+        let world_max = view.screen_to_view(rect.max);
+
+        // Horizontales Lineal
+        let first = ((world_min.x - origin.x) / 1000.0 / minor_mm).floor() as i64;
+        let last = ((world_max.x - origin.x) / 1000.0 / minor_mm).ceil() as i64;
+        if last - first < 1000 {
+            for k in first..=last {
+                let value_mm = k as f64 * minor_mm;
+                let x = view.view_to_screen(&Point::new(origin.x + value_mm * 1000.0, origin.y)).x;
+                if x < left.max.x || x > rect.max.x {
+                    continue;
+                }
+                let major = k.rem_euclid(2) == 0;
+                let tick_len = if major { 12.0 } else { 6.0 };
+                painter.line_segment([Pos2::new(x, top.max.y - tick_len), Pos2::new(x, top.max.y)], tick_stroke);
+                if major {
+                    painter.text(
+                        Pos2::new(x + 3.0, top.min.y + 1.0),
+                        egui::Align2::LEFT_TOP,
+                        format_ruler_label(value_mm, step_mm, decimal),
+                        font.clone(),
+                        tick_color,
+                    );
+                }
+            }
+        }
+
+        // Vertikales Lineal
+        let first = ((world_min.y - origin.y) / 1000.0 / minor_mm).floor() as i64;
+        let last = ((world_max.y - origin.y) / 1000.0 / minor_mm).ceil() as i64;
+        if last - first < 1000 {
+            for k in first..=last {
+                let value_mm = k as f64 * minor_mm;
+                let y = view.view_to_screen(&Point::new(origin.x, origin.y + value_mm * 1000.0)).y;
+                if y < top.max.y || y > rect.max.y {
+                    continue;
+                }
+                let major = k.rem_euclid(2) == 0;
+                let tick_len = if major { 12.0 } else { 6.0 };
+                painter.line_segment([Pos2::new(left.max.x - tick_len, y), Pos2::new(left.max.x, y)], tick_stroke);
+                if major {
+                    painter.text(
+                        Pos2::new(left.min.x + 2.0, y + 2.0),
+                        egui::Align2::LEFT_TOP,
+                        format_ruler_label(value_mm, step_mm, decimal),
+                        font.clone(),
+                        tick_color,
+                    );
+                }
+            }
+        }
+
+        // Maßstabsleiste unten rechts
+        let bar_mm = nice_step_mm(view.px_per_mm(), 120.0);
+        let bar_px = (bar_mm as f32) * view.px_per_mm();
+        let bar_right = Pos2::new(rect.max.x - 30.0, rect.max.y - STATUS_BAR_HEIGHT - 30.0);
+        let bar_left = bar_right - Vec2::new(bar_px, 0.0);
+        let bar_rect = egui::Rect::from_min_max(bar_left - Vec2::new(0.0, 6.0), bar_right);
+        painter.rect_filled(bar_rect, 0.0, Color32::from_rgb(40, 40, 40));
+        painter.rect_filled(
+            egui::Rect::from_min_max(bar_rect.min + Vec2::new(bar_px / 2.0, 1.0), bar_rect.max - Vec2::new(0.0, 1.0)),
+            0.0,
+            Color32::WHITE,
+        );
+        painter.text(
+            Pos2::new(bar_left.x + bar_px / 2.0, bar_rect.min.y - 4.0),
+            egui::Align2::CENTER_BOTTOM,
+            format_ruler_label(bar_mm, bar_mm, decimal),
+            egui::FontId::proportional(16.0),
+            Color32::from_rgb(40, 40, 40),
+        );
+    }
+
+    /// Statusleiste: Mausposition relativ zu A (in mm) und Abstand zur nächsten Seite
+    fn draw_status_bar(
+        &self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+        view: &ViewTransform,
+        hover_pos: Option<Pos2>,
+    ) {
+        let fmt = self.number_format();
+        let bar = egui::Rect::from_min_max(
+            Pos2::new(rect.min.x, rect.max.y - STATUS_BAR_HEIGHT),
+            rect.max,
+        );
+        painter.rect_filled(bar, 0.0, Color32::from_rgba_unmultiplied(230, 230, 230, 240));
+
+        let text = match hover_pos {
+            Some(pos) if rect.contains(pos) => {
+                let world = view.to_world(pos);
+                let origin = &self.quad.vertices[0];
+                let x_mm = (world.x - origin.x) / 1000.0;
+                let y_mm = (world.y - origin.y) / 1000.0;
+
+                let (nearest_side, nearest_um) = (0..4)
+                    .map(|i| {
+                        let next = (i + 1) % 4;
+                        (i, distance_point_to_segment(&world, &self.quad.vertices[i], &self.quad.vertices[next]))
+                    })
+                    .fold((0, f64::MAX), |best, cur| if cur.1 < best.1 { cur } else { best });
+
+                format!(
+                    "X: {} mm   Y: {} mm   |   Abstand zu {}: {} mm",
+                    fmt.number(x_mm),
+                    fmt.number(y_mm),
+                    self.config.labels.side(nearest_side),
+                    fmt.number(nearest_um / 1000.0),
+                )
+            }
+            _ => "X: –   Y: –".to_string(),
+        };
+
+        painter.text(
+            Pos2::new(bar.min.x + 10.0, bar.center().y),
+            egui::Align2::LEFT_CENTER,
+            text,
+            egui::FontId::proportional(16.0),
+            Color32::from_rgb(40, 40, 40),
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn take_screenshot(&self) {
+        if let Ok(screens) = screenshots::Screen::all() {
+            if let Some(screen) = screens.first() {
+                if let Ok(image) = screen.capture() {
+                    let desktop = storage::desktop_dir();
+                    let filename = desktop.join(format!("cad_screenshot_{}.png", 
+                        chrono::Local::now().format("%Y%m%d_%H%M%S")));
+                    
+                    let _ = image.save(&filename);
+                }
+            }
+        }
+    }
+
+    /// Im Browser wird die App über die Webseite aktualisiert
+    #[cfg(target_arch = "wasm32")]
+    fn check_for_updates(&mut self) {
+        updater::set_state(
+            &self.update_state,
+            UpdateState::Failed("Updates gibt es nur in der Desktop-Version".to_string()),
+        );
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn install_update(&mut self, _info: &UpdateInfo) {}
+
+    #[cfg(target_arch = "wasm32")]
+    fn rollback_update(&mut self) {}
+
+    #[cfg(target_arch = "wasm32")]
+    fn install_pending_update(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn discard_pending_update(&mut self) {}
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn check_for_updates(&mut self) {
+        updater::set_state(&self.update_state, UpdateState::Checking);
+        let update_state = self.update_state.clone();
+        let channel = self.config.update_channel;
+        
+        tokio::spawn(async move {
+            let new_state = match updater::check_for_updates(channel).await {
+                Ok(info) => UpdateState::Result(info),
+                Err(e) => {
+                    let message = format!("Update-Prüfung fehlgeschlagen: {}", e);
+                    diagnostics::log(LogLevel::Warning, "Update", &message);
+                    UpdateState::Failed(message)
+                }
+            };
+            updater::set_state(&update_state, new_state);
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn install_update(&mut self, info: &UpdateInfo) {
+        if let Some(ref url) = info.download_url {
+            let url = url.clone();
+            let checksum_url = info.checksum_url.clone();
+            let update_state = self.update_state.clone();
+            
+            let version = info.latest_version.clone();
+            
+            tokio::spawn(async move {
+                let new_state = match updater::download_update(
+                    &url,
+                    checksum_url.as_deref(),
+                    &update_state,
+                ).await {
+                    Ok(_) => UpdateState::Ready(version),
+                    Err(e) => {
+                        let message = format!("Update fehlgeschlagen: {}", e);
+                        diagnostics::log(LogLevel::Error, "Update", &message);
+                        UpdateState::Failed(message)
+                    }
+                };
+                updater::set_state(&update_state, new_state);
+            });
+        }
+    }
+
+    /// Installiert das im Hintergrund geladene Update (Programmdatei wird ersetzt, Neustart nötig)
+    #[cfg(not(target_arch = "wasm32"))]
+    fn install_pending_update(&mut self) -> Result<(), String> {
+        updater::install_staged_update().map_err(|e| format!("Update fehlgeschlagen: {}", e))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn discard_pending_update(&mut self) {
+        updater::discard_staged_update();
+    }
+
+    /// Ist ein Update geladen, wird beim Schließen nachgefragt, statt sofort zu beenden
+    fn handle_close_request(&mut self, ctx: &egui::Context) {
+        if self.pending_update.is_some() && !self.exit_confirmed && ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.exit_prompt = true;
+        }
+        if !self.exit_prompt {
+            return;
+        }
+
+        let version = self.pending_update.clone().unwrap_or_default();
+        let (mut install, mut quit) = (false, false);
+        egui::Window::new("🔄 Update installieren?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Version {} wurde im Hintergrund geladen.", version));
+                ui.label("Jetzt installieren? Beim nächsten Start läuft die neue Version.");
+                ui.label(egui::RichText::new("Nicht gespeicherte Projekte vorher unter 🗂 Projekt sichern").small());
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    install = ui.button("✅ Installieren und beenden").clicked();
+                    quit = ui.button("Ohne Update beenden").clicked();
+                    if ui.button("Abbrechen").clicked() {
+                        self.exit_prompt = false;
+                    }
+                });
+            });
+
+        if install {
+            match self.install_pending_update() {
+                Ok(()) => quit = true,
+                Err(message) => {
+                    diagnostics::log(LogLevel::Error, "Update", &message);
+                    updater::set_state(&self.update_state, UpdateState::Failed(message));
+                    self.pending_update = None;
+                    self.exit_prompt = false;
+                }
+            }
+        } else if quit {
+            self.discard_pending_update();
+        }
+        if quit {
+            self.exit_confirmed = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
+    /// Stellt die beim letzten Update gesicherte Version wieder her
+    #[cfg(not(target_arch = "wasm32"))]
+    fn rollback_update(&mut self) {
+        let new_state = match updater::rollback() {
+            Ok(version) => {
+                diagnostics::log(LogLevel::Warning, "Update", &format!("Zurück auf Version {}", version));
+                UpdateState::RolledBack(version)
+            }
+            Err(e) => {
+                let message = format!("Rückkehr zur vorherigen Version fehlgeschlagen: {}", e);
+                diagnostics::log(LogLevel::Error, "Update", &message);
+                UpdateState::Failed(message)
+            }
+        };
+        updater::set_state(&self.update_state, new_state);
+    }
+
+    /// Import-Dialog für die Stapelberechnung, Ergebnis-CSV landet auf dem Desktop
+    fn show_batch_window(&mut self, ctx: &egui::Context) {
+        // Auf das Fenster gezogene Datei übernehmen
+        if let Some(path) = storage::first_dropped_file(ctx) {
+            self.input_batch_path = path.display().to_string();
+        }
+        
+        let mut open = self.show_batch;
+        egui::Window::new("📂 Stapelberechnung")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label("CSV mit einer Zeile je Viereck, Kopfzeile z.B.:");
+                ui.monospace("Name;AB;BC;CD;DA;A;B;C;D");
+                ui.label(egui::RichText::new("Leere Zellen gelten als nicht gemessen").small());
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.label("Datei:");
+                    ui.add(egui::TextEdit::singleline(&mut self.input_batch_path).desired_width(320.0));
+                });
+                ui.label(egui::RichText::new("Pfad eingeben oder Datei auf das Fenster ziehen").small());
+                ui.add_space(5.0);
+                
+                if ui.button("🔢 Berechnen und speichern").clicked() {
+                    let fmt = self.number_format();
+                    self.batch_message = Some(
+                        storage::read_to_string(std::path::Path::new(self.input_batch_path.trim()))
+                            .map_err(|e| format!("Datei konnte nicht gelesen werden: {}", e))
+                            .and_then(|content| batch::run_batch(&content, &fmt))
+                            .and_then(|result| {
+                                let path = export::save_to_desktop("Stapel", "csv", result.csv.as_bytes())?;
+                                Ok(format!(
+                                    "{} berechnet, {} fehlerhaft\nGespeichert: {}",
+                                    result.solved,
+                                    result.failed,
+                                    path.display()
+                                ))
+                            }),
+                    );
+                }
+                
+                match &self.batch_message {
+                    Some(Ok(message)) => {
+                        ui.colored_label(Color32::from_rgb(0, 130, 0), message);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                    }
+                    None => {}
+                }
+            });
+        self.show_batch = open;
+    }
+
+    /// Skriptkonsole: Skript bearbeiten, aus Datei laden und ausführen
+    fn show_script_window(&mut self, ctx: &egui::Context) {
+        // Auf das Fenster gezogene Skriptdatei übernehmen
+        if let Some(path) = storage::first_dropped_file(ctx) {
+            if path.extension().is_some_and(|e| e == SCRIPT_EXTENSION) {
+                self.input_script_path = path.display().to_string();
+            }
+        }
+        
+        let mut open = self.show_script;
+        let (mut run, mut load, mut save) = (false, false, false);
+        egui::Window::new("📜 Skript")
+            .collapsible(false)
+            .default_width(560.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Datei:");
+                    ui.add(egui::TextEdit::singleline(&mut self.input_script_path).desired_width(280.0));
+                    load = ui.button("📂 Laden").clicked();
+                    save = ui.button("💾 Speichern").clicked();
+                });
+                ui.label(egui::RichText::new(format!("Pfad eingeben oder .{}-Datei auf das Fenster ziehen", SCRIPT_EXTENSION)).small());
+                
+                egui::CollapsingHeader::new("Funktionen")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.monospace("quad(#{ab: 4000, bc: 3000, cd: 4000, da: 3000, a: 90})");
+                        ui.monospace("rectangle(breite, höhe)");
+                        ui.monospace("side(0..3)  angle(0..3)  diagonal(0|1)  area()");
+                        ui.monospace("side_line(seite, 0..1, seite, 0..1)  vertex_line(ecke, ecke)");
+                        ui.monospace("line_length(nr)  line_name(nr, \"Name\")");
+                        ui.monospace("save(\"name\", \"csv\", text)  print(wert)");
+                        ui.label(egui::RichText::new("Längen in mm, Winkel in Grad, Fläche in m², Seiten/Ecken 0 = AB bzw. A").small());
+                        ui.label(egui::RichText::new("save() legt nur csv-, txt- oder json-Dateien auf dem Desktop ab").small());
+                    });
+                
+                egui::ScrollArea::vertical()
+                    .id_source("script_source")
+                    .max_height(280.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.script_source)
+                                .code_editor()
+                                .desired_rows(14)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                
+                run = ui.button("▶ Ausführen").clicked();
+                
+                if !self.script_log.is_empty() || self.script_error.is_some() {
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .id_source("script_log")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            for line in &self.script_log {
+                                ui.monospace(line);
+                            }
+                            if let Some(e) = &self.script_error {
+                                ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                            }
+                        });
+                }
+            });
+        self.show_script = open;
+        
+        if load {
+            match storage::read_to_string(std::path::Path::new(self.input_script_path.trim())) {
+                Ok(source) => {
+                    self.script_source = source;
+                    self.script_error = None;
+                }
+                Err(e) => self.script_error = Some(format!("Datei konnte nicht gelesen werden: {}", e)),
+            }
+        }
+        if save {
+            let path = self.input_script_path.trim();
+            let result = if path.is_empty() {
+                export::save_to_desktop("skript", SCRIPT_EXTENSION, self.script_source.as_bytes())
+            } else {
+                let mut path = PathBuf::from(path);
+                if path.extension().is_none() {
+                    path.set_extension(SCRIPT_EXTENSION);
+                }
+                storage::write(&path, self.script_source.as_bytes())
+                    .map(|_| path)
+                    .map_err(|e| format!("Datei konnte nicht gespeichert werden: {}", e))
+            };
+            match result {
+                Ok(path) => {
+                    self.input_script_path = path.display().to_string();
+                    self.script_error = None;
+                }
+                Err(e) => self.script_error = Some(e),
+            }
+        }
+        if run {
+            self.run_script();
+        }
+    }
+
+    /// Fehlerprotokoll anzeigen, als Textdatei exportieren (z.B. zum Versenden) oder leeren
+    fn show_log_window(&mut self, ctx: &egui::Context) {
+        let log_text = self.log_text.get_or_insert_with(diagnostics::read_all);
+        let mut open = self.show_log;
+        let (mut reload, mut export, mut clear) = (false, false, false);
+        egui::Window::new("🩺 Fehlerprotokoll")
+            .collapsible(false)
+            .default_width(640.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Rechenfehler, Abstürze und fehlgeschlagene Updates").small());
+                ui.horizontal(|ui| {
+                    reload = ui.button("🔄 Aktualisieren").clicked();
+                    export = ui.button("💾 Exportieren").on_hover_text("Als Textdatei auf dem Desktop speichern").clicked();
+                    clear = ui.button("🗑 Leeren").clicked();
+                });
+                if let Some(message) = &self.log_message {
+                    ui.label(egui::RichText::new(message).small());
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in log_text.lines() {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        self.show_log = open;
+
+        if export {
+            self.log_message = Some(match export::save_to_desktop("fehlerprotokoll", "txt", log_text.as_bytes()) {
+                Ok(path) => format!("✅ Gespeichert: {}", path.display()),
+                Err(e) => format!("❌ {}", e),
+            });
+        }
+        if clear {
+            self.log_message = Some(match diagnostics::clear() {
+                Ok(()) => "✅ Protokoll geleert".to_string(),
+                Err(e) => format!("❌ {}", e),
+            });
+        }
+        if reload || clear {
+            self.log_text = None;
+        }
+    }
+
+    /// Führt das Skript aus und übernimmt das berechnete Viereck samt Linien
+    fn run_script(&mut self) {
+        let result = script::run(&self.script_source);
+        self.script_log = result.log;
+        self.script_error = result.error;
+        if self.script_error.is_some() {
+            return;
+        }
+        
+        if let Some(input) = result.input {
+            self.load_inputs(&input);
+            if self.calculated {
+                // Einzeln anhängen, damit jede Linie wie beim Zeichnen im Messprotokoll steht
+                let mut lines = result.lines;
+                update_all_lines(&self.quad, &mut lines);
+                self.custom_lines.clear();
+                for line in lines {
+                    self.add_line(line);
+                }
+                self.next_line_number = self.custom_lines.len() + 1;
+                self.selected_line = None;
+                self.selected_endpoint = None;
+                self.multi_selection.clear();
+            }
+        }
+    }
+
+    /// Projektangaben bearbeiten, Projekt speichern und öffnen
+    fn show_project_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_project;
+        let mut save = false;
+        let mut load = false;
+        egui::Window::new("🗂 Projekt")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("project_grid").num_columns(2).spacing([20.0, 6.0]).show(ui, |ui| {
+                    ui.label("Projektname:");
+                    ui.add(egui::TextEdit::singleline(&mut self.project.name).desired_width(240.0));
+                    ui.end_row();
+                    ui.label("Bauherr:");
+                    ui.add(egui::TextEdit::singleline(&mut self.project.client).desired_width(240.0));
+                    ui.end_row();
+                    ui.label("Bearbeiter:");
+                    ui.add(egui::TextEdit::singleline(&mut self.project.author).desired_width(240.0));
+                    ui.end_row();
+                    ui.label("Datum:");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.project.date).desired_width(120.0));
+                        if ui.button("Heute").clicked() {
+                            self.project.date = chrono::Local::now().format("%d.%m.%Y").to_string();
+                        }
+                    });
+                    ui.end_row();
+                });
+                ui.label("Notizen:");
+                ui.add(egui::TextEdit::multiline(&mut self.project.notes).desired_rows(4).desired_width(340.0));
+                ui.label(egui::RichText::new("Erscheint im Schriftkopf des Aufmaß-Protokolls").small());
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Datei:");
+                    ui.add(egui::TextEdit::singleline(&mut self.input_project_path).desired_width(280.0));
+                });
+                ui.label(egui::RichText::new(format!("Leer = Desktop, Endung .{}; Datei auch per Drag & Drop", PROJECT_EXTENSION)).small());
+                ui.horizontal(|ui| {
+                    save = ui.button("💾 Speichern").clicked();
+                    load = ui.add_enabled(!self.input_project_path.trim().is_empty(), egui::Button::new("📂 Öffnen")).clicked();
+                });
+                
+                match &self.project_message {
+                    Some(Ok(message)) => {
+                        ui.colored_label(Color32::from_rgb(0, 130, 0), message);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                    }
+                    None => {}
+                }
+            });
+        self.show_project = open;
+        
+        if save {
+            self.project_message = Some(self.save_project());
+        }
+        if load {
+            self.project_message = Some(self.load_project());
+        }
+    }
+
+    /// Speichert Angaben sowie Messwerte und Zeichnungselemente aller Blätter
+    fn save_project(&mut self) -> Result<String, String> {
+        let sheets = self
+            .sheets
+            .iter()
+            .enumerate()
+            .map(|(idx, sheet)| {
+                // Das aktive Blatt steht in den Feldern der App
+                let (input, lines, circles, dimensions, construction, snapshots) = if idx == self.active_sheet {
+                    (
+                        &self.last_input,
+                        &self.custom_lines,
+                        &self.custom_circles,
+                        &self.angle_dimensions,
+                        &self.construction_lines,
+                        &self.snapshots,
+                    )
+                } else {
+                    (
+                        &sheet.last_input,
+                        &sheet.custom_lines,
+                        &sheet.custom_circles,
+                        &sheet.angle_dimensions,
+                        &sheet.construction_lines,
+                        &sheet.snapshots,
+                    )
+                };
+                ProjectSheet {
+                    name: sheet.name.clone(),
+                    input: input.clone().unwrap_or_default(),
+                    lines: lines.clone(),
+                    circles: circles.clone(),
+                    angle_dimensions: dimensions.clone(),
+                    construction_lines: construction.clone(),
+                    snapshots: snapshots.clone(),
+                }
+            })
+            .collect();
+        let project = ProjectFile {
+            metadata: self.project.clone(),
+            sheets,
+        };
+        let path = self.input_project_path.trim();
+        let path = if path.is_empty() {
+            let json = serde_json::to_string_pretty(&project).map_err(|e| e.to_string())?;
+            export::save_to_desktop("projekt", PROJECT_EXTENSION, json.as_bytes())?
+        } else {
+            let mut path = PathBuf::from(path);
+            if path.extension().is_none() {
+                path.set_extension(PROJECT_EXTENSION);
+            }
+            project.save(&path)?;
+            path
+        };
+        self.input_project_path = path.display().to_string();
+        Ok(format!("✅ Gespeichert: {}", path.display()))
+    }
+
+    /// Öffnet ein Projekt: je Blatt Eingaben übernehmen, neu rechnen, Zeichnungselemente wiederherstellen
+    /// Die bisherigen Blätter werden ersetzt
+    fn load_project(&mut self) -> Result<String, String> {
+        let project = ProjectFile::load(std::path::Path::new(self.input_project_path.trim()))?;
+        if project.sheets.is_empty() {
+            return Err("Die Projektdatei enthält keine Arbeitsblätter".to_string());
+        }
+        self.project = project.metadata;
+        
+        let mut failed = Vec::new();
+        let mut sheets = Vec::new();
+        for (idx, sheet) in project.sheets.into_iter().enumerate() {
+            let name = if sheet.name.trim().is_empty() { format!("Blatt {}", idx + 1) } else { sheet.name };
+            self.sheets = vec![Worksheet::new(name.clone())];
+            self.active_sheet = 0;
+            self.put_sheet_state(Worksheet::new(name.clone()));
+            
+            self.load_inputs(&sheet.input);
+            self.snapshots = sheet.snapshots;
+            if self.calculated {
+                self.custom_lines = sheet.lines;
+                update_all_lines(&self.quad, &mut self.custom_lines);
+                self.next_line_number = self.custom_lines.len() + 1;
+                self.custom_circles = sheet.circles;
+                self.next_circle_number = self.custom_circles.len() + 1;
+                self.angle_dimensions = sheet.angle_dimensions;
+                self.construction_lines = sheet.construction_lines;
+            } else if sheet.input != QuadInput::default() {
+                failed.push(name);
+            }
+            sheets.push(self.take_sheet_state());
+        }
+        // Fehler einzelner Blätter gesammelt melden statt nur den letzten anzuzeigen
+        self.error_message = None;
+        
+        let first = std::mem::replace(&mut sheets[0], Worksheet::new(String::new()));
+        sheets[0].name = first.name.clone();
+        self.sheets = sheets;
+        self.active_sheet = 0;
+        self.put_sheet_state(first);
+        
+        if failed.is_empty() {
+            Ok(format!("✅ Geöffnet: {}", self.input_project_path.trim()))
+        } else {
+            Err(format!("Geöffnet, aber nicht berechenbar: {}", failed.join(", ")))
+        }
+    }
+
+    /// Dialog für alle dauerhaft gespeicherten Einstellungen
+    fn show_settings_window(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let mut open = self.show_settings;
+        let mut changed = false;
+        let mut theme_changed = false;
+        
+        egui::Window::new("⚙ Einstellungen")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("settings_grid").num_columns(2).spacing([20.0, 10.0]).show(ui, |ui| {
+                    ui.label("Längeneinheit:");
+                    egui::ComboBox::from_id_source("settings_unit")
+                        .selected_text(self.config.length_unit.label())
+                        .show_ui(ui, |ui| {
+                            for unit in [LengthUnit::Auto, LengthUnit::Millimeter, LengthUnit::Centimeter, LengthUnit::Meter] {
+                                changed |= ui.selectable_value(&mut self.config.length_unit, unit, unit.label()).changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Winkelanzeige:")
+                        .on_hover_text("Außenwinkel = 360° − Innenwinkel; gilt für Zeichnung, Ergebnisse und Export");
+                    egui::ComboBox::from_id_source("settings_angle_display")
+                        .selected_text(self.config.angle_display.label())
+                        .show_ui(ui, |ui| {
+                            for display in [AngleDisplay::Interior, AngleDisplay::Exterior, AngleDisplay::Both] {
+                                changed |= ui.selectable_value(&mut self.config.angle_display, display, display.label()).changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Eingabeeinheit:")
+                        .on_hover_text("Für Maße ohne Einheit; \"2,5 m\" oder \"250 cm\" wird immer erkannt");
+                    let previous_input_unit = self.config.input_unit;
+                    egui::ComboBox::from_id_source("settings_input_unit")
+                        .selected_text(self.config.input_unit.label())
+                        .show_ui(ui, |ui| {
+                            for unit in [LengthUnit::Millimeter, LengthUnit::Centimeter, LengthUnit::Meter] {
+                                ui.selectable_value(&mut self.config.input_unit, unit, unit.label());
+                            }
+                        });
+                    if self.config.input_unit != previous_input_unit {
+                        self.convert_length_inputs(previous_input_unit);
+                        changed = true;
+                    }
+                    ui.end_row();
+                    
+                    ui.label("Nachkommastellen:");
+                    changed |= ui.add(egui::DragValue::new(&mut self.config.decimal_places).range(0..=MAX_DECIMAL_PLACES)).changed();
+                    ui.end_row();
+                    
+                    ui.label("Dezimaltrennzeichen:");
+                    egui::ComboBox::from_id_source("settings_decimal_separator")
+                        .selected_text(self.config.decimal_separator.label())
+                        .show_ui(ui, |ui| {
+                            for separator in [DecimalSeparator::Comma, DecimalSeparator::Point] {
+                                changed |= ui.selectable_value(&mut self.config.decimal_separator, separator, separator.label()).changed();
+                            }
+                        });
+                    ui.end_row();
+                    
+                    ui.label("Tausendertrennung:");
+                    changed |= ui.checkbox(&mut self.config.thousands_grouping, "Ziffern in Dreiergruppen (1.234,5)").changed();
+                    ui.end_row();
+                    
+                    ui.label("Sprache:");
+                    ui.vertical(|ui| {
+                        egui::ComboBox::from_id_source("settings_language")
+                            .selected_text(self.config.language.label())
+                            .show_ui(ui, |ui| {
+                                for language in [Language::German, Language::English] {
+                                    changed |= ui.selectable_value(&mut self.config.language, language, language.label()).changed();
+                                }
+                            });
+                        if self.config.language != Language::German {
+                            ui.label(egui::RichText::new("Übersetzung noch unvollständig").small());
+                        }
+                    });
+                    ui.end_row();
+                    
+                    ui.label("Farbschema:");
+                    egui::ComboBox::from_id_source("settings_theme")
+                        .selected_text(self.config.theme.label())
+                        .show_ui(ui, |ui| {
+                            for theme in [ThemeMode::System, ThemeMode::Light, ThemeMode::Dark] {
+                                theme_changed |= ui.selectable_value(&mut self.config.theme, theme, theme.label()).changed();
+                            }
+                        });
+                    ui.end_row();
+                    
+                    ui.label("Toleranz Kontrollmaß:");
+                    changed |= ui.add(
+                        egui::DragValue::new(&mut self.config.check_tolerance_mm)
+                            .speed(0.5)
+                            .range(0.0..=1000.0)
+                            .prefix("± ")
+                            .suffix(" mm"),
+                    ).changed();
+                    ui.end_row();
+                    
+                    ui.label("Warnbereich:")
+                        .on_hover_text("Widersprüche bis zu diesen Werten werden gezeichnet und gelb gemeldet, größere brechen ab");
+                    ui.horizontal(|ui| {
+                        changed |= ui.add(
+                            egui::DragValue::new(&mut self.config.plausibility.length_mm)
+                                .speed(0.5)
+                                .range(0.0..=100.0)
+                                .prefix("Maße ± ")
+                                .suffix(" mm"),
+                        ).changed();
+                        changed |= ui.add(
+                            egui::DragValue::new(&mut self.config.plausibility.angle_sum_deg)
+                                .speed(0.05)
+                                .range(0.0..=5.0)
+                                .prefix("Winkelsumme ± ")
+                                .suffix("°"),
+                        ).changed();
+                    });
+                    ui.end_row();
+                    
+                    ui.label("Fangen:");
+                    ui.vertical(|ui| {
+                        changed |= ui.checkbox(&mut self.config.snap.vertices, "An Eckpunkten einrasten").changed();
+                        changed |= ui.checkbox(&mut self.config.snap.circle_centers, "Kreismittelpunkte einrasten").changed();
+                        changed |= ui
+                            .checkbox(&mut self.config.snap.centroid_midpoints, "An Schwerpunkt und Seitenmitten einrasten")
+                            .on_hover_text("Nur wenn Schwerpunkt und Seitenmitten eingeblendet sind")
+                            .changed();
+                        changed |= ui.add(
+                            egui::Slider::new(&mut self.config.snap.radius_px, 4.0..=40.0).text("Fangradius (px)"),
+                        ).changed();
+                        changed |= ui.add(
+                            egui::DragValue::new(&mut self.config.snap.nudge_step_mm)
+                                .speed(0.1)
+                                .range(0.1..=1000.0)
+                                .prefix("Pfeiltasten: ")
+                                .suffix(" mm (Shift ×10)"),
+                        ).changed();
+                        changed |= ui
+                            .checkbox(&mut self.config.snap.smart_guides, "Hilfslinien beim Ziehen (Flucht, gleicher Abstand, runde Länge)")
+                            .changed();
+                        ui.horizontal(|ui| {
+                            changed |= ui.checkbox(&mut self.config.snap.magnifier, "Lupe beim Platzieren").changed();
+                            changed |= ui.add_enabled(
+                                self.config.snap.magnifier,
+                                egui::Slider::new(&mut self.config.snap.magnifier_zoom, 2.0..=10.0).suffix("×"),
+                            ).changed();
+                        });
+                    });
+                    ui.end_row();
+                    
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        ui.label("HTTP-Schnittstelle:");
+                        ui.vertical(|ui| {
+                            let mut api_changed = ui
+                                .checkbox(&mut self.config.api_enabled, "Lokal aktivieren (127.0.0.1)")
+                                .on_hover_text("POST /calculate mit Seiten/Winkeln als JSON, Antwort: berechnetes Viereck")
+                                .changed();
+                            ui.horizontal(|ui| {
+                                ui.label("Port:");
+                                // Erst nach der Eingabe neu starten, nicht bei jedem Zwischenwert
+                                let port = ui.add(egui::DragValue::new(&mut self.config.api_port).range(1024..=65535));
+                                changed |= port.changed();
+                                api_changed |= port.lost_focus() || port.drag_stopped();
+                            });
+                            match (&self.api_error, &self.api_server) {
+                                (Some(e), _) => {
+                                    ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                                }
+                                (None, Some(_)) => {
+                                    ui.label(
+                                        egui::RichText::new(format!("Läuft auf http://127.0.0.1:{}", self.config.api_port)).small(),
+                                    );
+                                }
+                                (None, None) => {}
+                            }
+                            if api_changed {
+                                changed = true;
+                                self.update_api_server();
+                            }
+                        });
+                        ui.end_row();
+                        
+                        ui.label("Dateiverknüpfung:");
+                        ui.vertical(|ui| {
+                            if ui
+                                .button(format!("📎 .{}-Dateien mit dieser App öffnen", PROJECT_EXTENSION))
+                                .on_hover_text("Doppelklick auf eine Projektdatei startet die App mit dem Projekt")
+                                .clicked()
+                            {
+                                self.association_message = Some(
+                                    association::register().map(|_| "✅ Verknüpfung eingerichtet".to_string()),
+                                );
+                            }
+                            match &self.association_message {
+                                Some(Ok(message)) => {
+                                    ui.colored_label(Color32::from_rgb(0, 130, 0), message);
+                                }
+                                Some(Err(e)) => {
+                                    ui.colored_label(Color32::from_rgb(200, 40, 40), e);
+                                }
+                                None => {}
+                            }
+                        });
+                        ui.end_row();
+                    }
+                    
+                    ui.label("Fenstermodus:");
+                    ui.vertical(|ui| {
+                        egui::ComboBox::from_id_source("settings_window")
+                            .selected_text(self.config.window_mode.label())
+                            .show_ui(ui, |ui| {
+                                for mode in [WindowMode::Fullscreen, WindowMode::Maximized, WindowMode::Windowed] {
+                                    changed |= ui.selectable_value(&mut self.config.window_mode, mode, mode.label()).changed();
+                                }
+                            });
+                        ui.label(egui::RichText::new("Wirksam ab dem nächsten Start").small());
+                    });
+                    ui.end_row();
+                });
+                
+                ui.add_space(10.0);
+                if ui.button("Standardwerte wiederherstellen").clicked() {
+                    // Update-Einstellungen und übersprungene Versionen bleiben erhalten
+                    let defaults = AppConfig::default();
+                    self.config.length_unit = defaults.length_unit;
+                    if self.config.input_unit != defaults.input_unit {
+                        let previous_input_unit = std::mem::replace(&mut self.config.input_unit, defaults.input_unit);
+                        self.convert_length_inputs(previous_input_unit);
+                    }
+                    self.config.decimal_places = defaults.decimal_places;
+                    self.config.decimal_separator = defaults.decimal_separator;
+                    self.config.thousands_grouping = defaults.thousands_grouping;
+                    self.config.language = defaults.language;
+                    self.config.theme = defaults.theme;
+                    self.config.check_tolerance_mm = defaults.check_tolerance_mm;
+                    self.config.plausibility = defaults.plausibility;
+                    self.config.snap = defaults.snap;
+                    self.config.window_mode = defaults.window_mode;
+                    changed = true;
+                    theme_changed = true;
+                }
+            });
+        
+        self.show_settings = open;
+        
+        if theme_changed {
+            let visuals = match self.config.theme {
+                ThemeMode::Light => egui::Visuals::light(),
+                ThemeMode::Dark => egui::Visuals::dark(),
+                ThemeMode::System => match frame.info().system_theme {
+                    Some(eframe::Theme::Dark) => egui::Visuals::dark(),
+                    _ => egui::Visuals::light(),
+                },
+            };
+            ctx.set_visuals(visuals);
+        }
+        
+        if changed || theme_changed {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                *self.api_tolerance.lock().unwrap() = self.config.plausibility;
+            }
+            self.save_config();
+        }
+    }
+
+    /// Startet bzw. beendet die lokale HTTP-Schnittstelle passend zu den Einstellungen
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_api_server(&mut self) {
+        if let Some(server) = self.api_server.take() {
+            server.abort();
+        }
+        self.api_error = None;
+        *self.api_tolerance.lock().unwrap() = self.config.plausibility;
+        if self.config.api_enabled {
+            match api::start(self.config.api_port, self.api_tolerance.clone()) {
+                Ok(server) => self.api_server = Some(server),
+                Err(e) => self.api_error = Some(e),
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn update_api_server(&mut self) {}
+
+    fn show_update_window(&mut self, ctx: &egui::Context, state: UpdateState) {
+        // Während laufender Vorgänge regelmäßig neu zeichnen
+        if matches!(
+            state,
+            UpdateState::Checking | UpdateState::Downloading { .. }
+        ) {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        egui::Window::new("🔄 Updates")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                match state {
+                    UpdateState::Idle => {}
+                    UpdateState::Checking => {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Spinner::new());
+                            ui.label("Prüfe Updates...");
+                        });
+                    }
+                    UpdateState::Result(info) => {
+                        if let Some(previous) = updater::previous_version() {
+                            if ui
+                                .button(format!("↩ Zur vorherigen Version zurückkehren ({})", previous))
+                                .on_hover_text("Falls die neue Version Probleme macht; wirksam nach dem Neustart")
+                                .clicked()
+                            {
+                                self.rollback_update();
+                            }
+                            ui.add_space(5.0);
+                        }
                         if info.available {
                             ui.label(format!("Aktuelle Version: {}", info.current_version));
                             ui.label(format!("Neue Version: {}", info.latest_version));
@@ -357,608 +6321,333 @@ impl eframe::App for CadApp {
                             ui.label("Eine neue Version ist verfügbar!");
                             ui.add_space(5.0);
                             
-                            if !self.update_status.is_empty() {
-                                ui.colored_label(Color32::from_rgb(0, 150, 0), &self.update_status);
-                                ui.add_space(5.0);
-                            }
-                            
                             ui.horizontal(|ui| {
-                                if ui.button("✅ Jetzt installieren").clicked() {
-                                    self.install_update();
+                                if ui
+                                    .button("⬇ Herunterladen")
+                                    .on_hover_text("Lädt im Hintergrund, installiert wird erst beim Beenden")
+                                    .clicked()
+                                {
+                                    self.install_update(&info);
+                                }
+                                if ui.button("⏭ Diese Version überspringen").clicked() {
+                                    if !self.config.is_version_skipped(&info.latest_version) {
+                                        self.config.skipped_versions.push(info.latest_version.clone());
+                                        self.save_config();
+                                    }
+                                    updater::set_state(&self.update_state, UpdateState::Idle);
                                 }
                                 if ui.button("❌ Abbrechen").clicked() {
-                                    self.show_update_dialog = false;
+                                    updater::set_state(&self.update_state, UpdateState::Idle);
                                 }
                             });
                         } else {
                             ui.label("Sie verwenden bereits die neueste Version!");
                             ui.add_space(10.0);
                             if ui.button("OK").clicked() {
-                                self.show_update_dialog = false;
+                                updater::set_state(&self.update_state, UpdateState::Idle);
                             }
                         }
                     }
-                });
-        }
+                    UpdateState::Downloading { progress } => {
+                        ui.label("Download läuft im Hintergrund – Sie können weiterarbeiten.");
+                        ui.add_space(5.0);
+                        match progress {
+                            Some(p) => {
+                                ui.add(egui::ProgressBar::new(p).show_percentage());
+                            }
+                            None => {
+                                ui.add(egui::Spinner::new());
+                            }
+                        }
+                    }
+                    UpdateState::Ready(version) => {
+                        ui.colored_label(
+                            Color32::from_rgb(0, 150, 0),
+                            format!("✅ Version {} geladen und geprüft.", version),
+                        );
+                        ui.label("Sie können weiterarbeiten – installiert wird beim Beenden der App.");
+                        ui.add_space(10.0);
+                        ui.horizontal(|ui| {
+                            if ui.button("Jetzt beenden").clicked() {
+                                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                            if ui.button("Weiterarbeiten").clicked() {
+                                updater::set_state(&self.update_state, UpdateState::Idle);
+                            }
+                        });
+                    }
+                    UpdateState::RolledBack(version) => {
+                        ui.colored_label(
+                            Color32::from_rgb(0, 150, 0),
+                            format!("✅ Version {} wiederhergestellt! Bitte die App neu starten.", version),
+                        );
+                        ui.add_space(10.0);
+                        ui.horizontal(|ui| {
+                            if ui.button("App schließen").clicked() {
+                                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                            if ui.button("Später").clicked() {
+                                updater::set_state(&self.update_state, UpdateState::Idle);
+                            }
+                        });
+                    }
+                    UpdateState::Failed(message) => {
+                        ui.colored_label(Color32::from_rgb(200, 50, 50), &message);
+                        ui.add_space(10.0);
+                        if ui.button("OK").clicked() {
+                            updater::set_state(&self.update_state, UpdateState::Idle);
+                        }
+                    }
+                }
+            });
     }
 }
 
-impl CadApp {
-    fn calculate_quadrilateral(&mut self) {
-        self.error_message = None;
-        
-        // Setze ALLE Werte zurück, damit leere Felder auch wirklich None werden
-        self.quad.side_ab_um = None;
-        self.quad.side_bc_um = None;
-        self.quad.side_cd_um = None;
-        self.quad.side_da_um = None;
-        self.quad.angle_a = None;
-        self.quad.angle_b = None;
-        self.quad.angle_c = None;
-        self.quad.angle_d = None;
-        
-        // Jetzt setze nur die ausgefüllten Felder
-        if !self.input_ab.is_empty() {
-            if let Ok(mm) = self.input_ab.replace(',', ".").parse::<f64>() {
-                self.quad.set_side_mm("AB", mm);
-            }
-        }
-        if !self.input_bc.is_empty() {
-            if let Ok(mm) = self.input_bc.replace(',', ".").parse::<f64>() {
-                self.quad.set_side_mm("BC", mm);
-            }
+/// Ein Messwert-Eingabefeld mit Text, Prüfergebnis und Rechenwert-Kennzeichnung
+struct InputField<'a> {
+    field: usize, // Position in der Tab-Reihenfolge
+    label: &'a str,
+    text: &'a mut String,
+    error: &'a mut Option<String>,
+    computed: &'a mut bool,
+}
+
+/// Eingabezeile mit Prüfung: fehlerhafte Werte werden rot umrandet und mit Hinweis angezeigt
+/// Gibt true zurück, wenn die Eingabe mit Enter abgeschlossen wurde
+fn validated_input_row(
+    ui: &mut egui::Ui,
+    focus: &mut InputFocus,
+    input: InputField,
+    validate: impl Fn(&str) -> Result<Option<f64>, String>,
+) -> bool {
+    let InputField { field, label, text, error, computed } = input;
+    let error_color = Color32::from_rgb(200, 50, 50);
+    let mut submitted = false;
+    
+    ui.horizontal(|ui| {
+        let label = ui.label(label);
+        // Tab übernimmt InputFocus, nicht die Standard-Reihenfolge von egui
+        let id = egui::Id::new(("quad_input", field));
+        let mut edit = egui::TextEdit::singleline(text).id(id).desired_width(120.0).lock_focus(true);
+        if *computed {
+            edit = edit.text_color(Color32::GRAY);
         }
-        if !self.input_cd.is_empty() {
-            if let Ok(mm) = self.input_cd.replace(',', ".").parse::<f64>() {
-                self.quad.set_side_mm("CD", mm);
-            }
+        // Beschriftung als Name für Screenreader
+        let response = ui.add(edit).labelled_by(label.id);
+        submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        focus.shown.push((field, id));
+        if focus.pending == Some(field) {
+            response.request_focus();
+            focus.pending = None;
         }
-        if !self.input_da.is_empty() {
-            if let Ok(mm) = self.input_da.replace(',', ".").parse::<f64>() {
-                self.quad.set_side_mm("DA", mm);
+        if response.has_focus() {
+            if let Some(backwards) = ui.input(|i| i.key_pressed(egui::Key::Tab).then_some(i.modifiers.shift)) {
+                focus.tab_from = Some((field, backwards));
             }
         }
-        
-        // Für Winkel: .parse().ok() gibt automatisch None bei leerem String
-        if !self.input_angle_a.is_empty() {
-            self.quad.angle_a = self.input_angle_a.replace(',', ".").parse::<f64>().ok();
-        }
-        if !self.input_angle_b.is_empty() {
-            self.quad.angle_b = self.input_angle_b.replace(',', ".").parse::<f64>().ok();
-        }
-        if !self.input_angle_c.is_empty() {
-            self.quad.angle_c = self.input_angle_c.replace(',', ".").parse::<f64>().ok();
+        if response.changed() {
+            *error = validate(text).err();
+            // Bearbeitete Rechenwerte gelten ab jetzt als Messung
+            *computed = false;
         }
-        if !self.input_angle_d.is_empty() {
-            self.quad.angle_d = self.input_angle_d.replace(',', ".").parse::<f64>().ok();
+        if error.is_some() {
+            ui.painter().rect_stroke(response.rect.expand(2.0), 3.0, Stroke::new(2.0, error_color));
         }
-
-        match self.quad.calculate() {
-            Ok(_) => {
-                self.calculated = true;
-                self.custom_lines.clear();
-            }
-            Err(e) => {
-                self.error_message = Some(e);
-                self.calculated = false;
-            }
+        if *computed {
+            ui.label(egui::RichText::new("berechnet").small().italics());
         }
+    });
+    
+    if let Some(message) = error {
+        ui.colored_label(error_color, format!("  ⚠ {}", message));
     }
+    submitted
+}
 
-    fn draw_quadrilateral(&mut self, ui: &mut egui::Ui) {
-        let available_size = ui.available_size();
-        let (response, painter) = ui.allocate_painter(available_size, egui::Sense::click_and_drag());
-
-        let mut min_x = f64::MAX;
-        let mut max_x = f64::MIN;
-        let mut min_y = f64::MAX;
-        let mut max_y = f64::MIN;
-
-        for v in &self.quad.vertices {
-            min_x = min_x.min(v.x);
-            max_x = max_x.max(v.x);
-            min_y = min_y.min(v.y);
-            max_y = max_y.max(v.y);
-        }
-
-        let width = max_x - min_x;
-        let height = max_y - min_y;
-        
-        let padding = 120.0;
-        let scale_x = (available_size.x - 2.0 * padding) / width as f32;
-        let scale_y = (available_size.y - 2.0 * padding) / height as f32;
-        let scale = scale_x.min(scale_y);
-
-        let offset_x = (available_size.x - width as f32 * scale) / 2.0;
-        let offset_y = (available_size.y - height as f32 * scale) / 2.0;
-
-        let to_screen = |p: &Point| -> Pos2 {
-            Pos2::new(
-                response.rect.min.x + offset_x + (p.x - min_x) as f32 * scale,
-                response.rect.min.y + offset_y + (p.y - min_y) as f32 * scale,
-            )
-        };
-
-        let screen_vertices: Vec<Pos2> = self.quad.vertices.iter().map(to_screen).collect();
-        
-        for i in 0..4 {
-            let next = (i + 1) % 4;
-            painter.line_segment(
-                [screen_vertices[i], screen_vertices[next]],
-                Stroke::new(4.0, Color32::from_rgb(50, 50, 200)),
-            );
-        }
-
-        let labels = ["A", "B", "C", "D"];
-        let angles = [self.quad.angle_a, self.quad.angle_b, self.quad.angle_c, self.quad.angle_d];
-        
-        for i in 0..4 {
-            painter.circle_filled(screen_vertices[i], 8.0, Color32::from_rgb(200, 50, 50));
-            
-            let offset = Vec2::new(-25.0, -25.0);
-            painter.text(
-                screen_vertices[i] + offset,
-                egui::Align2::CENTER_CENTER,
-                labels[i],
-                egui::FontId::proportional(28.0),
-                Color32::BLACK,
-            );
+/// Kennzeichnung hinter einem Ergebniswert: "gemessen", "berechnet" oder "angepasst" mit Messwert
+fn value_source_label(ui: &mut egui::Ui, text: String) {
+    ui.label(egui::RichText::new(text).small().italics().color(Color32::GRAY));
+}
 
-            if let Some(angle) = angles[i] {
-                let angle_offset = Vec2::new(30.0, 30.0);
-                painter.text(
-                    screen_vertices[i] + angle_offset,
-                    egui::Align2::LEFT_TOP,
-                    format!("{}°", format_angle_with_comma(angle)),
-                    egui::FontId::proportional(22.0),
-                    Color32::from_rgb(100, 100, 100),
-                );
-            }
+/// Pfeiltasten in Schritten (rechts/oben +1, links/unten -1) und ob Shift gedrückt ist
+fn arrow_key_steps(ui: &egui::Ui) -> (f64, bool) {
+    ui.input(|i| {
+        let mut steps = 0.0;
+        if i.key_pressed(egui::Key::ArrowRight) || i.key_pressed(egui::Key::ArrowUp) {
+            steps += 1.0;
         }
-
-        let side_names = ["AB", "BC", "CD", "DA"];
-        
-        let max_length_um = [
-            self.quad.get_side_length_um(0),
-            self.quad.get_side_length_um(1),
-            self.quad.get_side_length_um(2),
-            self.quad.get_side_length_um(3),
-        ].iter().fold(0_i64, |a, &b| a.max(b));
-        
-        let use_cm = max_length_um < 10_000_000;
-        
-        for i in 0..4 {
-            let next = (i + 1) % 4;
-            let mid = Pos2::new(
-                (screen_vertices[i].x + screen_vertices[next].x) / 2.0,
-                (screen_vertices[i].y + screen_vertices[next].y) / 2.0,
-            );
-            
-            let length_mm = self.quad.get_side_length_mm(i);
-            let formatted = if use_cm {
-                format!("{}: {} cm", side_names[i], format_with_comma(length_mm / 10.0))
-            } else {
-                format!("{}: {} m", side_names[i], format_with_comma(length_mm / 1000.0))
-            };
-            
-            painter.text(
-                mid,
-                egui::Align2::CENTER_CENTER,
-                formatted,
-                egui::FontId::proportional(22.0),
-                Color32::from_rgb(0, 120, 0),
-            );
+        if i.key_pressed(egui::Key::ArrowLeft) || i.key_pressed(egui::Key::ArrowDown) {
+            steps -= 1.0;
         }
+        (steps, i.modifiers.shift)
+    })
+}
 
-        // Zeichne custom lines
-        for (idx, line) in self.custom_lines.iter().enumerate() {
-            let start_screen = to_screen(&line.start);
-            let end_screen = to_screen(&line.end);
-            
-            let is_hovered = self.hovered_line == Some(idx);
-            let line_color = if is_hovered {
-                Color32::from_rgb(255, 150, 0)
-            } else {
-                Color32::from_rgb(200, 100, 0)
-            };
-            let line_width = if is_hovered { 4.0 } else { 3.0 };
-            
-            painter.line_segment(
-                [start_screen, end_screen],
-                Stroke::new(line_width, line_color),
-            );
-
-            let mid = Pos2::new(
-                (start_screen.x + end_screen.x) / 2.0,
-                (start_screen.y + end_screen.y) / 2.0,
-            );
-            
-            let length_mm = line.length_um as f64 / 1000.0;
-            let formatted = if use_cm {
-                format!("{} cm", format_with_comma(length_mm / 10.0))
-            } else {
-                format!("{} m", format_with_comma(length_mm / 1000.0))
-            };
-            
-            painter.text(
-                mid,
-                egui::Align2::CENTER_CENTER,
-                formatted,
-                egui::FontId::proportional(20.0),
-                Color32::from_rgb(56, 62, 66),  //Anthrazit
-            );
-
-            painter.circle_filled(start_screen, 4.0, Color32::from_rgb(255, 200, 0));
-            painter.text(
-                start_screen + Vec2::new(15.0, -15.0),
-                egui::Align2::LEFT_BOTTOM,
-                format!("{}°", format_angle_with_comma(line.start_angle)),
-                egui::FontId::proportional(16.0),
-                Color32::from_rgb(56, 62, 66),  //Anthrazit
-            );
-
-            painter.circle_filled(end_screen, 4.0, Color32::from_rgb(255, 200, 0));
-            painter.text(
-                end_screen + Vec2::new(15.0, -15.0),
-                egui::Align2::LEFT_BOTTOM,
-                format!("{}°", format_angle_with_comma(line.end_angle)),
-                egui::FontId::proportional(16.0),
-                Color32::from_rgb(56, 62, 66),  //Anthrazit
-            );
-
-            let start_side_idx = line.start_side;
-            let start_vertex = &self.quad.vertices[start_side_idx];
-            let segment_start_length_um = distance_um(start_vertex, &line.start);
-            let segment_start_mm = segment_start_length_um as f64 / 1000.0;
-            let segment_start_formatted = if use_cm {
-                format!("{} cm", format_with_comma(segment_start_mm / 10.0))
-            } else {
-                format!("{} m", format_with_comma(segment_start_mm / 1000.0))
-            };
-            
-            let segment_start_screen = Pos2::new(
-                (screen_vertices[start_side_idx].x + start_screen.x) / 2.0,
-                (screen_vertices[start_side_idx].y + start_screen.y) / 2.0,
-            );
-            
-            painter.text(
-                segment_start_screen,
-                egui::Align2::CENTER_CENTER,
-                segment_start_formatted,
-                egui::FontId::proportional(14.0),
-                Color32::from_rgb(150, 150, 150),
-            );
+/// Symbol-Schaltfläche mit Tooltip, der Name wird auch Screenreadern statt des Symbols vorgelesen
+fn icon_button(ui: &mut egui::Ui, button: egui::Button, name: &str) -> egui::Response {
+    let response = ui.add(button).on_hover_text(name);
+    let enabled = ui.is_enabled();
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, enabled, name));
+    response
+}
 
-            let end_side_idx = line.end_side;
-            let next_end_idx = (end_side_idx + 1) % 4;
-            let end_vertex = &self.quad.vertices[next_end_idx];
-            let segment_end_length_um = distance_um(&line.end, end_vertex);
-            let segment_end_mm = segment_end_length_um as f64 / 1000.0;
-            let segment_end_formatted = if use_cm {
-                format!("{} cm", format_with_comma(segment_end_mm / 10.0))
-            } else {
-                format!("{} m", format_with_comma(segment_end_mm / 1000.0))
-            };
-            
-            let segment_end_screen = Pos2::new(
-                (end_screen.x + screen_vertices[next_end_idx].x) / 2.0,
-                (end_screen.y + screen_vertices[next_end_idx].y) / 2.0,
-            );
-            
-            painter.text(
-                segment_end_screen,
-                egui::Align2::CENTER_CENTER,
-                segment_end_formatted,
-                egui::FontId::proportional(14.0),
-                Color32::from_rgb(150, 150, 150),
-            );
+/// Kurzbeschreibung des Konstruktionswegs, z.B. "4 Seiten + Winkel A, Kreisschnitt für C"
+fn method_text(method: &ConstructionMethod, labels: &VertexLabels) -> String {
+    match *method {
+        ConstructionMethod::AdjacentAngles { vertex } => format!(
+            "4 Seiten + Winkel {} und {}, Kontrolle über {}",
+            labels.vertex(vertex),
+            labels.vertex((vertex + 1) % 4),
+            labels.side((vertex + 2) % 4)
+        ),
+        ConstructionMethod::SingleAngle { vertex } => format!(
+            "4 Seiten + Winkel {}, Kreisschnitt für {}",
+            labels.vertex(vertex),
+            labels.vertex((vertex + 2) % 4)
+        ),
+        ConstructionMethod::Diagonal { diagonal } => {
+            format!("4 Seiten + Diagonale {}, zwei Dreiecke aus drei Seiten", labels.diagonal(diagonal))
         }
+        ConstructionMethod::ThreeSides { missing_side, vertex } => format!(
+            "3 Seiten + Winkel {} und {}, {} berechnet",
+            labels.vertex(vertex),
+            labels.vertex((vertex + 1) % 4),
+            labels.side(missing_side)
+        ),
+    }
+}
 
-        // ========== LINIEN-INTERAKTION: HOVER UND VERSCHIEBEN ==========
-        let pointer_pos = response.interact_pointer_pos();
-        
-        // Hover-Erkennung für Linien-Endpunkte
-        if let Some(pos) = pointer_pos {
-            self.hovered_line = None;
-            
-            if !self.drawing_line && self.dragging_line_idx.is_none() {
-                // Prüfe zuerst Endpunkte (höhere Priorität als Linien)
-                for (idx, line) in self.custom_lines.iter().enumerate() {
-                    let start_screen = to_screen(&line.start);
-                    let end_screen = to_screen(&line.end);
-                    
-                    // Hover auf Endpunkten (größerer Radius)
-                    if (pos - start_screen).length() < 12.0 || (pos - end_screen).length() < 12.0 {
-                        self.hovered_line = Some(idx);
-                        break;
-                    }
-                    
-                    // Sonst: Hover auf der Linie selbst
-                    let dist = point_to_line_distance(pos, start_screen, end_screen);
-                    if dist < 15.0 {
-                        self.hovered_line = Some(idx);
-                        break;
-                    }
-                }
-            }
-
-            // ========== DRAG START: Endpunkt zum Verschieben auswählen ==========
-            if response.drag_started() && !self.drawing_line {
-                for (idx, line) in self.custom_lines.iter().enumerate() {
-                    let start_screen = to_screen(&line.start);
-                    let end_screen = to_screen(&line.end);
-                    
-                    let dist_to_start = (pos - start_screen).length();
-                    let dist_to_end = (pos - end_screen).length();
-                    
-                    // Prüfe ob auf einem Endpunkt geklickt wurde
-                    if dist_to_start < 12.0 || dist_to_end < 12.0 {
-                        self.dragging_line_idx = Some(idx);
-                        // Merke welcher Endpunkt näher ist
-                        self.drag_offset = if dist_to_start < dist_to_end {
-                            Vec2::new(0.0, 0.0) // Start-Punkt wird verschoben
-                        } else {
-                            Vec2::new(1.0, 0.0) // End-Punkt wird verschoben (x=1 als Flag)
-                        };
-                        break;
-                    }
-                }
-            }
-
-            // ========== WÄHREND DES VERSCHIEBENS ==========
-            if let Some(drag_idx) = self.dragging_line_idx {
-                if response.dragged() {
-                    let moving_start = self.drag_offset.x == 0.0; // true = Start, false = End
-                    
-                    // Finde beste Position auf einer Seite
-                    let mut best_side = 0;
-                    let mut best_ratio = 0.5;
-                    let mut min_dist = f32::MAX;
-                    
-                    for side_idx in 0..4 {
-                        let next_idx = (side_idx + 1) % 4;
-                        let side_start = screen_vertices[side_idx];
-                        let side_end = screen_vertices[next_idx];
-                        
-                        let ratio = project_point_on_line(pos, side_start, side_end);
-                        let point_on_side = Pos2::new(
-                            side_start.x + (side_end.x - side_start.x) * ratio as f32,
-                            side_start.y + (side_end.y - side_start.y) * ratio as f32,
-                        );
-                        
-                        let dist = (pos - point_on_side).length();
-                        if dist < min_dist {
-                            min_dist = dist;
-                            best_side = side_idx;
-                            best_ratio = ratio;
-                        }
-                    }
-                    
-                    // Hole die aktuelle Linie
-                    let current_line = &self.custom_lines[drag_idx];
-                    
-                    // Berechne neue Punkte (nur EINEN Punkt verschieben!)
-                    let (new_start_point, new_start_side, new_start_ratio, new_end_point, new_end_side, new_end_ratio) = 
-                        if moving_start {
-                            // Verschiebe Start-Punkt, End-Punkt bleibt
-                            (
-                                self.quad.get_point_on_side(best_side, best_ratio),
-                                best_side,
-                                best_ratio,
-                                current_line.end.clone(),
-                                current_line.end_side,
-                                current_line.end_ratio
-                            )
-                        } else {
-                            // Verschiebe End-Punkt, Start-Punkt bleibt
-                            (
-                                current_line.start.clone(),
-                                current_line.start_side,
-                                current_line.start_ratio,
-                                self.quad.get_point_on_side(best_side, best_ratio),
-                                best_side,
-                                best_ratio
-                            )
-                        };
-                    
-                    let length_um = distance_um(&new_start_point, &new_end_point);
-                    
-                    // Berechne neue Schnittwinkel
-                    let start_vertex_idx = new_start_side;
-                    let start_next_idx = (new_start_side + 1) % 4;
-                    let start_angle = calculate_intersection_angle(
-                        &self.quad.vertices[start_vertex_idx],
-                        &self.quad.vertices[start_next_idx],
-                        &new_start_point,
-                        &new_end_point,
-                    );
-                    
-                    let end_vertex_idx = new_end_side;
-                    let end_next_idx = (new_end_side + 1) % 4;
-                    let end_angle = calculate_intersection_angle(
-                        &self.quad.vertices[end_vertex_idx],
-                        &self.quad.vertices[end_next_idx],
-                        &new_end_point,
-                        &new_start_point,
-                    );
-                    
-                    // Aktualisiere die Linie
-                    self.custom_lines[drag_idx] = CustomLine {
-                        start: new_start_point,
-                        end: new_end_point,
-                        length_um,
-                        start_side: new_start_side,
-                        end_side: new_end_side,
-                        start_ratio: new_start_ratio,
-                        end_ratio: new_end_ratio,
-                        start_angle,
-                        end_angle,
-                    };
-                }
-            }
-
-            if response.drag_stopped() {
-                self.dragging_line_idx = None;
-            }
-
-            // ========== ZEICHNEN NEUER LINIEN ==========
-            if self.dragging_line_idx.is_none() {
-                if response.drag_started() && !self.drawing_line {
-                    for i in 0..4 {
-                        let next = (i + 1) % 4;
-                        let dist = point_to_line_distance(pos, screen_vertices[i], screen_vertices[next]);
-                        
-                        if dist < 10.0 {
-                            let ratio = project_point_on_line(pos, screen_vertices[i], screen_vertices[next]);
-                            self.line_start = Some((i, ratio, pos));
-                            self.drawing_line = true;
-                            break;
-                        }
-                    }
-                }
+/// Hinweis auf die fehlenden Werte, z.B. "Es fehlt noch 1 Winkel oder die Diagonale AC oder BD"
+fn missing_input_text(missing: &MissingInput, labels: &VertexLabels) -> String {
+    let name = |value: &InputValue| match *value {
+        InputValue::Side(i) => format!("Seite {}", labels.side(i)),
+        InputValue::Angle(i) => format!("Winkel {}", labels.vertex(i)),
+        InputValue::Diagonal(i) => format!("Diagonale {}", labels.diagonal(i)),
+    };
+    if missing.count > 1 {
+        // Mehrere Werte: eine der kleinsten Ergänzungen als Beispiel
+        let names: Vec<String> = missing.options[0].iter().map(name).collect();
+        let (last, rest) = names.split_last().expect("Ergänzung mit mehreren Werten");
+        return format!("Es fehlen noch {} Werte, z.B. {} und {}", missing.count, rest.join(", "), last);
+    }
 
-                if self.drawing_line {
-                    self.preview_end = Some(pos);
-                    
-                    if let Some((start_side, start_ratio, _)) = self.line_start {
-                        let start_point = self.quad.get_point_on_side(start_side, start_ratio);
-                        let start_screen = to_screen(&start_point);
-                        
-                        painter.line_segment(
-                            [start_screen, pos],
-                            Stroke::new(3.0, Color32::from_rgba_unmultiplied(200, 100, 0, 128)),
-                        );
-                    }
-                }
+    let single: Vec<InputValue> = missing.options.iter().map(|option| option[0]).collect();
+    let sides: Vec<String> = single.iter().filter(|v| matches!(v, InputValue::Side(_))).map(name).collect();
+    let angles: Vec<usize> = single.iter().filter_map(|v| if let InputValue::Angle(i) = v { Some(*i) } else { None }).collect();
+    let diagonals: Vec<String> = single
+        .iter()
+        .filter_map(|v| if let InputValue::Diagonal(i) = v { Some(labels.diagonal(*i)) } else { None })
+        .collect();
 
-                if response.drag_stopped() && self.drawing_line {
-                    if let Some((start_side, start_ratio, _)) = self.line_start {
-                        for i in 0..4 {
-                            let next = (i + 1) % 4;
-                            let dist = point_to_line_distance(pos, screen_vertices[i], screen_vertices[next]);
-                            
-                            if dist < 10.0 {
-                                let end_ratio = project_point_on_line(pos, screen_vertices[i], screen_vertices[next]);
-                                
-                                let start_point = self.quad.get_point_on_side(start_side, start_ratio);
-                                let end_point = self.quad.get_point_on_side(i, end_ratio);
-                                let length_um = distance_um(&start_point, &end_point);
-                                
-                                let start_vertex_idx = start_side;
-                                let start_next_idx = (start_side + 1) % 4;
-                                let start_angle = calculate_intersection_angle(
-                                    &self.quad.vertices[start_vertex_idx],
-                                    &self.quad.vertices[start_next_idx],
-                                    &start_point,
-                                    &end_point,
-                                );
-                                
-                                let end_vertex_idx = i;
-                                let end_next_idx = (i + 1) % 4;
-                                let end_angle = calculate_intersection_angle(
-                                    &self.quad.vertices[end_vertex_idx],
-                                    &self.quad.vertices[end_next_idx],
-                                    &end_point,
-                                    &start_point,
-                                );
-                                
-                                self.custom_lines.push(CustomLine {
-                                    start: start_point,
-                                    end: end_point,
-                                    length_um,
-                                    start_side,
-                                    end_side: i,
-                                    start_ratio,
-                                    end_ratio,
-                                    start_angle,
-                                    end_angle,
-                                });
-                                break;
-                            }
-                        }
-                    }
-                    
-                    self.drawing_line = false;
-                    self.line_start = None;
-                    self.preview_end = None;
-                }
-            }
-        }
+    let mut parts = sides;
+    match angles.len() {
+        0 => {}
+        // Jeder Winkel genügt
+        4 => parts.push("1 Winkel".to_string()),
+        _ => parts.push(format!("Winkel {}", angles.iter().map(|&i| labels.vertex(i)).collect::<Vec<_>>().join(" oder "))),
     }
-
-    fn take_screenshot(&self) {
-        if let Ok(screens) = screenshots::Screen::all() {
-            if let Some(screen) = screens.first() {
-                if let Ok(image) = screen.capture() {
-                    let desktop = dirs::desktop_dir().unwrap_or_else(|| PathBuf::from("."));
-                    let filename = desktop.join(format!("cad_screenshot_{}.png", 
-                        chrono::Local::now().format("%Y%m%d_%H%M%S")));
-                    
-                    let _ = image.save(&filename);
-                }
-            }
-        }
+    if !diagonals.is_empty() {
+        parts.push(format!("die Diagonale {}", diagonals.join(" oder ")));
     }
+    format!("Es fehlt noch {}", parts.join(" oder "))
+}
 
-    fn check_for_updates(&mut self) {
-        self.checking_update = true;
-        let update_info = self.update_info.clone();
-        
-        tokio::spawn(async move {
-            match updater::check_for_updates().await {
-                Ok(info) => {
-                    *update_info.lock().unwrap() = Some(info);
-                }
-                Err(_) => {
-                    *update_info.lock().unwrap() = Some(UpdateInfo {
-                        available: false,
-                        current_version: env!("CARGO_PKG_VERSION").to_string(),
-                        latest_version: env!("CARGO_PKG_VERSION").to_string(),
-                        download_url: None,
-                    });
-                }
-            }
-        });
-        
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        self.checking_update = false;
-        self.show_update_dialog = true;
+/// Ein Schritt der Herleitung mit seinen Zwischenwerten
+fn step_text(step: &DerivationStep, labels: &VertexLabels, fmt: &NumberFormat, unit: LengthUnit) -> String {
+    match *step {
+        DerivationStep::AngleSum { vertex, angle_deg } => format!(
+            "Winkel {} = 360° − Summe der übrigen Winkel = {}°",
+            labels.vertex(vertex),
+            fmt.number(angle_deg)
+        ),
+        DerivationStep::Base { from, vertex, length_mm } => format!(
+            "{} in den Ursprung, {} im Abstand {} = {} auf die x-Achse",
+            labels.vertex(from),
+            labels.vertex(vertex),
+            labels.segment(from, vertex),
+            fmt.length(length_mm, unit)
+        ),
+        DerivationStep::Polar { vertex, from, length_mm, angle_deg } => format!(
+            "{} von {} aus: {} = {} unter Winkel {} = {}°",
+            labels.vertex(vertex),
+            labels.vertex(from),
+            labels.segment(from, vertex),
+            fmt.length(length_mm, unit),
+            labels.vertex(from),
+            fmt.number(angle_deg)
+        ),
+        DerivationStep::CircleIntersection { vertex, centers, radii_mm, center_distance_mm } => format!(
+            "{} als Schnitt der Kreise um {} (r = {}) und {} (r = {}), Mittelpunktabstand {} = {}",
+            labels.vertex(vertex),
+            labels.vertex(centers[0]),
+            fmt.length(radii_mm[0], unit),
+            labels.vertex(centers[1]),
+            fmt.length(radii_mm[1], unit),
+            labels.segment(centers[0], centers[1]),
+            fmt.length(center_distance_mm, unit)
+        ),
+        DerivationStep::RayCircle { vertex, from, angle_deg, center, radius_mm } => format!(
+            "{} auf dem Strahl von {} unter Winkel {} = {}°, im Abstand {} = {} von {}",
+            labels.vertex(vertex),
+            labels.vertex(from),
+            labels.vertex(from),
+            fmt.number(angle_deg),
+            labels.segment(center, vertex),
+            fmt.length(radius_mm, unit),
+            labels.vertex(center)
+        ),
+        DerivationStep::SlidingSide { vertex, from, side, center, radius_mm } => {
+            let end = (side + 1) % 4;
+            format!(
+                "{} auf dem Strahl von {}, verschoben bis {} im Abstand {} = {} von {} liegt",
+                labels.vertex(vertex),
+                labels.vertex(from),
+                labels.vertex(end),
+                labels.segment(end, center),
+                fmt.length(radius_mm, unit),
+                labels.vertex(center)
+            )
+        }
+        DerivationStep::Side { side, length_mm } => {
+            format!("{} aus den Eckpunkten berechnet: {}", labels.side(side), fmt.length(length_mm, unit))
+        }
+        DerivationStep::Check { side, measured_mm, actual_mm } => format!(
+            "Kontrolle {}: gemessen {}, konstruiert {} (Abweichung {} mm)",
+            labels.side(side),
+            fmt.length(measured_mm, unit),
+            fmt.length(actual_mm, unit),
+            fmt.signed(actual_mm - measured_mm)
+        ),
     }
+}
 
-    fn install_update(&mut self) {
-        if let Some(ref info) = *self.update_info.lock().unwrap() {
-            if let Some(ref url) = info.download_url {
-                let url = url.clone();
-                self.update_status = "Download läuft...".to_string();
-                
-                tokio::spawn(async move {
-                    match updater::download_and_install_update(&url).await {
-                        Ok(_) => {
-                            std::process::exit(0);
-                        }
-                        Err(e) => {
-                            eprintln!("Update fehlgeschlagen: {}", e);
-                        }
-                    }
-                });
+/// Reihe von Farbfeldern, gibt die angeklickte Farbe zurück
+fn quick_color_buttons(ui: &mut egui::Ui) -> Option<[u8; 3]> {
+    let mut picked = None;
+    ui.horizontal(|ui| {
+        for color in QUICK_COLORS {
+            let [r, g, b] = color;
+            let button = egui::Button::new("").fill(Color32::from_rgb(r, g, b)).min_size(Vec2::splat(18.0));
+            if ui.add(button).clicked() {
+                picked = Some(color);
             }
         }
-    }
+    });
+    picked
 }
 
-fn point_to_line_distance(p: Pos2, line_start: Pos2, line_end: Pos2) -> f32 {
-    let line_vec = line_end - line_start;
-    let point_vec = p - line_start;
-    
-    let line_len_sq = line_vec.x * line_vec.x + line_vec.y * line_vec.y;
-    if line_len_sq == 0.0 {
-        return point_vec.length();
+/// Punkt-in-Polygon-Test (Strahlverfahren)
+fn point_in_polygon(p: Pos2, polygon: &[Pos2]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
     }
-    
-    let t = ((point_vec.x * line_vec.x + point_vec.y * line_vec.y) / line_len_sq).clamp(0.0, 1.0);
-    let projection = line_start + t * line_vec;
-    
-    (p - projection).length()
+    inside
 }
 
 fn project_point_on_line(p: Pos2, line_start: Pos2, line_end: Pos2) -> f64 {
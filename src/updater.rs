@@ -1,31 +1,82 @@
+// Update-Prüfung und Installation über GitHub-Releases; in der Web-Version gibt es nur die Zustandstypen
+
 use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use sha2::{Digest, Sha256};
+#[cfg(not(target_arch = "wasm32"))]
 use std::error::Error;
+use std::sync::{Arc, Mutex};
 
+#[cfg(not(target_arch = "wasm32"))]
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+#[cfg(not(target_arch = "wasm32"))]
 const GITHUB_REPO: &str = "clanmonsterxd-cmd/CAD-Zeichner";
 
+/// Release-Kanal: Stable erhält nur reguläre Releases, Beta auch Pre-Releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpdateChannel {
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "Stabil",
+            UpdateChannel::Beta => "Beta (Vorabversionen)",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
     pub available: bool,
     pub current_version: String,
     pub latest_version: String,
     pub download_url: Option<String>,
+    pub checksum_url: Option<String>,
+}
+
+/// Zustand des Update-Vorgangs, wird von der UI in jedem Frame abgefragt
+#[derive(Debug, Clone)]
+pub enum UpdateState {
+    Idle,
+    Checking,
+    Result(UpdateInfo),
+    Downloading { progress: Option<f32> }, // 0.0 bis 1.0, None wenn Größe unbekannt
+    Ready(String),      // Im Hintergrund geladen und geprüft, wird beim Beenden installiert
+    RolledBack(String), // Wiederhergestellte Version
+    Failed(String),
 }
 
+pub type SharedUpdateState = Arc<Mutex<UpdateState>>;
+
+pub fn set_state(state: &SharedUpdateState, new_state: UpdateState) {
+    *state.lock().unwrap() = new_state;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
     assets: Vec<GitHubAsset>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Deserialize)]
 struct GitHubAsset {
     name: String,
     browser_download_url: String,
 }
 
-pub async fn check_for_updates() -> Result<UpdateInfo, Box<dyn Error>> {
-    let url = format!("https://api.github.com/repos/{}/releases/latest", GITHUB_REPO);
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn check_for_updates(channel: UpdateChannel) -> Result<UpdateInfo, Box<dyn Error>> {
+    // Alle Releases abfragen, da /releases/latest keine Pre-Releases liefert
+    let url = format!("https://api.github.com/repos/{}/releases", GITHUB_REPO);
     
     let client = reqwest::Client::builder()
         .user_agent("simple-cad-updater")
@@ -39,10 +90,22 @@ pub async fn check_for_updates() -> Result<UpdateInfo, Box<dyn Error>> {
             current_version: CURRENT_VERSION.to_string(),
             latest_version: CURRENT_VERSION.to_string(),
             download_url: None,
+            checksum_url: None,
         });
     }
     
-    let release: GitHubRelease = response.json().await?;
+    let releases: Vec<GitHubRelease> = response.json().await?;
+    
+    // Neuestes Release des gewählten Kanals finden
+    let Some(release) = select_release(&releases, channel) else {
+        return Ok(UpdateInfo {
+            available: false,
+            current_version: CURRENT_VERSION.to_string(),
+            latest_version: CURRENT_VERSION.to_string(),
+            download_url: None,
+            checksum_url: None,
+        });
+    };
     
     // Entferne 'v' prefix falls vorhanden
     let latest_version = release.tag_name.trim_start_matches('v');
@@ -54,6 +117,14 @@ pub async fn check_for_updates() -> Result<UpdateInfo, Box<dyn Error>> {
     
     let download_url = exe_asset.map(|a| a.browser_download_url.clone());
     
+    // Finde passende Prüfsumme (<name>.sha256)
+    let checksum_url = exe_asset.and_then(|exe| {
+        let checksum_name = format!("{}.sha256", exe.name);
+        release.assets.iter()
+            .find(|asset| asset.name.eq_ignore_ascii_case(&checksum_name))
+            .map(|a| a.browser_download_url.clone())
+    });
+    
     // Vergleiche Versionen
     let is_newer = is_version_newer(CURRENT_VERSION, latest_version);
     
@@ -62,43 +133,219 @@ pub async fn check_for_updates() -> Result<UpdateInfo, Box<dyn Error>> {
         current_version: CURRENT_VERSION.to_string(),
         latest_version: latest_version.to_string(),
         download_url,
+        checksum_url,
     })
 }
 
-pub async fn download_and_install_update(download_url: &str) -> Result<(), Box<dyn Error>> {
+/// Lädt das Update im Hintergrund, prüft die Prüfsumme und legt es neben der Programmdatei ab;
+/// ersetzt wird die laufende Version erst mit `install_staged_update` beim Beenden
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn download_update(
+    download_url: &str,
+    checksum_url: Option<&str>,
+    state: &SharedUpdateState,
+) -> Result<(), Box<dyn Error>> {
+    // Ohne veröffentlichte Prüfsumme wird nichts installiert
+    let checksum_url = checksum_url.ok_or(
+        "Keine Prüfsumme (.sha256) für dieses Release gefunden.\n\
+        Update wurde aus Sicherheitsgründen abgebrochen."
+    )?;
+    
     // Download neue Version
     let client = reqwest::Client::builder()
         .user_agent("simple-cad-updater")
         .build()?;
     
-    let response = client.get(download_url).send().await?;
-    let bytes = response.bytes().await?;
+    set_state(state, UpdateState::Downloading { progress: Some(0.0) });
+    
+    let mut response = client.get(download_url).send().await?.error_for_status()?;
+    let total_size = response.content_length();
+    let mut bytes = Vec::new();
+    
+    // Stückweise laden, um den Fortschritt anzeigen zu können
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        let progress = total_size
+            .filter(|&total| total > 0)
+            .map(|total| (bytes.len() as f32 / total as f32).min(1.0));
+        set_state(state, UpdateState::Downloading { progress });
+    }
+    
+    // Prüfsumme laden und vergleichen
+    let checksum_text = client.get(checksum_url).send().await?.error_for_status()?.text().await?;
+    let expected = parse_checksum(&checksum_text)
+        .ok_or("Prüfsummen-Datei hat ein ungültiges Format.")?;
+    let actual = sha256_hex(&bytes);
+    
+    if actual != expected {
+        return Err(format!(
+            "Prüfsumme stimmt nicht überein!\n\
+            Erwartet: {}\n\
+            Erhalten: {}\n\
+            Update wurde abgebrochen.",
+            expected, actual
+        ).into());
+    }
     
-    // Aktuellen Pfad ermitteln
+    // Neue Version bis zum Beenden neben der Programmdatei ablegen, mit Prüfsumme für die Installation
+    let (staged_exe, staged_checksum) = staged_paths(&std::env::current_exe()?);
+    std::fs::write(&staged_exe, bytes)?;
+    std::fs::write(&staged_checksum, &expected)?;
+    
+    Ok(())
+}
+
+/// Ersetzt die laufende Programmdatei durch das geladene Update; wirksam nach dem Neustart
+#[cfg(not(target_arch = "wasm32"))]
+pub fn install_staged_update() -> Result<(), Box<dyn Error>> {
     let current_exe = std::env::current_exe()?;
-    let temp_exe = current_exe.with_extension("exe.new");
+    let (staged_exe, staged_checksum) = staged_paths(&current_exe);
+    if !staged_exe.is_file() {
+        return Err("Kein heruntergeladenes Update gefunden.".into());
+    }
     
-    // Neue Version temporär speichern
-    std::fs::write(&temp_exe, bytes)?;
+    // Die Datei kann seit dem Download verändert worden sein, daher vor dem Ersetzen erneut prüfen
+    if let Err(e) = verify_staged(&staged_exe, &staged_checksum) {
+        discard_staged_update();
+        return Err(e);
+    }
     
-    // Self-update durchführen
-    self_replace::self_replace(&temp_exe)?;
+    // Laufende Version für die Rückkehr sichern, eine ältere Sicherung wird ersetzt
+    let (backup_exe, backup_version) = backup_paths(&current_exe);
+    std::fs::copy(&current_exe, &backup_exe)
+        .map_err(|e| format!("Sicherung der laufenden Version fehlgeschlagen: {}", e))?;
+    std::fs::write(&backup_version, CURRENT_VERSION)?;
     
-    // Cleanup
-    let _ = std::fs::remove_file(&temp_exe);
+    self_replace::self_replace(&staged_exe)?;
+    let _ = std::fs::remove_file(&staged_exe);
+    let _ = std::fs::remove_file(&staged_checksum);
     
     Ok(())
 }
 
+/// Verwirft ein geladenes, aber nicht installiertes Update
+#[cfg(not(target_arch = "wasm32"))]
+pub fn discard_staged_update() {
+    if let Ok(current_exe) = std::env::current_exe() {
+        let (staged_exe, staged_checksum) = staged_paths(&current_exe);
+        let _ = std::fs::remove_file(staged_exe);
+        let _ = std::fs::remove_file(staged_checksum);
+    }
+}
+
+/// Ablage des geladenen Updates bis zur Installation und die Datei mit seiner erwarteten Prüfsumme
+#[cfg(not(target_arch = "wasm32"))]
+fn staged_paths(current_exe: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    (current_exe.with_extension("exe.new"), current_exe.with_extension("exe.new.sha256"))
+}
+
+/// Vergleicht die abgelegte Programmdatei mit der beim Download gespeicherten Prüfsumme
+#[cfg(not(target_arch = "wasm32"))]
+fn verify_staged(staged_exe: &std::path::Path, staged_checksum: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    let expected = std::fs::read_to_string(staged_checksum)
+        .ok()
+        .and_then(|text| parse_checksum(&text))
+        .ok_or("Prüfsumme des geladenen Updates fehlt.\nInstallation wurde aus Sicherheitsgründen abgebrochen.")?;
+    let actual = sha256_hex(&std::fs::read(staged_exe)?);
+    if actual != expected {
+        return Err(format!(
+            "Geladenes Update wurde verändert!\n\
+            Erwartet: {}\n\
+            Erhalten: {}\n\
+            Installation wurde abgebrochen.",
+            expected, actual
+        ).into());
+    }
+    Ok(())
+}
+
+/// Sicherung der vorherigen Version neben der Programmdatei und die Datei mit ihrer Versionsnummer
+#[cfg(not(target_arch = "wasm32"))]
+fn backup_paths(current_exe: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    (current_exe.with_extension("exe.bak"), current_exe.with_extension("exe.bak.version"))
+}
+
+/// Version der beim letzten Update gesicherten Programmdatei, None ohne Sicherung
+#[cfg(not(target_arch = "wasm32"))]
+pub fn previous_version() -> Option<String> {
+    let (backup_exe, backup_version) = backup_paths(&std::env::current_exe().ok()?);
+    if !backup_exe.is_file() {
+        return None;
+    }
+    let version = std::fs::read_to_string(backup_version).unwrap_or_default();
+    Some(if version.trim().is_empty() { "unbekannt".to_string() } else { version.trim().to_string() })
+}
+
+/// Im Browser gibt es keine gesicherte Programmdatei
+#[cfg(target_arch = "wasm32")]
+pub fn previous_version() -> Option<String> {
+    None
+}
+
+/// Ersetzt die Programmdatei durch die Sicherung; wirksam nach dem Neustart
+/// Gibt die wiederhergestellte Version zurück, die Sicherung wird danach entfernt
+#[cfg(not(target_arch = "wasm32"))]
+pub fn rollback() -> Result<String, Box<dyn Error>> {
+    let version = previous_version().ok_or("Keine vorherige Version gesichert.")?;
+    let (backup_exe, backup_version) = backup_paths(&std::env::current_exe()?);
+    self_replace::self_replace(&backup_exe)?;
+    let _ = std::fs::remove_file(&backup_exe);
+    let _ = std::fs::remove_file(&backup_version);
+    Ok(version)
+}
+
+/// Liest den SHA-256-Hash aus einer Prüfsummen-Datei
+/// Unterstützt das Format von `sha256sum` ("<hash>  <dateiname>") und reine Hashes
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_checksum(text: &str) -> Option<String> {
+    let hash = text.split_whitespace().next()?.to_lowercase();
+    
+    if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(hash)
+    } else {
+        None
+    }
+}
+
+/// Berechnet den SHA-256-Hash als Hex-String (klein geschrieben)
+#[cfg(not(target_arch = "wasm32"))]
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Wählt das Release mit der höchsten Version, das zum Kanal passt
+#[cfg(not(target_arch = "wasm32"))]
+fn select_release(releases: &[GitHubRelease], channel: UpdateChannel) -> Option<&GitHubRelease> {
+    releases
+        .iter()
+        .filter(|r| !r.draft)
+        .filter(|r| channel == UpdateChannel::Beta || !r.prerelease)
+        .fold(None, |best: Option<&GitHubRelease>, r| match best {
+            Some(b) if !is_version_newer(
+                b.tag_name.trim_start_matches('v'),
+                r.tag_name.trim_start_matches('v'),
+            ) => Some(b),
+            _ => Some(r),
+        })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 fn is_version_newer(current: &str, latest: &str) -> bool {
-    let parse_version = |v: &str| -> Vec<u32> {
-        v.split('.')
-            .filter_map(|s| s.parse().ok())
-            .collect()
+    // Aufteilung in Versionskern und Pre-Release-Kennung ("10.1.0-beta.1"), Build-Metadaten werden ignoriert
+    let parse_version = |v: &str| -> (Vec<u32>, Option<Vec<String>>) {
+        let v = v.split('+').next().unwrap_or("");
+        let (core, pre) = match v.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.split('.').map(str::to_string).collect())),
+            None => (v, None),
+        };
+        (core.split('.').filter_map(|s| s.parse().ok()).collect(), pre)
     };
     
-    let current_parts = parse_version(current);
-    let latest_parts = parse_version(latest);
+    let (current_parts, current_pre) = parse_version(current);
+    let (latest_parts, latest_pre) = parse_version(latest);
     
     for (c, l) in current_parts.iter().zip(latest_parts.iter()) {
         if l > c {
@@ -107,21 +354,35 @@ fn is_version_newer(current: &str, latest: &str) -> bool {
             return false;
         }
     }
+    if latest_parts.len() != current_parts.len() {
+        return latest_parts.len() > current_parts.len();
+    }
     
-    latest_parts.len() > current_parts.len()
+    // Gleicher Versionskern: Ein reguläres Release ist neuer als jedes Pre-Release derselben Version
+    match (current_pre, latest_pre) {
+        (Some(c), Some(l)) => compare_pre_release(&c, &l) == std::cmp::Ordering::Less,
+        (Some(_), None) => true,
+        _ => false,
+    }
 }
 
-// Alternative: Verwende self_update crate
-pub async fn auto_update_with_crate() -> Result<self_update::Status, Box<dyn Error>> {
-    let status = self_update::backends::github::Update::configure()
-        .repo_owner("clanmonsterxd-cmd")
-        .repo_name("simple-cad")
-        .bin_name("simple_cad")
-        .current_version(CURRENT_VERSION)
-        .build()?
-        .update()?;
-    
-    Ok(status)
+/// Vergleicht Pre-Release-Kennungen nach SemVer: numerische Teile als Zahl, numerisch vor alphanumerisch,
+/// bei gleichem Anfang ist die kürzere Kennung kleiner
+#[cfg(not(target_arch = "wasm32"))]
+fn compare_pre_release(a: &[String], b: &[String]) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    a.len().cmp(&b.len())
 }
 
 #[cfg(test)]
@@ -134,5 +395,97 @@ mod tests {
         assert!(is_version_newer("0.1.0", "0.1.1"));
         assert!(!is_version_newer("0.2.0", "0.1.0"));
         assert!(!is_version_newer("0.1.1", "0.1.1"));
+        assert!(is_version_newer("10.0.0", "10.1.0-beta.1"));
+        assert!(!is_version_newer("10.1.0", "10.1.0-beta.2"));
+        assert!(is_version_newer("10.1.0-beta.1", "10.1.0-beta.2"));
+        assert!(!is_version_newer("10.1.0-beta.2", "10.1.0-beta.1"));
+        assert!(is_version_newer("10.1.0-beta.2", "10.1.0-beta.10"));
+        assert!(is_version_newer("10.1.0-beta.1", "10.1.0"));
+        assert!(is_version_newer("10.1.0-alpha", "10.1.0-beta"));
+        assert!(is_version_newer("10.1.0-beta", "10.1.0-beta.1"));
+        assert!(!is_version_newer("10.1.0-beta.1", "10.1.0-beta.1"));
+    }
+    
+    #[test]
+    fn test_release_channel_selection() {
+        let release = |tag: &str, prerelease: bool| GitHubRelease {
+            tag_name: tag.to_string(),
+            prerelease,
+            draft: false,
+            assets: Vec::new(),
+        };
+        let releases = vec![
+            release("v10.2.0-beta.1", true),
+            release("v10.1.0", false),
+            release("v10.0.0", false),
+        ];
+        
+        let stable = select_release(&releases, UpdateChannel::Stable).unwrap();
+        assert_eq!(stable.tag_name, "v10.1.0");
+        
+        let beta = select_release(&releases, UpdateChannel::Beta).unwrap();
+        assert_eq!(beta.tag_name, "v10.2.0-beta.1");
+        
+        // Innerhalb derselben Version: beta.2 vor beta.1, das finale Release vor allen Betas
+        let releases = vec![
+            release("v10.1.0-beta.1", true),
+            release("v10.1.0-beta.2", true),
+        ];
+        let beta = select_release(&releases, UpdateChannel::Beta).unwrap();
+        assert_eq!(beta.tag_name, "v10.1.0-beta.2");
+        
+        let releases = vec![
+            release("v10.1.0-beta.2", true),
+            release("v10.1.0", false),
+            release("v10.1.0-beta.1", true),
+        ];
+        let beta = select_release(&releases, UpdateChannel::Beta).unwrap();
+        assert_eq!(beta.tag_name, "v10.1.0");
+    }
+    
+    #[test]
+    fn test_checksum_parsing() {
+        let hash = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+        assert_eq!(
+            parse_checksum(&format!("{}  CAD-Zeichner_windows.exe\n", hash)),
+            Some(hash.to_lowercase())
+        );
+        assert_eq!(parse_checksum("abc123"), None);
+        assert_eq!(parse_checksum(""), None);
+    }
+    
+    #[test]
+    fn test_backup_paths() {
+        let (exe, version) = backup_paths(std::path::Path::new("C:/Programme/CAD-Zeichner.exe"));
+        assert_eq!(exe, std::path::Path::new("C:/Programme/CAD-Zeichner.exe.bak"));
+        assert_eq!(version, std::path::Path::new("C:/Programme/CAD-Zeichner.exe.bak.version"));
+    }
+
+    #[test]
+    fn test_staged_update_verification() {
+        let (exe, checksum) = staged_paths(std::path::Path::new("C:/Programme/CAD-Zeichner.exe"));
+        assert_eq!(exe, std::path::Path::new("C:/Programme/CAD-Zeichner.exe.new"));
+        assert_eq!(checksum, std::path::Path::new("C:/Programme/CAD-Zeichner.exe.new.sha256"));
+        
+        let dir = std::env::temp_dir().join(format!("cad_staged_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (exe, checksum) = staged_paths(&dir.join("CAD-Zeichner.exe"));
+        std::fs::write(&exe, b"neue Version").unwrap();
+        assert!(verify_staged(&exe, &checksum).is_err());
+        
+        std::fs::write(&checksum, sha256_hex(b"neue Version")).unwrap();
+        assert!(verify_staged(&exe, &checksum).is_ok());
+        
+        std::fs::write(&exe, b"manipuliert").unwrap();
+        assert!(verify_staged(&exe, &checksum).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
     }
 }
\ No newline at end of file
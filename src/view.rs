@@ -0,0 +1,397 @@
+// Ansichts-Transformation zwischen Weltkoordinaten (µm) und Bildschirm (Pixel)
+
+use crate::geometry::{Point, Quadrilateral};
+use egui::{Pos2, Rect, Vec2};
+
+/// Drehung und Spiegelung der Ansicht, die gespeicherte Geometrie bleibt unverändert
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ViewOrientation {
+    pub rotation_deg: f64, // Im Uhrzeigersinn auf dem Bildschirm
+    pub mirror_x: bool,    // Links/rechts spiegeln
+    pub mirror_y: bool,    // Oben/unten spiegeln
+}
+
+impl ViewOrientation {
+    pub fn is_identity(&self) -> bool {
+        self.rotation_deg.rem_euclid(360.0) == 0.0 && !self.mirror_x && !self.mirror_y
+    }
+
+    /// Weltkoordinaten → gedrehte/gespiegelte Ansichtskoordinaten (µm)
+    /// Erst wird gedreht, dann gespiegelt
+    pub fn apply(&self, p: &Point) -> Point {
+        let (sin, cos) = self.rotation_deg.to_radians().sin_cos();
+        let x = p.x * cos - p.y * sin;
+        let y = p.x * sin + p.y * cos;
+        Point::new(
+            if self.mirror_x { -x } else { x },
+            if self.mirror_y { -y } else { y },
+        )
+    }
+
+    /// Dreht so, dass die Seite `side` des Vierecks waagerecht unten liegt (Bezugsachse)
+    /// Die Fläche liegt danach über der Seite, unabhängig vom Konstruktionsweg; Spiegelungen bleiben erhalten
+    pub fn with_baseline(self, quad: &Quadrilateral, side: usize) -> Self {
+        let a = &quad.vertices[side % 4];
+        let b = &quad.vertices[(side + 1) % 4];
+        let rotation = -(b.y - a.y).atan2(b.x - a.x);
+
+        // Schwerpunkt der Ecken relativ zur Seite nach dem Drehen; Bildschirm-y zeigt nach unten
+        let cx = quad.vertices.iter().map(|p| p.x).sum::<f64>() / 4.0 - a.x;
+        let cy = quad.vertices.iter().map(|p| p.y).sum::<f64>() / 4.0 - a.y;
+        let below = cx * rotation.sin() + cy * rotation.cos();
+        let below = if self.mirror_y { -below } else { below };
+        let flip = if below > 0.0 { 180.0 } else { 0.0 };
+
+        Self { rotation_deg: (rotation.to_degrees() + flip).rem_euclid(360.0), ..self }
+    }
+
+    /// Umkehrung von apply
+    pub fn invert(&self, p: &Point) -> Point {
+        let x = if self.mirror_x { -p.x } else { p.x };
+        let y = if self.mirror_y { -p.y } else { p.y };
+        let (sin, cos) = self.rotation_deg.to_radians().sin_cos();
+        Point::new(x * cos + y * sin, -x * sin + y * cos)
+    }
+}
+
+/// Bildet Weltkoordinaten (µm) auf Bildschirmkoordinaten ab und zurück
+/// Intern wird in Ansichtskoordinaten (nach Drehung/Spiegelung) gerechnet
+#[derive(Clone, Copy, Debug)]
+pub struct ViewTransform {
+    world_origin_x: f64, // Ansichtspunkt, der auf screen_origin liegt (µm)
+    world_origin_y: f64,
+    screen_origin: Pos2,
+    scale: f32, // Pixel pro µm
+    orientation: ViewOrientation,
+}
+
+/// Für den Schlüssel der zwischengespeicherten Darstellung
+impl std::hash::Hash for ViewTransform {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.world_origin_x.to_bits().hash(state);
+        self.world_origin_y.to_bits().hash(state);
+        self.screen_origin.x.to_bits().hash(state);
+        self.screen_origin.y.to_bits().hash(state);
+        self.scale.to_bits().hash(state);
+        self.orientation.rotation_deg.to_bits().hash(state);
+        self.orientation.mirror_x.hash(state);
+        self.orientation.mirror_y.hash(state);
+    }
+}
+
+impl ViewTransform {
+    /// Passt die Ansicht so an, dass alle Punkte mit Rand in das Rechteck passen
+    /// zoom und pan werden danach angewendet (zoom um die Mitte des Rechtecks)
+    pub fn fit(
+        rect: Rect,
+        points: &[Point],
+        orientation: ViewOrientation,
+        padding: f32,
+        zoom: f32,
+        pan: Vec2,
+    ) -> Self {
+        let mut min_x = f64::MAX;
+        let mut max_x = f64::MIN;
+        let mut min_y = f64::MAX;
+        let mut max_y = f64::MIN;
+
+        for p in points.iter().map(|p| orientation.apply(p)) {
+            min_x = min_x.min(p.x);
+            max_x = max_x.max(p.x);
+            min_y = min_y.min(p.y);
+            max_y = max_y.max(p.y);
+        }
+
+        // Mindestgröße verhindert Division durch 0 bei entarteten Figuren
+        let width = (max_x - min_x).max(1.0);
+        let height = (max_y - min_y).max(1.0);
+
+        let scale_x = (rect.width() - 2.0 * padding) / width as f32;
+        let scale_y = (rect.height() - 2.0 * padding) / height as f32;
+        let scale = scale_x.min(scale_y).max(f32::MIN_POSITIVE) * zoom;
+
+        let offset_x = (rect.width() - width as f32 * scale) / 2.0;
+        let offset_y = (rect.height() - height as f32 * scale) / 2.0;
+
+        Self {
+            world_origin_x: min_x,
+            world_origin_y: min_y,
+            screen_origin: rect.min + Vec2::new(offset_x, offset_y) + pan,
+            scale,
+            orientation,
+        }
+    }
+
+    /// Weltkoordinaten (µm) → Bildschirm
+    pub fn to_screen(self, p: &Point) -> Pos2 {
+        self.view_to_screen(&self.orientation.apply(p))
+    }
+
+    /// Bildschirm → Weltkoordinaten (µm)
+    pub fn to_world(self, pos: Pos2) -> Point {
+        self.orientation.invert(&self.screen_to_view(pos))
+    }
+
+    /// Ansichtskoordinaten (µm, achsparallel zum Bildschirm) → Bildschirm
+    /// Für Raster und Lineale, die immer am Bildschirm ausgerichtet sind
+    pub fn view_to_screen(&self, p: &Point) -> Pos2 {
+        Pos2::new(
+            self.screen_origin.x + (p.x - self.world_origin_x) as f32 * self.scale,
+            self.screen_origin.y + (p.y - self.world_origin_y) as f32 * self.scale,
+        )
+    }
+
+    /// Bildschirm → Ansichtskoordinaten (µm)
+    pub fn screen_to_view(&self, pos: Pos2) -> Point {
+        Point::new(
+            self.world_origin_x + ((pos.x - self.screen_origin.x) / self.scale) as f64,
+            self.world_origin_y + ((pos.y - self.screen_origin.y) / self.scale) as f64,
+        )
+    }
+
+    /// Vergrößerte Teilansicht (Lupe): der Bildschirmpunkt `focus` erscheint
+    /// um `factor` vergrößert an der Stelle `target`
+    pub fn magnified(&self, focus: Pos2, factor: f32, target: Pos2) -> Self {
+        let center = self.screen_to_view(focus);
+        Self {
+            world_origin_x: center.x,
+            world_origin_y: center.y,
+            screen_origin: target,
+            scale: self.scale * factor,
+            orientation: self.orientation,
+        }
+    }
+
+    /// Welt → Ansichtskoordinaten (µm)
+    pub fn world_to_view(&self, p: &Point) -> Point {
+        self.orientation.apply(p)
+    }
+
+    /// Pixel pro Millimeter
+    pub fn px_per_mm(&self) -> f32 {
+        self.scale * 1000.0
+    }
+}
+
+/// Wählt einen "runden" Abstand (1, 2, 5 × 10^n mm), der mindestens min_px Pixel breit ist
+pub fn nice_step_mm(px_per_mm: f32, min_px: f32) -> f64 {
+    let raw = (min_px / px_per_mm.max(f32::MIN_POSITIVE)) as f64;
+    let magnitude = 10_f64.powi(raw.log10().floor() as i32);
+
+    for factor in [1.0, 2.0, 5.0, 10.0] {
+        if factor * magnitude >= raw {
+            return factor * magnitude;
+        }
+    }
+    10.0 * magnitude
+}
+
+/// Schraffurlinien innerhalb eines Polygons (Bildschirmkoordinaten)
+/// Linien im Winkel angle_deg mit Abstand spacing (Pixel), Gerade-Ungerade-Regel
+pub fn hatch_segments(polygon: &[Pos2], angle_deg: f32, spacing: f32) -> Vec<[Pos2; 2]> {
+    let mut segments = Vec::new();
+    if polygon.len() < 3 || spacing <= 0.0 {
+        return segments;
+    }
+
+    let rad = angle_deg.to_radians();
+    let dir = Vec2::new(rad.cos(), rad.sin());
+    let normal = Vec2::new(-rad.sin(), rad.cos());
+
+    let offsets: Vec<f32> = polygon.iter().map(|p| p.to_vec2().dot(normal)).collect();
+    let min = offsets.iter().cloned().fold(f32::MAX, f32::min);
+    let max = offsets.iter().cloned().fold(f32::MIN, f32::max);
+
+    let mut c = (min / spacing).ceil() * spacing;
+    while c <= max {
+        let mut hits: Vec<Pos2> = Vec::new();
+        for i in 0..polygon.len() {
+            let j = (i + 1) % polygon.len();
+            let (sa, sb) = (offsets[i], offsets[j]);
+            // Halboffenes Intervall, damit Eckpunkte nicht doppelt zählen
+            if (sa <= c) != (sb <= c) {
+                let t = (c - sa) / (sb - sa);
+                hits.push(polygon[i] + (polygon[j] - polygon[i]) * t);
+            }
+        }
+        hits.sort_by(|a, b| a.to_vec2().dot(dir).total_cmp(&b.to_vec2().dot(dir)));
+        for pair in hits.chunks_exact(2) {
+            segments.push([pair[0], pair[1]]);
+        }
+        c += spacing;
+    }
+
+    segments
+}
+
+/// Radius eines Winkelbogens: entspricht etwa 30 cm im Grundriss, auf dem Bildschirm zwischen
+/// 18 und 60 Pixeln und höchstens 40 % der verfügbaren Schenkellänge `room_px`
+pub fn angle_arc_radius(px_per_mm: f32, room_px: f32) -> f32 {
+    (px_per_mm * 300.0).clamp(18.0, 60.0).min(room_px * 0.4)
+}
+
+/// Bogen eines Winkelmaßes am Scheitel zwischen zwei Schenkelrichtungen (Bildschirmkoordinaten)
+/// Ohne `reflex` läuft der Bogen über den kleineren Winkel, sonst über den überstumpfen
+/// Liefert die Bogenpunkte und die Richtung der Winkelhalbierenden für die Beschriftung
+pub fn angle_arc(vertex: Pos2, first: Vec2, second: Vec2, radius: f32, reflex: bool) -> (Vec<Pos2>, Vec2) {
+    let start = first.y.atan2(first.x);
+    let mut sweep = second.y.atan2(second.x) - start;
+    if sweep > std::f32::consts::PI {
+        sweep -= std::f32::consts::TAU;
+    } else if sweep < -std::f32::consts::PI {
+        sweep += std::f32::consts::TAU;
+    }
+    if reflex {
+        sweep -= std::f32::consts::TAU.copysign(sweep);
+    }
+
+    let segments = ((sweep.abs() / std::f32::consts::PI * 24.0).ceil() as usize).max(4);
+    let arc = (0..=segments)
+        .map(|k| {
+            let a = start + sweep * k as f32 / segments as f32;
+            vertex + Vec2::new(a.cos(), a.sin()) * radius
+        })
+        .collect();
+    let middle = start + sweep / 2.0;
+    (arc, Vec2::new(middle.cos(), middle.sin()))
+}
+
+/// Formatiert einen Lineal-Wert in mm passend zur Schrittweite (cm oder m)
+pub fn format_ruler_label(value_mm: f64, step_mm: f64, decimal: char) -> String {
+    if step_mm >= 1000.0 {
+        format!("{} m", trim_number(value_mm / 1000.0, decimal))
+    } else {
+        format!("{} cm", trim_number(value_mm / 10.0, decimal))
+    }
+}
+
+fn trim_number(value: f64, decimal: char) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.replace('.', &decimal.to_string()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_roundtrip() {
+        let rect = Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::new(800.0, 600.0));
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(4_000_000.0, 0.0),
+            Point::new(4_000_000.0, 3_000_000.0),
+            Point::new(0.0, 3_000_000.0),
+        ];
+        let orientations = [
+            ViewOrientation::default(),
+            ViewOrientation { rotation_deg: 37.0, mirror_x: true, mirror_y: false },
+            ViewOrientation { rotation_deg: -120.0, mirror_x: false, mirror_y: true },
+        ];
+
+        for orientation in orientations {
+            let view = ViewTransform::fit(rect, &points, orientation, 50.0, 1.5, Vec2::new(30.0, -10.0));
+
+            let p = Point::new(1_234_000.0, 2_345_000.0);
+            let back = view.to_world(view.to_screen(&p));
+            assert!((back.x - p.x).abs() < 1_000.0);
+            assert!((back.y - p.y).abs() < 1_000.0);
+        }
+    }
+
+    #[test]
+    fn test_with_baseline() {
+        let quad = crate::geometry::solve(&crate::geometry::QuadInput::rectangle(4000.0, 3000.0)).unwrap().quad;
+        for side in 0..4 {
+            for mirror_y in [false, true] {
+                let orientation = ViewOrientation { mirror_y, ..Default::default() }.with_baseline(&quad, side);
+                let view: Vec<Point> = quad.vertices.iter().map(|p| orientation.apply(p)).collect();
+                let (a, b) = (&view[side], &view[(side + 1) % 4]);
+                assert!((a.y - b.y).abs() < 1e-3);
+                // Übrige Ecken liegen auf dem Bildschirm darüber (kleineres y)
+                assert!(view.iter().all(|p| p.y <= a.y + 1e-3));
+            }
+        }
+    }
+
+    #[test]
+    fn test_magnified() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let points = [Point::new(0.0, 0.0), Point::new(4_000_000.0, 3_000_000.0)];
+        let orientation = ViewOrientation { rotation_deg: 30.0, ..Default::default() };
+        let view = ViewTransform::fit(rect, &points, orientation, 50.0, 1.0, Vec2::ZERO);
+
+        let focus = Pos2::new(300.0, 200.0);
+        let lens = view.magnified(focus, 4.0, Pos2::new(700.0, 100.0));
+        let p = view.to_world(focus);
+        let at = lens.to_screen(&p);
+        assert!((at - Pos2::new(700.0, 100.0)).length() < 0.01);
+
+        // 10 px Abstand in der Ansicht werden in der Lupe zu 40 px
+        let q = view.to_world(focus + Vec2::new(10.0, 0.0));
+        assert!(((lens.to_screen(&q) - at).length() - 40.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_angle_arc() {
+        let vertex = Pos2::new(100.0, 100.0);
+        let (arc, bisector) = angle_arc(vertex, Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0), 20.0, false);
+        assert!((arc[0] - Pos2::new(120.0, 100.0)).length() < 1e-3);
+        assert!((*arc.last().unwrap() - Pos2::new(100.0, 120.0)).length() < 1e-3);
+        assert!((bisector - Vec2::new(1.0, 1.0).normalized()).length() < 1e-5);
+
+        // Überstumpf: gleiche Endpunkte, Halbierende zeigt in die Gegenrichtung
+        let (arc, bisector) = angle_arc(vertex, Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0), 20.0, true);
+        assert!((*arc.last().unwrap() - Pos2::new(100.0, 120.0)).length() < 1e-3);
+        assert!((bisector + Vec2::new(1.0, 1.0).normalized()).length() < 1e-5);
+
+        assert_eq!(angle_arc_radius(0.01, 500.0), 18.0);
+        assert_eq!(angle_arc_radius(1.0, 500.0), 60.0);
+        assert_eq!(angle_arc_radius(1.0, 50.0), 20.0);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let rotate = ViewOrientation { rotation_deg: 90.0, ..Default::default() };
+        let p = rotate.apply(&Point::new(1000.0, 0.0));
+        assert!(p.x.abs() < 1e-6 && (p.y - 1000.0).abs() < 1e-6);
+
+        let mirror = ViewOrientation { mirror_x: true, ..Default::default() };
+        let p = mirror.apply(&Point::new(1000.0, 500.0));
+        assert_eq!((p.x, p.y), (-1000.0, 500.0));
+
+        assert!(ViewOrientation { rotation_deg: 360.0, ..Default::default() }.is_identity());
+        assert!(!mirror.is_identity());
+    }
+
+    #[test]
+    fn test_nice_step() {
+        assert_eq!(nice_step_mm(1.0, 60.0), 100.0);
+        assert_eq!(nice_step_mm(0.1, 60.0), 1000.0);
+        assert_eq!(nice_step_mm(0.02, 60.0), 5000.0);
+    }
+
+    #[test]
+    fn test_hatch_segments_square() {
+        let square = [
+            Pos2::new(0.0, 0.0),
+            Pos2::new(100.0, 0.0),
+            Pos2::new(100.0, 100.0),
+            Pos2::new(0.0, 100.0),
+        ];
+        // Waagerechte Schraffur: jede Linie quert das Quadrat vollständig
+        let segments = hatch_segments(&square, 0.0, 10.0);
+        assert_eq!(segments.len(), 10);
+        for [a, b] in segments {
+            assert!(((b - a).length() - 100.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_ruler_label() {
+        assert_eq!(format_ruler_label(1500.0, 500.0, ','), "150 cm");
+        assert_eq!(format_ruler_label(2500.0, 1000.0, ','), "2,5 m");
+        assert_eq!(format_ruler_label(2500.0, 1000.0, '.'), "2.5 m");
+    }
+}
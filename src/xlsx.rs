@@ -0,0 +1,353 @@
+// Excel-Export (XLSX) ohne zusätzliche Abhängigkeit
+// Eine XLSX-Datei ist ein ZIP-Archiv mit XML-Dateien; die Einträge werden unkomprimiert abgelegt
+
+use crate::report::Report;
+
+/// Zelleninhalt
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::Text(text)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+/// Tabellenblatt der Arbeitsmappe
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+// === ZIP (nur "stored", ohne Kompression) ===
+
+/// CRC-32 (IEEE) wie von ZIP verlangt
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Schreibt ein ZIP-Archiv mit unkomprimierten Einträgen
+fn zip_stored(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    const DOS_DATE: u16 = (1 << 5) | 1; // 01.01.1980, Zeitstempel spielt für Excel keine Rolle
+
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let offset = out.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&20u16.to_le_bytes()); // benötigte Version
+        out.extend_from_slice(&0u16.to_le_bytes()); // Flags
+        out.extend_from_slice(&0u16.to_le_bytes()); // Methode: stored
+        out.extend_from_slice(&0u16.to_le_bytes()); // Uhrzeit
+        out.extend_from_slice(&DOS_DATE.to_le_bytes());
+        out.extend_from_slice(&crc.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // Extrafeld
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // erstellt mit
+        central.extend_from_slice(&20u16.to_le_bytes()); // benötigte Version
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&DOS_DATE.to_le_bytes());
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&[0; 12]); // Extrafeld, Kommentar, Disk, interne/externe Attribute
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]); // Disk-Nummern
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // Kommentar
+    out
+}
+
+// === SpreadsheetML ===
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+const NS_MAIN: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const NS_REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const NS_PACKAGE_REL: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Spaltenbuchstaben: 0 → A, 25 → Z, 26 → AA
+fn column_name(mut column: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (column % 26) as u8);
+        if column < 26 {
+            break;
+        }
+        column = column / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Excel erlaubt höchstens 31 Zeichen und keine Zeichen aus []:*?/\
+fn sheet_name(name: &str) -> String {
+    let cleaned: String = name.chars().filter(|c| !"[]:*?/\\".contains(*c)).take(31).collect();
+    if cleaned.trim().is_empty() {
+        "Tabelle".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn worksheet_xml(sheet: &Sheet, decimals: usize) -> String {
+    let mut xml = format!("{}<worksheet xmlns=\"{}\"><sheetData>", XML_HEADER, NS_MAIN);
+    for (r, row) in sheet.rows.iter().enumerate() {
+        xml.push_str(&format!("<row r=\"{}\">", r + 1));
+        for (c, cell) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(c), r + 1);
+            match cell {
+                Cell::Text(text) => xml.push_str(&format!(
+                    "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    reference,
+                    escape(text)
+                )),
+                Cell::Number(value) if value.is_finite() => {
+                    xml.push_str(&format!("<c r=\"{}\"><v>{:.*}</v></c>", reference, decimals, value))
+                }
+                Cell::Number(_) | Cell::Empty => {}
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Erstellt die Arbeitsmappe; Zahlen werden auf `decimals` Nachkommastellen gerundet
+pub fn workbook(sheets: &[Sheet], decimals: usize) -> Vec<u8> {
+    let mut content_types = format!(
+        "{}<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+         <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+        XML_HEADER
+    );
+    let mut sheet_entries = String::new();
+    let mut workbook_rels = format!("{}<Relationships xmlns=\"{}\">", XML_HEADER, NS_PACKAGE_REL);
+    let mut files = Vec::new();
+
+    for (i, sheet) in sheets.iter().enumerate() {
+        let n = i + 1;
+        content_types.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            n
+        ));
+        sheet_entries.push_str(&format!(
+            "<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+            escape(&sheet_name(&sheet.name)),
+            n,
+            n
+        ));
+        workbook_rels.push_str(&format!(
+            "<Relationship Id=\"rId{}\" Type=\"{}/worksheet\" Target=\"worksheets/sheet{}.xml\"/>",
+            n, NS_REL, n
+        ));
+        files.push((format!("xl/worksheets/sheet{}.xml", n), worksheet_xml(sheet, decimals).into_bytes()));
+    }
+    content_types.push_str("</Types>");
+    workbook_rels.push_str("</Relationships>");
+
+    let root_rels = format!(
+        "{}<Relationships xmlns=\"{}\"><Relationship Id=\"rId1\" Type=\"{}/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>",
+        XML_HEADER, NS_PACKAGE_REL, NS_REL
+    );
+    let workbook = format!(
+        "{}<workbook xmlns=\"{}\" xmlns:r=\"{}\"><sheets>{}</sheets></workbook>",
+        XML_HEADER, NS_MAIN, NS_REL, sheet_entries
+    );
+
+    let mut all = vec![
+        ("[Content_Types].xml".to_string(), content_types.into_bytes()),
+        ("_rels/.rels".to_string(), root_rels.into_bytes()),
+        ("xl/workbook.xml".to_string(), workbook.into_bytes()),
+        ("xl/_rels/workbook.xml.rels".to_string(), workbook_rels.into_bytes()),
+    ];
+    all.extend(files);
+    zip_stored(&all)
+}
+
+// === Aufmaß als Arbeitsmappe ===
+
+/// Blätter "Maße", "Linien" und "Projekt" aus denselben Daten wie das Protokoll
+/// Längen stehen in mm, damit Excel ohne Einheitenumrechnung weiterrechnen kann
+pub fn report_workbook(report: &Report) -> Vec<u8> {
+    let input = report.input.cloned().unwrap_or_default();
+    let quad = report.quad;
+    // Messwerte im Status in derselben Einheit wie die Spalte "Wert"
+    let length = |mm: f64| format!("{} mm", report.number.number(mm));
+    let angle = |degrees: f64| format!("{}°", report.number.number(degrees));
+
+    let mut values = vec![vec![Cell::from("Größe"), Cell::from("Wert"), Cell::from("Einheit"), Cell::from("Status")]];
+    for i in 0..4 {
+        let length_mm = quad.get_side_length_mm(i);
+        values.push(vec![
+            format!("Seite {}", report.labels.side(i)).into(),
+            length_mm.into(),
+            "mm".into(),
+            input.side_source(i, length_mm).describe(length).into(),
+        ]);
+    }
+    let angles = [quad.angle_a, quad.angle_b, quad.angle_c, quad.angle_d];
+    for (i, corner) in angles.iter().enumerate() {
+        let Some(corner) = corner else {
+            let status = if input.angles[i].is_some() { "gemessen" } else { "berechnet" };
+            values.push(vec![format!("Winkel {}", report.labels.vertex(i)).into(), Cell::Empty, "°".into(), status.into()]);
+            continue;
+        };
+        let status = input.angle_source(i, *corner).describe(angle);
+        for (suffix, degrees) in report.angle_display.corner_values(*corner) {
+            values.push(vec![
+                format!("Winkel {}{}", report.labels.vertex(i), suffix).into(),
+                degrees.into(),
+                "°".into(),
+                status.clone().into(),
+            ]);
+        }
+    }
+    for d in 0..2 {
+        let length_mm = quad.diagonal_um(d) as f64 / 1000.0;
+        values.push(vec![
+            format!("Diagonale {}", report.labels.diagonal(d)).into(),
+            length_mm.into(),
+            "mm".into(),
+            input.diagonal_source(d, length_mm).describe(length).into(),
+        ]);
+    }
+    let perimeter_mm: f64 = (0..4).map(|i| quad.get_side_length_mm(i)).sum();
+    values.push(vec!["Umfang".into(), perimeter_mm.into(), "mm".into(), "berechnet".into()]);
+    values.push(vec!["Fläche".into(), (quad.area_mm2() / 1_000_000.0).into(), "m²".into(), "berechnet".into()]);
+
+    let mut lines = vec![vec![
+        Cell::from("Name"),
+        Cell::from("Länge (mm)"),
+        Cell::from("Winkel Start (°)"),
+        Cell::from("Winkel Ende (°)"),
+    ]];
+    for line in report.visible_lines() {
+        lines.push(vec![
+            line.name.clone().into(),
+            (line.length_um as f64 / 1000.0).into(),
+            line.start_angle.into(),
+            line.end_angle.into(),
+        ]);
+    }
+
+    let mut project: Vec<Vec<Cell>> = Vec::new();
+    let branding = report.branding;
+    if !branding.company.trim().is_empty() {
+        project.push(vec!["Firma".into(), branding.company.trim().into()]);
+    }
+    let address: Vec<&str> = branding.address.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    if !address.is_empty() {
+        project.push(vec!["Anschrift".into(), address.join(", ").into()]);
+    }
+    project.extend(
+        report
+            .metadata
+            .title_block()
+            .into_iter()
+            .map(|(label, value)| vec![label.into(), value.into()]),
+    );
+    if report.metadata.date.trim().is_empty() {
+        project.push(vec!["Datum".into(), report.date.clone().into()]);
+    }
+    if !report.metadata.notes.trim().is_empty() {
+        project.push(vec!["Notizen".into(), report.metadata.notes.trim().into()]);
+    }
+
+    let sheets = [
+        Sheet { name: "Maße".to_string(), rows: values },
+        Sheet { name: "Linien".to_string(), rows: lines },
+        Sheet { name: "Projekt".to_string(), rows: project },
+    ];
+    workbook(&sheets, report.number.decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+    }
+
+    #[test]
+    fn test_workbook() {
+        let sheet = Sheet {
+            name: "Maße [alt]".to_string(),
+            rows: vec![vec![Cell::from("Seite <AB>"), Cell::from(4000.04)]],
+        };
+        let xml = worksheet_xml(&sheet, 1);
+        assert!(xml.contains("<c r=\"A1\" t=\"inlineStr\"><is><t xml:space=\"preserve\">Seite &lt;AB&gt;</t></is></c>"));
+        assert!(xml.contains("<c r=\"B1\"><v>4000.0</v></c>"));
+        assert_eq!(sheet_name(&sheet.name), "Maße alt");
+
+        let bytes = workbook(&[sheet], 1);
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        // Endsatz: 4 Verwaltungsdateien + 1 Tabellenblatt
+        let end = bytes.len() - 22;
+        assert_eq!(&bytes[end..end + 4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([bytes[end + 10], bytes[end + 11]]), 5);
+    }
+}